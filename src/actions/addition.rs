@@ -9,19 +9,32 @@ use crate::{
         },
         timestr,
     },
-    config::get_project,
-    context::Context,
+    context::{
+        project::active_project,
+        Context,
+    },
     db::{
+        audit::{
+            log_change,
+            Change,
+            AUDIT_CREATE,
+            TABLE_ITEMS,
+        },
         crud::insert_item,
         item::{
             Item,
             RECORD,
+            RECURRING_TASK,
             TASK,
         },
         link::add_link,
+        sync::{
+            save_sync_state,
+            SyncState,
+        },
         user::get_user_by_name,
     },
-    github::{get_issue, is_gh_available, parse_issue_ref},
+    forge::{issue_provider, parse_forge_ref, ForgeKind, ForgeScope},
 };
 
 pub fn handle_taskcmd(conn: &Connection, ctx: &Context, cmd: &TaskCommand) -> Result<(), String> {
@@ -30,22 +43,22 @@ pub fn handle_taskcmd(conn: &Connection, ctx: &Context, cmd: &TaskCommand) -> Re
         return handle_from_issue(conn, ctx, cmd, issue_str);
     }
 
+    let new_task = add_task(conn, ctx, cmd)?;
+    if new_task.action == RECURRING_TASK {
+        display::print_bold("Inserted Recurring Task:");
+    } else {
+        display::print_bold("Inserted Task:");
+    }
+    display::print_items(&[new_task], false, false);
+    Ok(())
+}
+
+/// Inserts a task, or a recurring task when the time is a schedule, owned by
+/// the current user in the current namespace. Returns it with its ID.
+pub fn add_task(conn: &Connection, ctx: &Context, cmd: &TaskCommand) -> Result<Item, String> {
     let content = cmd.content.clone();
     let target_timestr = cmd.timestr.clone().unwrap_or_else(|| "today".to_string());
-    let category: String = cmd
-        .category
-        .clone()
-        .unwrap_or_else(|| "default".to_string());
-
-    // Validate project exists in config if specified
-    if let Some(ref project_name) = cmd.project {
-        if get_project(project_name).is_none() {
-            return Err(format!(
-                "Project '{}' not found in config. Add it to ~/.config/ctm/config.json",
-                project_name
-            ));
-        }
-    }
+    let (project, category) = project_and_category(conn, ctx, cmd)?;
 
     // Resolve assignee username to ID if provided
     let assignee_id = if let Some(ref assignee_name) = cmd.assignee {
@@ -56,55 +69,45 @@ pub fn handle_taskcmd(conn: &Connection, ctx: &Context, cmd: &TaskCommand) -> Re
         None
     };
 
-    match timestr::to_unix_epoch(&target_timestr) {
+    let mut new_task = match timestr::to_unix_epoch(&target_timestr) {
         Ok(target_time) => {
             let mut new_task =
                 Item::with_target_time(TASK.to_string(), category, content, Some(target_time));
             new_task.reminder_days = cmd.reminder;
-            new_task.project = cmd.project.clone();
-            // Set multi-tenant fields
-            new_task.owner_id = Some(ctx.current_user_id);
-            new_task.assignee_id = assignee_id;
-            new_task.namespace_id = Some(ctx.current_namespace_id);
-            new_task.priority = cmd.priority;
-            new_task.estimate_minutes = cmd.estimate;
-            insert_item(conn, &new_task).map_err(|e| e.to_string())?;
-
-            display::print_bold("Inserted Task:");
-            display::print_items(&[new_task], false, false);
-            Ok(())
+            new_task.project = project;
+            new_task
         }
         Err(_) => match timestr::parse_recurring_timestr(&target_timestr) {
             Ok(cron_schedule) => {
-                let mut new_recurring_task =
-                    Item::create_recurring_task(category, content, cron_schedule, target_timestr);
-                // Set multi-tenant fields for recurring tasks too
-                new_recurring_task.owner_id = Some(ctx.current_user_id);
-                new_recurring_task.assignee_id = assignee_id;
-                new_recurring_task.namespace_id = Some(ctx.current_namespace_id);
-                new_recurring_task.priority = cmd.priority;
-                new_recurring_task.estimate_minutes = cmd.estimate;
-                insert_item(conn, &new_recurring_task).map_err(|e| e.to_string())?;
-
-                display::print_bold("Inserted Recurring Task:");
-                display::print_items(&[new_recurring_task], false, false);
-                Ok(())
+                Item::create_recurring_task(category, content, cron_schedule, target_timestr)
+            }
+            Err(_) => {
+                return Err(format!(
+                    "Could not parse '{}' as a valid time or recurring schedule",
+                    target_timestr
+                ))
             }
-            Err(_) => Err(format!(
-                "Could not parse '{}' as a valid time or recurring schedule",
-                target_timestr
-            )),
         },
-    }
+    };
+    // Set multi-tenant fields
+    new_task.owner_id = Some(ctx.current_user_id);
+    new_task.assignee_id = assignee_id;
+    new_task.namespace_id = Some(ctx.current_namespace_id);
+    new_task.priority = cmd.priority;
+    new_task.estimate_minutes = cmd.estimate;
+    let task_id = insert_item(conn, &new_task).map_err(|e| e.to_string())?;
+    log_created(conn, ctx, task_id, &new_task)?;
+    new_task.id = Some(task_id);
+    Ok(new_task)
 }
 
-pub fn handle_recordcmd(conn: &Connection, cmd: &RecordCommand) -> Result<(), String> {
+pub fn handle_recordcmd(conn: &Connection, ctx: &Context, cmd: &RecordCommand) -> Result<(), String> {
     let content = cmd.content.clone();
     let category: String = cmd
         .category
         .clone()
         .unwrap_or_else(|| "default".to_string());
-    let new_record = match &cmd.timestr {
+    let mut new_record = match &cmd.timestr {
         Some(t) => {
             let create_time = timestr::to_unix_epoch(t)?;
             Item::with_create_time(RECORD.to_string(), category, content, create_time)
@@ -112,7 +115,11 @@ pub fn handle_recordcmd(conn: &Connection, cmd: &RecordCommand) -> Result<(), St
         None => Item::new(RECORD.to_string(), category, content),
     };
 
-    insert_item(conn, &new_record).map_err(|e| e.to_string())?;
+    new_record.owner_id = Some(ctx.current_user_id);
+    new_record.namespace_id = Some(ctx.current_namespace_id);
+
+    let record_id = insert_item(conn, &new_record).map_err(|e| e.to_string())?;
+    log_created(conn, ctx, record_id, &new_record)?;
 
     display::print_bold("Inserted Record:");
     display::print_items(&[new_record], true, false);
@@ -126,22 +133,17 @@ fn handle_from_issue(
     cmd: &TaskCommand,
     issue_str: &str,
 ) -> Result<(), String> {
-    // Check gh is available
-    if !is_gh_available() {
-        return Err(
-            "GitHub CLI (gh) is not installed or not authenticated. Run 'gh auth login' first."
-                .to_string(),
-        );
-    }
-
-    // Parse issue reference
-    let issue_ref = parse_issue_ref(issue_str)?;
-
-    // Fetch issue details
-    let issue = get_issue(&issue_ref)?;
+    let forge_ref = parse_forge_ref(issue_str)?;
+    let (project, category) = project_and_category(conn, ctx, cmd)?;
+    let scope = ForgeScope {
+        namespace: &ctx.current_namespace_name,
+        project: project.as_deref(),
+    };
+    let provider = issue_provider(&forge_ref, scope)?;
+    let issue = provider.fetch(&forge_ref)?;
 
-    if issue.state == "CLOSED" {
-        return Err(format!("Issue {} is already closed", issue_str));
+    if !issue.is_open() {
+        return Err(format!("The {} {} is already closed", forge_ref.noun(), issue_str));
     }
 
     // Use issue title as task content, or allow override via cmd.content if provided
@@ -152,20 +154,6 @@ fn handle_from_issue(
     };
 
     let target_timestr = cmd.timestr.clone().unwrap_or_else(|| "today".to_string());
-    let category = cmd
-        .category
-        .clone()
-        .unwrap_or_else(|| "default".to_string());
-
-    // Validate project if specified
-    if let Some(ref project_name) = cmd.project {
-        if get_project(project_name).is_none() {
-            return Err(format!(
-                "Project '{}' not found in config. Add it to ~/.config/ctm/config.json",
-                project_name
-            ));
-        }
-    }
 
     // Resolve assignee username to ID if provided
     let assignee_id = if let Some(ref assignee_name) = cmd.assignee {
@@ -181,7 +169,7 @@ fn handle_from_issue(
     let mut new_task =
         Item::with_target_time(TASK.to_string(), category, content, Some(target_time));
     new_task.reminder_days = cmd.reminder;
-    new_task.project = cmd.project.clone();
+    new_task.project = project;
     new_task.owner_id = Some(ctx.current_user_id);
     new_task.assignee_id = assignee_id;
     new_task.namespace_id = Some(ctx.current_namespace_id);
@@ -190,24 +178,65 @@ fn handle_from_issue(
     new_task.github_issue = Some(issue_str.to_string());
 
     let task_id = insert_item(conn, &new_task).map_err(|e| e.to_string())?;
+    log_created(conn, ctx, task_id, &new_task)?;
 
     // Auto-link the issue
     add_link(
         conn,
         task_id,
-        "issue",
+        forge_ref.link_type(),
         issue_str,
         Some(&issue.title),
         Some(ctx.current_user_id),
     )?;
 
-    display::print_bold("Created task from GitHub issue:");
+    // The issue as fetched is the starting point for `gh sync`
+    if provider.kind() == ForgeKind::GitHub {
+        save_sync_state(
+            conn,
+            &SyncState {
+                item_id: task_id,
+                reference: forge_ref.to_issue_ref()?.to_string(),
+                state: issue.state.clone(),
+                title: issue.title.clone(),
+                assignees: issue.assignees.clone(),
+                synced_at: new_task.create_time,
+            },
+        )?;
+    }
+
+    display::print_bold(&format!("Created task from {} {}:", provider.kind().name(), forge_ref.noun()));
     display::print_items(&[new_task], false, false);
     println!("  Linked: {}", issue.url);
 
     Ok(())
 }
 
+/// The project and category for a new task: as given, else those of the
+/// project the current directory belongs to
+fn project_and_category(
+    conn: &Connection,
+    ctx: &Context,
+    cmd: &TaskCommand,
+) -> Result<(Option<String>, String), String> {
+    let project = match cmd.project {
+        Some(ref project_name) => Some(active_project(conn, ctx, project_name)?),
+        None => ctx.current_project.clone(),
+    };
+    let category = cmd
+        .category
+        .clone()
+        .or_else(|| project.as_ref().and_then(|p| p.category.clone()))
+        .unwrap_or_else(|| "default".to_string());
+    Ok((project.map(|p| p.name), category))
+}
+
+/// Records the creation of an item in the activity feed
+pub(crate) fn log_created(conn: &Connection, ctx: &Context, item_id: i64, item: &Item) -> Result<(), String> {
+    let change = Change::new(TABLE_ITEMS, AUDIT_CREATE).with_new_value(&item.content);
+    log_change(conn, item_id, item.namespace_id, ctx.current_user_id, change)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -314,7 +343,8 @@ mod tests {
             timestr: None,
         };
         let (conn, _temp_file) = get_test_conn();
-        handle_recordcmd(&conn, &rc).unwrap();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        handle_recordcmd(&conn, &ctx, &rc).unwrap();
         let items = query_items(
             &conn,
             &ItemQuery::new()
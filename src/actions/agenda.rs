@@ -0,0 +1,514 @@
+use chrono::Local;
+use rusqlite::Connection;
+use serde_json::{json, Value};
+
+use crate::{
+    actions::{
+        display::{self, fill_column_data, print_table, timestamp_to_display_string, DisplayRow},
+        list::{mark_recurring_task_by_completion, CREATE_TIME_COL, TARGET_TIME_COL},
+        recurring::{schedule_state, ScheduleState},
+    },
+    args::{
+        columns::Column,
+        cron,
+        estimate::format_estimate,
+        parser::{StandupCommand, TodayCommand},
+        priority::format_priority,
+        timestr,
+    },
+    context::Context,
+    db::{
+        cache,
+        crud::query_items,
+        item::{Item, ItemQuery, RECORD, RECURRING_TASK, RECURRING_TASK_RECORD, TASK},
+        user::get_user_by_name,
+    },
+};
+
+/// Statuses that put a task on the agenda; suspended tasks are blockers instead
+const AGENDA_STATUS_CODES: &[u8] = &[0, 6];
+const SUSPENDED_STATUS: u8 = 4;
+
+/// Today's tasks, grouped by why they are on the agenda
+#[derive(Debug, Default)]
+pub struct Agenda {
+    overdue: Vec<Item>,
+    due_today: Vec<Item>,
+    reminders: Vec<Item>,
+    recurring: Vec<Item>,
+}
+
+impl Agenda {
+    fn sections(&self) -> [(&'static str, &[Item]); 4] {
+        [
+            ("Overdue", &self.overdue),
+            ("Due Today", &self.due_today),
+            ("Recurring", &self.recurring),
+            ("Coming Up", &self.reminders),
+        ]
+    }
+
+    fn is_empty(&self) -> bool {
+        self.sections().iter().all(|(_, items)| items.is_empty())
+    }
+}
+
+/// Handles the today command - overdue, due today, reminders and recurring tasks
+pub fn handle_today(conn: &Connection, ctx: &Context, cmd: &TodayCommand) -> Result<(), String> {
+    let assignee_id = resolve_user(conn, cmd.user.as_deref())?;
+    let agenda = build_agenda(conn, ctx.current_namespace_id, assignee_id)?;
+
+    if cmd.json {
+        print_today_json(&agenda);
+    } else if cmd.md {
+        print_today_markdown(&agenda);
+    } else {
+        let columns = display::resolve_columns(cmd.columns.as_deref(), "today")?;
+        print_today_text(conn, &agenda, &columns)?;
+    }
+    Ok(())
+}
+
+/// Handles the standup command - what was done, what is planned and what is blocked
+pub fn handle_standup(conn: &Connection, ctx: &Context, cmd: &StandupCommand) -> Result<(), String> {
+    let user_id = resolve_user(conn, cmd.user.as_deref())?;
+    let since = timestr::since_to_unix_epoch(cmd.since.as_deref().unwrap_or("yesterday"))?;
+    let until = timestr::since_to_unix_epoch("today")?;
+
+    let namespace_id = ctx.current_namespace_id;
+    let done = query_done(conn, namespace_id, user_id, since, until)?;
+    let agenda = build_agenda(conn, namespace_id, user_id)?;
+    let blockers = query_blockers(conn, namespace_id, user_id)?;
+
+    if cmd.json {
+        print_standup_json(&done, &agenda, &blockers);
+    } else if cmd.md {
+        print_standup_markdown(&done, &agenda, &blockers);
+    } else {
+        print_standup_text(&done, &agenda, &blockers);
+    }
+    Ok(())
+}
+
+fn resolve_user(conn: &Connection, username: Option<&str>) -> Result<Option<i64>, String> {
+    match username {
+        Some(name) => {
+            let user = get_user_by_name(conn, name)?
+                .ok_or_else(|| format!("User '{}' not found", name))?;
+            Ok(Some(user.id))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Today's agenda in a namespace, of one assignee or everyone
+pub fn build_agenda(conn: &Connection, namespace_id: i64, assignee_id: Option<i64>) -> Result<Agenda, String> {
+    let now = Local::now().timestamp();
+    let start_of_today = timestr::since_to_unix_epoch("today")?;
+    let end_of_today = timestr::since_to_unix_epoch("tomorrow")? - 1;
+
+    let mut query = ItemQuery::new()
+        .with_action(TASK)
+        .with_namespace_id(namespace_id)
+        .with_statuses(AGENDA_STATUS_CODES.to_vec())
+        .with_awake_at(now)
+        .with_order_by(TARGET_TIME_COL);
+    if let Some(aid) = assignee_id {
+        query = query.with_assignee_id(aid);
+    }
+    let tasks = query_items(conn, &query).map_err(|e| e.to_string())?;
+
+    let mut agenda = Agenda::default();
+    for task in tasks {
+        let Some(target_time) = task.target_time else {
+            continue;
+        };
+        if target_time < now {
+            agenda.overdue.push(task);
+        } else if target_time <= end_of_today {
+            agenda.due_today.push(task);
+        } else if task
+            .reminder_days
+            .is_some_and(|days| target_time - days * 86400 <= now)
+        {
+            agenda.reminders.push(task);
+        }
+    }
+
+    let mut query = ItemQuery::new()
+        .with_action(RECURRING_TASK)
+        .with_namespace_id(namespace_id);
+    if let Some(aid) = assignee_id {
+        query = query.with_assignee_id(aid);
+    }
+    let recurring = query_items(conn, &query).map_err(|e| e.to_string())?;
+    for task in mark_recurring_task_by_completion(conn, recurring)? {
+        if task.recurring_interval_complete || schedule_state(&task, now)? != ScheduleState::Active {
+            continue;
+        }
+        let cron_schedule = task.cron_schedule.as_deref().unwrap_or_default();
+        // Due later today, or already missed earlier today
+        let next_occurrence = cron::get_next_occurrence(cron_schedule)?;
+        let last_occurrence = cron::get_last_occurrence(cron_schedule)?;
+        if next_occurrence <= end_of_today || last_occurrence >= start_of_today {
+            agenda.recurring.push(task);
+        }
+    }
+
+    Ok(agenda)
+}
+
+fn query_done(
+    conn: &Connection,
+    namespace_id: i64,
+    owner_id: Option<i64>,
+    since: i64,
+    until: i64,
+) -> Result<Vec<Item>, String> {
+    let mut query = ItemQuery::new()
+        .with_actions(vec![RECORD, RECURRING_TASK_RECORD])
+        .with_namespace_id(namespace_id)
+        .with_create_time_range(Some(since), Some(until))
+        .with_order_by(CREATE_TIME_COL);
+    if let Some(oid) = owner_id {
+        query = query.with_owner_id(oid);
+    }
+    query_items(conn, &query).map_err(|e| e.to_string())
+}
+
+fn query_blockers(conn: &Connection, namespace_id: i64, assignee_id: Option<i64>) -> Result<Vec<Item>, String> {
+    // Snoozed tasks are also suspended, but they are deferred rather than blocked
+    let mut query = ItemQuery::new()
+        .with_action(TASK)
+        .with_namespace_id(namespace_id)
+        .with_statuses(vec![SUSPENDED_STATUS])
+        .with_awake_at(Local::now().timestamp())
+        .with_order_by(TARGET_TIME_COL);
+    if let Some(aid) = assignee_id {
+        query = query.with_assignee_id(aid);
+    }
+    query_items(conn, &query).map_err(|e| e.to_string())
+}
+
+fn print_today_text(conn: &Connection, agenda: &Agenda, columns: &[Column]) -> Result<(), String> {
+    if agenda.is_empty() {
+        display::print_bold("Nothing on the agenda today");
+        return Ok(());
+    }
+
+    // Cache the agenda in display order so `ctm done <index>` works on it
+    let all_items: Vec<Item> = agenda
+        .sections()
+        .iter()
+        .flat_map(|(_, items)| items.iter().cloned())
+        .collect();
+    cache::clear(conn).map_err(|e| e.to_string())?;
+    cache::store(conn, &all_items).map_err(|e| e.to_string())?;
+
+    let mut index = 0;
+    for (title, items) in agenda.sections() {
+        if items.is_empty() {
+            continue;
+        }
+        display::print_bold(&format!("{}:", title));
+        let mut rows: Vec<DisplayRow> = items
+            .iter()
+            .map(|item| {
+                index += 1;
+                DisplayRow::from_task(index.to_string(), item)
+            })
+            .collect();
+        fill_column_data(conn, &mut rows, items, columns)?;
+        print_table(&rows, columns, false);
+    }
+    Ok(())
+}
+
+fn print_today_json(agenda: &Agenda) {
+    println!("{}", serde_json::to_string_pretty(&today_json(agenda)).unwrap());
+}
+
+pub fn today_json(agenda: &Agenda) -> Value {
+    json!({
+        "overdue": items_json(&agenda.overdue),
+        "due_today": items_json(&agenda.due_today),
+        "recurring": items_json(&agenda.recurring),
+        "reminders": items_json(&agenda.reminders),
+    })
+}
+
+fn print_today_markdown(agenda: &Agenda) {
+    println!("# Today\n");
+    if agenda.is_empty() {
+        println!("Nothing on the agenda today.");
+        return;
+    }
+    for (title, items) in agenda.sections() {
+        if items.is_empty() {
+            continue;
+        }
+        println!("## {}\n", title);
+        for item in items {
+            println!("- [ ] {}", item_markdown(item));
+        }
+        println!();
+    }
+}
+
+fn print_standup_text(done: &[Item], agenda: &Agenda, blockers: &[Item]) {
+    display::print_bold("Yesterday:");
+    if done.is_empty() {
+        println!("  Nothing recorded");
+    } else {
+        display::print_items(done, true, false);
+    }
+
+    display::print_bold("Today:");
+    let plan = agenda_plan(agenda);
+    if plan.is_empty() {
+        println!("  Nothing planned");
+    } else {
+        display::print_items(&plan, false, false);
+    }
+
+    display::print_bold("Blockers:");
+    if blockers.is_empty() {
+        println!("  None");
+    } else {
+        display::print_items(blockers, false, false);
+    }
+}
+
+fn print_standup_json(done: &[Item], agenda: &Agenda, blockers: &[Item]) {
+    let output = json!({
+        "yesterday": records_json(done),
+        "today": items_json(&agenda_plan(agenda)),
+        "blockers": items_json(blockers),
+    });
+    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+}
+
+fn print_standup_markdown(done: &[Item], agenda: &Agenda, blockers: &[Item]) {
+    println!("# Standup\n");
+    println!("## Yesterday\n");
+    if done.is_empty() {
+        println!("- Nothing recorded");
+    }
+    for record in done {
+        println!("- {}", first_line(&record.content));
+    }
+
+    println!("\n## Today\n");
+    let plan = agenda_plan(agenda);
+    if plan.is_empty() {
+        println!("- Nothing planned");
+    }
+    for item in &plan {
+        println!("- {}", item_markdown(item));
+    }
+
+    println!("\n## Blockers\n");
+    if blockers.is_empty() {
+        println!("- None");
+    }
+    for item in blockers {
+        println!("- {}", item_markdown(item));
+    }
+}
+
+/// Today's plan for standup: everything on the agenda except upcoming reminders
+fn agenda_plan(agenda: &Agenda) -> Vec<Item> {
+    agenda
+        .overdue
+        .iter()
+        .chain(&agenda.due_today)
+        .chain(&agenda.recurring)
+        .cloned()
+        .collect()
+}
+
+fn item_markdown(item: &Item) -> String {
+    let mut line = first_line(&item.content).to_string();
+    let when = match (&item.human_schedule, item.target_time) {
+        (Some(schedule), _) if item.action == RECURRING_TASK => schedule.clone(),
+        (_, Some(target_time)) => timestamp_to_display_string(target_time, false),
+        _ => String::new(),
+    };
+    if !when.is_empty() {
+        line.push_str(&format!(" — {}", when));
+    }
+    if item.priority == Some(0) {
+        line.push_str(" **HIGH**");
+    }
+    line
+}
+
+pub fn items_json(items: &[Item]) -> Vec<Value> {
+    items
+        .iter()
+        .map(|item| {
+            json!({
+                "id": item.id,
+                "content": item.content,
+                "category": item.category,
+                "recurring": item.action == RECURRING_TASK,
+                "schedule": item.human_schedule,
+                "due": item.target_time,
+                "status": display::translate_status(item.status),
+                "priority": format_priority(item.priority),
+                "estimate": item.estimate_minutes.map(|m| format_estimate(Some(m))),
+                "assignee_id": item.assignee_id,
+                "project": item.project,
+            })
+        })
+        .collect()
+}
+
+pub fn records_json(records: &[Item]) -> Vec<Value> {
+    records
+        .iter()
+        .map(|record| {
+            json!({
+                "id": record.id,
+                "content": record.content,
+                "category": record.category,
+                "time": record.create_time,
+            })
+        })
+        .collect()
+}
+
+fn first_line(content: &str) -> &str {
+    content.lines().next().unwrap_or(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        actions::modify::complete_item,
+        db::{
+            crud::{get_item, update_item},
+            namespace::create_namespace,
+            user::create_user,
+        },
+        tests::{get_test_conn, insert_record, insert_recurring_task, insert_task, update_status},
+    };
+
+    // The test helpers insert items without a namespace
+    fn move_all_to(conn: &Connection, namespace_id: i64) {
+        conn.execute("UPDATE items SET namespace_id = ?1", [namespace_id]).unwrap();
+    }
+
+    #[test]
+    fn test_build_agenda() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "missed deadline", "yesterday");
+        insert_task(&conn, "work", "due tonight", "today");
+        insert_task(&conn, "work", "far away", "2099-01-01");
+        let reminder_id = insert_task(&conn, "work", "renew passport", "tomorrow");
+        let mut reminder = get_item(&conn, reminder_id).unwrap();
+        reminder.reminder_days = Some(3);
+        update_item(&conn, &reminder).unwrap();
+        let done_id = insert_task(&conn, "work", "already done", "today");
+        update_status(&conn, done_id, 1);
+        insert_recurring_task(&conn, "work", "daily review", "daily");
+        let ctx = Context::default_from_db(&conn).unwrap();
+        move_all_to(&conn, ctx.current_namespace_id);
+        // Tasks of another namespace stay off the agenda
+        let other = create_namespace(&conn, "other", None, ctx.current_user_id).unwrap();
+        let mut hidden = get_item(&conn, insert_task(&conn, "work", "other team's deadline", "yesterday")).unwrap();
+        hidden.namespace_id = Some(other);
+        update_item(&conn, &hidden).unwrap();
+
+        let agenda = build_agenda(&conn, ctx.current_namespace_id, None).unwrap();
+        let contents = |items: &[Item]| items.iter().map(|i| i.content.clone()).collect::<Vec<_>>();
+        assert_eq!(contents(&agenda.overdue), vec!["missed deadline"]);
+        assert_eq!(contents(&agenda.due_today), vec!["due tonight"]);
+        assert_eq!(contents(&agenda.reminders), vec!["renew passport"]);
+        assert_eq!(contents(&agenda.recurring), vec!["daily review"]);
+    }
+
+    #[test]
+    fn test_standup_sections() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_record(&conn, "work", "Completed Task: ship it", "yesterday 3PM");
+        insert_record(&conn, "work", "logged today", "today 1AM");
+        let blocked_id = insert_task(&conn, "work", "waiting on review", "tomorrow");
+        update_status(&conn, blocked_id, SUSPENDED_STATUS);
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let namespace_id = ctx.current_namespace_id;
+        move_all_to(&conn, namespace_id);
+
+        let since = timestr::since_to_unix_epoch("yesterday").unwrap();
+        let until = timestr::since_to_unix_epoch("today").unwrap();
+        let done = query_done(&conn, namespace_id, None, since, until).unwrap();
+        assert_eq!(done.len(), 1);
+        assert_eq!(done[0].content, "Completed Task: ship it");
+
+        let blockers = query_blockers(&conn, namespace_id, None).unwrap();
+        assert_eq!(blockers.len(), 1);
+        assert_eq!(blockers[0].content, "waiting on review");
+    }
+
+    #[test]
+    fn test_standup_lists_completers_completions() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let mut task = get_item(&conn, insert_task(&conn, "work", "ship it", "today")).unwrap();
+        task.owner_id = Some(ctx.current_user_id);
+        task.namespace_id = Some(ctx.current_namespace_id);
+        update_item(&conn, &task).unwrap();
+        let task = get_item(&conn, task.id.unwrap()).unwrap();
+        complete_item(&conn, &ctx, task, 1, None).unwrap();
+
+        let since = timestr::since_to_unix_epoch("today").unwrap();
+        let until = timestr::since_to_unix_epoch("tomorrow").unwrap();
+        let done = query_done(&conn, ctx.current_namespace_id, Some(ctx.current_user_id), since, until).unwrap();
+        assert_eq!(done.len(), 1);
+        assert_eq!(done[0].content, "Completed Task: ship it");
+    }
+
+    #[test]
+    fn test_standup_credits_completer_not_owner() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let bob = create_user(&conn, "bob", None, None).unwrap();
+        let bob_ctx = Context { current_user_id: bob, current_user_name: "bob".to_string(), ..ctx.clone() };
+        let mut task = get_item(&conn, insert_task(&conn, "work", "ship it", "today")).unwrap();
+        task.owner_id = Some(ctx.current_user_id);
+        task.namespace_id = Some(ctx.current_namespace_id);
+        update_item(&conn, &task).unwrap();
+        let task = get_item(&conn, task.id.unwrap()).unwrap();
+        complete_item(&conn, &bob_ctx, task, 1, None).unwrap();
+
+        let since = timestr::since_to_unix_epoch("today").unwrap();
+        let until = timestr::since_to_unix_epoch("tomorrow").unwrap();
+        let done = query_done(&conn, ctx.current_namespace_id, Some(bob), since, until).unwrap();
+        assert_eq!(done.len(), 1);
+        assert_eq!(done[0].content, "Completed Task: ship it");
+        let done = query_done(&conn, ctx.current_namespace_id, Some(ctx.current_user_id), since, until).unwrap();
+        assert!(done.is_empty());
+    }
+
+    #[test]
+    fn test_handle_today_and_standup() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        insert_task(&conn, "work", "due tonight", "today");
+        move_all_to(&conn, ctx.current_namespace_id);
+
+        for (json, md) in [(false, false), (true, false), (false, true)] {
+            let cmd = TodayCommand { user: None, columns: None, json, md };
+            handle_today(&conn, &ctx, &cmd).unwrap();
+            let cmd = StandupCommand { user: None, since: None, json, md };
+            handle_standup(&conn, &ctx, &cmd).unwrap();
+        }
+
+        // The text view caches the agenda for index-based commands
+        assert_eq!(cache::read(&conn, 1).unwrap(), Some(1));
+
+        let cmd = TodayCommand { user: Some("nobody".to_string()), columns: None, json: false, md: false };
+        assert!(handle_today(&conn, &ctx, &cmd).unwrap_err().contains("not found"));
+    }
+}
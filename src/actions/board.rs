@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+
+use chrono::{
+    Datelike,
+    Duration,
+    Local,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display::{
+            print_board,
+            Card,
+            DisplayRow,
+            Style,
+        },
+        list::{
+            OPEN_STATUS_CODES,
+            TARGET_TIME_COL,
+        },
+    },
+    args::{
+        columns::Column,
+        parser::BoardCommand,
+    },
+    context::Context,
+    db::{
+        cache,
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            TASK,
+        },
+        user::list_users,
+    },
+};
+
+/// What the columns of the board are
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardGrouping {
+    Status,
+    Assignee,
+    Priority,
+}
+
+const DONE_STATUS: u8 = 1;
+
+/// Shows open tasks as columns, grouped by status, assignee or priority
+pub fn handle_boardcmd(conn: &Connection, ctx: &Context, cmd: &BoardCommand) -> Result<(), String> {
+    let columns = build_board(conn, ctx.current_namespace_id, cmd.by)?;
+
+    if cmd.md {
+        print_board_markdown(&columns);
+        return Ok(());
+    }
+
+    // Cache the cards in board order so `ctm done <index>` works on them
+    let all_items: Vec<Item> = columns.iter().flat_map(|(_, items)| items.iter().cloned()).collect();
+    cache::clear(conn).map_err(|e| e.to_string())?;
+    cache::store(conn, &all_items).map_err(|e| e.to_string())?;
+
+    let mut index = 0;
+    let board: Vec<(String, Vec<Card>)> = columns
+        .iter()
+        .map(|(title, items)| {
+            let cards = items
+                .iter()
+                .map(|item| {
+                    index += 1;
+                    card(index, item)
+                })
+                .collect();
+            (format!("{} ({})", title, items.len()), cards)
+        })
+        .collect();
+    print_board(&board);
+    Ok(())
+}
+
+/// Splits the namespace's tasks into titled columns, each ordered by deadline.
+/// Snoozed tasks stay off the board until they wake.
+fn build_board(
+    conn: &Connection,
+    namespace_id: i64,
+    grouping: BoardGrouping,
+) -> Result<Vec<(String, Vec<Item>)>, String> {
+    let open = query_items(
+        conn,
+        &ItemQuery::new()
+            .with_action(TASK)
+            .with_namespace_id(namespace_id)
+            .with_statuses(OPEN_STATUS_CODES.to_vec())
+            .with_awake_at(Local::now().timestamp())
+            .with_order_by(TARGET_TIME_COL),
+    )
+    .map_err(|e| e.to_string())?;
+
+    let columns = match grouping {
+        BoardGrouping::Status => {
+            let done = query_items(
+                conn,
+                &ItemQuery::new()
+                    .with_action(TASK)
+                    .with_namespace_id(namespace_id)
+                    .with_statuses(vec![DONE_STATUS])
+                    .with_completed_at_min(start_of_week())
+                    .with_order_by(TARGET_TIME_COL),
+            )
+            .map_err(|e| e.to_string())?;
+            vec![
+                ("Ongoing".to_string(), with_status(&open, 0)),
+                ("Pending".to_string(), with_status(&open, 6)),
+                ("Suspended".to_string(), with_status(&open, 4)),
+                ("Done This Week".to_string(), done),
+            ]
+        }
+        BoardGrouping::Priority => vec![
+            ("High".to_string(), with_priority(&open, 0)),
+            ("Normal".to_string(), with_priority(&open, 1)),
+            ("Low".to_string(), with_priority(&open, 2)),
+        ],
+        BoardGrouping::Assignee => {
+            let names: HashMap<i64, String> = list_users(conn)?
+                .into_iter()
+                .map(|user| (user.id, user.name))
+                .collect();
+            let mut by_user: HashMap<Option<i64>, Vec<Item>> = HashMap::new();
+            for task in open {
+                by_user.entry(task.assignee_id).or_default().push(task);
+            }
+            let unassigned = by_user.remove(&None);
+            let mut columns: Vec<(String, Vec<Item>)> = by_user
+                .into_iter()
+                .map(|(id, items)| {
+                    let id = id.unwrap_or(0);
+                    let name = names.get(&id).cloned().unwrap_or_else(|| format!("user_{}", id));
+                    (name, items)
+                })
+                .collect();
+            columns.sort_by(|a, b| a.0.cmp(&b.0));
+            // Unassigned work goes last, as in `team`
+            if let Some(items) = unassigned {
+                columns.push(("Unassigned".to_string(), items));
+            }
+            columns
+        }
+    };
+    Ok(columns)
+}
+
+fn with_status(tasks: &[Item], status: u8) -> Vec<Item> {
+    tasks.iter().filter(|t| t.status == status).cloned().collect()
+}
+
+// Tasks without a priority count as normal
+fn with_priority(tasks: &[Item], priority: u8) -> Vec<Item> {
+    tasks
+        .iter()
+        .filter(|t| t.priority.unwrap_or(1) == priority)
+        .cloned()
+        .collect()
+}
+
+fn start_of_week() -> i64 {
+    let today = Local::now().date_naive();
+    let monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    Local
+        .from_local_datetime(&monday.and_hms_opt(0, 0, 0).unwrap())
+        .earliest()
+        .map_or(0, |t| t.timestamp())
+}
+
+fn card(index: usize, item: &Item) -> Card {
+    let row = DisplayRow::from_task(index.to_string(), item);
+    Card {
+        text: format!("{}. {}", index, first_line(&item.content)),
+        meta: row.cell(Column::Time).to_string(),
+        meta_style: Some(if row.is_overdue { Style::Overdue } else { Style::Label }),
+    }
+}
+
+// A Markdown table with one column per group, usable in PR descriptions and wikis
+fn print_board_markdown(columns: &[(String, Vec<Item>)]) {
+    let headers: Vec<String> = columns
+        .iter()
+        .map(|(title, items)| format!("{} ({})", title, items.len()))
+        .collect();
+    println!("| {} |", headers.join(" | "));
+    println!("|{}", "---|".repeat(columns.len()));
+
+    let height = columns.iter().map(|(_, items)| items.len()).max().unwrap_or(0);
+    for i in 0..height {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|(_, items)| match items.get(i) {
+                Some(item) => {
+                    let row = DisplayRow::from_task(String::new(), item);
+                    format!(
+                        "{}<br>_{}_",
+                        escape_markdown(first_line(&item.content)),
+                        row.cell(Column::Time)
+                    )
+                }
+                None => String::new(),
+            })
+            .collect();
+        println!("| {} |", cells.join(" | "));
+    }
+}
+
+fn escape_markdown(s: &str) -> String {
+    s.replace('|', "\\|")
+}
+
+fn first_line(content: &str) -> &str {
+    content.lines().next().unwrap_or(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{
+            crud::{
+                get_item,
+                update_item,
+            },
+            user::create_user,
+        },
+        tests::{
+            get_test_conn,
+            insert_task,
+            update_status,
+        },
+    };
+
+    fn titles(columns: &[(String, Vec<Item>)]) -> Vec<(&str, usize)> {
+        columns.iter().map(|(title, items)| (title.as_str(), items.len())).collect()
+    }
+
+    // The test helpers insert items without a namespace
+    fn move_all_to(conn: &Connection, namespace_id: i64) {
+        conn.execute("UPDATE items SET namespace_id = ?1", [namespace_id]).unwrap();
+    }
+
+    #[test]
+    fn test_board_by_status() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        insert_task(&conn, "work", "ongoing task", "today");
+        let pending = insert_task(&conn, "work", "pending task", "tomorrow");
+        update_status(&conn, pending, 6);
+        let suspended = insert_task(&conn, "work", "suspended task", "tomorrow");
+        update_status(&conn, suspended, 4);
+        let snoozed = insert_task(&conn, "work", "snoozed task", "tomorrow");
+        update_status(&conn, snoozed, 4);
+        conn.execute(
+            "UPDATE items SET snooze_until = ?1 WHERE id = ?2",
+            [Local::now().timestamp() + 86400, snoozed],
+        )
+        .unwrap();
+        let done = insert_task(&conn, "work", "done task", "today");
+        update_status(&conn, done, 1);
+        let old = insert_task(&conn, "work", "done long ago", "today");
+        update_status(&conn, old, 1);
+        // Completed before this week started, and edited since
+        conn.execute("UPDATE items SET completed_at = 0 WHERE id = ?1", [old]).unwrap();
+        let mut old_task = get_item(&conn, old).unwrap();
+        old_task.content.push_str(" (renamed)");
+        update_item(&conn, &old_task).unwrap();
+        move_all_to(&conn, ctx.current_namespace_id);
+        insert_task(&conn, "work", "someone else's task", "today");
+
+        let columns = build_board(&conn, ctx.current_namespace_id, BoardGrouping::Status).unwrap();
+        assert_eq!(
+            titles(&columns),
+            vec![("Ongoing", 1), ("Pending", 1), ("Suspended", 1), ("Done This Week", 1)]
+        );
+        assert_eq!(columns[2].1[0].content, "suspended task");
+        assert_eq!(columns[3].1[0].content, "done task");
+    }
+
+    #[test]
+    fn test_board_by_assignee_and_priority() {
+        let (conn, _temp_file) = get_test_conn();
+        let sarah = create_user(&conn, "sarah", None, None).unwrap();
+        let alex = create_user(&conn, "alex", None, None).unwrap();
+        for (content, assignee, priority) in [
+            ("a", Some(sarah), Some(0)),
+            ("b", Some(alex), None),
+            ("c", Some(sarah), Some(2)),
+            ("d", None, Some(0)),
+        ] {
+            let id = insert_task(&conn, "work", content, "tomorrow");
+            let mut task = get_item(&conn, id).unwrap();
+            task.assignee_id = assignee;
+            task.priority = priority;
+            update_item(&conn, &task).unwrap();
+        }
+        let ctx = Context::default_from_db(&conn).unwrap();
+        move_all_to(&conn, ctx.current_namespace_id);
+
+        let columns = build_board(&conn, ctx.current_namespace_id, BoardGrouping::Assignee).unwrap();
+        assert_eq!(titles(&columns), vec![("alex", 1), ("sarah", 2), ("Unassigned", 1)]);
+
+        let columns = build_board(&conn, ctx.current_namespace_id, BoardGrouping::Priority).unwrap();
+        assert_eq!(titles(&columns), vec![("High", 2), ("Normal", 1), ("Low", 1)]);
+    }
+
+    #[test]
+    fn test_handle_board_caches_cards() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let pending = insert_task(&conn, "work", "pending task", "tomorrow");
+        update_status(&conn, pending, 6);
+        let ongoing = insert_task(&conn, "work", "ongoing task", "tomorrow");
+        move_all_to(&conn, ctx.current_namespace_id);
+
+        let cmd = BoardCommand {
+            by: BoardGrouping::Status,
+            md: false,
+        };
+        handle_boardcmd(&conn, &ctx, &cmd).unwrap();
+        // Cards are numbered column by column
+        assert_eq!(cache::read(&conn, 1).unwrap(), Some(ongoing));
+        assert_eq!(cache::read(&conn, 2).unwrap(), Some(pending));
+    }
+}
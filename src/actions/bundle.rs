@@ -0,0 +1,545 @@
+use chrono::{Local, TimeZone};
+use rusqlite::Connection;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    actions::{
+        display,
+        git::task_id_from_branch,
+    },
+    args::{
+        estimate::format_estimate,
+        parser::ContextCommand,
+        priority::format_priority,
+    },
+    config::str_to_pathbuf,
+    context::project::find_project,
+    db::{
+        cache,
+        crud::{get_item, query_items},
+        item::{Item, ItemQuery, RECORD, RECURRING_TASK, RECURRING_TASK_RECORD},
+        link::{get_links_for_item, TaskLink, LINK_TYPE_COMMIT, LINK_TYPE_URL},
+        namespace::get_namespace_by_id,
+        note::get_notes_for_item,
+        project::Project,
+        user::get_user_by_id,
+    },
+    forge::{issue_provider, parse_forge_ref, ForgeScope},
+    utils::git::Git,
+};
+
+// Bodies are never cut shorter than this before whole entries are dropped
+const MIN_BODY_CHARS: usize = 300;
+// Records from this many days back count as related
+const RELATED_RECORD_DAYS: i64 = 14;
+const MAX_RELATED_RECORDS: usize = 10;
+const GIT_LOG_COUNT: &str = "10";
+const DEFAULT_PROMPT: &str = "Work on task {id}: {content}\n\n{context}";
+
+/// Everything known about a task, for an AI session's prompt
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    pub title: String,
+    pub fields: Vec<(&'static str, String)>,
+    pub content: String,
+    /// In order of importance; the last ones give way first
+    pub sections: Vec<Section>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Section {
+    /// JSON key
+    pub key: &'static str,
+    pub title: &'static str,
+    pub entries: Vec<Entry>,
+    /// Entries dropped to fit the budget
+    pub omitted: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub title: String,
+    pub fields: Vec<(&'static str, String)>,
+    pub body: String,
+}
+
+impl Entry {
+    fn new(title: String, body: String) -> Self {
+        Entry {
+            title,
+            fields: Vec::new(),
+            body,
+        }
+    }
+}
+
+/// Handles `ctm context <index>`
+pub fn handle_contextcmd(conn: &Connection, cmd: &ContextCommand) -> Result<(), String> {
+    let row_id = cache::rowid_for_index(conn, cmd.index)?;
+    let item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {:?}", e))?;
+    if item.action == RECORD || item.action == RECURRING_TASK_RECORD {
+        return Err("Context is only built for tasks".to_string());
+    }
+
+    let bundle = build_bundle(conn, &item, !cmd.offline)?;
+    let output = if cmd.json {
+        serde_json::to_string_pretty(&fit_json(&bundle, cmd.max_chars)).unwrap()
+    } else if cmd.prompt {
+        let template = task_project(conn, &item)?.and_then(|p| p.prompt_template);
+        render_prompt(template.as_deref(), &item, &bundle, cmd.max_chars)
+    } else {
+        fit_markdown(&bundle, cmd.max_chars)
+    };
+    println!("{}", output);
+    Ok(())
+}
+
+/// Gathers the task's fields, notes, links, git state and related records.
+/// With `fetch`, linked issues and pull requests are fetched for their bodies.
+pub fn build_bundle(conn: &Connection, item: &Item, fetch: bool) -> Result<Bundle, String> {
+    let id = item.id.ok_or_else(|| "Cannot build context for an item without id".to_string())?;
+    let project = task_project(conn, item)?;
+    let git = project
+        .as_ref()
+        .and_then(|p| p.path.clone())
+        .and_then(|path| str_to_pathbuf(path).ok())
+        .filter(|dir| dir.is_dir())
+        .map(Git::new);
+
+    let namespace = match item.namespace_id {
+        Some(ns_id) => get_namespace_by_id(conn, ns_id)?.map(|ns| ns.name),
+        None => None,
+    }
+    .unwrap_or_else(|| "default".to_string());
+    let scope = ForgeScope {
+        namespace: &namespace,
+        project: item.project.as_deref(),
+    };
+
+    let links = get_links_for_item(conn, id)?
+        .iter()
+        .map(|link| link_entry(link, scope, git.as_ref(), fetch))
+        .collect();
+    let sections = vec![
+        Section::new("notes", "Notes", note_entries(conn, id)?),
+        Section::new("links", "Links", links),
+        Section::new("git", "Git", git.map(|g| git_entries(&g, id)).unwrap_or_default()),
+        Section::new("records", "Related records", record_entries(conn, item)?),
+    ];
+    Ok(Bundle {
+        title: format!("Task ctm-{}: {}", id, first_line(&item.content)),
+        fields: task_fields(conn, item)?,
+        content: item.content.clone(),
+        sections,
+    })
+}
+
+// The task's project with this user's settings, if it has one
+fn task_project(conn: &Connection, item: &Item) -> Result<Option<Project>, String> {
+    match (&item.project, item.namespace_id) {
+        (Some(name), Some(ns_id)) => find_project(conn, ns_id, name),
+        _ => Ok(None),
+    }
+}
+
+fn task_fields(conn: &Connection, item: &Item) -> Result<Vec<(&'static str, String)>, String> {
+    let mut fields = vec![
+        ("status", display::translate_status(item.status)),
+        ("priority", format_priority(item.priority).to_string()),
+        ("category", item.category.clone()),
+    ];
+    if let Some(project) = &item.project {
+        fields.push(("project", project.clone()));
+    }
+    if item.action == RECURRING_TASK {
+        if let Some(schedule) = &item.human_schedule {
+            fields.push(("schedule", schedule.clone()));
+        }
+    } else if let Some(target_time) = item.target_time {
+        fields.push(("due", format_time(target_time)));
+    }
+    if item.estimate_minutes.is_some() {
+        fields.push(("estimate", format_estimate(item.estimate_minutes)));
+    }
+    for (key, user_id) in [("assignee", item.assignee_id), ("owner", item.owner_id)] {
+        if let Some(user) = user_id.map(|id| get_user_by_id(conn, id)).transpose()?.flatten() {
+            fields.push((key, user.name));
+        }
+    }
+    fields.push(("created", format_time(item.create_time)));
+    Ok(fields)
+}
+
+// Newest first, so the oldest give way to the budget
+fn note_entries(conn: &Connection, item_id: i64) -> Result<Vec<Entry>, String> {
+    let mut entries = Vec::new();
+    for note in get_notes_for_item(conn, item_id)?.into_iter().rev() {
+        let author = note
+            .created_by
+            .map(|id| get_user_by_id(conn, id))
+            .transpose()?
+            .flatten()
+            .map(|u| format!(" by {}", u.name))
+            .unwrap_or_default();
+        entries.push(Entry::new(format!("{}{}", format_time(note.created_at), author), note.content));
+    }
+    Ok(entries)
+}
+
+fn link_entry(link: &TaskLink, scope: ForgeScope, git: Option<&Git>, fetch: bool) -> Entry {
+    let mut entry = Entry::new(link.display(), String::new());
+    match link.link_type.as_str() {
+        LINK_TYPE_URL => {}
+        LINK_TYPE_COMMIT => {
+            if let Some(git) = git {
+                entry.body = git
+                    .run(&["show", "--stat", "--format=%s%n%n%b", &link.reference])
+                    .unwrap_or_else(|e| format!("(not found: {})", e));
+            }
+        }
+        _ if fetch => {
+            let fetched = parse_forge_ref(&link.reference)
+                .and_then(|forge_ref| issue_provider(&forge_ref, scope)?.fetch(&forge_ref));
+            match fetched {
+                Ok(issue) => {
+                    entry.fields.push(("state", issue.state));
+                    entry.fields.push(("url", issue.url));
+                    entry.body = issue.body.unwrap_or_default();
+                }
+                Err(e) => entry.body = format!("(could not fetch: {})", e),
+            }
+        }
+        _ => {}
+    }
+    entry
+}
+
+fn git_entries(git: &Git, task_id: i64) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    if let Some(branch) = git.current_branch() {
+        let mut title = format!("Branch {}", branch);
+        if task_id_from_branch(&branch) == Some(task_id) {
+            title.push_str(" (this task's branch)");
+        }
+        entries.push(Entry::new(title, String::new()));
+    }
+    if let Ok(log) = git.run(&["log", "--oneline", "-n", GIT_LOG_COUNT]) {
+        entries.push(Entry::new("Recent commits".to_string(), log));
+    }
+    if let Ok(stat) = git.run(&["diff", "--stat", "HEAD"]) {
+        if !stat.is_empty() {
+            entries.push(Entry::new("Uncommitted changes".to_string(), stat));
+        }
+    }
+    entries
+}
+
+// Records of a recurring task, or recent records in the task's category
+fn record_entries(conn: &Connection, item: &Item) -> Result<Vec<Entry>, String> {
+    let query = if item.action == RECURRING_TASK {
+        ItemQuery::new()
+            .with_action(RECURRING_TASK_RECORD)
+            .with_recurring_task_id(item.id.unwrap_or_default())
+    } else {
+        let since = Local::now().timestamp() - RELATED_RECORD_DAYS * 86400;
+        ItemQuery::new()
+            .with_action(RECORD)
+            .with_category(&item.category)
+            .with_create_time_min(since)
+    };
+    let mut records = query_items(conn, &query.with_order_by("create_time")).map_err(|e| e.to_string())?;
+    records.reverse();
+    records.truncate(MAX_RELATED_RECORDS);
+    Ok(records
+        .into_iter()
+        .map(|record| {
+            let (first, rest) = record.content.split_once('\n').unwrap_or((&record.content, ""));
+            Entry::new(format!("{} — {}", format_time(record.create_time), first), rest.trim().to_string())
+        })
+        .collect())
+}
+
+impl Section {
+    fn new(key: &'static str, title: &'static str, entries: Vec<Entry>) -> Self {
+        Section {
+            key,
+            title,
+            entries,
+            omitted: 0,
+        }
+    }
+}
+
+impl Bundle {
+    /// The bundle as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n\n", self.title);
+        for (key, value) in &self.fields {
+            out.push_str(&format!("- {}: {}\n", key, value));
+        }
+        out.push_str(&format!("\n## Description\n\n{}\n", self.content));
+        for section in &self.sections {
+            if section.entries.is_empty() && section.omitted == 0 {
+                continue;
+            }
+            out.push_str(&format!("\n## {}\n", section.title));
+            for entry in &section.entries {
+                out.push_str(&format!("\n### {}\n", entry.title));
+                for (key, value) in &entry.fields {
+                    out.push_str(&format!("- {}: {}\n", key, value));
+                }
+                if !entry.body.is_empty() {
+                    out.push_str(&format!("\n{}\n", entry.body));
+                }
+            }
+            if section.omitted > 0 {
+                out.push_str(&format!("\n({} more omitted)\n", section.omitted));
+            }
+        }
+        out
+    }
+
+    /// The bundle as a JSON object
+    pub fn to_json(&self) -> Value {
+        let mut task: Map<String, Value> = self
+            .fields
+            .iter()
+            .map(|(key, value)| (key.to_string(), json!(value)))
+            .collect();
+        task.insert("title".to_string(), json!(self.title));
+        task.insert("content".to_string(), json!(self.content));
+        let mut out = Map::new();
+        out.insert("task".to_string(), Value::Object(task));
+        for section in &self.sections {
+            let entries: Vec<Value> = section
+                .entries
+                .iter()
+                .map(|entry| {
+                    let mut object: Map<String, Value> = entry
+                        .fields
+                        .iter()
+                        .map(|(key, value)| (key.to_string(), json!(value)))
+                        .collect();
+                    object.insert("title".to_string(), json!(entry.title));
+                    object.insert("body".to_string(), json!(entry.body));
+                    Value::Object(object)
+                })
+                .collect();
+            out.insert(section.key.to_string(), json!(entries));
+            if section.omitted > 0 {
+                out.insert(format!("{}_omitted", section.key), json!(section.omitted));
+            }
+        }
+        Value::Object(out)
+    }
+
+    // A copy with every entry body cut to `max_chars`
+    fn with_body_cap(&self, max_chars: usize) -> Bundle {
+        let mut bundle = self.clone();
+        for entry in bundle.sections.iter_mut().flat_map(|s| s.entries.iter_mut()) {
+            entry.body = truncate(&entry.body, max_chars);
+        }
+        bundle
+    }
+
+    // Drops the last entry of the least important section that has one
+    fn drop_entry(&mut self) -> bool {
+        match self.sections.iter_mut().rev().find(|s| !s.entries.is_empty()) {
+            Some(section) => {
+                section.entries.pop();
+                section.omitted += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Shrinks the bundle until `render` fits in `budget` characters:
+    /// first bodies are cut down, then the least important entries go,
+    /// and last the description is cut
+    pub fn fit<T>(&self, budget: usize, render: impl Fn(&Bundle) -> T, size: impl Fn(&T) -> usize) -> T {
+        let longest = self
+            .sections
+            .iter()
+            .flat_map(|s| &s.entries)
+            .map(|e| e.body.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut cap = longest;
+        let mut bundle = self.clone();
+        loop {
+            let capped = bundle.with_body_cap(cap);
+            let output = render(&capped);
+            let over = size(&output).saturating_sub(budget);
+            if over == 0 {
+                return output;
+            }
+            if cap > MIN_BODY_CHARS {
+                cap = (cap * 2 / 3).max(MIN_BODY_CHARS);
+            } else if !bundle.drop_entry() {
+                let content_chars = capped.content.chars().count();
+                let mut last = capped;
+                last.content = truncate(&last.content, content_chars.saturating_sub(over + 30).max(MIN_BODY_CHARS));
+                return render(&last);
+            }
+        }
+    }
+}
+
+/// The bundle as Markdown in at most `budget` characters, where it can be
+pub fn fit_markdown(bundle: &Bundle, budget: usize) -> String {
+    bundle.fit(budget, Bundle::to_markdown, |s| s.chars().count())
+}
+
+/// The bundle as JSON whose pretty form fits `budget` characters, where it can be
+pub fn fit_json(bundle: &Bundle, budget: usize) -> Value {
+    bundle.fit(budget, Bundle::to_json, |v| {
+        serde_json::to_string_pretty(v).map(|s| s.chars().count()).unwrap_or(0)
+    })
+}
+
+/// Fills a prompt template: {id}, {content}, {project} and {context}, the
+/// bundle as Markdown in what is left of the budget
+pub fn render_prompt(template: Option<&str>, item: &Item, bundle: &Bundle, budget: usize) -> String {
+    let template = template.unwrap_or(DEFAULT_PROMPT);
+    let filled = template
+        .replace("{id}", &format!("ctm-{}", item.id.unwrap_or_default()))
+        .replace("{content}", first_line(&item.content))
+        .replace("{project}", item.project.as_deref().unwrap_or(""));
+    if !filled.contains("{context}") {
+        return filled;
+    }
+    let room = budget.saturating_sub(filled.chars().count() - "{context}".len());
+    filled.replace("{context}", &fit_markdown(bundle, room))
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    let count = text.chars().count();
+    if count <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars).collect();
+    format!("{}\n… [{} more characters]", kept.trim_end(), count - max_chars)
+}
+
+fn first_line(content: &str) -> &str {
+    content.lines().next().unwrap_or(content)
+}
+
+fn format_time(timestamp: i64) -> String {
+    match Local.timestamp_opt(timestamp, 0) {
+        chrono::LocalResult::Single(dt) => dt.format("%Y-%m-%d %H:%M").to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        context::Context,
+        db::{
+            crud::update_item,
+            link::add_link,
+            note::add_note,
+            project::create_project,
+        },
+        tests::{get_test_conn, insert_record, insert_task, GitRepo},
+    };
+
+    // A task in a project checked out in a git repository, on its own branch
+    fn task_in_repo(conn: &Connection, repo: &GitRepo) -> Item {
+        let ctx = Context::default_from_db(conn).unwrap();
+        let project = Project {
+            namespace_id: ctx.current_namespace_id,
+            name: "api".to_string(),
+            path: Some(repo.dir.path().to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        create_project(conn, &project).unwrap();
+        let id = insert_task(conn, "work", "Add rate limits\nPer token, not per IP", "tomorrow");
+        let mut item = get_item(conn, id).unwrap();
+        item.project = Some("api".to_string());
+        item.namespace_id = Some(ctx.current_namespace_id);
+        item.owner_id = Some(ctx.current_user_id);
+        update_item(conn, &item).unwrap();
+        repo.git.run(&["checkout", "--quiet", "-b", &format!("ctm-{}-rate-limits", id)]).unwrap();
+        get_item(conn, id).unwrap()
+    }
+
+    #[test]
+    fn test_build_bundle() {
+        let (conn, _temp_file) = get_test_conn();
+        let repo = GitRepo::init();
+        let item = task_in_repo(&conn, &repo);
+        let id = item.id.unwrap();
+        let sha = repo.commit("Add a token bucket");
+        add_note(&conn, id, "Started on the middleware", None).unwrap();
+        add_link(&conn, id, LINK_TYPE_COMMIT, &sha, None, None).unwrap();
+        add_link(&conn, id, LINK_TYPE_URL, "https://example.com/spec", Some("Spec"), None).unwrap();
+        add_link(&conn, id, "issue", "o/r#7", None, None).unwrap();
+        insert_record(&conn, "work", "Load tested the gateway\n2k rps", "today");
+        insert_record(&conn, "life", "Groceries", "today");
+
+        let bundle = build_bundle(&conn, &item, false).unwrap();
+        let markdown = bundle.to_markdown();
+        assert!(markdown.starts_with(&format!("# Task ctm-{}: Add rate limits\n", id)));
+        assert!(markdown.contains("- project: api"));
+        assert!(markdown.contains("Per token, not per IP"));
+        assert!(markdown.contains("## Notes"));
+        assert!(markdown.contains("Started on the middleware"));
+        assert!(markdown.contains("### [url] https://example.com/spec - Spec"));
+        assert!(markdown.contains("### [issue] o/r#7"));
+        assert!(markdown.contains("Add a token bucket"));
+        assert!(markdown.contains("(this task's branch)"));
+        assert!(markdown.contains("Load tested the gateway"));
+        assert!(!markdown.contains("Groceries"));
+
+        let json = bundle.to_json();
+        assert_eq!(json["task"]["project"], "api");
+        assert_eq!(json["links"].as_array().unwrap().len(), 3);
+        assert_eq!(json["records"][0]["body"], "2k rps");
+    }
+
+    #[test]
+    fn test_fit_to_budget() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "Write the migration guide", "tomorrow");
+        for n in 0..5 {
+            add_note(&conn, id, &format!("note {} {}", n, "x".repeat(2000)), None).unwrap();
+        }
+        let bundle = build_bundle(&conn, &get_item(&conn, id).unwrap(), false).unwrap();
+        assert!(bundle.to_markdown().chars().count() > 10000);
+
+        let markdown = fit_markdown(&bundle, 4000);
+        assert!(markdown.chars().count() <= 4000);
+        assert!(markdown.contains("more characters]"));
+        assert!(markdown.contains("note 4"));
+
+        // Too small for every note: the oldest go first
+        let markdown = fit_markdown(&bundle, 1200);
+        assert!(markdown.chars().count() <= 1200);
+        assert!(markdown.contains("note 4"));
+        assert!(!markdown.contains("note 0"));
+        assert!(markdown.contains("more omitted)"));
+
+        let json = fit_json(&bundle, 1200);
+        assert!(serde_json::to_string_pretty(&json).unwrap().chars().count() <= 1200);
+        assert!(json["notes_omitted"].as_u64().unwrap() > 0);
+    }
+
+    #[test]
+    fn test_render_prompt() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "Fix the flaky test", "tomorrow");
+        let item = get_item(&conn, id).unwrap();
+        let bundle = build_bundle(&conn, &item, false).unwrap();
+
+        let prompt = render_prompt(Some("Do {id}: {content}"), &item, &bundle, 1000);
+        assert_eq!(prompt, format!("Do ctm-{}: Fix the flaky test", id));
+
+        let prompt = render_prompt(None, &item, &bundle, 1000);
+        assert!(prompt.starts_with(&format!("Work on task ctm-{}: Fix the flaky test\n\n# Task", id)));
+        assert!(prompt.contains("- category: work"));
+    }
+}
@@ -0,0 +1,306 @@
+use std::collections::BTreeMap;
+
+use chrono::{
+    Datelike,
+    Duration,
+    Local,
+    NaiveDate,
+    TimeZone,
+};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display::{
+            print_board,
+            print_bold,
+            print_grid,
+            Card,
+            Style,
+        },
+        list::{
+            mark_recurring_task_by_completion,
+            OPEN_STATUS_CODES,
+            TARGET_TIME_COL,
+        },
+        recurring::bounded_occurrences,
+    },
+    args::{
+        cron,
+        parser::CalCommand,
+        timestr::parse_flexible_timestr,
+    },
+    context::Context,
+    db::{
+        cache,
+        crud::query_items,
+        item::{
+            Item,
+            ItemQuery,
+            RECURRING_TASK,
+            TASK,
+        },
+    },
+};
+
+/// How much time the calendar covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalendarSpan {
+    Week,
+    Month,
+}
+
+/// A task placed on the calendar; recurring tasks get one entry per occurrence
+struct Entry {
+    time: i64,
+    item: Item,
+    overdue: bool,
+}
+
+/// Shows open tasks on their due days, with recurring tasks expanded
+pub fn handle_calcmd(conn: &Connection, ctx: &Context, cmd: &CalCommand) -> Result<(), String> {
+    let day = match &cmd.date {
+        Some(date) => parse_flexible_timestr(date)?.date(),
+        None => Local::now().date_naive(),
+    };
+    let (first, last) = window(cmd.span, day);
+    let days = collect_entries(conn, ctx.current_namespace_id, first, last)?;
+
+    // Cache entries in display order so `ctm done <index>` works on them
+    let all_items: Vec<Item> = days.values().flatten().map(|e| e.item.clone()).collect();
+    cache::clear(conn).map_err(|e| e.to_string())?;
+    cache::store(conn, &all_items).map_err(|e| e.to_string())?;
+
+    let mut index = 0;
+    let mut cards_for = |date: NaiveDate| -> Vec<Card> {
+        days.get(&date)
+            .map(|entries| {
+                entries
+                    .iter()
+                    .map(|entry| {
+                        index += 1;
+                        card(index, entry)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    match cmd.span {
+        CalendarSpan::Week => {
+            print_bold(&format!("Week of {}", first.format("%Y/%m/%d")));
+            let columns: Vec<(String, Vec<Card>)> = dates(first, last)
+                .map(|date| (date.format("%a %m/%d").to_string(), cards_for(date)))
+                .collect();
+            print_board(&columns);
+        }
+        CalendarSpan::Month => {
+            print_bold(&day.format("%B %Y").to_string());
+            // One row of the grid per week, Monday to Sunday
+            let mut weeks = Vec::new();
+            let mut week_start = monday_of(first);
+            while week_start <= last {
+                let week: Vec<(String, Vec<Card>)> = (0..7)
+                    .map(|offset| {
+                        let date = week_start + Duration::days(offset);
+                        if date < first || date > last {
+                            (String::new(), Vec::new())
+                        } else {
+                            (date.format("%a %d").to_string(), cards_for(date))
+                        }
+                    })
+                    .collect();
+                weeks.push(week);
+                week_start += Duration::days(7);
+            }
+            print_grid(&weeks);
+        }
+    }
+    Ok(())
+}
+
+/// First and last day shown: Monday to Sunday, or the whole month
+fn window(span: CalendarSpan, day: NaiveDate) -> (NaiveDate, NaiveDate) {
+    match span {
+        CalendarSpan::Week => {
+            let monday = monday_of(day);
+            (monday, monday + Duration::days(6))
+        }
+        CalendarSpan::Month => {
+            let first = day.with_day(1).unwrap();
+            let next_month = if first.month() == 12 {
+                NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)
+            } else {
+                NaiveDate::from_ymd_opt(first.year(), first.month() + 1, 1)
+            };
+            (first, next_month.unwrap() - Duration::days(1))
+        }
+    }
+}
+
+fn monday_of(day: NaiveDate) -> NaiveDate {
+    day - Duration::days(day.weekday().num_days_from_monday() as i64)
+}
+
+fn dates(first: NaiveDate, last: NaiveDate) -> impl Iterator<Item = NaiveDate> {
+    first.iter_days().take_while(move |date| *date <= last)
+}
+
+fn start_of(date: NaiveDate) -> i64 {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+        .earliest()
+        .map_or(0, |t| t.timestamp())
+}
+
+fn date_of(timestamp: i64) -> NaiveDate {
+    Local.timestamp_opt(timestamp, 0).unwrap().date_naive()
+}
+
+/// The namespace's open tasks due between the two days (inclusive) and every
+/// occurrence of its recurring tasks in that range, grouped by day and ordered
+/// by time. Snoozed tasks stay off the calendar until they wake.
+fn collect_entries(
+    conn: &Connection,
+    namespace_id: i64,
+    first: NaiveDate,
+    last: NaiveDate,
+) -> Result<BTreeMap<NaiveDate, Vec<Entry>>, String> {
+    let now = Local::now().timestamp();
+    let start = start_of(first);
+    let end = start_of(last + Duration::days(1));
+    let mut entries = Vec::new();
+
+    let tasks = query_items(
+        conn,
+        &ItemQuery::new()
+            .with_action(TASK)
+            .with_namespace_id(namespace_id)
+            .with_statuses(OPEN_STATUS_CODES.to_vec())
+            .with_awake_at(now)
+            .with_target_time_range(Some(start - 1), Some(end - 1))
+            .with_order_by(TARGET_TIME_COL),
+    )
+    .map_err(|e| e.to_string())?;
+    for task in tasks {
+        let time = task.target_time.unwrap_or(start);
+        entries.push(Entry {
+            time,
+            overdue: time < now,
+            item: task,
+        });
+    }
+
+    let recurring = query_items(
+        conn,
+        &ItemQuery::new()
+            .with_action(RECURRING_TASK)
+            .with_namespace_id(namespace_id),
+    )
+    .map_err(|e| e.to_string())?;
+    for task in mark_recurring_task_by_completion(conn, recurring)? {
+        let cron_schedule = task.cron_schedule.clone().unwrap_or_default();
+        // Only the current interval can still be missed; earlier ones are history
+        let last_occurrence = cron::get_last_occurrence(&cron_schedule)?;
+        for time in bounded_occurrences(&task, start, end)? {
+            entries.push(Entry {
+                time,
+                overdue: time == last_occurrence && !task.recurring_interval_complete,
+                item: task.clone(),
+            });
+        }
+    }
+
+    entries.sort_by_key(|entry| entry.time);
+    let mut days: BTreeMap<NaiveDate, Vec<Entry>> = BTreeMap::new();
+    for entry in entries {
+        days.entry(date_of(entry.time)).or_default().push(entry);
+    }
+    Ok(days)
+}
+
+fn card(index: usize, entry: &Entry) -> Card {
+    let content = entry.item.content.lines().next().unwrap_or_default();
+    // Markers keep overdue and recurring entries apparent without colour
+    let mut meta = Local.timestamp_opt(entry.time, 0).unwrap().format("%H:%M").to_string();
+    if entry.item.action == RECURRING_TASK {
+        meta.push_str(" ↻");
+    }
+    if entry.overdue {
+        meta.insert_str(0, "! ");
+    }
+    Card {
+        text: format!("{}. {}", index, content),
+        meta,
+        meta_style: Some(if entry.overdue { Style::Overdue } else { Style::Label }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_recurring_task,
+        insert_task,
+        update_status,
+    };
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_window() {
+        // 2024-03-20 is a Wednesday
+        assert_eq!(window(CalendarSpan::Week, date("2024-03-20")), (date("2024-03-18"), date("2024-03-24")));
+        assert_eq!(window(CalendarSpan::Month, date("2024-02-10")), (date("2024-02-01"), date("2024-02-29")));
+        assert_eq!(window(CalendarSpan::Month, date("2024-12-31")), (date("2024-12-01"), date("2024-12-31")));
+    }
+
+    #[test]
+    fn test_collect_entries() {
+        let (conn, _temp_file) = get_test_conn();
+        let today = Local::now().date_naive();
+        let (first, last) = window(CalendarSpan::Week, today);
+
+        insert_task(&conn, "work", "due today", "today");
+        let done = insert_task(&conn, "work", "already done", "today");
+        update_status(&conn, done, 1);
+        let yesterday = (today - Duration::days(1)).format("%Y-%m-%d").to_string();
+        insert_task(&conn, "work", "missed", &yesterday);
+        insert_recurring_task(&conn, "work", "standup", "daily 9am");
+        let snoozed = insert_task(&conn, "work", "snoozed", "today");
+        update_status(&conn, snoozed, 4);
+        conn.execute(
+            "UPDATE items SET snooze_until = ?1 WHERE id = ?2",
+            [Local::now().timestamp() + 86400, snoozed],
+        )
+        .unwrap();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        // The test helpers insert items without a namespace
+        conn.execute("UPDATE items SET namespace_id = ?1", [ctx.current_namespace_id])
+            .unwrap();
+        insert_task(&conn, "work", "someone else's", "today");
+
+        let days = collect_entries(&conn, ctx.current_namespace_id, first, last).unwrap();
+        let today_entries: Vec<&str> = days[&today].iter().map(|e| e.item.content.as_str()).collect();
+        assert!(today_entries.contains(&"due today"));
+        assert!(today_entries.contains(&"standup"));
+        assert!(!today_entries.contains(&"already done"));
+        assert!(!today_entries.contains(&"snoozed"));
+        assert!(!today_entries.contains(&"someone else's"));
+
+        // The daily task shows on every day of the week
+        let standups = days.values().flatten().filter(|e| e.item.content == "standup").count();
+        assert_eq!(standups, 7);
+
+        if first < today {
+            let missed = days[&(today - Duration::days(1))]
+                .iter()
+                .find(|e| e.item.content == "missed")
+                .unwrap();
+            assert!(missed.overdue);
+        }
+    }
+}
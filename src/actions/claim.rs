@@ -4,9 +4,10 @@ use crate::{
     actions::display,
     context::Context,
     db::{
+        audit::log_item_changes,
         cache,
-        crud::{get_item, update_item},
-        item::{RECORD, RECURRING_TASK_RECORD},
+        crud::{describe_update_error, get_item, update_item},
+        item::{Item, RECORD, RECURRING_TASK_RECORD},
     },
 };
 
@@ -15,8 +16,17 @@ pub fn handle_claimcmd(conn: &Connection, ctx: &Context, index: usize) -> Result
     validate_cache(conn)?;
     let row_id = get_rowid_from_cache(conn, index)?;
 
-    let mut item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {:?}", e))?;
+    let item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {:?}", e))?;
+    let item = claim_item(conn, ctx, item)?;
 
+    display::print_bold(&format!("Claimed task (assigned to {}):", ctx.current_user_name));
+    display::print_items(&[item], false, false);
+
+    Ok(())
+}
+
+/// Assigns an unassigned task to the current user
+pub fn claim_item(conn: &Connection, ctx: &Context, mut item: Item) -> Result<Item, String> {
     if item.action == RECORD || item.action == RECURRING_TASK_RECORD {
         return Err("Cannot claim a record".to_string());
     }
@@ -26,19 +36,15 @@ pub fn handle_claimcmd(conn: &Connection, ctx: &Context, index: usize) -> Result
         if assignee_id == ctx.current_user_id {
             return Err("You are already assigned to this task".to_string());
         }
-        return Err(format!(
-            "Task is already assigned. Use update command to reassign."
-        ));
+        return Err("Task is already assigned. Use update command to reassign.".to_string());
     }
 
     // Claim the task
+    let before = item.clone();
     item.assignee_id = Some(ctx.current_user_id);
-    update_item(conn, &item).map_err(|e| format!("Failed to update item: {:?}", e))?;
-
-    display::print_bold(&format!("Claimed task (assigned to {}):", ctx.current_user_name));
-    display::print_items(&[item], false, false);
-
-    Ok(())
+    update_item(conn, &item).map_err(describe_update_error)?;
+    log_item_changes(conn, &before, &item, ctx.current_user_id)?;
+    Ok(item)
 }
 
 fn validate_cache(conn: &Connection) -> Result<(), String> {
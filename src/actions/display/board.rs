@@ -0,0 +1,122 @@
+use terminal_size::{
+    terminal_size,
+    Width,
+};
+
+use crate::actions::display::{
+    paint_opt,
+    table::{
+        pad_string,
+        wrap,
+    },
+    Style,
+};
+
+// Space left free on the right of the board, as for tables
+const MARGIN: usize = 10;
+const MIN_COLUMN_WIDTH: usize = 12;
+const MAX_COLUMN_WIDTH: usize = 40;
+
+/// One task on a board: its text and a styled line underneath (deadline, assignee, ...)
+#[derive(Clone)]
+pub struct Card {
+    pub text: String,
+    pub meta: String,
+    pub meta_style: Option<Style>,
+}
+
+/// Prints titled columns of cards side by side, wrapping cards to fit the terminal
+pub fn print_board(columns: &[(String, Vec<Card>)]) {
+    print_grid(&[columns.to_vec()]);
+}
+
+/// Prints several boards with the same number of columns as rows of one grid,
+/// e.g. the weeks of a month
+pub fn print_grid(rows: &[Vec<(String, Vec<Card>)>]) {
+    let terminal_width = if let Some((Width(w), _)) = terminal_size() {
+        w as usize
+    } else {
+        120 // Default if unable to detect
+    };
+    let count = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let width = board_column_width(count, terminal_width);
+    let separator_width = (width + 2) * count + 1;
+
+    println!("{:-<width$}", "", width = separator_width);
+    for columns in rows {
+        let headers: Vec<String> = columns.iter().map(|(title, _)| pad_string(title, width)).collect();
+        println!("| {}|", headers.join("| "));
+        println!("{:-<width$}", "", width = separator_width);
+
+        // Each column becomes a list of styled lines, with a blank line between cards
+        let lines: Vec<Vec<(String, Option<Style>)>> = columns
+            .iter()
+            .map(|(_, cards)| card_lines(cards, width))
+            .collect();
+        let height = lines.iter().map(Vec::len).max().unwrap_or(0);
+        for i in 0..height {
+            let cells: Vec<String> = lines
+                .iter()
+                .map(|column| match column.get(i) {
+                    Some((line, style)) => paint_opt(&pad_string(line, width), *style),
+                    None => pad_string("", width),
+                })
+                .collect();
+            println!("| {}|", cells.join("| "));
+        }
+        println!("{:-<width$}", "", width = separator_width);
+    }
+}
+
+fn card_lines(cards: &[Card], width: usize) -> Vec<(String, Option<Style>)> {
+    let mut lines = Vec::new();
+    for (i, card) in cards.iter().enumerate() {
+        if i > 0 {
+            lines.push((String::new(), None));
+        }
+        lines.extend(wrap(&card.text, width).into_iter().map(|line| (line, None)));
+        lines.extend(wrap(&card.meta, width).into_iter().map(|line| (line, card.meta_style)));
+    }
+    lines
+}
+
+// Columns share the terminal equally, within readable bounds
+fn board_column_width(count: usize, terminal_width: usize) -> usize {
+    if count == 0 {
+        return MIN_COLUMN_WIDTH;
+    }
+    // Each column is "| " plus its width, closed by a final "|"
+    let available = terminal_width.saturating_sub(2 * count + 1 + MARGIN);
+    (available / count).clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_board_layout() {
+        assert_eq!(board_column_width(4, 120), 25);
+        assert_eq!(board_column_width(3, 80), 21);
+        assert_eq!(board_column_width(1, 200), MAX_COLUMN_WIDTH);
+        // Narrow terminals keep columns readable and let the board overflow
+        assert_eq!(board_column_width(5, 40), MIN_COLUMN_WIDTH);
+
+        let cards = vec![
+            Card {
+                text: "1. fix the login page".to_string(),
+                meta: "Today".to_string(),
+                meta_style: Some(Style::Overdue),
+            },
+            Card {
+                text: "2. docs".to_string(),
+                meta: String::new(),
+                meta_style: None,
+            },
+        ];
+        let lines = card_lines(&cards, 12);
+        let text: Vec<&str> = lines.iter().map(|(line, _)| line.as_str()).collect();
+        assert_eq!(text, vec!["1. fix the", "login page", "Today", "", "2. docs"]);
+        assert_eq!(lines[2].1, Some(Style::Overdue));
+    }
+}
@@ -0,0 +1,40 @@
+const SPARK_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One block character per value, scaled to the largest value
+pub fn sparkline(values: &[i64]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+    values
+        .iter()
+        .map(|v| {
+            let level = (v.max(&0) * (SPARK_LEVELS.len() as i64 - 1) + max / 2) / max;
+            SPARK_LEVELS[level as usize]
+        })
+        .collect()
+}
+
+/// A horizontal bar of up to `width` blocks, scaled to `max`
+pub fn bar(value: i64, max: i64, width: usize) -> String {
+    if max <= 0 || value <= 0 {
+        return String::new();
+    }
+    let blocks = ((value * width as i64 + max - 1) / max).min(width as i64);
+    "█".repeat(blocks as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_charts() {
+        assert_eq!(sparkline(&[0, 1, 2, 4, 8]), "▁▂▃▅█");
+        assert_eq!(sparkline(&[0, 0]), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+
+        assert_eq!(bar(5, 10, 10), "█████");
+        // Any non-zero value shows at least one block
+        assert_eq!(bar(1, 100, 10), "█");
+        assert_eq!(bar(0, 10, 10), "");
+        assert_eq!(bar(20, 10, 10).chars().count(), 10);
+    }
+}
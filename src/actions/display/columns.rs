@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use rusqlite::Connection;
+
+use crate::{
+    actions::display::DisplayRow,
+    args::columns::{
+        parse_columns,
+        Column,
+        DEFAULT_COLUMNS,
+    },
+    config::get_columns,
+    db::{
+        item::Item,
+        link::count_links_for_item,
+        note::count_notes_for_item,
+        user::list_users,
+    },
+};
+
+/// Columns for a table: the `--columns` flag, then the config entry
+/// for the view (e.g. "list_task"), then the defaults.
+pub fn resolve_columns(flag: Option<&str>, view: &str) -> Result<Vec<Column>, String> {
+    match flag.map(str::to_string).or_else(|| get_columns(view)) {
+        Some(spec) => parse_columns(&spec),
+        None => Ok(DEFAULT_COLUMNS.to_vec()),
+    }
+}
+
+/// Fills in the columns that need more than the item itself,
+/// querying only for the columns that are shown.
+pub fn fill_column_data(
+    conn: &Connection,
+    rows: &mut [DisplayRow],
+    items: &[Item],
+    columns: &[Column],
+) -> Result<(), String> {
+    if columns.contains(&Column::Assignee) {
+        let names: HashMap<i64, String> = list_users(conn)?
+            .into_iter()
+            .map(|user| (user.id, user.name))
+            .collect();
+        for (row, item) in rows.iter_mut().zip(items) {
+            if let Some(name) = item.assignee_id.and_then(|id| names.get(&id)) {
+                row.assignee = name.clone();
+            }
+        }
+    }
+    if columns.contains(&Column::Links) || columns.contains(&Column::Notes) {
+        for (row, item) in rows.iter_mut().zip(items) {
+            let Some(id) = item.id else {
+                continue;
+            };
+            if columns.contains(&Column::Links) {
+                row.links = count_links_for_item(conn, id)?.to_string();
+            }
+            if columns.contains(&Column::Notes) {
+                row.notes = count_notes_for_item(conn, id)?.to_string();
+            }
+        }
+    }
+    Ok(())
+}
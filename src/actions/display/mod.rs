@@ -1,13 +1,45 @@
+mod board;
+mod chart;
+mod columns;
 mod print;
 mod row;
 mod table;
+mod theme;
 
 pub use crate::actions::display::{
+    board::{
+        print_board,
+        print_grid,
+        Card,
+    },
+    chart::{
+        bar,
+        sparkline,
+    },
+    columns::{
+        fill_column_data,
+        resolve_columns,
+    },
     print::{
         print_bold,
         print_items,
+        print_items_with_columns,
         print_red,
     },
-    row::DisplayRow,
+    row::{
+        timestamp_to_display_string,
+        translate_status,
+        DisplayRow,
+    },
     table::print_table,
+    theme::{
+        init_color,
+        link_style,
+        paint,
+        paint_opt,
+        priority_style,
+        status_style,
+        ColorMode,
+        Style,
+    },
 };
@@ -1,7 +1,16 @@
+use rusqlite::Connection;
+
 use crate::{
     actions::display::{
+        fill_column_data,
+        paint,
         print_table,
         DisplayRow,
+        Style,
+    },
+    args::columns::{
+        Column,
+        DEFAULT_COLUMNS,
     },
     db::item::Item,
 };
@@ -16,15 +25,34 @@ pub fn debug_print_items(header: &str, items: &[Item]) {
 }
 
 pub fn print_bold(text: &str) {
-    println!("\x1b[1m{}\x1b[0m", text);
+    println!("{}", paint(text, Style::Heading));
 }
 
 pub fn print_red(text: &str) {
-    println!("\x1b[91m{}\x1b[0m", text);
+    println!("{}", paint(text, Style::Error));
 }
 
 // print items in a table.
 pub fn print_items(items: &[Item], is_record: bool, is_list: bool) {
+    let results = to_rows(items, is_record, is_list);
+    print_table(&results, DEFAULT_COLUMNS, is_record);
+}
+
+// print items in a table with the given columns.
+pub fn print_items_with_columns(
+    conn: &Connection,
+    items: &[Item],
+    is_record: bool,
+    is_list: bool,
+    columns: &[Column],
+) -> Result<(), String> {
+    let mut results = to_rows(items, is_record, is_list);
+    fill_column_data(conn, &mut results, items, columns)?;
+    print_table(&results, columns, is_record);
+    Ok(())
+}
+
+fn to_rows(items: &[Item], is_record: bool, is_list: bool) -> Vec<DisplayRow> {
     let mut results: Vec<DisplayRow> = Vec::with_capacity(items.len());
     for (index, item) in items.iter().enumerate() {
         let indexstr = if is_list {
@@ -38,5 +66,5 @@ pub fn print_items(items: &[Item], is_record: bool, is_list: bool) {
             results.push(DisplayRow::from_task(indexstr, item))
         }
     }
-    print_table(&results, is_record);
+    results
 }
@@ -6,13 +6,37 @@ use chrono::{
     Weekday,
 };
 
-use crate::db::item::Item;
+use crate::{
+    actions::display::{
+        priority_style,
+        status_style,
+        Style,
+    },
+    args::{
+        columns::Column,
+        estimate::format_estimate,
+        priority::format_priority,
+    },
+    db::item::Item,
+};
 
 pub struct DisplayRow {
     pub index: String,
     pub category: String,
     pub content: String,
     pub timestr: String,
+    pub status: String,
+    pub priority: String,
+    pub estimate: String,
+    pub project: String,
+    // Filled in from the database by `ColumnData`, only when shown
+    pub assignee: String,
+    pub links: String,
+    pub notes: String,
+    // Raw values the theme styles cells by
+    pub priority_level: Option<u8>,
+    pub status_code: u8,
+    pub is_overdue: bool,
 }
 
 impl DisplayRow {
@@ -29,9 +53,14 @@ impl DisplayRow {
             timestamp_to_display_string(task.target_time.unwrap(), false)
         };
 
-        if task.status != 0 {
+        if let Some(wake_time) = task.snooze_until {
+            let wake_str = timestamp_to_display_string(wake_time, false);
+            timestr.push_str(&format!(" (snoozed until {})", wake_str));
+        } else if task.status != 0 {
             let status_str = translate_status(task.status);
             timestr.push_str(&format!(" ({})", status_str));
+        } else if task.paused_at.is_some() {
+            timestr.push_str(" (paused)");
         } else if task.recurring_interval_complete {
             timestr.push_str(" (fulfilled)");
         }
@@ -41,6 +70,7 @@ impl DisplayRow {
             category,
             content,
             timestr,
+            ..DisplayRow::details(task)
         }
     }
 
@@ -57,11 +87,60 @@ impl DisplayRow {
             category,
             content,
             timestr,
+            ..DisplayRow::details(record)
+        }
+    }
+
+    // Columns read straight off the item, shared by tasks and records
+    fn details(item: &Item) -> Self {
+        let or_dash = |s: Option<String>| s.unwrap_or_else(|| "-".to_string());
+        DisplayRow {
+            index: String::new(),
+            category: String::new(),
+            content: String::new(),
+            timestr: String::new(),
+            status: translate_status(item.status),
+            priority: format_priority(item.priority).to_string(),
+            estimate: format_estimate(item.estimate_minutes),
+            project: or_dash(item.project.clone()),
+            assignee: "-".to_string(),
+            links: "-".to_string(),
+            notes: "-".to_string(),
+            priority_level: item.priority,
+            status_code: item.status,
+            is_overdue: item.action == "task"
+                && matches!(item.status, 0 | 4 | 6)
+                && item.target_time.is_some_and(|t| t < Local::now().timestamp()),
+        }
+    }
+
+    pub fn cell_style(&self, column: Column) -> Option<Style> {
+        match column {
+            Column::Time if self.is_overdue => Some(Style::Overdue),
+            Column::Priority => priority_style(self.priority_level),
+            Column::Status => status_style(self.status_code),
+            _ => None,
+        }
+    }
+
+    pub fn cell(&self, column: Column) -> &str {
+        match column {
+            Column::Index => &self.index,
+            Column::Category => &self.category,
+            Column::Content => &self.content,
+            Column::Time => &self.timestr,
+            Column::Status => &self.status,
+            Column::Priority => &self.priority,
+            Column::Estimate => &self.estimate,
+            Column::Assignee => &self.assignee,
+            Column::Project => &self.project,
+            Column::Links => &self.links,
+            Column::Notes => &self.notes,
         }
     }
 }
 
-fn timestamp_to_display_string(timestamp: i64, is_record: bool) -> String {
+pub fn timestamp_to_display_string(timestamp: i64, is_record: bool) -> String {
     let dt = match Local.timestamp_opt(timestamp, 0) {
         chrono::LocalResult::Single(dt) => dt,
         _ => return "Invalid timestamp".to_string(),
@@ -159,7 +238,7 @@ fn format_hour(hour: u32, minute: u32) -> String {
     format!("{}:{:02}{}", hour12, minute, period)
 }
 
-fn translate_status(status: u8) -> String {
+pub fn translate_status(status: u8) -> String {
     match status {
         0 => "ongoing".to_string(),
         1 => "completed".to_string(),
@@ -1,5 +1,3 @@
-use std::cmp;
-
 use terminal_size::{
     terminal_size,
     Width,
@@ -9,86 +7,60 @@ use unicode_width::{
     UnicodeWidthStr,
 };
 
-use crate::actions::display::DisplayRow;
+use crate::{
+    actions::display::{
+        paint_opt,
+        DisplayRow,
+    },
+    args::columns::Column,
+};
+
+// Space left free on the right of the table
+const MARGIN: usize = 10;
 
-pub fn print_table(rows: &[DisplayRow], is_record: bool) {
+pub fn print_table(rows: &[DisplayRow], columns: &[Column], is_record: bool) {
     let terminal_width = if let Some((Width(w), _)) = terminal_size() {
         w as usize
     } else {
         120 // Default if unable to detect
     };
+    let widths = column_widths(columns, terminal_width);
 
-    // Define column widths
-    let index_width = 7;
-    let category_width = 20;
-    let timestr_width = 20;
-    let margin = 10;
-
-    // Calculate content width
-    // Total used: column widths + 5 delimiters (|) + margin
-    let content_width =
-        terminal_width.saturating_sub(index_width + category_width + timestr_width + 5 + margin);
-
-    let time_header = if is_record { "Created At" } else { "Deadline" };
-
-    let separator_width = terminal_width - margin + 4;
+    // Each column is "| " plus its width, closed by a final "|"
+    let separator_width = widths.iter().map(|w| w + 2).sum::<usize>() + 1;
 
     // Print table header
     println!("{:-<width$}", "", width = separator_width);
-    println!(
-        "| {:<index_width$}| {:<category_width$}| {:<content_width$}| {:<timestr_width$}|",
-        "Index",
-        "Category",
-        "Content",
-        time_header,
-        index_width = index_width,
-        category_width = category_width,
-        content_width = content_width,
-        timestr_width = timestr_width
-    );
+    let headers: Vec<String> = columns
+        .iter()
+        .zip(&widths)
+        .map(|(column, width)| pad_string(column.header(is_record), *width))
+        .collect();
+    println!("| {}|", headers.join("| "));
     println!("{:-<width$}", "", width = separator_width);
 
     for row in rows {
-        let wrapped_index = wrap(&row.index, index_width);
-        let wrapped_category = wrap(&row.category, category_width);
-        let wrapped_content = wrap(&row.content, content_width);
-        let wrapped_timestr = wrap(&row.timestr, timestr_width);
+        let wrapped: Vec<Vec<String>> = columns
+            .iter()
+            .zip(&widths)
+            .map(|(column, width)| wrap(row.cell(*column), *width))
+            .collect();
 
         // Find the maximum number of lines needed
-        let max_lines = cmp::max(
-            cmp::max(wrapped_index.len(), wrapped_category.len()),
-            cmp::max(wrapped_content.len(), wrapped_timestr.len()),
-        );
+        let max_lines = wrapped.iter().map(Vec::len).max().unwrap_or(0);
 
         for i in 0..max_lines {
-            let index_line = if i < wrapped_index.len() {
-                &wrapped_index[i]
-            } else {
-                ""
-            };
-            let category_line = if i < wrapped_category.len() {
-                &wrapped_category[i]
-            } else {
-                ""
-            };
-            let content_line = if i < wrapped_content.len() {
-                &wrapped_content[i]
-            } else {
-                ""
-            };
-            let timestr_line = if i < wrapped_timestr.len() {
-                &wrapped_timestr[i]
-            } else {
-                ""
-            };
-
-            println!(
-                "| {}| {}| {}| {}|",
-                pad_string(index_line, index_width),
-                pad_string(category_line, category_width),
-                pad_string(content_line, content_width),
-                pad_string(timestr_line, timestr_width)
-            );
+            // Style after padding so escape codes do not count towards the width
+            let cells: Vec<String> = columns
+                .iter()
+                .zip(&wrapped)
+                .zip(&widths)
+                .map(|((column, lines), width)| {
+                    let cell = pad_string(lines.get(i).map_or("", String::as_str), *width);
+                    paint_opt(&cell, row.cell_style(*column))
+                })
+                .collect();
+            println!("| {}|", cells.join("| "));
         }
 
         // Print separator between rows
@@ -96,7 +68,44 @@ pub fn print_table(rows: &[DisplayRow], is_record: bool) {
     }
 }
 
-fn pad_string(s: &str, width: usize) -> String {
+// Sizes columns to the terminal: fixed columns get their preferred width
+// and content gets the rest. When content would drop below its minimum,
+// the widest fixed columns give up space down to their own minimums.
+fn column_widths(columns: &[Column], terminal_width: usize) -> Vec<usize> {
+    let mut widths: Vec<usize> = columns.iter().map(|c| c.width().0).collect();
+    let Some(content_pos) = columns.iter().position(|c| *c == Column::Content) else {
+        return widths;
+    };
+    let min_content = Column::Content.width().1;
+    // Total used: column widths + delimiters (|) + margin
+    let overhead = columns.len() + 1 + MARGIN;
+    let available = terminal_width.saturating_sub(overhead);
+
+    loop {
+        let fixed: usize = widths.iter().sum::<usize>() - widths[content_pos];
+        let content = available.saturating_sub(fixed);
+        if content >= min_content {
+            widths[content_pos] = content;
+            return widths;
+        }
+        // Shrink the column with the most room above its minimum
+        let shrinkable = columns
+            .iter()
+            .enumerate()
+            .filter(|(i, c)| *i != content_pos && widths[*i] > c.width().1)
+            .max_by_key(|(i, c)| widths[*i] - c.width().1)
+            .map(|(i, _)| i);
+        match shrinkable {
+            Some(i) => widths[i] -= 1,
+            None => {
+                widths[content_pos] = min_content;
+                return widths;
+            }
+        }
+    }
+}
+
+pub(super) fn pad_string(s: &str, width: usize) -> String {
     let term_width = UnicodeWidthStr::width(s);
     if term_width >= width {
         s.to_string()
@@ -108,7 +117,7 @@ fn pad_string(s: &str, width: usize) -> String {
 }
 
 // Wraps text with consideration for unicode characters and word continuity.
-fn wrap(text: &str, max_length: usize) -> Vec<String> {
+pub(super) fn wrap(text: &str, max_length: usize) -> Vec<String> {
     if max_length == 0 || text.is_empty() {
         return vec![];
     }
@@ -190,6 +199,22 @@ fn wrap(text: &str, max_length: usize) -> Vec<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::args::columns::DEFAULT_COLUMNS;
+
+    #[test]
+    fn test_column_widths() {
+        // Default layout: content takes what the fixed columns leave
+        assert_eq!(column_widths(DEFAULT_COLUMNS, 120), vec![7, 20, 58, 20]);
+
+        // Narrow terminal: fixed columns shrink before content drops below its minimum
+        let columns = [Column::Index, Column::Priority, Column::Content, Column::Assignee, Column::Time];
+        let widths = column_widths(&columns, 60);
+        assert_eq!(widths[2], 10);
+        assert_eq!(widths.iter().sum::<usize>() + columns.len() + 1 + MARGIN, 60);
+
+        // Too narrow for anything: everything at its minimum
+        assert_eq!(column_widths(&columns, 20), vec![7, 8, 10, 6, 10]);
+    }
 
     #[test]
     fn test_wrap_function() {
@@ -0,0 +1,138 @@
+use std::{
+    io::IsTerminal,
+    sync::OnceLock,
+};
+
+/// When to colour output, set with the global `--color` flag
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+/// Semantic styles; the theme decides what each one looks like
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Heading,
+    Error,
+    Label,
+    Warning,
+    Overdue,
+    DueToday,
+    PriorityHigh,
+    PriorityLow,
+    StatusDone,
+    StatusInactive,
+    LinkCommit,
+    LinkIssue,
+    LinkPr,
+    LinkUrl,
+    Selected,
+}
+
+static COLOR_ENABLED: OnceLock<bool> = OnceLock::new();
+
+/// Decides once whether output is coloured. Without a call, `Auto` is used.
+pub fn init_color(mode: ColorMode) {
+    let _ = COLOR_ENABLED.set(detect_color(mode));
+}
+
+pub fn color_enabled() -> bool {
+    *COLOR_ENABLED.get_or_init(|| detect_color(ColorMode::Auto))
+}
+
+fn detect_color(mode: ColorMode) -> bool {
+    resolve_color(
+        mode,
+        std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()),
+        std::env::var("CLICOLOR_FORCE").is_ok_and(|v| !v.is_empty() && v != "0"),
+        std::io::stdout().is_terminal(),
+    )
+}
+
+// The flag wins; otherwise NO_COLOR, then CLICOLOR_FORCE, then whether stdout is a terminal
+fn resolve_color(mode: ColorMode, no_color: bool, force: bool, is_tty: bool) -> bool {
+    match mode {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => !no_color && (force || is_tty),
+    }
+}
+
+fn ansi_code(style: Style) -> &'static str {
+    match style {
+        Style::Heading => "1",
+        Style::Error | Style::Overdue | Style::PriorityHigh => "91",
+        Style::Label | Style::PriorityLow | Style::StatusInactive => "90",
+        Style::Warning | Style::LinkCommit => "33",
+        Style::DueToday => "93",
+        Style::StatusDone | Style::LinkIssue => "32",
+        Style::LinkPr => "35",
+        Style::LinkUrl => "36",
+        Style::Selected => "7",
+    }
+}
+
+/// Wraps text in the style's escape codes, or returns it as is when colour is off
+pub fn paint(text: &str, style: Style) -> String {
+    if color_enabled() {
+        format!("\x1b[{}m{}\x1b[0m", ansi_code(style), text)
+    } else {
+        text.to_string()
+    }
+}
+
+pub fn priority_style(priority: Option<u8>) -> Option<Style> {
+    match priority {
+        Some(0) => Some(Style::PriorityHigh),
+        Some(2) => Some(Style::PriorityLow),
+        _ => None,
+    }
+}
+
+pub fn status_style(status: u8) -> Option<Style> {
+    match status {
+        1 => Some(Style::StatusDone),
+        2..=5 => Some(Style::StatusInactive),
+        _ => None,
+    }
+}
+
+pub fn link_style(link_type: &str) -> Option<Style> {
+    match link_type {
+        "commit" => Some(Style::LinkCommit),
+        "issue" => Some(Style::LinkIssue),
+        "pr" => Some(Style::LinkPr),
+        "url" => Some(Style::LinkUrl),
+        _ => None,
+    }
+}
+
+/// Paints text with an optional style
+pub fn paint_opt(text: &str, style: Option<Style>) -> String {
+    match style {
+        Some(style) => paint(text, style),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_color() {
+        // The flag overrides the environment
+        assert!(resolve_color(ColorMode::Always, true, false, false));
+        assert!(!resolve_color(ColorMode::Never, false, true, true));
+
+        // Auto follows the terminal
+        assert!(resolve_color(ColorMode::Auto, false, false, true));
+        assert!(!resolve_color(ColorMode::Auto, false, false, false));
+
+        // CLICOLOR_FORCE colours piped output, NO_COLOR turns everything off
+        assert!(resolve_color(ColorMode::Auto, false, true, false));
+        assert!(!resolve_color(ColorMode::Auto, true, true, true));
+    }
+}
@@ -0,0 +1,315 @@
+use chrono::{Local, TimeZone};
+use rusqlite::Connection;
+use serde_json::json;
+
+use crate::{
+    actions::display::{print_bold, translate_status},
+    args::{
+        estimate::format_estimate,
+        parser::FeedCommand,
+        priority::format_priority,
+        timestr,
+    },
+    context::Context,
+    db::{
+        audit::{
+            get_feed,
+            AuditEntry,
+            AUDIT_COMPLETE,
+            AUDIT_CREATE,
+            AUDIT_DELETE,
+            TABLE_LINKS,
+            TABLE_NOTES,
+            TABLE_PROJECTS,
+        },
+        user::{get_user_by_id, get_user_by_name},
+    },
+};
+
+const DEFAULT_FEED_DAYS: usize = 7;
+const TITLE_WIDTH: usize = 40;
+
+/// Handles the feed command - shows who changed what in the current namespace
+pub fn handle_feedcmd(conn: &Connection, ctx: &Context, cmd: &FeedCommand) -> Result<(), String> {
+    let since = match &cmd.since {
+        Some(t) => timestr::since_to_unix_epoch(t)?,
+        None => timestr::days_before_to_unix_epoch(DEFAULT_FEED_DAYS),
+    };
+
+    let user_id = match &cmd.user {
+        Some(username) => {
+            let user = get_user_by_name(conn, username)?
+                .ok_or_else(|| format!("User '{}' not found", username))?;
+            Some(user.id)
+        }
+        None => None,
+    };
+
+    let entries = get_feed(conn, ctx.current_namespace_id, since, user_id, cmd.limit)?;
+
+    if cmd.json {
+        print_feed_json(conn, &entries);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        print_bold("No activity found");
+        return Ok(());
+    }
+
+    print_bold(&format!("Activity in '{}':", ctx.current_namespace_name));
+    for entry in &entries {
+        println!(
+            "{}  {:<12} {}",
+            format_timestamp(entry.created_at),
+            entry.user_name.as_deref().unwrap_or("-"),
+            describe_entry(conn, entry)
+        );
+    }
+    Ok(())
+}
+
+/// One-line description of an entry, e.g. `updated "Fix login": priority normal -> HIGH`
+fn describe_entry(conn: &Connection, entry: &AuditEntry) -> String {
+    if entry.table_name == TABLE_PROJECTS {
+        return describe_project_entry(entry);
+    }
+    let title = format!("\"{}\"", truncate(&entry_title(entry), TITLE_WIDTH));
+    match (entry.table_name.as_str(), entry.action.as_str()) {
+        (TABLE_NOTES, _) => format!(
+            "noted     {}: {}",
+            title,
+            entry.new_value.as_deref().unwrap_or("")
+        ),
+        (TABLE_LINKS, _) => format!(
+            "linked    {}: {}",
+            title,
+            entry.new_value.as_deref().unwrap_or("")
+        ),
+        (_, AUDIT_CREATE) => format!("created   {}", title),
+        (_, AUDIT_DELETE) => format!("deleted   {}", title),
+        (_, AUDIT_COMPLETE) => format!("completed {}", title),
+        _ => {
+            let field = entry.field_name.as_deref().unwrap_or("");
+            format!(
+                "updated   {}: {} {} -> {}",
+                title,
+                field_label(field),
+                format_value(conn, field, entry.old_value.as_deref()),
+                format_value(conn, field, entry.new_value.as_deref())
+            )
+        }
+    }
+}
+
+// Project entries carry the project's name instead of an item
+fn describe_project_entry(entry: &AuditEntry) -> String {
+    let old_name = entry.old_value.as_deref().unwrap_or("");
+    let name = entry.new_value.as_deref().unwrap_or("");
+    match entry.field_name.as_deref() {
+        Some("name") => format!("renamed   project \"{}\" -> \"{}\"", old_name, name),
+        Some(field) => format!("{:<9} project \"{}\"", field, name),
+        None => format!("updated   project \"{}\"", name),
+    }
+}
+
+/// The item's current content, or what it was when deleted
+fn entry_title(entry: &AuditEntry) -> String {
+    let content = entry
+        .item_content
+        .as_deref()
+        .or(entry.old_value.as_deref().filter(|_| entry.action == AUDIT_DELETE))
+        .unwrap_or("(deleted)");
+    content.lines().next().unwrap_or(content).to_string()
+}
+
+fn field_label(field: &str) -> &str {
+    match field {
+        "target_time" => "due",
+        "human_schedule" => "schedule",
+        "assignee_id" => "assignee",
+        "estimate_minutes" => "estimate",
+        "reminder_days" => "reminder",
+        "snooze_until" => "snoozed until",
+        "skipped_until" => "skipped until",
+        "starts_at" => "starts",
+        "ends_at" => "ends",
+        "max_occurrences" => "occurrence limit",
+        "paused_at" => "paused",
+        other => other,
+    }
+}
+
+/// Renders a raw audit value for humans, based on the field it belongs to
+fn format_value(conn: &Connection, field: &str, value: Option<&str>) -> String {
+    let Some(value) = value else {
+        return "-".to_string();
+    };
+    match field {
+        "status" => value
+            .parse()
+            .map(translate_status)
+            .unwrap_or_else(|_| value.to_string()),
+        "priority" => format_priority(value.parse().ok()).to_string(),
+        "estimate_minutes" => format_estimate(value.parse().ok()),
+        "target_time" | "snooze_until" | "skipped_until" | "starts_at" | "ends_at" | "paused_at" => value
+            .parse()
+            .map(format_timestamp)
+            .unwrap_or_else(|_| value.to_string()),
+        "assignee_id" => value
+            .parse()
+            .ok()
+            .and_then(|id| get_user_by_id(conn, id).ok().flatten())
+            .map(|user| user.name)
+            .unwrap_or_else(|| value.to_string()),
+        "content" => format!("\"{}\"", truncate(value, TITLE_WIDTH)),
+        _ => value.to_string(),
+    }
+}
+
+fn print_feed_json(conn: &Connection, entries: &[AuditEntry]) {
+    let output: Vec<_> = entries
+        .iter()
+        .map(|entry| {
+            json!({
+                "time": entry.created_at,
+                "user": entry.user_name,
+                "user_id": entry.created_by,
+                "item_id": entry.item_id,
+                "item": entry_title(entry),
+                "table": entry.table_name,
+                "action": entry.action,
+                "field": entry.field_name,
+                "old_value": entry.old_value,
+                "new_value": entry.new_value,
+                "description": describe_entry(conn, entry),
+            })
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    match Local.timestamp_opt(timestamp, 0) {
+        chrono::LocalResult::Single(dt) => dt.format("%Y-%m-%d %H:%M").to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn truncate(s: &str, max_chars: usize) -> String {
+    if s.chars().count() <= max_chars {
+        s.to_string()
+    } else {
+        let truncated: String = s.chars().take(max_chars - 1).collect();
+        format!("{}…", truncated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        actions::{claim::handle_claimcmd, modify::handle_donecmd, note::handle_notecmd},
+        args::parser::{DoneCommand, NoteCommand},
+        db::{
+            audit::AUDIT_UPDATE,
+            cache,
+            crud::{get_item, query_items},
+            item::{ItemQuery, TASK},
+            project::{create_project, rename_project, set_project_archived, Project},
+        },
+        tests::{get_test_conn, insert_task},
+    };
+
+    #[test]
+    fn test_feed_records_task_activity() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let task_id = insert_task(&conn, "work", "Fix login bug", "tomorrow");
+        conn.execute(
+            "UPDATE items SET namespace_id = ?1 WHERE id = ?2",
+            rusqlite::params![ctx.current_namespace_id, task_id],
+        )
+        .unwrap();
+        let items = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        cache::store(&conn, &items).unwrap();
+        let created = get_item(&conn, task_id).unwrap().modify_time;
+
+        let note_cmd = NoteCommand {
+            index: 1,
+            content: "Reproduced locally".to_string(),
+        };
+        handle_notecmd(&conn, &ctx, &note_cmd).unwrap();
+        handle_claimcmd(&conn, &ctx, 1).unwrap();
+        let done_cmd = DoneCommand {
+            index: 1,
+            status: 1,
+            comment: None,
+            close_issue: false,
+        };
+        handle_donecmd(&conn, &ctx, &done_cmd).unwrap();
+
+        // Every mutation moved modify_time forward
+        assert!(get_item(&conn, task_id).unwrap().modify_time > created);
+
+        let entries = get_feed(&conn, ctx.current_namespace_id, 0, None, 50).unwrap();
+        let actions: Vec<(&str, &str)> = entries
+            .iter()
+            .map(|e| (e.table_name.as_str(), e.action.as_str()))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                ("items", AUDIT_COMPLETE),
+                ("items", AUDIT_UPDATE),
+                (TABLE_NOTES, AUDIT_CREATE),
+            ]
+        );
+        assert_eq!(
+            describe_entry(&conn, &entries[1]),
+            format!("updated   \"Fix login bug\": assignee - -> {}", ctx.current_user_name)
+        );
+        assert_eq!(
+            describe_entry(&conn, &entries[2]),
+            "noted     \"Fix login bug\": Reproduced locally"
+        );
+    }
+
+    #[test]
+    fn test_feed_records_project_changes() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let project = Project {
+            namespace_id: ctx.current_namespace_id,
+            name: "api".to_string(),
+            ..Default::default()
+        };
+        create_project(&conn, &project).unwrap();
+        let task_id = insert_task(&conn, "work", "Add rate limits", "tomorrow");
+        conn.execute(
+            "UPDATE items SET namespace_id = ?1, project = 'api' WHERE id = ?2",
+            rusqlite::params![ctx.current_namespace_id, task_id],
+        )
+        .unwrap();
+
+        rename_project(&conn, ctx.current_namespace_id, "api", "gateway", ctx.current_user_id).unwrap();
+        set_project_archived(&conn, ctx.current_namespace_id, "gateway", true, ctx.current_user_id).unwrap();
+
+        let entries = get_feed(&conn, ctx.current_namespace_id, 0, None, 50).unwrap();
+        let descriptions: Vec<String> = entries.iter().map(|e| describe_entry(&conn, e)).collect();
+        assert_eq!(
+            descriptions,
+            vec![
+                "archived  project \"gateway\"",
+                "renamed   project \"api\" -> \"gateway\"",
+                "updated   \"Add rate limits\": project api -> gateway",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("a longer title", 8), "a longe…");
+    }
+}
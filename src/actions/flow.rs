@@ -0,0 +1,471 @@
+use std::collections::BTreeMap;
+
+use chrono::{
+    Duration,
+    Local,
+    TimeZone,
+};
+use rusqlite::Connection;
+use serde_json::{
+    json,
+    Value,
+};
+
+use crate::{
+    actions::display::{
+        print_bold,
+        translate_status,
+    },
+    args::{
+        parser::FlowCommand,
+        timestr::since_to_unix_epoch,
+    },
+    context::Context,
+    db::{
+        cache,
+        crud::get_item,
+        metrics::{
+            flow_tasks,
+            FlowTask,
+            MetricsScope,
+        },
+        user::get_user_by_name,
+    },
+};
+
+const DEFAULT_FLOW_DAYS: i64 = 30;
+const DONE_STATUS: u8 = 1;
+const ONGOING_STATUS: u8 = 0;
+
+/// How long a task took and where it spent its time
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct FlowTimes {
+    // From creation to completion, for done tasks
+    lead: Option<i64>,
+    // From when work started to completion, for done tasks
+    cycle: Option<i64>,
+    // Seconds spent in each status before completion, or until now
+    in_status: BTreeMap<u8, i64>,
+    // When the task entered its current status
+    status_since: i64,
+}
+
+/// Median and 90th percentile lead and cycle times of a group of tasks
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct FlowSummary {
+    done: usize,
+    open: usize,
+    lead_median: Option<i64>,
+    lead_p90: Option<i64>,
+    cycle_median: Option<i64>,
+    cycle_p90: Option<i64>,
+}
+
+/// Shows lead and cycle times, time spent per status and the oldest open tasks
+pub fn handle_flow(conn: &Connection, ctx: &Context, cmd: &FlowCommand) -> Result<(), String> {
+    let now = Local::now().timestamp();
+    let since = match &cmd.since {
+        Some(since) => since_to_unix_epoch(since)?,
+        None => now - Duration::days(DEFAULT_FLOW_DAYS).num_seconds(),
+    };
+    if since > now {
+        return Err("Flow report must start now or earlier".to_string());
+    }
+
+    let assignee_id = match &cmd.user {
+        Some(name) => Some(
+            get_user_by_name(conn, name)?
+                .ok_or_else(|| format!("User '{}' not found", name))?
+                .id,
+        ),
+        None => None,
+    };
+    let scope = MetricsScope {
+        namespace_id: ctx.current_namespace_id,
+        assignee_id,
+        category: cmd.category.as_deref(),
+    };
+    let tasks = flow_tasks(conn, &scope, since)?;
+    let times: Vec<FlowTimes> = tasks.iter().map(|t| flow_times(t, now)).collect();
+    let aging = oldest_open(&tasks, &times, cmd.limit);
+
+    if cmd.json {
+        print_flow_json(&tasks, &times, &aging, now);
+        return Ok(());
+    }
+
+    // Cache the aging tasks so `ctm show <index>` works on them
+    let aging_items = aging
+        .iter()
+        .map(|&i| get_item(conn, tasks[i].id))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    cache::clear(conn).map_err(|e| e.to_string())?;
+    cache::store(conn, &aging_items).map_err(|e| e.to_string())?;
+
+    print_flow_text(ctx, since, &tasks, &times, &aging, now);
+    Ok(())
+}
+
+/// Replays the status history of a task. The status before the first recorded
+/// change is taken as the one the task was created with. Work starts at the
+/// first move into ongoing, or at creation for tasks that were created ongoing.
+fn flow_times(task: &FlowTask, now: i64) -> FlowTimes {
+    let initial = task.transitions.first().map_or(task.status, |&(old, _, _)| old);
+    let mut in_status = BTreeMap::new();
+    let mut status = initial;
+    let mut since = task.create_time;
+    for &(_, new, at) in &task.transitions {
+        if status != DONE_STATUS {
+            *in_status.entry(status).or_insert(0) += (at - since).max(0);
+        }
+        status = new;
+        since = at;
+    }
+    if status != DONE_STATUS {
+        *in_status.entry(status).or_insert(0) += (now - since).max(0);
+    }
+
+    let started = if initial == ONGOING_STATUS {
+        Some(task.create_time)
+    } else {
+        task.transitions
+            .iter()
+            .find(|&&(_, new, _)| new == ONGOING_STATUS)
+            .map(|&(_, _, at)| at)
+    };
+    let completed = task.completed_at.filter(|_| task.status == DONE_STATUS);
+    FlowTimes {
+        lead: completed.map(|done| (done - task.create_time).max(0)),
+        // Tasks finished without ever being worked on count from creation
+        cycle: completed.map(|done| (done - started.unwrap_or(task.create_time)).max(0)),
+        in_status,
+        status_since: since,
+    }
+}
+
+fn summarize<'a>(tasks: impl Iterator<Item = (&'a FlowTask, &'a FlowTimes)>) -> FlowSummary {
+    let mut summary = FlowSummary::default();
+    let mut leads = Vec::new();
+    let mut cycles = Vec::new();
+    for (task, times) in tasks {
+        if task.status == DONE_STATUS {
+            summary.done += 1;
+        } else {
+            summary.open += 1;
+        }
+        leads.extend(times.lead);
+        cycles.extend(times.cycle);
+    }
+    leads.sort_unstable();
+    cycles.sort_unstable();
+    summary.lead_median = percentile(&leads, 50);
+    summary.lead_p90 = percentile(&leads, 90);
+    summary.cycle_median = percentile(&cycles, 50);
+    summary.cycle_p90 = percentile(&cycles, 90);
+    summary
+}
+
+// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[i64], p: usize) -> Option<i64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    Some(sorted[rank - 1])
+}
+
+/// Summaries per group, ordered by group name
+fn breakdown(
+    tasks: &[FlowTask],
+    times: &[FlowTimes],
+    key: impl Fn(&FlowTask) -> String,
+) -> Vec<(String, FlowSummary)> {
+    let mut groups: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, task) in tasks.iter().enumerate() {
+        groups.entry(key(task)).or_default().push(i);
+    }
+    groups
+        .into_iter()
+        .map(|(name, indexes)| {
+            let summary = summarize(indexes.iter().map(|&i| (&tasks[i], &times[i])));
+            (name, summary)
+        })
+        .collect()
+}
+
+fn priority_name(task: &FlowTask) -> String {
+    // Tasks without a priority count as normal, as on the board
+    match task.priority.unwrap_or(1) {
+        0 => "high".to_string(),
+        2 => "low".to_string(),
+        _ => "normal".to_string(),
+    }
+}
+
+fn assignee_name(task: &FlowTask) -> String {
+    task.assignee.clone().unwrap_or_else(|| "unassigned".to_string())
+}
+
+/// Indexes of the open tasks that were created longest ago
+fn oldest_open(tasks: &[FlowTask], times: &[FlowTimes], limit: usize) -> Vec<usize> {
+    let mut open: Vec<usize> = (0..tasks.len()).filter(|&i| tasks[i].status != DONE_STATUS).collect();
+    open.sort_by_key(|&i| (tasks[i].create_time, times[i].status_since));
+    open.truncate(limit);
+    open
+}
+
+/// Formats a number of seconds as days, hours and minutes
+fn format_duration(seconds: Option<i64>) -> String {
+    let Some(seconds) = seconds else {
+        return "-".to_string();
+    };
+    let minutes = seconds / 60;
+    let (days, hours, minutes) = (minutes / 1440, minutes % 1440 / 60, minutes % 60);
+    match (days, hours, minutes) {
+        (0, 0, m) => format!("{}m", m),
+        (0, h, 0) => format!("{}h", h),
+        (0, h, m) => format!("{}h{}m", h, m),
+        (d, 0, _) => format!("{}d", d),
+        (d, h, _) => format!("{}d{}h", d, h),
+    }
+}
+
+fn print_flow_text(
+    ctx: &Context,
+    since: i64,
+    tasks: &[FlowTask],
+    times: &[FlowTimes],
+    aging: &[usize],
+    now: i64,
+) {
+    let line = "━".repeat(72);
+    println!();
+    print_bold(&format!(
+        "Flow since {} ({})",
+        Local.timestamp_opt(since, 0).unwrap().format("%Y/%m/%d"),
+        ctx.current_namespace_name
+    ));
+    println!("{}", line);
+    let overall = summarize(tasks.iter().zip(times));
+    println!("Done {}, still open {}", overall.done, overall.open);
+    println!("{:<12} {:>10} {:>10}", "", "Median", "P90");
+    println!(
+        "{:<12} {:>10} {:>10}",
+        "Lead time",
+        format_duration(overall.lead_median),
+        format_duration(overall.lead_p90)
+    );
+    println!(
+        "{:<12} {:>10} {:>10}",
+        "Cycle time",
+        format_duration(overall.cycle_median),
+        format_duration(overall.cycle_p90)
+    );
+
+    println!();
+    print_bold("Time in Status");
+    println!("{}", line);
+    println!("{:<12} {:>8} {:>10} {:>10}", "Status", "Tasks", "Total", "Average");
+    let mut per_status: BTreeMap<u8, (usize, i64)> = BTreeMap::new();
+    for t in times {
+        for (&status, &seconds) in &t.in_status {
+            let entry = per_status.entry(status).or_default();
+            entry.0 += 1;
+            entry.1 += seconds;
+        }
+    }
+    for (status, (count, total)) in &per_status {
+        println!(
+            "{:<12} {:>8} {:>10} {:>10}",
+            translate_status(*status),
+            count,
+            format_duration(Some(*total)),
+            format_duration(Some(total / *count as i64))
+        );
+    }
+
+    for (title, key) in [
+        ("By Category", (|t: &FlowTask| t.category.clone()) as fn(&FlowTask) -> String),
+        ("By Priority", priority_name),
+        ("By Assignee", assignee_name),
+    ] {
+        println!();
+        print_bold(title);
+        println!("{}", line);
+        println!(
+            "{:<16} {:>6} {:>6} {:>10} {:>10} {:>10} {:>10}",
+            "", "Done", "Open", "Lead p50", "Lead p90", "Cycle p50", "Cycle p90"
+        );
+        for (name, s) in breakdown(tasks, times, key) {
+            println!(
+                "{:<16} {:>6} {:>6} {:>10} {:>10} {:>10} {:>10}",
+                name,
+                s.done,
+                s.open,
+                format_duration(s.lead_median),
+                format_duration(s.lead_p90),
+                format_duration(s.cycle_median),
+                format_duration(s.cycle_p90)
+            );
+        }
+    }
+
+    println!();
+    print_bold("Oldest Open Tasks");
+    println!("{}", line);
+    if aging.is_empty() {
+        println!("No open tasks");
+    }
+    for (index, &i) in aging.iter().enumerate() {
+        let task = &tasks[i];
+        println!(
+            "{:>3}. {:<40} {:>8} {:>10} in {}",
+            index + 1,
+            task.content.lines().next().unwrap_or_default(),
+            format_duration(Some(now - task.create_time)),
+            translate_status(task.status),
+            format_duration(Some(now - times[i].status_since))
+        );
+    }
+    println!();
+}
+
+fn summary_json(s: &FlowSummary) -> Value {
+    json!({
+        "done": s.done,
+        "open": s.open,
+        "lead_median_seconds": s.lead_median,
+        "lead_p90_seconds": s.lead_p90,
+        "cycle_median_seconds": s.cycle_median,
+        "cycle_p90_seconds": s.cycle_p90,
+    })
+}
+
+fn print_flow_json(tasks: &[FlowTask], times: &[FlowTimes], aging: &[usize], now: i64) {
+    let groups = |key: fn(&FlowTask) -> String| -> Value {
+        breakdown(tasks, times, key)
+            .iter()
+            .map(|(name, s)| (name.clone(), summary_json(s)))
+            .collect::<serde_json::Map<_, _>>()
+            .into()
+    };
+    let mut in_status: BTreeMap<String, i64> = BTreeMap::new();
+    for t in times {
+        for (&status, &seconds) in &t.in_status {
+            *in_status.entry(translate_status(status)).or_insert(0) += seconds;
+        }
+    }
+    let oldest: Vec<Value> = aging
+        .iter()
+        .map(|&i| {
+            json!({
+                "id": tasks[i].id,
+                "content": tasks[i].content,
+                "status": translate_status(tasks[i].status),
+                "age_seconds": now - tasks[i].create_time,
+                "in_status_seconds": now - times[i].status_since,
+            })
+        })
+        .collect();
+    let output = json!({
+        "summary": summary_json(&summarize(tasks.iter().zip(times))),
+        "time_in_status_seconds": in_status,
+        "by_category": groups(|t| t.category.clone()),
+        "by_priority": groups(priority_name),
+        "by_assignee": groups(assignee_name),
+        "oldest_open": oldest,
+    });
+    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_task,
+    };
+
+    fn task(status: u8, create_time: i64, completed_at: Option<i64>, transitions: Vec<(u8, u8, i64)>) -> FlowTask {
+        FlowTask {
+            id: 1,
+            content: "task".to_string(),
+            category: "work".to_string(),
+            priority: None,
+            assignee: None,
+            status,
+            create_time,
+            completed_at,
+            transitions,
+        }
+    }
+
+    #[test]
+    fn test_flow_times() {
+        // Pending for 100s, ongoing for 50s, suspended for 20s, ongoing for 30s
+        let done = task(1, 0, Some(200), vec![(6, 0, 100), (0, 4, 150), (4, 0, 170), (0, 1, 200)]);
+        let times = flow_times(&done, 1000);
+        assert_eq!((times.lead, times.cycle), (Some(200), Some(100)));
+        assert_eq!(times.in_status, BTreeMap::from([(0, 80), (4, 20), (6, 100)]));
+
+        // Created ongoing: work starts at creation
+        let done = task(1, 0, Some(60), vec![(0, 1, 60)]);
+        assert_eq!(flow_times(&done, 1000).cycle, Some(60));
+
+        // Open tasks have no lead time and accumulate time until now
+        let open = task(6, 0, None, vec![(0, 6, 40)]);
+        let times = flow_times(&open, 100);
+        assert_eq!(times.lead, None);
+        assert_eq!(times.in_status, BTreeMap::from([(0, 40), (6, 60)]));
+        assert_eq!(times.status_since, 40);
+    }
+
+    #[test]
+    fn test_percentile_and_format() {
+        let values = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        assert_eq!(percentile(&values, 50), Some(5));
+        assert_eq!(percentile(&values, 90), Some(9));
+        assert_eq!(percentile(&[7], 90), Some(7));
+        assert_eq!(percentile(&[], 50), None);
+
+        assert_eq!(format_duration(Some(59)), "0m");
+        assert_eq!(format_duration(Some(5400)), "1h30m");
+        assert_eq!(format_duration(Some(2 * 86400 + 3 * 3600)), "2d3h");
+        assert_eq!(format_duration(None), "-");
+    }
+
+    #[test]
+    fn test_handle_flow() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let old = insert_task(&conn, "work", "oldest open", "tomorrow");
+        let newer = insert_task(&conn, "home", "newer open", "tomorrow");
+        for (id, created) in [(old, 1000), (newer, 2000)] {
+            conn.execute(
+                "UPDATE items SET namespace_id = ?1, create_time = ?2 WHERE id = ?3",
+                rusqlite::params![ctx.current_namespace_id, created, id],
+            )
+            .unwrap();
+        }
+
+        let cmd = FlowCommand {
+            since: None,
+            user: None,
+            category: None,
+            limit: 5,
+            json: false,
+        };
+        handle_flow(&conn, &ctx, &cmd).unwrap();
+        // The oldest open task comes first
+        assert_eq!(cache::read(&conn, 1).unwrap(), Some(old));
+        assert_eq!(cache::read(&conn, 2).unwrap(), Some(newer));
+
+        let cmd = FlowCommand {
+            since: Some("tomorrow".to_string()),
+            json: true,
+            ..cmd
+        };
+        assert!(handle_flow(&conn, &ctx, &cmd).is_err());
+    }
+}
@@ -0,0 +1,778 @@
+use chrono::Local;
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        addition::log_created,
+        display::{
+            self,
+            translate_status,
+        },
+        list::OPEN_STATUS_CODES,
+        modify::complete_item,
+    },
+    args::{
+        parser::{
+            GhCommand,
+            GhImportCommand,
+            GhSyncCommand,
+        },
+        priority::parse_priority,
+        timestr,
+    },
+    config::{
+        get_forges,
+        get_github_config,
+        GitHubConfig,
+    },
+    context::Context,
+    db::{
+        audit::log_item_changes,
+        crud::{
+            describe_update_error,
+            get_item,
+            insert_item,
+            query_items,
+            update_item,
+        },
+        item::{
+            Item,
+            ItemQuery,
+            TASK,
+        },
+        link::{
+            add_link,
+            get_links_by_type,
+            issue_tracked,
+            LINK_TYPE_ISSUE,
+            LINK_TYPE_PR,
+        },
+        namespace::get_namespace_by_id,
+        sync::{
+            get_sync_state,
+            save_sync_state,
+            SyncState,
+        },
+        user::get_user_by_name,
+    },
+    forge::{
+        parse_forge_ref,
+        resolve_kind,
+        ForgeKind,
+        ForgeScope,
+    },
+    github::{
+        forge_client,
+        parse_issue_ref,
+        ForgeClient,
+        GitHubIssue,
+        IssueFilter,
+        IssueRef,
+    },
+};
+
+/// Which side wins when a task and its issue disagree and there is no
+/// record of which one moved since the last sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    // The side that was modified last
+    Newest,
+    Local,
+    Remote,
+    Skip,
+}
+
+/// One change sync makes to a task or to its issue
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncChange {
+    PullStatus { from: u8, to: u8 },
+    PullTitle { from: String, to: String },
+    PullAssignee { login: String, user_id: i64 },
+    CloseRemote,
+    ReopenRemote,
+    // Left alone under the skip policy
+    Conflict { field: &'static str, local: String, remote: String },
+}
+
+/// What sync would do for one task
+#[derive(Debug, Clone)]
+pub struct SyncPlan {
+    pub item: Item,
+    pub reference: IssueRef,
+    pub is_pull_request: bool,
+    pub remote: GitHubIssue,
+    pub changes: Vec<SyncChange>,
+}
+
+/// Which way one field flows
+enum Side {
+    Local,
+    Remote,
+    Neither,
+}
+
+pub fn handle_gh_cmd(conn: &Connection, ctx: &Context, cmd: GhCommand) -> Result<(), String> {
+    let config = get_github_config();
+    let client = forge_client(&config)?;
+    client.check_auth()?;
+    match cmd {
+        GhCommand::Sync(cmd) => handle_synccmd(conn, ctx, client.as_ref(), &config, &cmd),
+        GhCommand::Import(cmd) => handle_importcmd(conn, ctx, client.as_ref(), &config, &cmd),
+    }
+}
+
+/// Handles `gh sync` - reconciles every task linked to an issue or pull request
+pub fn handle_synccmd(
+    conn: &Connection,
+    ctx: &Context,
+    client: &dyn ForgeClient,
+    config: &GitHubConfig,
+    cmd: &GhSyncCommand,
+) -> Result<(), String> {
+    let plans = plan_sync(conn, client, config, cmd.on_conflict)?;
+    let mut changed = 0;
+    for plan in plans.iter().filter(|p| !p.changes.is_empty()) {
+        print_plan(plan);
+        if !cmd.dry_run {
+            apply_plan(conn, ctx, client, plan)?;
+        }
+        changed += 1;
+    }
+    if !cmd.dry_run {
+        // Tasks that were already in agreement still need a baseline
+        for plan in plans.iter().filter(|p| p.changes.is_empty()) {
+            save_baseline(conn, plan)?;
+        }
+    }
+
+    let summary = format!(
+        "{} of {} linked tasks {}",
+        changed,
+        plans.len(),
+        if cmd.dry_run { "would change (dry run)" } else { "changed" }
+    );
+    display::print_bold(&summary);
+    Ok(())
+}
+
+/// Fetches the issue or pull request of every linked task and works out
+/// what to change. Nothing is written.
+pub fn plan_sync(
+    conn: &Connection,
+    client: &dyn ForgeClient,
+    config: &GitHubConfig,
+    policy: ConflictPolicy,
+) -> Result<Vec<SyncPlan>, String> {
+    let mut plans = Vec::new();
+    for (item, reference, is_pull_request) in linked_tasks(conn)? {
+        let fetched = if is_pull_request {
+            client.get_pull_request(&reference)
+        } else {
+            client.get_issue(&reference)
+        };
+        let remote = match fetched {
+            Ok(remote) => remote,
+            Err(err) => {
+                display::print_red(&format!("Skipping {}: {}", reference, err));
+                continue;
+            }
+        };
+        let id = item.id.unwrap();
+        let baseline = get_sync_state(conn, id, &reference.to_string())?;
+        let changes = plan_changes(conn, config, &item, is_pull_request, &remote, baseline.as_ref(), policy)?;
+        plans.push(SyncPlan {
+            item,
+            reference,
+            is_pull_request,
+            remote,
+            changes,
+        });
+    }
+    Ok(plans)
+}
+
+/// Tasks with the issue or pull request they sync with:
+/// the task's own issue, else its first linked issue, else its first linked PR
+fn linked_tasks(conn: &Connection) -> Result<Vec<(Item, IssueRef, bool)>, String> {
+    let tasks = query_items(conn, &ItemQuery::new().with_action(TASK))
+    .map_err(|e| format!("Failed to query tasks: {:?}", e))?;
+
+    let forges = get_forges();
+    let mut linked = Vec::new();
+    for item in tasks {
+        let id = item.id.unwrap();
+        let issue = match item.github_issue.clone() {
+            Some(issue) => Some(issue),
+            None => get_links_by_type(conn, id, LINK_TYPE_ISSUE)?
+                .into_iter()
+                .next()
+                .map(|l| l.reference),
+        };
+        let target = match issue {
+            Some(issue) => Some((issue, false)),
+            None => get_links_by_type(conn, id, LINK_TYPE_PR)?
+                .into_iter()
+                .next()
+                .map(|l| (l.reference, true)),
+        };
+        let Some((reference, is_pull_request)) = target else {
+            continue;
+        };
+        // GitLab and Gitea share the "owner/repo#N" form; the task's forge decides
+        let Ok(forge_ref) = parse_forge_ref(&reference) else {
+            continue;
+        };
+        let namespace = match item.namespace_id {
+            Some(id) => get_namespace_by_id(conn, id)?.map(|ns| ns.name),
+            None => None,
+        };
+        let scope = ForgeScope {
+            namespace: namespace.as_deref().unwrap_or_default(),
+            project: item.project.as_deref(),
+        };
+        if resolve_kind(&forges, &forge_ref, scope)? != ForgeKind::GitHub {
+            continue;
+        }
+        if let Ok(reference) = forge_ref.to_issue_ref() {
+            linked.push((item, reference, is_pull_request));
+        }
+    }
+    Ok(linked)
+}
+
+/// Three-way comparison of the task, the remote and the state at the last sync.
+/// A field changed on one side only flows to the other; without a baseline,
+/// or when both sides changed, the policy decides.
+pub fn plan_changes(
+    conn: &Connection,
+    config: &GitHubConfig,
+    item: &Item,
+    is_pull_request: bool,
+    remote: &GitHubIssue,
+    baseline: Option<&SyncState>,
+    policy: ConflictPolicy,
+) -> Result<Vec<SyncChange>, String> {
+    let mut changes = Vec::new();
+    let local_time = item.modify_time.unwrap_or(item.create_time);
+    let remote_time = remote.updated_at.unwrap_or(0);
+    let resolve = |field: &'static str, local: String, remote: String, changes: &mut Vec<SyncChange>| {
+        let side = match policy {
+            ConflictPolicy::Newest if remote_time > local_time => Side::Remote,
+            ConflictPolicy::Newest | ConflictPolicy::Local => Side::Local,
+            ConflictPolicy::Remote => Side::Remote,
+            ConflictPolicy::Skip => Side::Neither,
+        };
+        if let Side::Neither = side {
+            changes.push(SyncChange::Conflict { field, local, remote });
+        }
+        side
+    };
+
+    // Status: open or closed on either side
+    let local_open = OPEN_STATUS_CODES.contains(&item.status);
+    let remote_open = remote.is_open();
+    if local_open != remote_open {
+        let side = match baseline {
+            // Only one side can differ from the baseline, and that side moved
+            Some(base) if base.is_open() == local_open => Side::Remote,
+            Some(_) => Side::Local,
+            None => resolve(
+                "status",
+                translate_status(item.status),
+                remote.state.to_lowercase(),
+                &mut changes,
+            ),
+        };
+        match side {
+            Side::Remote => changes.push(SyncChange::PullStatus {
+                from: item.status,
+                to: if remote_open { 0 } else { 1 },
+            }),
+            // Pull request state is decided on GitHub, so it only flows in
+            Side::Local if !is_pull_request => changes.push(if local_open {
+                SyncChange::ReopenRemote
+            } else {
+                SyncChange::CloseRemote
+            }),
+            _ => {}
+        }
+    }
+
+    // Title: the first line of the task; titles are never pushed
+    let local_title = item.content.lines().next().unwrap_or("").to_string();
+    if local_title != remote.title {
+        let side = match baseline {
+            Some(base) if base.title == local_title => Side::Remote,
+            Some(base) if base.title == remote.title => Side::Local,
+            _ => resolve("title", local_title.clone(), remote.title.clone(), &mut changes),
+        };
+        if let Side::Remote = side {
+            changes.push(SyncChange::PullTitle {
+                from: local_title,
+                to: remote.title.clone(),
+            });
+        }
+    }
+
+    // Assignee: follows the first remote assignee known as a user here,
+    // and only when the remote assignees changed since the last sync
+    let mut remote_assignees = remote.assignees.clone();
+    remote_assignees.sort();
+    if baseline.is_none_or(|base| base.assignees != remote_assignees) {
+        if let Some((login, user_id)) = assignee_for(conn, config, &remote.assignees)? {
+            if item.assignee_id != Some(user_id) {
+                changes.push(SyncChange::PullAssignee { login, user_id });
+            }
+        }
+    }
+    Ok(changes)
+}
+
+/// Handles `gh import` - creates a task for every matching open issue not yet tracked
+pub fn handle_importcmd(
+    conn: &Connection,
+    ctx: &Context,
+    client: &dyn ForgeClient,
+    config: &GitHubConfig,
+    cmd: &GhImportCommand,
+) -> Result<(), String> {
+    let filter = IssueFilter {
+        labels: cmd.label.clone(),
+        assignee: cmd.assignee.clone(),
+        milestone: cmd.milestone.clone(),
+        limit: cmd.limit,
+    };
+    let issues = client.list_issues(&cmd.repo, &filter)?;
+    let target_time = timestr::to_unix_epoch(cmd.due.as_deref().unwrap_or("today"))?;
+
+    let mut imported = Vec::new();
+    let mut tracked = 0;
+    for issue in &issues {
+        let reference = parse_issue_ref(&format!("{}#{}", cmd.repo, issue.number))?.to_string();
+        if issue_tracked(conn, &reference)? {
+            tracked += 1;
+            continue;
+        }
+        imported.push(import_issue(conn, ctx, config, issue, &reference, target_time)?);
+    }
+
+    display::print_bold(&format!(
+        "Imported {} of {} issues from {} ({} already tracked):",
+        imported.len(),
+        issues.len(),
+        cmd.repo,
+        tracked
+    ));
+    if !imported.is_empty() {
+        display::print_items(&imported, false, false);
+    }
+    Ok(())
+}
+
+/// Creates a task for an issue, linked to it and with its sync baseline
+fn import_issue(
+    conn: &Connection,
+    ctx: &Context,
+    config: &GitHubConfig,
+    issue: &GitHubIssue,
+    reference: &str,
+    target_time: i64,
+) -> Result<Item, String> {
+    let category = category_for(config, &issue.labels).unwrap_or_else(|| "default".to_string());
+    let mut task = Item::with_target_time(TASK.to_string(), category, issue.title.clone(), Some(target_time));
+    task.owner_id = Some(ctx.current_user_id);
+    task.assignee_id = assignee_for(conn, config, &issue.assignees)?.map(|(_, id)| id);
+    task.namespace_id = Some(ctx.current_namespace_id);
+    task.priority = priority_for(config, &issue.labels)?;
+    task.github_issue = Some(reference.to_string());
+
+    let task_id = insert_item(conn, &task).map_err(|e| e.to_string())?;
+    log_created(conn, ctx, task_id, &task)?;
+    add_link(conn, task_id, LINK_TYPE_ISSUE, reference, Some(&issue.title), Some(ctx.current_user_id))?;
+    save_sync_state(
+        conn,
+        &SyncState {
+            item_id: task_id,
+            reference: reference.to_string(),
+            state: issue.state.clone(),
+            title: issue.title.clone(),
+            assignees: issue.assignees.clone(),
+            synced_at: task.create_time,
+        },
+    )?;
+    task.id = Some(task_id);
+    Ok(task)
+}
+
+/// The first login that maps to a user here, through the configured
+/// login table or else a user of the same name
+fn assignee_for(
+    conn: &Connection,
+    config: &GitHubConfig,
+    logins: &[String],
+) -> Result<Option<(String, i64)>, String> {
+    for login in logins {
+        let name = config
+            .users
+            .as_ref()
+            .and_then(|users| users.get(login))
+            .unwrap_or(login);
+        if let Some(user) = get_user_by_name(conn, name)? {
+            return Ok(Some((login.clone(), user.id)));
+        }
+    }
+    Ok(None)
+}
+
+/// The priority of the first label that has one configured
+fn priority_for(config: &GitHubConfig, labels: &[String]) -> Result<Option<u8>, String> {
+    let Some(priorities) = config.priority_labels.as_ref() else {
+        return Ok(None);
+    };
+    labels
+        .iter()
+        .find_map(|label| priorities.get(label))
+        .map(|priority| parse_priority(priority))
+        .transpose()
+}
+
+/// The category of the first label that has one configured
+fn category_for(config: &GitHubConfig, labels: &[String]) -> Option<String> {
+    let categories = config.category_labels.as_ref()?;
+    labels.iter().find_map(|label| categories.get(label)).cloned()
+}
+
+/// Writes a plan: pulled changes to the task, pushed ones to GitHub,
+/// then the new baseline unless a conflict was left unresolved
+pub fn apply_plan(conn: &Connection, ctx: &Context, client: &dyn ForgeClient, plan: &SyncPlan) -> Result<(), String> {
+    let mut item = plan.item.clone();
+    let before = item.clone();
+    let mut status = None;
+    let mut remote_state = plan.remote.state.clone();
+    for change in &plan.changes {
+        match change {
+            SyncChange::PullTitle { to, .. } => {
+                let rest: Vec<&str> = before.content.lines().skip(1).collect();
+                item.content = std::iter::once(to.as_str()).chain(rest).collect::<Vec<_>>().join("\n");
+            }
+            SyncChange::PullAssignee { user_id, .. } => item.assignee_id = Some(*user_id),
+            SyncChange::PullStatus { to, .. } => status = Some(*to),
+            SyncChange::CloseRemote => {
+                client.close_issue(&plan.reference)?;
+                remote_state = "CLOSED".to_string();
+            }
+            SyncChange::ReopenRemote => {
+                client.reopen_issue(&plan.reference)?;
+                remote_state = "OPEN".to_string();
+            }
+            SyncChange::Conflict { .. } => {}
+        }
+    }
+
+    if item.content != before.content || item.assignee_id != before.assignee_id {
+        update_item(conn, &item).map_err(describe_update_error)?;
+        log_item_changes(conn, &before, &item, ctx.current_user_id)?;
+        item = get_item(conn, item.id.unwrap()).map_err(|e| format!("Failed to get item: {:?}", e))?;
+    }
+    match status {
+        Some(1) => {
+            complete_item(conn, ctx, item, 1, None)?;
+        }
+        Some(status) => {
+            let before = item.clone();
+            item.status = status;
+            update_item(conn, &item).map_err(describe_update_error)?;
+            log_item_changes(conn, &before, &item, ctx.current_user_id)?;
+        }
+        None => {}
+    }
+
+    let unresolved = plan
+        .changes
+        .iter()
+        .any(|c| matches!(c, SyncChange::Conflict { .. }));
+    if !unresolved {
+        let mut remote = plan.remote.clone();
+        remote.state = remote_state;
+        save_baseline(conn, &SyncPlan { remote, ..plan.clone() })?;
+    }
+    Ok(())
+}
+
+fn save_baseline(conn: &Connection, plan: &SyncPlan) -> Result<(), String> {
+    save_sync_state(
+        conn,
+        &SyncState {
+            item_id: plan.item.id.unwrap(),
+            reference: plan.reference.to_string(),
+            state: plan.remote.state.clone(),
+            title: plan.remote.title.clone(),
+            assignees: plan.remote.assignees.clone(),
+            synced_at: Local::now().timestamp(),
+        },
+    )
+}
+
+/// Prints the changes for one task as a diff, one line per change
+fn print_plan(plan: &SyncPlan) {
+    let kind = if plan.is_pull_request { "PR" } else { "issue" };
+    let title = plan.item.content.lines().next().unwrap_or("");
+    display::print_bold(&format!("{} {}  {}", kind, plan.reference, title));
+    for change in &plan.changes {
+        let line = match change {
+            SyncChange::PullStatus { from, to } => format!(
+                "  < status: {} -> {}",
+                translate_status(*from),
+                translate_status(*to)
+            ),
+            SyncChange::PullTitle { from, to } => format!("  < title: \"{}\" -> \"{}\"", from, to),
+            SyncChange::PullAssignee { login, .. } => format!("  < assignee: {}", login),
+            SyncChange::CloseRemote => format!("  > close {} on GitHub", kind),
+            SyncChange::ReopenRemote => format!("  > reopen {} on GitHub", kind),
+            SyncChange::Conflict { field, local, remote } => format!(
+                "  ! {} conflict skipped: \"{}\" here, \"{}\" on GitHub",
+                field, local, remote
+            ),
+        };
+        println!("{}", line);
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{
+            link::add_link,
+            user::create_user,
+        },
+        tests::{
+            get_test_conn,
+            insert_task,
+            update_status,
+            StubGh,
+        },
+    };
+
+    fn issue_json(title: &str, state: &str, assignees: &[&str], updated_at: &str) -> String {
+        let assignees: Vec<String> = assignees
+            .iter()
+            .map(|a| format!("{{\"login\": \"{}\"}}", a))
+            .collect();
+        format!(
+            "{{\"title\": \"{}\", \"state\": \"{}\", \"url\": \"https://github.com/o/r/issues/1\", \"assignees\": [{}], \"updatedAt\": \"{}\"}}",
+            title,
+            state,
+            assignees.join(", "),
+            updated_at
+        )
+    }
+
+    fn link_issue(conn: &Connection, id: i64, reference: &str) {
+        let mut item = get_item(conn, id).unwrap();
+        item.github_issue = Some(reference.to_string());
+        update_item(conn, &item).unwrap();
+    }
+
+    fn sync(conn: &Connection, stub: &StubGh, dry_run: bool, on_conflict: ConflictPolicy) {
+        let ctx = Context::default_from_db(conn).unwrap();
+        let cmd = GhSyncCommand { dry_run, on_conflict };
+        handle_synccmd(conn, &ctx, &stub.gh, &GitHubConfig::default(), &cmd).unwrap();
+    }
+
+    #[test]
+    fn test_sync_pulls_remote_close_title_and_assignee() {
+        let (conn, _temp_file) = get_test_conn();
+        let alice = create_user(&conn, "alice", None, None).unwrap();
+        let id = insert_task(&conn, "work", "Fix login\nsee logs", "today");
+        link_issue(&conn, id, "o/r#1");
+
+        let stub = StubGh::default();
+        stub.respond("issue", 1, &issue_json("Fix login on Safari", "CLOSED", &["alice"], "2020-01-01T00:00:00Z"));
+
+        // No baseline yet, and the local task is newer than the issue
+        sync(&conn, &stub, false, ConflictPolicy::Remote);
+        let item = get_item(&conn, id).unwrap();
+        assert_eq!(item.status, 1);
+        assert_eq!(item.content, "Fix login on Safari\nsee logs");
+        assert_eq!(item.assignee_id, Some(alice));
+        assert!(stub.calls().iter().all(|c| !c.starts_with("issue close")));
+
+        let baseline = get_sync_state(&conn, id, "o/r#1").unwrap().unwrap();
+        assert_eq!(baseline.state, "CLOSED");
+        assert_eq!(baseline.assignees, vec!["alice"]);
+    }
+
+    #[test]
+    fn test_sync_pushes_local_completion_and_reopen() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "Fix login", "today");
+        link_issue(&conn, id, "o/r#1");
+        let stub = StubGh::default();
+        stub.respond("issue", 1, &issue_json("Fix login", "OPEN", &[], "2020-01-01T00:00:00Z"));
+        sync(&conn, &stub, false, ConflictPolicy::Newest);
+
+        // Completed locally since the last sync: close the issue
+        update_status(&conn, id, 1);
+        sync(&conn, &stub, false, ConflictPolicy::Skip);
+        assert!(stub.calls().contains(&"issue close 1 -R o/r".to_string()));
+        assert_eq!(get_item(&conn, id).unwrap().status, 1);
+
+        // Reopened locally: reopen the issue
+        stub.respond("issue", 1, &issue_json("Fix login", "CLOSED", &[], "2020-01-02T00:00:00Z"));
+        update_status(&conn, id, 0);
+        sync(&conn, &stub, false, ConflictPolicy::Skip);
+        assert!(stub.calls().contains(&"issue reopen 1 -R o/r".to_string()));
+        assert_eq!(get_sync_state(&conn, id, "o/r#1").unwrap().unwrap().state, "OPEN");
+    }
+
+    #[test]
+    fn test_sync_dry_run_changes_nothing() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "Fix login", "today");
+        link_issue(&conn, id, "o/r#1");
+        let stub = StubGh::default();
+        stub.respond("issue", 1, &issue_json("Fix login", "CLOSED", &[], "2020-01-01T00:00:00Z"));
+
+        sync(&conn, &stub, true, ConflictPolicy::Remote);
+        assert_eq!(get_item(&conn, id).unwrap().status, 0);
+        assert_eq!(get_sync_state(&conn, id, "o/r#1").unwrap(), None);
+        assert!(stub.calls().iter().all(|c| c.contains("view") || c == "auth status"));
+    }
+
+    #[test]
+    fn test_conflict_policies() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "Fix login", "today");
+        let item = get_item(&conn, id).unwrap();
+        let local_time = item.modify_time.unwrap();
+        let config = GitHubConfig::default();
+        let mut remote = GitHubIssue {
+            number: 1,
+            title: "Fix login".to_string(),
+            body: None,
+            state: "CLOSED".to_string(),
+            url: String::new(),
+            assignees: vec![],
+            labels: vec![],
+            updated_at: Some(local_time - 10),
+        };
+        let plan = |remote: &GitHubIssue, policy| plan_changes(&conn, &config, &item, false, remote, None, policy).unwrap();
+
+        assert_eq!(plan(&remote, ConflictPolicy::Local), vec![SyncChange::ReopenRemote]);
+        assert_eq!(plan(&remote, ConflictPolicy::Remote), vec![SyncChange::PullStatus { from: 0, to: 1 }]);
+        assert_eq!(plan(&remote, ConflictPolicy::Newest), vec![SyncChange::ReopenRemote]);
+        remote.updated_at = Some(local_time + 10);
+        assert_eq!(plan(&remote, ConflictPolicy::Newest), vec![SyncChange::PullStatus { from: 0, to: 1 }]);
+        assert!(matches!(
+            plan(&remote, ConflictPolicy::Skip).as_slice(),
+            [SyncChange::Conflict { field: "status", .. }]
+        ));
+
+        // With a baseline the side that moved wins, whatever the policy
+        let baseline = SyncState {
+            item_id: id,
+            reference: "o/r#1".to_string(),
+            state: "OPEN".to_string(),
+            title: "Fix login".to_string(),
+            assignees: vec![],
+            synced_at: 0,
+        };
+        let changes = plan_changes(&conn, &config, &item, false, &remote, Some(&baseline), ConflictPolicy::Skip).unwrap();
+        assert_eq!(changes, vec![SyncChange::PullStatus { from: 0, to: 1 }]);
+    }
+
+    #[test]
+    fn test_import_skips_tracked_issues_and_maps_labels() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let alice = create_user(&conn, "alice", None, None).unwrap();
+        let tracked = insert_task(&conn, "work", "Tracked", "today");
+        link_issue(&conn, tracked, "o/r#1");
+        let linked = insert_task(&conn, "work", "Linked", "today");
+        add_link(&conn, linked, LINK_TYPE_ISSUE, "o/r#2", None, None).unwrap();
+
+        let stub = StubGh::default();
+        stub.respond_list(
+            r#"[
+                {"number": 1, "title": "Tracked", "state": "OPEN", "assignees": [], "labels": []},
+                {"number": 2, "title": "Linked", "state": "OPEN", "assignees": [], "labels": []},
+                {"number": 3, "title": "Crash on save", "state": "OPEN",
+                 "assignees": [{"login": "octocat"}], "labels": [{"name": "bug"}, {"name": "P0"}]},
+                {"number": 4, "title": "Update docs", "state": "OPEN", "assignees": [], "labels": []}
+            ]"#,
+        );
+        let config = GitHubConfig {
+            users: Some([("octocat".to_string(), "alice".to_string())].into()),
+            priority_labels: Some([("P0".to_string(), "high".to_string())].into()),
+            category_labels: Some([("bug".to_string(), "bugs".to_string())].into()),
+            ..Default::default()
+        };
+        let cmd = GhImportCommand {
+            repo: "o/r".to_string(),
+            label: vec!["triage".to_string()],
+            assignee: Some("@me".to_string()),
+            milestone: Some("v1".to_string()),
+            due: None,
+            limit: 100,
+        };
+        handle_importcmd(&conn, &ctx, &stub.gh, &config, &cmd).unwrap();
+        assert_eq!(
+            stub.calls(),
+            vec!["issue list -R o/r --state open --limit 100 --label triage --assignee @me --milestone v1 --json number,title,body,state,url,assignees,labels,updatedAt"]
+        );
+
+        let tasks = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        assert_eq!(tasks.len(), 4);
+        let crash = tasks.iter().find(|t| t.content == "Crash on save").unwrap();
+        assert_eq!(crash.github_issue.as_deref(), Some("o/r#3"));
+        assert_eq!(crash.category, "bugs");
+        assert_eq!(crash.priority, Some(0));
+        assert_eq!(crash.assignee_id, Some(alice));
+        assert!(get_sync_state(&conn, crash.id.unwrap(), "o/r#3").unwrap().is_some());
+        let docs = tasks.iter().find(|t| t.content == "Update docs").unwrap();
+        assert_eq!(docs.category, "default");
+        assert_eq!(docs.assignee_id, None);
+
+        // Running it again imports nothing
+        handle_importcmd(&conn, &ctx, &stub.gh, &config, &cmd).unwrap();
+        assert_eq!(query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_priority_for_rejects_unknown_priority() {
+        let config = GitHubConfig {
+            priority_labels: Some([("urgent".to_string(), "asap".to_string())].into()),
+            ..Default::default()
+        };
+        assert_eq!(priority_for(&config, &["docs".to_string()]), Ok(None));
+        assert!(priority_for(&config, &["urgent".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_pull_request_state_only_flows_in() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "Add SSO", "today");
+        let ctx = Context::default_from_db(&conn).unwrap();
+        add_link(&conn, id, LINK_TYPE_PR, "o/r#7", None, Some(ctx.current_user_id)).unwrap();
+
+        let stub = StubGh::default();
+        stub.respond("pr", 7, &issue_json("Add SSO", "OPEN", &[], "2020-01-01T00:00:00Z"));
+        sync(&conn, &stub, false, ConflictPolicy::Local);
+        assert!(stub.calls().contains(&"pr view 7 -R o/r --json number,title,body,state,url,assignees,labels,updatedAt".to_string()));
+
+        // Done before the merge: nothing is pushed to the pull request
+        update_status(&conn, id, 1);
+        sync(&conn, &stub, false, ConflictPolicy::Local);
+        assert!(stub.calls().iter().all(|c| !c.starts_with("pr close")));
+        assert_eq!(get_item(&conn, id).unwrap().status, 1);
+
+        // Reopened on GitHub after a revert: pulled in
+        stub.respond("pr", 7, &issue_json("Add SSO", "OPEN", &[], "2020-01-02T00:00:00Z"));
+        let mut baseline = get_sync_state(&conn, id, "o/r#7").unwrap().unwrap();
+        baseline.state = "MERGED".to_string();
+        save_sync_state(&conn, &baseline).unwrap();
+        sync(&conn, &stub, false, ConflictPolicy::Local);
+        assert_eq!(get_item(&conn, id).unwrap().status, 0);
+    }
+}
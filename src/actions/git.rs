@@ -0,0 +1,428 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::OPEN_STATUS_CODES,
+        modify::complete_item,
+    },
+    args::parser::{
+        BranchCommand,
+        GitCommand,
+        GitHookCommand,
+        GitHookInstallCommand,
+    },
+    config::str_to_pathbuf,
+    context::{
+        project::find_project,
+        Context,
+    },
+    db::{
+        audit::{log_change, Change, AUDIT_CREATE, TABLE_LINKS},
+        cache,
+        crud::get_item,
+        item::{Item, RECORD, RECURRING_TASK_RECORD, TASK},
+        link::{add_link, link_exists, LINK_TYPE_COMMIT},
+    },
+    utils::git::Git,
+};
+
+// Task references in branch names and commit messages: ctm-42 or ctm#42
+const TASK_REF_PREFIX: &str = "ctm";
+// Marks hooks this command wrote, so they can be replaced without --force
+const HOOK_MARKER: &str = "# Installed by ctm git hook install";
+const MAX_SLUG_LEN: usize = 40;
+const CLOSING_KEYWORDS: &[&str] = &[
+    "fix", "fixes", "fixed", "close", "closes", "closed", "resolve", "resolves", "resolved",
+];
+
+/// A task referenced from a commit message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskRef {
+    pub id: i64,
+    // Preceded by a closing keyword, as in "fixes ctm-42"
+    pub closes: bool,
+}
+
+/// Handles `ctm branch <index>`: creates and checks out the task's branch in
+/// its project directory, or the current one for tasks without a project
+pub fn handle_branchcmd(conn: &Connection, cmd: &BranchCommand) -> Result<(), String> {
+    let row_id = cache::rowid_for_index(conn, cmd.index)?;
+    let item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {:?}", e))?;
+    if item.action == RECORD || item.action == RECURRING_TASK_RECORD {
+        return Err("Cannot create a branch for a record".to_string());
+    }
+
+    let dir = project_dir(conn, &item)?;
+    let (branch, created) = checkout_task_branch(&Git::new(&dir), &item)?;
+    if created {
+        display::print_bold(&format!("Created branch {} in {}", branch, dir.display()));
+    } else {
+        display::print_bold(&format!("Switched to branch {} in {}", branch, dir.display()));
+    }
+    Ok(())
+}
+
+pub fn handle_git_cmd(conn: &Connection, ctx: &Context, cmd: GitCommand) -> Result<(), String> {
+    match cmd {
+        GitCommand::Hook(GitHookCommand::Install(cmd)) => handle_hook_install(&cmd),
+        GitCommand::Hook(GitHookCommand::CommitMsg(cmd)) => commit_msg_hook(&Git::new("."), Path::new(&cmd.file)),
+        GitCommand::Hook(GitHookCommand::PostCommit(cmd)) => {
+            for line in post_commit_hook(conn, ctx, &Git::new("."), cmd.close)? {
+                println!("ctm: {}", line);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn handle_hook_install(cmd: &GitHookInstallCommand) -> Result<(), String> {
+    let dir = match &cmd.dir {
+        Some(dir) => PathBuf::from(dir),
+        None => std::env::current_dir().map_err(|e| format!("Cannot read the current directory: {}", e))?,
+    };
+    let git = Git::new(dir);
+    let exe = std::env::current_exe().map_err(|e| format!("Cannot find the ctm executable: {}", e))?;
+    for path in install_hooks(&git, &exe, cmd.close, cmd.force)? {
+        println!("Installed {}", path.display());
+    }
+    if cmd.close {
+        println!("Commits saying \"fixes {}-<id>\" will complete the task", TASK_REF_PREFIX);
+    }
+    Ok(())
+}
+
+fn project_dir(conn: &Connection, item: &Item) -> Result<PathBuf, String> {
+    match &item.project {
+        Some(name) => {
+            let project = find_project(conn, item.namespace_id.unwrap_or_default(), name)?
+                .ok_or_else(|| format!("Project '{}' not found", name))?;
+            let path = project.path.ok_or_else(|| {
+                format!(
+                    "Project '{}' has no path. Set projects.{}.path in ~/.config/ctm/config.json",
+                    name, name
+                )
+            })?;
+            str_to_pathbuf(path)
+        }
+        None => std::env::current_dir().map_err(|e| format!("Cannot read the current directory: {}", e)),
+    }
+}
+
+/// "ctm-42-fix-login-crash": the task's ID and a slug of its first line
+pub fn branch_name(item: &Item) -> String {
+    let mut slug = String::new();
+    for c in item.content.lines().next().unwrap_or("").chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    if slug.len() > MAX_SLUG_LEN {
+        slug.truncate(MAX_SLUG_LEN);
+    }
+    let slug = slug.trim_end_matches('-');
+
+    let id = item.id.unwrap_or_default();
+    if slug.is_empty() {
+        format!("{}-{}", TASK_REF_PREFIX, id)
+    } else {
+        format!("{}-{}-{}", TASK_REF_PREFIX, id, slug)
+    }
+}
+
+/// Checks out the task's branch, creating it from HEAD unless one was made
+/// for the task before (its slug may be from older content). Returns the
+/// branch and whether it was created.
+fn checkout_task_branch(git: &Git, item: &Item) -> Result<(String, bool), String> {
+    if git.toplevel().is_none() {
+        return Err("Not a git repository. Give the task a project with a path in a repository".to_string());
+    }
+    let id = item.id.unwrap_or_default();
+    let branches = git.run(&["for-each-ref", "--format=%(refname:short)", "refs/heads/"])?;
+    if let Some(branch) = branches.lines().find(|b| task_id_from_branch(b) == Some(id)) {
+        git.run(&["checkout", "--quiet", branch])?;
+        return Ok((branch.to_string(), false));
+    }
+    let branch = branch_name(item);
+    git.run(&["checkout", "--quiet", "-b", &branch])?;
+    Ok((branch, true))
+}
+
+/// "ctm-42", "CTM#42" or a task branch like "ctm-42-fix-login" -> 42
+fn parse_task_token(token: &str) -> Option<i64> {
+    let token = token.to_ascii_lowercase();
+    let rest = token.strip_prefix(TASK_REF_PREFIX)?;
+    let rest = rest.strip_prefix('-').or_else(|| rest.strip_prefix('#'))?;
+    let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    let after = &rest[digits.len()..];
+    if digits.is_empty() || !(after.is_empty() || after.starts_with('-')) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// The task a branch was made for, also under a prefix like "feature/"
+pub fn task_id_from_branch(branch: &str) -> Option<i64> {
+    parse_task_token(branch.rsplit('/').next().unwrap_or(branch))
+}
+
+/// Tasks referenced in a commit message, in order, each once
+pub fn task_refs(message: &str) -> Vec<TaskRef> {
+    let mut refs: Vec<TaskRef> = Vec::new();
+    let mut previous = String::new();
+    for word in message.split_whitespace() {
+        let token = word.trim_matches(|c: char| !c.is_ascii_alphanumeric() && c != '#');
+        if let Some(id) = parse_task_token(token) {
+            let closes = CLOSING_KEYWORDS.contains(&previous.as_str());
+            match refs.iter_mut().find(|r| r.id == id) {
+                Some(existing) => existing.closes |= closes,
+                None => refs.push(TaskRef { id, closes }),
+            }
+        }
+        previous = token.to_ascii_lowercase();
+    }
+    refs
+}
+
+/// Writes the commit-msg and post-commit hooks, refusing to replace hooks
+/// that ctm did not install unless forced
+fn install_hooks(git: &Git, exe: &Path, close: bool, force: bool) -> Result<Vec<PathBuf>, String> {
+    if git.toplevel().is_none() {
+        return Err("Not a git repository".to_string());
+    }
+    let hooks_dir = git.hooks_dir()?;
+    fs::create_dir_all(&hooks_dir).map_err(|e| format!("Failed to create {}: {}", hooks_dir.display(), e))?;
+
+    // Hooks never fail the commit because of ctm
+    let exe = shell_quote(&exe.to_string_lossy());
+    let close_flag = if close { " --close" } else { "" };
+    let hooks = [
+        ("commit-msg", format!("{} git hook commit-msg \"$1\" || true", exe)),
+        ("post-commit", format!("{} git hook post-commit{} || true", exe, close_flag)),
+    ];
+
+    let paths: Vec<PathBuf> = hooks.iter().map(|(name, _)| hooks_dir.join(name)).collect();
+    for path in &paths {
+        let ours = fs::read_to_string(path).map_or(true, |content| content.contains(HOOK_MARKER));
+        if !ours && !force {
+            return Err(format!(
+                "{} exists and was not installed by ctm. Use --force to replace it",
+                path.display()
+            ));
+        }
+    }
+    for ((_, command), path) in hooks.iter().zip(&paths) {
+        let script = format!("#!/bin/sh\n{}\n{}\n", HOOK_MARKER, command);
+        fs::write(path, script).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        make_executable(path)?;
+    }
+    Ok(paths)
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .map_err(|e| format!("Failed to make {} executable: {}", path.display(), e))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// commit-msg: a message without a task reference, committed on a task
+/// branch, gets a "Task: ctm-<id>" trailer
+fn commit_msg_hook(git: &Git, message_file: &Path) -> Result<(), String> {
+    let message = fs::read_to_string(message_file)
+        .map_err(|e| format!("Failed to read {}: {}", message_file.display(), e))?;
+    // Comment lines name the branch too; only the message itself counts
+    let body: String = message
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if !task_refs(&body).is_empty() {
+        return Ok(());
+    }
+    let Some(id) = git.current_branch().as_deref().and_then(task_id_from_branch) else {
+        return Ok(());
+    };
+    let trailer = format!("Task: {}-{}", TASK_REF_PREFIX, id);
+    let file = message_file.to_string_lossy();
+    git.run(&["interpret-trailers", "--in-place", "--trailer", &trailer, &file])
+        .map(|_| ())
+}
+
+/// post-commit: links HEAD to every task its message references, and with
+/// `close`, completes open tasks it says it fixes. Returns what was done.
+fn post_commit_hook(conn: &Connection, ctx: &Context, git: &Git, close: bool) -> Result<Vec<String>, String> {
+    let log = git.run(&["log", "-1", "--format=%H%n%B"])?;
+    let (sha, message) = log.split_once('\n').unwrap_or((log.as_str(), ""));
+    let subject = message.lines().next().unwrap_or("");
+    let short = &sha[..sha.len().min(8)];
+
+    let mut done = Vec::new();
+    for task_ref in task_refs(message) {
+        let name = format!("{}-{}", TASK_REF_PREFIX, task_ref.id);
+        let Ok(item) = get_item(conn, task_ref.id) else {
+            done.push(format!("no task {}, not linked", name));
+            continue;
+        };
+        if item.action == RECORD || item.action == RECURRING_TASK_RECORD {
+            continue;
+        }
+
+        if !link_exists(conn, task_ref.id, sha)? {
+            add_link(conn, task_ref.id, LINK_TYPE_COMMIT, sha, Some(subject), Some(ctx.current_user_id))?;
+            let change = Change::new(TABLE_LINKS, AUDIT_CREATE)
+                .with_new_value(&format!("{} {}", LINK_TYPE_COMMIT, sha));
+            log_change(conn, task_ref.id, item.namespace_id, ctx.current_user_id, change)?;
+            done.push(format!("linked {} to {}: {}", short, name, item.content));
+        }
+
+        if close && task_ref.closes && item.action == TASK && OPEN_STATUS_CODES.contains(&item.status) {
+            // Linking bumped modify_time, so complete the current version
+            let item = get_item(conn, task_ref.id).map_err(|e| format!("Failed to get item: {:?}", e))?;
+            complete_item(conn, ctx, item, 1, None)?;
+            done.push(format!("completed {}", name));
+        }
+    }
+    Ok(done)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::link::get_links_by_type,
+        tests::{get_test_conn, insert_task, GitRepo},
+    };
+
+    fn task(conn: &Connection, content: &str) -> Item {
+        let id = insert_task(conn, "work", content, "today");
+        get_item(conn, id).unwrap()
+    }
+
+    #[test]
+    fn test_task_refs() {
+        let refs = task_refs("Handle empty config (ctm-42)\n\nFixes CTM#7, see ctm-42-login.\nCloses: ctm-42");
+        assert_eq!(
+            refs,
+            vec![TaskRef { id: 42, closes: true }, TaskRef { id: 7, closes: true }]
+        );
+        assert!(task_refs("ctm-x, actm-4, ctm-4x, ctm 4").is_empty());
+        assert_eq!(task_id_from_branch("feature/ctm-12-add-sso"), Some(12));
+        assert_eq!(task_id_from_branch("main"), None);
+    }
+
+    #[test]
+    fn test_branch_name() {
+        let (conn, _temp_file) = get_test_conn();
+        let item = task(&conn, "Fix login crash: empty password!\nmore details");
+        assert_eq!(branch_name(&item), format!("ctm-{}-fix-login-crash-empty-password", item.id.unwrap()));
+        let item = task(&conn, "Rewrite the configuration loader so that it no longer re-reads the file");
+        assert_eq!(branch_name(&item), format!("ctm-{}-rewrite-the-configuration-loader-so-that", item.id.unwrap()));
+        let item = task(&conn, "!!!");
+        assert_eq!(branch_name(&item), format!("ctm-{}", item.id.unwrap()));
+    }
+
+    #[test]
+    fn test_checkout_task_branch() {
+        let (conn, _temp_file) = get_test_conn();
+        let repo = GitRepo::init();
+        let mut item = task(&conn, "Add SSO");
+
+        let (branch, created) = checkout_task_branch(&repo.git, &item).unwrap();
+        assert!(created);
+        assert_eq!(repo.git.current_branch(), Some(branch.clone()));
+
+        // Back on main, the same task returns to its branch even after an edit
+        repo.git.run(&["checkout", "--quiet", "main"]).unwrap();
+        item.content = "Add SSO with SAML".to_string();
+        assert_eq!(checkout_task_branch(&repo.git, &item).unwrap(), (branch.clone(), false));
+        assert_eq!(repo.git.current_branch(), Some(branch));
+
+        let not_a_repo = tempfile::tempdir().unwrap();
+        assert!(checkout_task_branch(&Git::new(not_a_repo.path()), &item).is_err());
+    }
+
+    #[test]
+    fn test_install_hooks() {
+        let repo = GitRepo::init();
+        let exe = Path::new("/opt/it's/ctm");
+        let paths = install_hooks(&repo.git, exe, true, false).unwrap();
+        let post_commit = fs::read_to_string(&paths[1]).unwrap();
+        assert!(post_commit.contains("'/opt/it'\\''s/ctm' git hook post-commit --close || true"));
+
+        // Reinstalling replaces our own hooks, but not someone else's
+        install_hooks(&repo.git, exe, false, false).unwrap();
+        fs::write(&paths[0], "#!/bin/sh\nlint-commit \"$1\"\n").unwrap();
+        let err = install_hooks(&repo.git, exe, false, false).unwrap_err();
+        assert!(err.contains("not installed by ctm"));
+        install_hooks(&repo.git, exe, false, true).unwrap();
+        assert!(fs::read_to_string(&paths[0]).unwrap().contains(HOOK_MARKER));
+    }
+
+    #[test]
+    fn test_commit_msg_hook_adds_task_trailer() {
+        let repo = GitRepo::init();
+        let file = repo.dir.path().join("COMMIT_EDITMSG");
+
+        // Not on a task branch: unchanged
+        fs::write(&file, "Tidy up\n").unwrap();
+        commit_msg_hook(&repo.git, &file).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "Tidy up\n");
+
+        repo.git.run(&["checkout", "--quiet", "-b", "ctm-5-add-sso"]).unwrap();
+        fs::write(&file, "Add SAML login\n# On branch ctm-5-add-sso\n").unwrap();
+        commit_msg_hook(&repo.git, &file).unwrap();
+        assert!(fs::read_to_string(&file).unwrap().contains("Task: ctm-5"));
+
+        // A message that names a task already is left alone
+        fs::write(&file, "Fix typo for ctm-9\n").unwrap();
+        commit_msg_hook(&repo.git, &file).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "Fix typo for ctm-9\n");
+    }
+
+    #[test]
+    fn test_post_commit_hook_links_and_closes() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let repo = GitRepo::init();
+        let fixed = task(&conn, "Crash on save").id.unwrap();
+        let mentioned = task(&conn, "Refactor storage").id.unwrap();
+
+        let sha = repo.commit(&format!("Guard against empty path\n\nFixes ctm-{}, see ctm-{}", fixed, mentioned));
+        let done = post_commit_hook(&conn, &ctx, &repo.git, true).unwrap();
+        assert_eq!(done.len(), 3);
+
+        let links = get_links_by_type(&conn, fixed, LINK_TYPE_COMMIT).unwrap();
+        assert_eq!(links[0].reference, sha);
+        assert_eq!(links[0].title.as_deref(), Some("Guard against empty path"));
+        assert_eq!(get_links_by_type(&conn, mentioned, LINK_TYPE_COMMIT).unwrap().len(), 1);
+        assert_eq!(get_item(&conn, fixed).unwrap().status, 1);
+        assert_eq!(get_item(&conn, mentioned).unwrap().status, 0);
+
+        // Running again links nothing twice
+        assert!(post_commit_hook(&conn, &ctx, &repo.git, true).unwrap().is_empty());
+
+        // Without --close, fixing only links
+        let other = task(&conn, "Slow startup").id.unwrap();
+        repo.commit(&format!("Cache the index, fixes ctm-{} and ctm-999", other));
+        let done = post_commit_hook(&conn, &ctx, &repo.git, false).unwrap();
+        assert_eq!(done[1], "no task ctm-999, not linked");
+        assert_eq!(get_item(&conn, other).unwrap().status, 0);
+    }
+}
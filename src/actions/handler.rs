@@ -3,44 +3,86 @@ use rusqlite::Connection;
 use crate::{
     actions::{
         addition,
+        agenda,
+        board,
+        bundle,
+        calendar,
         claim,
+        feed,
+        flow,
+        gh,
+        git,
         link,
         list,
         modify,
         namespace,
         note,
+        project,
+        prs,
+        recurring,
         reporting,
+        schema,
         show,
+        snooze,
+        trends,
+        tui,
         user,
     },
     args::parser::{
         Action,
         CliArgs,
         ListCommand,
+        ReportCommand,
     },
     context::Context,
+    mcp,
 };
 
 pub fn handle_commands(conn: &Connection, ctx: &Context, args: CliArgs) -> Result<(), String> {
+    snooze::wake_snoozed_tasks(conn)?;
     match args.arguments {
         Action::Task(cmd) => addition::handle_taskcmd(conn, ctx, &cmd),
-        Action::Record(cmd) => addition::handle_recordcmd(conn, &cmd),
-        Action::Done(cmd) => modify::handle_donecmd(conn, &cmd),
-        Action::Delete(cmd) => modify::handle_deletecmd(conn, &cmd),
-        Action::Update(cmd) => modify::handle_updatecmd(conn, &cmd),
+        Action::Record(cmd) => addition::handle_recordcmd(conn, ctx, &cmd),
+        Action::Done(cmd) => modify::handle_donecmd(conn, ctx, &cmd),
+        Action::Delete(cmd) => modify::handle_deletecmd(conn, ctx, &cmd),
+        Action::Update(cmd) => modify::handle_updatecmd(conn, ctx, &cmd),
         Action::List(list_cmd) => match list_cmd {
-            ListCommand::Task(cmd) => list::handle_listtasks(conn, cmd),
+            ListCommand::Task(cmd) => {
+                let (cmd, namespace_id) = list::resolve_here(ctx, cmd)?;
+                list::handle_listtasks(conn, cmd, namespace_id)
+            }
             ListCommand::Record(cmd) => list::handle_listrecords(conn, cmd),
             ListCommand::Show(cmd) => list::handle_showcontent(conn, cmd),
         },
         Action::Note(cmd) => note::handle_notecmd(conn, ctx, &cmd),
         Action::Show(cmd) => show::handle_showcmd(conn, cmd.index),
+        Action::Context(cmd) => bundle::handle_contextcmd(conn, &cmd),
         Action::Claim(cmd) => claim::handle_claimcmd(conn, ctx, cmd.index),
+        Action::Snooze(cmd) => snooze::handle_snoozecmd(conn, ctx, &cmd),
+        Action::Skip(cmd) => recurring::handle_skipcmd(conn, ctx, &cmd),
         Action::Link(cmd) => link::handle_linkcmd(conn, ctx, &cmd),
+        Action::Branch(cmd) => git::handle_branchcmd(conn, &cmd),
+        Action::Git(cmd) => git::handle_git_cmd(conn, ctx, cmd),
         Action::User(cmd) => user::handle_user_cmd(conn, ctx, cmd),
         Action::Ns(cmd) => namespace::handle_namespace_cmd(conn, ctx, cmd),
+        Action::Project(cmd) => project::handle_project_cmd(conn, ctx, cmd),
         Action::Team(cmd) => reporting::handle_team(conn, ctx, &cmd),
         Action::Workload(cmd) => reporting::handle_workload(conn, ctx, &cmd),
         Action::Stats(cmd) => reporting::handle_stats(conn, ctx, &cmd),
+        Action::Report(report_cmd) => match report_cmd {
+            ReportCommand::Burndown(cmd) => trends::handle_burndown(conn, ctx, &cmd),
+            ReportCommand::Velocity(cmd) => trends::handle_velocity(conn, ctx, &cmd),
+            ReportCommand::Flow(cmd) => flow::handle_flow(conn, ctx, &cmd),
+        },
+        Action::Board(cmd) => board::handle_boardcmd(conn, ctx, &cmd),
+        Action::Cal(cmd) => calendar::handle_calcmd(conn, ctx, &cmd),
+        Action::Today(cmd) => agenda::handle_today(conn, ctx, &cmd),
+        Action::Standup(cmd) => agenda::handle_standup(conn, ctx, &cmd),
+        Action::Feed(cmd) => feed::handle_feedcmd(conn, ctx, &cmd),
+        Action::Tui(cmd) => tui::handle_tuicmd(conn, ctx, &cmd),
+        Action::Mcp => mcp::handle_mcpcmd(conn, ctx),
+        Action::Gh(cmd) => gh::handle_gh_cmd(conn, ctx, cmd),
+        Action::Prs(cmd) => prs::handle_prscmd(conn, ctx, &cmd),
+        Action::Db(cmd) => schema::handle_db_cmd(conn, cmd),
     }
 }
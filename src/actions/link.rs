@@ -5,11 +5,13 @@ use crate::{
     args::parser::LinkCommand,
     context::Context,
     db::{
+        audit::{log_change, Change, AUDIT_CREATE, TABLE_LINKS},
         cache,
         crud::get_item,
-        item::{RECORD, RECURRING_TASK_RECORD},
-        link::{add_link, link_exists},
+        item::{Item, RECORD, RECURRING_TASK_RECORD},
+        link::{add_link, link_exists, LINK_TYPE_COMMIT, LINK_TYPE_URL},
     },
+    forge::parse_forge_ref,
 };
 
 /// Handles the link command - attaches a commit, issue, PR, or URL to a task
@@ -19,12 +21,8 @@ pub fn handle_linkcmd(conn: &Connection, ctx: &Context, cmd: &LinkCommand) -> Re
 
     let item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {:?}", e))?;
 
-    if item.action == RECORD || item.action == RECURRING_TASK_RECORD {
-        return Err("Cannot add links to records".to_string());
-    }
-
-    // Determine link type and reference from command flags
-    let (link_type, reference) = if let Some(ref commit) = cmd.commit {
+    // Determine link kind and reference from command flags
+    let (kind, reference) = if let Some(ref commit) = cmd.commit {
         ("commit", commit.as_str())
     } else if let Some(ref issue) = cmd.issue {
         ("issue", issue.as_str())
@@ -35,20 +33,9 @@ pub fn handle_linkcmd(conn: &Connection, ctx: &Context, cmd: &LinkCommand) -> Re
     } else {
         return Err("Must specify one of: --commit, --issue, --pr, or --url".to_string());
     };
+    let link_type = resolve_link_type(kind, reference)?;
 
-    // Check if link already exists
-    if link_exists(conn, row_id, reference)? {
-        return Err(format!("Link '{}' already exists for this task", reference));
-    }
-
-    let link_id = add_link(
-        conn,
-        row_id,
-        link_type,
-        reference,
-        cmd.title.as_deref(),
-        Some(ctx.current_user_id),
-    )?;
+    let link_id = add_item_link(conn, ctx, &item, link_type, reference, cmd.title.as_deref())?;
 
     display::print_bold(&format!("Added {} link #{} to task:", link_type, link_id));
     display::print_items(&[item], false, false);
@@ -60,6 +47,57 @@ pub fn handle_linkcmd(conn: &Connection, ctx: &Context, cmd: &LinkCommand) -> Re
     Ok(())
 }
 
+/// Attaches a link by the current user to a task. Returns the link's ID.
+pub fn add_item_link(
+    conn: &Connection,
+    ctx: &Context,
+    item: &Item,
+    link_type: &str,
+    reference: &str,
+    title: Option<&str>,
+) -> Result<i64, String> {
+    let row_id = item.id.ok_or_else(|| "Cannot add links to an item without id".to_string())?;
+    if item.action == RECORD || item.action == RECURRING_TASK_RECORD {
+        return Err("Cannot add links to records".to_string());
+    }
+
+    // Check if link already exists
+    if link_exists(conn, row_id, reference)? {
+        return Err(format!("Link '{}' already exists for this task", reference));
+    }
+
+    let link_id = add_link(conn, row_id, link_type, reference, title, Some(ctx.current_user_id))?;
+    let change = Change::new(TABLE_LINKS, AUDIT_CREATE)
+        .with_new_value(&format!("{} {}", link_type, reference));
+    log_change(conn, row_id, item.namespace_id, ctx.current_user_id, change)?;
+    Ok(link_id)
+}
+
+/// The stored link type for a commit, issue, pr or url reference
+pub fn resolve_link_type(kind: &str, reference: &str) -> Result<&'static str, String> {
+    match kind {
+        "commit" => Ok(LINK_TYPE_COMMIT),
+        "issue" => forge_link_type(reference, false),
+        "pr" => forge_link_type(reference, true),
+        "url" => Ok(LINK_TYPE_URL),
+        _ => Err(format!("Unknown link kind '{}': expected commit, issue, pr or url", kind)),
+    }
+}
+
+/// The link type of an --issue or --pr reference: "owner/repo#N" is taken as
+/// the flag says, merge requests and tickets are typed by their form
+fn forge_link_type(reference: &str, pull_request: bool) -> Result<&'static str, String> {
+    let mut forge_ref = parse_forge_ref(reference)?;
+    if forge_ref.kind.is_none() && forge_ref.host.is_none() {
+        forge_ref.pull_request = pull_request;
+    }
+    if forge_ref.pull_request != pull_request {
+        let flag = if forge_ref.pull_request { "--pr" } else { "--issue" };
+        return Err(format!("'{}' is a {}; use {}", reference, forge_ref.noun(), flag));
+    }
+    Ok(forge_ref.link_type())
+}
+
 fn validate_cache(conn: &Connection) -> Result<(), String> {
     match cache::validate_cache(conn) {
         Ok(true) => Ok(()),
@@ -186,6 +224,35 @@ mod tests {
         assert_eq!(links[0].title, Some("Documentation".to_string()));
     }
 
+    #[test]
+    fn test_handle_linkcmd_forge_references() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let task_id = insert_task(&conn, "work", "Test task", "today");
+
+        let items = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        cache::store(&conn, &items).unwrap();
+
+        let mut cmd = make_link_cmd(1);
+        cmd.issue = Some("OPS-12".to_string());
+        handle_linkcmd(&conn, &ctx, &cmd).unwrap();
+
+        let mut cmd = make_link_cmd(1);
+        cmd.pr = Some("https://gitlab.example.com/g/p/-/merge_requests/4".to_string());
+        handle_linkcmd(&conn, &ctx, &cmd).unwrap();
+
+        let links = get_links_for_item(&conn, task_id).unwrap();
+        let types: Vec<&str> = links.iter().map(|l| l.link_type.as_str()).collect();
+        assert_eq!(types, vec!["ticket", "mr"]);
+
+        // A merge request is not an issue, and references must parse
+        let mut cmd = make_link_cmd(1);
+        cmd.issue = Some("g/p!5".to_string());
+        assert!(handle_linkcmd(&conn, &ctx, &cmd).unwrap_err().contains("use --pr"));
+        cmd.issue = Some("not a ref".to_string());
+        assert!(handle_linkcmd(&conn, &ctx, &cmd).unwrap_err().contains("Invalid reference"));
+    }
+
     #[test]
     fn test_handle_linkcmd_no_type_specified() {
         let (conn, _temp_file) = get_test_conn();
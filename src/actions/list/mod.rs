@@ -3,7 +3,11 @@ mod tasks;
 
 pub use records::handle_listrecords;
 use rusqlite::Connection;
-pub use tasks::handle_listtasks;
+pub(crate) use tasks::mark_recurring_task_by_completion;
+pub use tasks::{
+    handle_listtasks,
+    resolve_here,
+};
 
 use crate::{
     args::parser::ShowContentCommand,
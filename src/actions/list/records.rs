@@ -44,8 +44,9 @@ pub fn handle_listrecords(conn: &Connection, cmd: ListRecordCommand) -> Result<(
     }
     .map_err(|e| e.to_string())?;
 
+    let columns = display::resolve_columns(cmd.columns.as_deref(), "list_record")?;
     display::print_bold("Records List:");
-    display::print_items(&records, true, true);
+    display::print_items_with_columns(conn, &records, true, true, &columns)?;
     Ok(())
 }
 
@@ -105,6 +106,7 @@ mod tests {
                 ending_time: None,
                 next_page: false,
                 search: None,
+                columns: None,
             }
         }
 
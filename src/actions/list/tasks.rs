@@ -8,12 +8,16 @@ use super::{
     TARGET_TIME_COL,
 };
 use crate::{
-    actions::display,
+    actions::{
+        display,
+        recurring::{bounded_occurrences, missed_last_interval, schedule_state, ScheduleState},
+    },
     args::{
         cron,
         parser::ListTaskCommand,
         timestr,
     },
+    context::Context,
     db::{
         cache,
         crud::query_items,
@@ -29,7 +33,23 @@ use crate::{
     },
 };
 
-pub fn handle_listtasks(conn: &Connection, cmd: ListTaskCommand) -> Result<(), String> {
+/// Narrows `--here` down to the project of the current directory, in the
+/// current namespace
+pub fn resolve_here(ctx: &Context, mut cmd: ListTaskCommand) -> Result<(ListTaskCommand, Option<i64>), String> {
+    if !cmd.here {
+        return Ok((cmd, None));
+    }
+    let project = ctx.current_project.as_ref().ok_or_else(|| {
+        format!(
+            "The current directory is not in a project of namespace '{}'",
+            ctx.current_namespace_name
+        )
+    })?;
+    cmd.project = Some(project.name.clone());
+    Ok((cmd, Some(ctx.current_namespace_id)))
+}
+
+pub fn handle_listtasks(conn: &Connection, cmd: ListTaskCommand, namespace_id: Option<i64>) -> Result<(), String> {
     // Resolve user filter to assignee_id (skip if all_users is true)
     let assignee_id = if cmd.all_users {
         None
@@ -41,7 +61,7 @@ pub fn handle_listtasks(conn: &Connection, cmd: ListTaskCommand) -> Result<(), S
         None
     };
 
-    let recurring_tasks = match query_recurring_tasks(conn, &cmd, assignee_id) {
+    let recurring_tasks = match query_recurring_tasks(conn, &cmd, assignee_id, namespace_id) {
         Ok(tasks) => tasks,
         Err(estr) => {
             display::print_bold(&estr);
@@ -58,7 +78,17 @@ pub fn handle_listtasks(conn: &Connection, cmd: ListTaskCommand) -> Result<(), S
 
     // Mark completion status for all recurring tasks
     let recurring_tasks = mark_recurring_task_by_completion(conn, recurring_tasks)?;
-    let recurring_tasks = if cmd.status == 255 {
+    let recurring_tasks = if cmd.missed {
+        // Missed is about the previous interval, whatever the state of the current one
+        let now = Local::now().timestamp();
+        let mut missed = Vec::new();
+        for task in recurring_tasks {
+            if missed_last_interval(conn, &task, now)? {
+                missed.push(task);
+            }
+        }
+        missed
+    } else if cmd.status == 255 {
         recurring_tasks
     } else if cmd.status == 253 || cmd.status == 1 {
         // 253 = closed statuses; 1 = done, show only completed tasks
@@ -73,6 +103,19 @@ pub fn handle_listtasks(conn: &Connection, cmd: ListTaskCommand) -> Result<(), S
             .filter(|t| !t.recurring_interval_complete)
             .collect()
     };
+    let recurring_tasks = if cmd.status == 255 || cmd.missed {
+        recurring_tasks
+    } else {
+        // Paused, ended or not yet started tasks have no current interval
+        let now = Local::now().timestamp();
+        let mut active = Vec::new();
+        for task in recurring_tasks {
+            if schedule_state(&task, now)? == ScheduleState::Active {
+                active.push(task);
+            }
+        }
+        active
+    };
     let recurring_tasks = filter_recurring_task_by_time(recurring_tasks, &cmd)?;
 
     let all_tasks = if recurring_hit_limit {
@@ -81,7 +124,7 @@ pub fn handle_listtasks(conn: &Connection, cmd: ListTaskCommand) -> Result<(), S
         recurring_tasks
     } else {
         // Recurring tasks didn't hit limit, safe to query and combine with regular tasks
-        let regular_tasks = match query_tasks(conn, &cmd, assignee_id) {
+        let regular_tasks = match query_tasks(conn, &cmd, assignee_id, namespace_id) {
             Ok(tasks) => tasks,
             Err(estr) => {
                 display::print_bold(&estr);
@@ -122,8 +165,9 @@ pub fn handle_listtasks(conn: &Connection, cmd: ListTaskCommand) -> Result<(), S
     }
     .map_err(|e| e.to_string())?;
 
+    let columns = display::resolve_columns(cmd.columns.as_deref(), "list_task")?;
     display::print_bold("Tasks List:");
-    display::print_items(&all_tasks, false, true);
+    display::print_items_with_columns(conn, &all_tasks, false, true, &columns)?;
     Ok(())
 }
 
@@ -133,17 +177,31 @@ fn query_recurring_tasks(
     conn: &Connection,
     cmd: &ListTaskCommand,
     assignee_id: Option<i64>,
+    namespace_id: Option<i64>,
 ) -> Result<Vec<Item>, String> {
+    // Recurring tasks cannot be snoozed
+    if cmd.snoozed {
+        return Ok(Vec::new());
+    }
     let mut query = ItemQuery::new().with_action(RECURRING_TASK);
     if let Some(cat) = &cmd.category {
         query = query.with_category(cat);
     }
+    if let Some(project) = &cmd.project {
+        query = query.with_project(project);
+    }
+    if let Some(namespace_id) = namespace_id {
+        query = query.with_namespace_id(namespace_id);
+    }
     if let Some(search_term) = &cmd.search {
         query = query.with_content_like(search_term);
     }
     if let Some(aid) = assignee_id {
         query = query.with_assignee_id(aid);
     }
+    if let Some(since) = &cmd.changed_since {
+        query = query.with_modify_time_min(timestr::since_to_unix_epoch(since)?);
+    }
     let mut offset = Offset::None;
     if cmd.next_page {
         offset = handle_next_page(conn);
@@ -178,10 +236,10 @@ fn filter_recurring_task_by_time(
     }
     match target_interval_end {
         Some(et) => {
+            let now = Local::now().timestamp();
             for recurring_task in recurring_tasks {
-                let cron_schedule = recurring_task.cron_schedule.as_ref().unwrap();
-                let next_occurrence = cron::get_next_occurrence(cron_schedule)?;
-                if next_occurrence < et {
+                // The next occurrence within the task's bounds
+                if !bounded_occurrences(&recurring_task, now + 1, et)?.is_empty() {
                     filtered_tasks.push(recurring_task);
                 }
             }
@@ -191,7 +249,7 @@ fn filter_recurring_task_by_time(
     }
 }
 
-fn mark_recurring_task_by_completion(
+pub(crate) fn mark_recurring_task_by_completion(
     conn: &Connection,
     mut recurring_tasks: Vec<Item>,
 ) -> Result<Vec<Item>, String> {
@@ -215,7 +273,12 @@ fn query_tasks(
     conn: &Connection,
     cmd: &ListTaskCommand,
     assignee_id: Option<i64>,
+    namespace_id: Option<i64>,
 ) -> Result<Vec<Item>, String> {
+    // Only recurring tasks have intervals to miss
+    if cmd.missed {
+        return Ok(Vec::new());
+    }
     let mut task_query = ItemQuery::new().with_action(TASK);
     let now = Local::now().timestamp();
 
@@ -227,10 +290,8 @@ fn query_tasks(
     // Store the user's requested cutoff for post-filtering
     let user_cutoff: Option<i64> = if let Some(t) = &cmd.timestr {
         Some(timestr::to_unix_epoch(t)?)
-    } else if let Some(days) = cmd.days {
-        Some(timestr::days_after_to_unix_epoch(days))
     } else {
-        None
+        cmd.days.map(timestr::days_after_to_unix_epoch)
     };
 
     // For reminder window support, query with extended range if cutoff is set
@@ -241,17 +302,33 @@ fn query_tasks(
         task_query = task_query.with_target_time_max(extended_cutoff);
     }
 
-    if !cmd.overdue {
-        task_query = task_query.with_target_time_min(now);
+    if cmd.snoozed {
+        task_query = task_query.with_snoozed_only();
+    } else {
+        task_query = task_query.with_awake_at(now);
+        if !cmd.overdue {
+            task_query = task_query.with_target_time_min(now);
+        }
     }
     if let Some(cat) = &cmd.category {
         task_query = task_query.with_category(cat);
     }
+    if let Some(project) = &cmd.project {
+        task_query = task_query.with_project(project);
+    }
+    if let Some(namespace_id) = namespace_id {
+        task_query = task_query.with_namespace_id(namespace_id);
+    }
     if let Some(search_term) = &cmd.search {
         task_query = task_query.with_content_like(search_term);
     }
+    if let Some(since) = &cmd.changed_since {
+        task_query = task_query.with_modify_time_min(timestr::since_to_unix_epoch(since)?);
+    }
 
     match cmd.status {
+        // Snoozed tasks are listed whatever their status
+        _ if cmd.snoozed => {}
         // 255 status means we query all task items regardless of status.
         255 => {}
         // 254 status indicates a combination of statuses that are open
@@ -309,6 +386,11 @@ fn query_tasks(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::db::crud::{get_item, update_item};
+    use crate::db::{
+        namespace::create_namespace,
+        project::{create_project, get_project, Project},
+    };
     use crate::tests::{
         get_test_conn,
         insert_recurring_record,
@@ -328,8 +410,14 @@ mod tests {
                 limit: 100,
                 next_page: false,
                 search: None,
+                project: None,
+                here: false,
                 user: None,
                 all_users: false,
+                changed_since: None,
+                snoozed: false,
+                missed: false,
+                columns: None,
             }
         }
 
@@ -338,6 +426,11 @@ mod tests {
             self
         }
 
+        fn with_project(mut self, project: &str) -> Self {
+            self.project = Some(project.to_string());
+            self
+        }
+
         fn with_status(mut self, status: u8) -> Self {
             self.status = status;
             self
@@ -362,6 +455,21 @@ mod tests {
             self.search = Some(search.to_string());
             self
         }
+
+        fn with_changed_since(mut self, changed_since: &str) -> Self {
+            self.changed_since = Some(changed_since.to_string());
+            self
+        }
+
+        fn with_snoozed(mut self) -> Self {
+            self.snoozed = true;
+            self
+        }
+
+        fn with_missed(mut self) -> Self {
+            self.missed = true;
+            self
+        }
     }
 
     #[test]
@@ -372,17 +480,33 @@ mod tests {
         insert_task(&conn, "fun", "first_due", "yesterday");
 
         let list_tasks_default = ListTaskCommand::default_test();
-        let results = query_tasks(&conn, &list_tasks_default, None).unwrap();
+        let results = query_tasks(&conn, &list_tasks_default, None, None).unwrap();
         assert_eq!(results.len(), 2);
         assert_eq!(results.first().unwrap().content, "second_due");
         assert_eq!(results.last().unwrap().content, "third_due");
 
         let list_tasks_with_overdue = ListTaskCommand::default_test().with_overdue(true);
-        let results = query_tasks(&conn, &list_tasks_with_overdue, None).unwrap();
+        let results = query_tasks(&conn, &list_tasks_with_overdue, None, None).unwrap();
         assert_eq!(results.len(), 3);
         assert_eq!(results.first().unwrap().content, "first_due");
     }
 
+    #[test]
+    fn test_query_tasks_by_project() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "fix the build", "tomorrow");
+        insert_task(&conn, "work", "elsewhere", "tomorrow");
+        let mut task = get_item(&conn, id).unwrap();
+        task.project = Some("ctm".to_string());
+        update_item(&conn, &task).unwrap();
+
+        let cmd = ListTaskCommand::default_test().with_project("ctm");
+        let results = query_tasks(&conn, &cmd, None, None).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "fix the build");
+        assert!(query_recurring_tasks(&conn, &cmd, None, None).unwrap().is_empty());
+    }
+
     #[test]
     fn test_query_tasks_pagination() {
         let (conn, _temp_file) = get_test_conn();
@@ -405,13 +529,13 @@ mod tests {
             .with_category("test")
             .with_limit(10);
 
-        let results = query_tasks(&conn, &list_task, None).unwrap();
+        let results = query_tasks(&conn, &list_task, None, None).unwrap();
         cache::store_with_next(&conn, &results).unwrap();
         assert_eq!(results.len(), 10);
         assert!(results.iter().all(|i| i.content.contains("AM")));
 
         let list_task_next = list_task.with_next_page();
-        let results = query_tasks(&conn, &list_task_next, None).unwrap();
+        let results = query_tasks(&conn, &list_task_next, None, None).unwrap();
 
         cache::clear(&conn).unwrap();
         cache::store_with_next(&conn, &results).unwrap();
@@ -419,7 +543,7 @@ mod tests {
         assert_eq!(results.first().unwrap().content, "index 11AM");
         assert_eq!(results.last().unwrap().content, "index 9PM");
 
-        let results = query_tasks(&conn, &list_task_next, None).unwrap();
+        let results = query_tasks(&conn, &list_task_next, None, None).unwrap();
 
         cache::clear(&conn).unwrap();
         cache::store(&conn, &results).unwrap();
@@ -427,7 +551,7 @@ mod tests {
         assert_eq!(results.first().unwrap().content, "index 10PM");
         assert_eq!(results.last().unwrap().content, "index 11PM");
 
-        let results = query_tasks(&conn, &list_task_next, None);
+        let results = query_tasks(&conn, &list_task_next, None, None);
         assert_eq!(results.unwrap_err(), "No next page available".to_string());
     }
 
@@ -451,12 +575,12 @@ mod tests {
         let list_open = ListTaskCommand::default_test().with_status(254);
         let list_closed = ListTaskCommand::default_test().with_status(253);
 
-        let results = query_tasks(&conn, &list_open, None).expect("Unable to query");
+        let results = query_tasks(&conn, &list_open, None, None).expect("Unable to query");
         assert_eq!(results.len(), 6);
         assert!(results
             .iter()
             .all(|t| t.category == "ongoing" || t.category == "pending"));
-        let results = query_tasks(&conn, &list_closed, None).expect("Unable to query");
+        let results = query_tasks(&conn, &list_closed, None, None).expect("Unable to query");
         assert_eq!(results.len(), 4);
         assert!(results
             .iter()
@@ -474,12 +598,12 @@ mod tests {
 
         // Test basic query
         let list_all = ListTaskCommand::default_test();
-        let results = query_recurring_tasks(&conn, &list_all, None).unwrap();
+        let results = query_recurring_tasks(&conn, &list_all, None, None).unwrap();
         assert_eq!(results.len(), 3);
 
         // Test category filter
         let list_work = ListTaskCommand::default_test().with_category("work");
-        let results = query_recurring_tasks(&conn, &list_work, None).unwrap();
+        let results = query_recurring_tasks(&conn, &list_work, None, None).unwrap();
         assert_eq!(results.len(), 2);
         for task in &results {
             assert_eq!(task.category, "work");
@@ -487,13 +611,13 @@ mod tests {
 
         // Test search filter
         let list_search = ListTaskCommand::default_test().with_search("standup");
-        let results = query_recurring_tasks(&conn, &list_search, None).unwrap();
+        let results = query_recurring_tasks(&conn, &list_search, None, None).unwrap();
         assert_eq!(results.len(), 1);
         assert!(results[0].content.contains("standup"));
 
         // Test limit
         let list_limited = ListTaskCommand::default_test().with_limit(2);
-        let results = query_recurring_tasks(&conn, &list_limited, None).unwrap();
+        let results = query_recurring_tasks(&conn, &list_limited, None, None).unwrap();
         assert_eq!(results.len(), 2);
     }
 
@@ -508,7 +632,7 @@ mod tests {
 
         // Query all recurring tasks
         let cmd = ListTaskCommand::default_test();
-        let all_tasks = query_recurring_tasks(&conn, &cmd, None).unwrap();
+        let all_tasks = query_recurring_tasks(&conn, &cmd, None, None).unwrap();
         assert_eq!(all_tasks.len(), 3);
 
         // Test with no time filter (should return all)
@@ -543,7 +667,7 @@ mod tests {
 
         // Query all recurring tasks
         let cmd = ListTaskCommand::default_test();
-        let all_tasks = query_recurring_tasks(&conn, &cmd, None).unwrap();
+        let all_tasks = query_recurring_tasks(&conn, &cmd, None, None).unwrap();
         assert_eq!(all_tasks.len(), 3);
 
         // Mark completion status
@@ -586,7 +710,7 @@ mod tests {
             status: 255,
             ..ListTaskCommand::default_test()
         };
-        let result = handle_listtasks(&conn, cmd);
+        let result = handle_listtasks(&conn, cmd, None);
         assert!(result.is_ok());
 
         // Verify cache was populated
@@ -617,7 +741,7 @@ mod tests {
             ..ListTaskCommand::default_test()
         };
 
-        let result = handle_listtasks(&conn, cmd);
+        let result = handle_listtasks(&conn, cmd, None);
         assert!(result.is_ok());
         assert!(cache::validate_cache(&conn).unwrap());
 
@@ -629,7 +753,7 @@ mod tests {
             ..ListTaskCommand::default_test()
         };
 
-        let result = handle_listtasks(&conn, cmd_next);
+        let result = handle_listtasks(&conn, cmd_next, None);
         assert!(result.is_ok());
         assert!(cache::validate_cache(&conn).unwrap());
     }
@@ -653,7 +777,7 @@ mod tests {
             status: 255, // all
             ..ListTaskCommand::default_test()
         };
-        let result = handle_listtasks(&conn, cmd);
+        let result = handle_listtasks(&conn, cmd, None);
         assert!(result.is_ok());
 
         // Second page: should get last recurring + first regular (transition page)
@@ -663,15 +787,15 @@ mod tests {
             next_page: true,
             ..ListTaskCommand::default_test()
         };
-        let recurring_and_regular = query_recurring_tasks(&conn, &cmd_next, None).unwrap();
-        let regular_tasks = query_tasks(&conn, &cmd_next, None).unwrap();
+        let recurring_and_regular = query_recurring_tasks(&conn, &cmd_next, None, None).unwrap();
+        let regular_tasks = query_tasks(&conn, &cmd_next, None, None).unwrap();
 
         // Should have 1 recurring task left (Recurring 3)
         assert_eq!(recurring_and_regular.len(), 1);
         assert_eq!(recurring_and_regular[0].content, "Recurring 3");
 
         // Should start getting regular tasks (didn't hit recurring limit)
-        assert!(regular_tasks.len() > 0);
+        assert!(!regular_tasks.is_empty());
 
         // Third page: should transition to regular tasks (not "No tasks found")
         let cmd_next = ListTaskCommand {
@@ -680,7 +804,7 @@ mod tests {
             next_page: true,
             ..ListTaskCommand::default_test()
         };
-        let result = handle_listtasks(&conn, cmd_next);
+        let result = handle_listtasks(&conn, cmd_next, None);
         assert!(result.is_ok()); // Should succeed and show regular tasks
     }
 
@@ -701,7 +825,7 @@ mod tests {
             status: 253,
             ..ListTaskCommand::default_test()
         };
-        let result = handle_listtasks(&conn, cmd_closed);
+        let result = handle_listtasks(&conn, cmd_closed, None);
         assert!(result.is_ok());
         // Should show completed recurring tasks
         assert!(cache::validate_cache(&conn).unwrap());
@@ -713,7 +837,7 @@ mod tests {
             status: 0,
             ..ListTaskCommand::default_test()
         };
-        let result = handle_listtasks(&conn, cmd_open);
+        let result = handle_listtasks(&conn, cmd_open, None);
         assert!(result.is_ok());
         // Should show incomplete recurring tasks plus any regular tasks
         assert!(cache::validate_cache(&conn).unwrap());
@@ -752,7 +876,7 @@ mod tests {
             status: 255, // Show all
             ..ListTaskCommand::default_test()
         };
-        let result = handle_listtasks(&conn, cmd);
+        let result = handle_listtasks(&conn, cmd, None);
         assert!(result.is_ok());
         assert!(cache::validate_cache(&conn).unwrap());
 
@@ -765,7 +889,7 @@ mod tests {
             next_page: true,
             ..ListTaskCommand::default_test()
         };
-        let result = handle_listtasks(&conn, cmd_page2);
+        let result = handle_listtasks(&conn, cmd_page2, None);
         assert!(result.is_ok());
         assert!(cache::validate_cache(&conn).unwrap());
     }
@@ -785,7 +909,7 @@ mod tests {
         let search_meeting_tasks = ListTaskCommand::default_test()
             .with_overdue(true)
             .with_search("meeting");
-        let results = query_tasks(&conn, &search_meeting_tasks, None).unwrap();
+        let results = query_tasks(&conn, &search_meeting_tasks, None, None).unwrap();
         assert_eq!(results.len(), 3);
         for task in &results {
             assert!(task.content.contains("meeting"));
@@ -796,11 +920,143 @@ mod tests {
             .with_category("work")
             .with_overdue(true)
             .with_search("meeting");
-        let results = query_tasks(&conn, &search_work_meeting, None).unwrap();
+        let results = query_tasks(&conn, &search_work_meeting, None, None).unwrap();
         assert_eq!(results.len(), 2);
         for task in &results {
             assert!(task.content.contains("meeting"));
             assert_eq!(task.category, "work");
         }
     }
+
+    #[test]
+    fn test_changed_since() {
+        let (conn, _temp_file) = get_test_conn();
+        let stale_id = insert_task(&conn, "work", "untouched for days", "tomorrow");
+        insert_task(&conn, "work", "fresh task", "tomorrow");
+        let stale_recurring_id = insert_recurring_task(&conn, "work", "stale standup", "daily");
+        insert_recurring_task(&conn, "work", "fresh standup", "daily");
+        let three_days_ago = Local::now().timestamp() - 3 * 86400;
+        for id in [stale_id, stale_recurring_id] {
+            conn.execute(
+                "UPDATE items SET modify_time = ?1 WHERE id = ?2",
+                rusqlite::params![three_days_ago, id],
+            )
+            .unwrap();
+        }
+
+        let cmd = ListTaskCommand::default_test().with_changed_since("yesterday");
+        let tasks = query_tasks(&conn, &cmd, None, None).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].content, "fresh task");
+        let recurring = query_recurring_tasks(&conn, &cmd, None, None).unwrap();
+        assert_eq!(recurring.len(), 1);
+        assert_eq!(recurring[0].content, "fresh standup");
+
+        let cmd = ListTaskCommand::default_test().with_changed_since("2000-01-01");
+        assert_eq!(query_tasks(&conn, &cmd, None, None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_snoozed_tasks() {
+        let (conn, _temp_file) = get_test_conn();
+        let snoozed_id = insert_task(&conn, "work", "snoozed task", "tomorrow");
+        insert_task(&conn, "work", "awake task", "tomorrow");
+        let next_week = Local::now().timestamp() + 7 * 86400;
+        conn.execute(
+            "UPDATE items SET status = 4, snooze_until = ?1 WHERE id = ?2",
+            rusqlite::params![next_week, snoozed_id],
+        )
+        .unwrap();
+
+        // Hidden from the default listing, even when asking for suspended tasks
+        let tasks = query_tasks(&conn, &ListTaskCommand::default_test(), None, None).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].content, "awake task");
+        let cmd = ListTaskCommand::default_test().with_status(4);
+        assert!(query_tasks(&conn, &cmd, None, None).unwrap().is_empty());
+
+        let cmd = ListTaskCommand::default_test().with_snoozed();
+        let tasks = query_tasks(&conn, &cmd, None, None).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].snooze_until, Some(next_week));
+        assert!(query_recurring_tasks(&conn, &cmd, None, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_missed_recurring_tasks() {
+        let (conn, _temp_file) = get_test_conn();
+        insert_task(&conn, "work", "regular task", "tomorrow");
+        let missed_id = insert_recurring_task(&conn, "work", "old standup", "daily 9am");
+        insert_recurring_task(&conn, "work", "new standup", "daily 9am");
+        let last_week = Local::now().timestamp() - 7 * 86400;
+        conn.execute(
+            "UPDATE items SET create_time = ?1 WHERE id = ?2",
+            rusqlite::params![last_week, missed_id],
+        )
+        .unwrap();
+
+        let cmd = ListTaskCommand::default_test().with_missed();
+        assert!(query_tasks(&conn, &cmd, None, None).unwrap().is_empty());
+        handle_listtasks(&conn, cmd, None).unwrap();
+        assert_eq!(cache::read(&conn, 1).unwrap(), Some(missed_id));
+        assert_eq!(cache::read(&conn, 2).unwrap(), None);
+    }
+
+    #[test]
+    fn test_paused_recurring_tasks_hidden() {
+        let (conn, _temp_file) = get_test_conn();
+        let paused_id = insert_recurring_task(&conn, "work", "paused standup", "daily 9am");
+        let active_id = insert_recurring_task(&conn, "work", "active standup", "daily 9am");
+        conn.execute(
+            "UPDATE items SET paused_at = ?1 WHERE id = ?2",
+            rusqlite::params![Local::now().timestamp(), paused_id],
+        )
+        .unwrap();
+
+        handle_listtasks(&conn, ListTaskCommand::default_test(), None).unwrap();
+        assert_eq!(cache::read(&conn, 1).unwrap(), Some(active_id));
+        assert_eq!(cache::read(&conn, 2).unwrap(), None);
+
+        // Still there when asking for everything
+        handle_listtasks(&conn, ListTaskCommand::default_test().with_status(255), None).unwrap();
+        assert_eq!(cache::read(&conn, 2).unwrap(), Some(active_id));
+    }
+
+    #[test]
+    fn test_here_scoped_to_current_namespace() {
+        let (conn, _temp_file) = get_test_conn();
+        let mut ctx = Context::default_from_db(&conn).unwrap();
+        let other = create_namespace(&conn, "other", None, ctx.current_user_id).unwrap();
+        for namespace_id in [ctx.current_namespace_id, other] {
+            let project = Project { namespace_id, name: "api".to_string(), ..Default::default() };
+            create_project(&conn, &project).unwrap();
+        }
+        let mine = insert_task(&conn, "work", "mine", "tomorrow");
+        let theirs = insert_task(&conn, "work", "theirs", "tomorrow");
+        for (id, namespace_id) in [(mine, ctx.current_namespace_id), (theirs, other)] {
+            conn.execute(
+                "UPDATE items SET namespace_id = ?1, project = 'api' WHERE id = ?2",
+                rusqlite::params![namespace_id, id],
+            )
+            .unwrap();
+        }
+
+        let mut cmd = ListTaskCommand::default_test();
+        cmd.here = true;
+        assert!(resolve_here(&ctx, cmd).unwrap_err().contains("not in a project"));
+
+        ctx.current_project = get_project(&conn, ctx.current_namespace_id, "api").unwrap();
+        let mut cmd = ListTaskCommand::default_test();
+        cmd.here = true;
+        let (cmd, namespace_id) = resolve_here(&ctx, cmd).unwrap();
+        assert_eq!(cmd.project.as_deref(), Some("api"));
+        assert_eq!(namespace_id, Some(ctx.current_namespace_id));
+        let tasks = query_tasks(&conn, &cmd, None, namespace_id).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, Some(mine));
+
+        // Without --here the project name matches in every namespace
+        let cmd = ListTaskCommand::default_test().with_project("api");
+        assert_eq!(query_tasks(&conn, &cmd, None, None).unwrap().len(), 2);
+    }
 }
@@ -1,12 +1,27 @@
 pub mod addition;
+pub mod agenda;
+pub mod board;
+pub mod bundle;
+pub mod calendar;
 pub mod claim;
 pub mod display;
+pub mod feed;
+pub mod flow;
+pub mod gh;
+pub mod git;
 pub mod handler;
 pub mod link;
 pub mod list;
 pub mod modify;
 pub mod namespace;
 pub mod note;
+pub mod project;
+pub mod prs;
+pub mod recurring;
 pub mod reporting;
+pub mod schema;
 pub mod show;
+pub mod snooze;
+pub mod trends;
+pub mod tui;
 pub mod user;
@@ -3,10 +3,14 @@ use std::{
     io::Write,
 };
 
+use chrono::Local;
 use rusqlite::Connection;
 
 use crate::{
-    actions::display,
+    actions::{
+        display,
+        recurring::{ensure_active, pause_item, resume_item},
+    },
     args::{
         cron,
         parser::{
@@ -16,11 +20,23 @@ use crate::{
         },
         timestr,
     },
-    config::get_project,
+    context::{
+        project::active_project,
+        Context,
+    },
     db::{
+        audit::{
+            log_change,
+            log_item_changes,
+            Change,
+            AUDIT_COMPLETE,
+            AUDIT_DELETE,
+            TABLE_ITEMS,
+        },
         cache,
         crud::{
             delete_item,
+            describe_update_error,
             get_item,
             insert_item,
             query_items,
@@ -34,29 +50,54 @@ use crate::{
             RECURRING_TASK_RECORD,
         },
     },
-    github::{close_issue, is_gh_available, parse_issue_ref},
+    forge::{issue_provider, parse_forge_ref, ForgeScope},
 };
 
-pub fn handle_donecmd(conn: &Connection, cmd: &DoneCommand) -> Result<(), String> {
+pub fn handle_donecmd(conn: &Connection, ctx: &Context, cmd: &DoneCommand) -> Result<(), String> {
     validate_cache(conn)?;
     let row_id = get_rowid_from_cache(conn, cmd.index)?;
     let status = cmd.status;
 
-    let mut item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {:?}", e))?;
+    let item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {:?}", e))?;
     if item.action == RECORD || item.action == RECURRING_TASK_RECORD {
         return Err("Cannot complete a record".to_string());
     }
 
     // Handle --close-issue flag before completing the task
     if cmd.close_issue {
-        close_linked_issue(&item)?;
+        close_linked_issue(ctx, &item)?;
     }
 
+    let item = complete_item(conn, ctx, item, status, cmd.comment.as_deref())?;
+    if item.action == RECURRING_TASK {
+        display::print_bold("Completed Recurring Task:");
+    } else {
+        display::print_bold("Completed Task:");
+    }
+    display::print_items(&[item], false, false);
+    Ok(())
+}
+
+/// Completes a task with the given status and writes its completion record.
+/// A recurring task is completed for the current interval only.
+pub fn complete_item(
+    conn: &Connection,
+    ctx: &Context,
+    mut item: Item,
+    status: u8,
+    comment: Option<&str>,
+) -> Result<Item, String> {
+    if item.action == RECORD || item.action == RECURRING_TASK_RECORD {
+        return Err("Cannot complete a record".to_string());
+    }
+    let row_id = item.id.ok_or_else(|| "Cannot complete an item without id".to_string())?;
+
     if item.action == RECURRING_TASK {
         let cron_schedule = item
             .cron_schedule
             .as_ref()
             .ok_or_else(|| "Recurring task missing cron schedule".to_string())?;
+        ensure_active(&item, Local::now().timestamp())?;
 
         let last_occurrence = cron::get_last_occurrence(cron_schedule)?;
 
@@ -78,71 +119,82 @@ pub fn handle_donecmd(conn: &Connection, cmd: &DoneCommand) -> Result<(), String
         let next_occurrence = cron::get_next_occurrence(cron_schedule)?;
 
         let mut record_content = format!("Completed Recurring Task: {}", item.content);
-        if let Some(comment) = &cmd.comment {
+        if let Some(comment) = comment {
             record_content.push('\n');
             record_content.push_str(comment);
         }
 
-        let completion_record = Item::create_recurring_record(
+        let mut completion_record = Item::create_recurring_record(
             item.category.clone(),
             record_content,
             item.id.unwrap(),
             next_occurrence,
         );
-        insert_item(conn, &completion_record)
+        completion_record.owner_id = Some(ctx.current_user_id);
+        completion_record.namespace_id = item.namespace_id;
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        insert_item(&tx, &completion_record)
             .map_err(|e| format!("Failed to create completion record: {:?}", e))?;
-
-        display::print_bold("Completed Recurring Task:");
-        display::print_items(&[item], false, false);
-        return Ok(());
+        let change = Change::new(TABLE_ITEMS, AUDIT_COMPLETE).with_new_value(&completion_record.content);
+        log_change(&tx, row_id, item.namespace_id, ctx.current_user_id, change)?;
+        tx.commit().map_err(|e| e.to_string())?;
+        return Ok(item);
     }
 
-    if let Some(comment) = &cmd.comment {
+    if let Some(comment) = comment {
         item.content.push('\n');
         item.content.push_str(comment);
     }
 
+    // The task and its completion record are written together, and the
+    // update goes first so a concurrent change aborts before the record
+    let before = item.clone();
+    item.status = status;
+    item.snooze_until = None;
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    update_item(&tx, &item).map_err(describe_update_error)?;
+    log_item_changes(&tx, &before, &item, ctx.current_user_id)?;
+
     let completion_content = format!("Completed Task: {}", item.content);
-    let completion_record = Item::new(
+    let mut completion_record = Item::new(
         RECORD.to_string(),
         item.category.clone(),
         completion_content,
     );
-    insert_item(conn, &completion_record)
+    // Standups find the record through whoever completed the task, namespace
+    // views through the task's namespace
+    completion_record.owner_id = Some(ctx.current_user_id);
+    completion_record.namespace_id = item.namespace_id;
+    insert_item(&tx, &completion_record)
         .map_err(|e| format!("Failed to create completion record: {:?}", e))?;
-
-    item.status = status;
-    update_item(conn, &item).map_err(|e| format!("Failed to update item: {:?}", e))?;
-    display::print_bold("Completed Task:");
-    display::print_items(&[item], false, false);
-    Ok(())
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(item)
 }
 
-/// Close the linked GitHub issue for a task
-fn close_linked_issue(item: &Item) -> Result<(), String> {
+/// Close the task's issue, merge request or ticket on its forge
+fn close_linked_issue(ctx: &Context, item: &Item) -> Result<(), String> {
     let issue_str = item.github_issue.as_ref().ok_or(
-        "Task has no linked GitHub issue. Use 'ctm link --issue owner/repo#N' first.".to_string(),
+        "Task has no linked issue. Use 'ctm link --issue owner/repo#N' first.".to_string(),
     )?;
 
-    if !is_gh_available() {
-        return Err(
-            "GitHub CLI (gh) is not installed or not authenticated. Run 'gh auth login' first."
-                .to_string(),
-        );
-    }
-
-    let issue_ref = parse_issue_ref(issue_str)?;
-    close_issue(&issue_ref)?;
+    let forge_ref = parse_forge_ref(issue_str)?;
+    let scope = ForgeScope {
+        namespace: &ctx.current_namespace_name,
+        project: item.project.as_deref(),
+    };
+    let provider = issue_provider(&forge_ref, scope)?;
+    provider.close(&forge_ref)?;
 
-    println!("Closed GitHub issue: {}", issue_str);
+    println!("Closed {} {}: {}", provider.kind().name(), forge_ref.noun(), issue_str);
     Ok(())
 }
 
-pub fn handle_deletecmd(conn: &Connection, cmd: &DeleteCommand) -> Result<(), String> {
+pub fn handle_deletecmd(conn: &Connection, ctx: &Context, cmd: &DeleteCommand) -> Result<(), String> {
     validate_cache(conn)?;
     let row_id = get_rowid_from_cache(conn, cmd.index)?;
     let item = get_item(conn, row_id).map_err(|e| format!("Failed to find item: {:?}", e))?;
     let item_type = item.action.clone();
+    let (content, namespace_id) = (item.content.clone(), item.namespace_id);
     let is_record = item_type == RECORD || item_type == RECURRING_TASK_RECORD;
     display::print_items(&[item], is_record, false);
     let accept = prompt_yes_no(&format!(
@@ -153,15 +205,24 @@ pub fn handle_deletecmd(conn: &Connection, cmd: &DeleteCommand) -> Result<(), St
     if !accept {
         return Err(format!("Not deleting the {}", &item_type));
     }
+    let change = Change::new(TABLE_ITEMS, AUDIT_DELETE).with_old_value(&content);
+    log_change(conn, row_id, namespace_id, ctx.current_user_id, change)?;
     delete_item(conn, row_id).map_err(|e| format!("Failed to update item: {:?}", e))?;
     display::print_bold("Deletion success");
     Ok(())
 }
 
-pub fn handle_updatecmd(conn: &Connection, cmd: &UpdateCommand) -> Result<(), String> {
+pub fn handle_updatecmd(conn: &Connection, ctx: &Context, cmd: &UpdateCommand) -> Result<(), String> {
     validate_cache(conn)?;
     let row_id = get_rowid_from_cache(conn, cmd.index)?;
     let mut item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {:?}", e))?;
+    let before = item.clone();
+
+    let changes_schedule =
+        cmd.starts.is_some() || cmd.ends.is_some() || cmd.max_occurrences.is_some() || cmd.pause || cmd.resume;
+    if changes_schedule && item.action != RECURRING_TASK {
+        return Err("Start, end, occurrence limit and pause only apply to recurring tasks".to_string());
+    }
 
     if item.action == RECURRING_TASK {
         if cmd.status.is_some() {
@@ -193,7 +254,37 @@ pub fn handle_updatecmd(conn: &Connection, cmd: &UpdateCommand) -> Result<(), St
             item.content = content.clone();
         }
 
-        update_item(conn, &item).map_err(|e| format!("Failed to update item: {:?}", e))?;
+        if let Some(starts) = &cmd.starts {
+            item.starts_at = parse_bound(starts, timestr::since_to_unix_epoch)?;
+        }
+        if let Some(ends) = &cmd.ends {
+            item.ends_at = parse_bound(ends, timestr::to_unix_epoch)?;
+        }
+        if let (Some(starts_at), Some(ends_at)) = (item.starts_at, item.ends_at) {
+            if starts_at > ends_at {
+                return Err("Recurring task must start before it ends".to_string());
+            }
+        }
+        if let Some(max_occurrences) = cmd.max_occurrences {
+            if max_occurrences > cron::MAX_OCCURRENCES as i64 {
+                return Err(format!("Occurrence limit cannot exceed {}", cron::MAX_OCCURRENCES));
+            }
+            item.max_occurrences = (max_occurrences > 0).then_some(max_occurrences);
+        }
+
+        let now = Local::now().timestamp();
+        if cmd.pause {
+            pause_item(&mut item, now)?;
+        }
+        // Resuming writes skip records, saved together with the task
+        let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+        if cmd.resume {
+            resume_item(&tx, &mut item, now)?;
+        }
+
+        update_item(&tx, &item).map_err(describe_update_error)?;
+        log_item_changes(&tx, &before, &item, ctx.current_user_id)?;
+        tx.commit().map_err(|e| e.to_string())?;
 
         display::print_bold("Updated Recurring Task:");
         display::print_items(&[item], false, false);
@@ -220,6 +311,8 @@ pub fn handle_updatecmd(conn: &Connection, cmd: &UpdateCommand) -> Result<(), St
 
     if let Some(status) = cmd.status {
         item.status = status;
+        // An explicit status change ends any snooze
+        item.snooze_until = None;
     }
 
     if let Some(reminder) = cmd.reminder {
@@ -227,16 +320,11 @@ pub fn handle_updatecmd(conn: &Connection, cmd: &UpdateCommand) -> Result<(), St
     }
 
     if let Some(ref project) = cmd.project {
-        if get_project(project).is_none() {
-            return Err(format!(
-                "Project '{}' not found in config. Add it to ~/.config/ctm/config.json",
-                project
-            ));
-        }
-        item.project = Some(project.clone());
+        item.project = Some(active_project(conn, ctx, project)?.name);
     }
 
-    update_item(conn, &item).map_err(|e| format!("Failed to update item: {:?}", e))?;
+    update_item(conn, &item).map_err(describe_update_error)?;
+    log_item_changes(conn, &before, &item, ctx.current_user_id)?;
 
     let is_record = item.action == RECORD || item.action == RECURRING_TASK_RECORD;
     let action = if is_record { "Record" } else { "Task" };
@@ -245,6 +333,15 @@ pub fn handle_updatecmd(conn: &Connection, cmd: &UpdateCommand) -> Result<(), St
     Ok(())
 }
 
+// "none" lifts a bound, anything else is a time
+fn parse_bound(value: &str, to_epoch: fn(&str) -> Result<i64, String>) -> Result<Option<i64>, String> {
+    if value.eq_ignore_ascii_case("none") {
+        Ok(None)
+    } else {
+        to_epoch(value).map(Some)
+    }
+}
+
 fn validate_cache(conn: &Connection) -> Result<(), String> {
     match cache::validate_cache(conn) {
         Ok(true) => Ok(()),
@@ -296,6 +393,7 @@ mod tests {
     #[test]
     fn test_handle_donecmd() {
         let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
         insert_task(&conn, "work", "finish report", "tomorrow");
         let items = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
         cache::store(&conn, &items).unwrap();
@@ -306,7 +404,7 @@ mod tests {
             comment: None,
             close_issue: false,
         };
-        handle_donecmd(&conn, &done_cmd).unwrap();
+        handle_donecmd(&conn, &ctx, &done_cmd).unwrap();
         let item_id = cache::read(&conn, 1).unwrap().unwrap();
         let updated_item = get_item(&conn, item_id).unwrap();
         assert_eq!(updated_item.status, 1);
@@ -322,7 +420,7 @@ mod tests {
             comment: None,
             close_issue: false,
         };
-        handle_donecmd(&conn, &done_cmd).unwrap();
+        handle_donecmd(&conn, &ctx, &done_cmd).unwrap();
         let updated_item = get_item(&conn, item_id).unwrap();
         assert_eq!(updated_item.status, 2);
 
@@ -333,6 +431,7 @@ mod tests {
     #[test]
     fn test_handle_donecmd_with_comment() {
         let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
         insert_task(&conn, "work", "finish report", "tomorrow");
         let items = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
         cache::store(&conn, &items).unwrap();
@@ -343,7 +442,7 @@ mod tests {
             comment: Some("Added extra analysis section".to_string()),
             close_issue: false,
         };
-        handle_donecmd(&conn, &done_cmd).unwrap();
+        handle_donecmd(&conn, &ctx, &done_cmd).unwrap();
         let item_id = cache::read(&conn, 1).unwrap().unwrap();
         let updated_item = get_item(&conn, item_id).unwrap();
 
@@ -362,9 +461,34 @@ mod tests {
         assert_eq!(records[0].category, "work");
     }
 
+    #[test]
+    fn test_complete_item_is_atomic() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let task_id = insert_task(&conn, "work", "finish report", "tomorrow");
+        conn.execute_batch(
+            "CREATE TRIGGER no_records BEFORE INSERT ON items WHEN NEW.action = 'record'
+             BEGIN SELECT RAISE(ABORT, 'no records'); END",
+        )
+        .unwrap();
+
+        let task = get_item(&conn, task_id).unwrap();
+        let result = complete_item(&conn, &ctx, task, 1, None);
+        assert!(result.unwrap_err().contains("Failed to create completion record"));
+        // The task is left open, and its completion is not in the audit log
+        let task = get_item(&conn, task_id).unwrap();
+        assert_eq!(task.status, 0);
+        assert_eq!(task.completed_at, None);
+        let logged: i64 = conn
+            .query_row("SELECT COUNT(*) FROM audit_log WHERE item_id = ?1", [task_id], |row| row.get(0))
+            .unwrap();
+        assert_eq!(logged, 0);
+    }
+
     #[test]
     fn test_handle_updatecmd() {
         let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
         insert_task(&conn, "home", "clean garage", "saturday");
         let items = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
         cache::store(&conn, &items).unwrap();
@@ -379,8 +503,13 @@ mod tests {
             status: None,
             reminder: None,
             project: None,
+            starts: None,
+            ends: None,
+            max_occurrences: None,
+            pause: false,
+            resume: false,
         };
-        handle_updatecmd(&conn, &update_cmd).unwrap();
+        handle_updatecmd(&conn, &ctx, &update_cmd).unwrap();
         let updated_item = get_item(&conn, item_id).unwrap();
         assert_eq!(updated_item.content, "reorganize garage thoroughly");
 
@@ -393,8 +522,13 @@ mod tests {
             status: None,
             reminder: None,
             project: None,
+            starts: None,
+            ends: None,
+            max_occurrences: None,
+            pause: false,
+            resume: false,
         };
-        handle_updatecmd(&conn, &update_cmd).unwrap();
+        handle_updatecmd(&conn, &ctx, &update_cmd).unwrap();
         let updated_item = get_item(&conn, item_id).unwrap();
         assert_eq!(
             updated_item.content,
@@ -410,8 +544,13 @@ mod tests {
             status: Some(3),
             reminder: None,
             project: None,
+            starts: None,
+            ends: None,
+            max_occurrences: None,
+            pause: false,
+            resume: false,
         };
-        handle_updatecmd(&conn, &update_cmd).unwrap();
+        handle_updatecmd(&conn, &ctx, &update_cmd).unwrap();
         let updated_item = get_item(&conn, item_id).unwrap();
         assert_eq!(updated_item.status, 3);
 
@@ -424,8 +563,13 @@ mod tests {
             status: None,
             reminder: None,
             project: None,
+            starts: None,
+            ends: None,
+            max_occurrences: None,
+            pause: false,
+            resume: false,
         };
-        handle_updatecmd(&conn, &update_cmd).unwrap();
+        handle_updatecmd(&conn, &ctx, &update_cmd).unwrap();
         let got_item = get_item(&conn, item_id).unwrap();
         assert_eq!(got_item.category, "chore");
     }
@@ -433,6 +577,7 @@ mod tests {
     #[test]
     fn test_handle_donecmd_recurring_task() {
         let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
         let task_id = insert_recurring_task(&conn, "work", "Daily standup", "Daily 9AM");
         let items = query_items(&conn, &ItemQuery::new().with_action(RECURRING_TASK)).unwrap();
         cache::store(&conn, &items).unwrap();
@@ -443,7 +588,7 @@ mod tests {
             comment: Some("Discussed sprint goals".to_string()),
             close_issue: false,
         };
-        let result = handle_donecmd(&conn, &done_cmd);
+        let result = handle_donecmd(&conn, &ctx, &done_cmd);
         assert!(result.is_ok());
 
         let records =
@@ -463,7 +608,7 @@ mod tests {
             comment: None,
             close_issue: false,
         };
-        let result = handle_donecmd(&conn, &done_cmd2);
+        let result = handle_donecmd(&conn, &ctx, &done_cmd2);
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err(),
@@ -478,6 +623,7 @@ mod tests {
     #[test]
     fn test_handle_updatecmd_recurring_task() {
         let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
         let task_id = insert_recurring_task(&conn, "work", "Daily standup", "Daily 9AM");
         let items = query_items(&conn, &ItemQuery::new().with_action(RECURRING_TASK)).unwrap();
         cache::store(&conn, &items).unwrap();
@@ -491,8 +637,13 @@ mod tests {
             status: None,
             reminder: None,
             project: None,
+            starts: None,
+            ends: None,
+            max_occurrences: None,
+            pause: false,
+            resume: false,
         };
-        let result = handle_updatecmd(&conn, &update_cmd);
+        let result = handle_updatecmd(&conn, &ctx, &update_cmd);
         assert!(result.is_ok());
 
         let updated_item = get_item(&conn, task_id).unwrap();
@@ -509,8 +660,13 @@ mod tests {
             status: None,
             reminder: None,
             project: None,
+            starts: None,
+            ends: None,
+            max_occurrences: None,
+            pause: false,
+            resume: false,
         };
-        let result = handle_updatecmd(&conn, &update_cmd);
+        let result = handle_updatecmd(&conn, &ctx, &update_cmd);
         assert!(result.is_ok());
         let updated_item = get_item(&conn, task_id).unwrap();
         assert_eq!(updated_item.cron_schedule, Some("0 15 * * *".to_string()));
@@ -525,8 +681,13 @@ mod tests {
             status: Some(1),
             reminder: None,
             project: None,
+            starts: None,
+            ends: None,
+            max_occurrences: None,
+            pause: false,
+            resume: false,
         };
-        let result = handle_updatecmd(&conn, &update_cmd);
+        let result = handle_updatecmd(&conn, &ctx, &update_cmd);
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err(),
@@ -542,8 +703,13 @@ mod tests {
             status: None,
             reminder: None,
             project: None,
+            starts: None,
+            ends: None,
+            max_occurrences: None,
+            pause: false,
+            resume: false,
         };
-        let result = handle_updatecmd(&conn, &update_cmd);
+        let result = handle_updatecmd(&conn, &ctx, &update_cmd);
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err(),
@@ -554,6 +720,7 @@ mod tests {
     #[test]
     fn test_block_task_conversions() {
         let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
 
         // Test blocking regular task to recurring conversion
         insert_task(&conn, "work", "finish report", "tomorrow");
@@ -569,8 +736,13 @@ mod tests {
             status: None,
             reminder: None,
             project: None,
+            starts: None,
+            ends: None,
+            max_occurrences: None,
+            pause: false,
+            resume: false,
         };
-        let result = handle_updatecmd(&conn, &update_cmd);
+        let result = handle_updatecmd(&conn, &ctx, &update_cmd);
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Couldn't parse"));
 
@@ -589,8 +761,119 @@ mod tests {
             status: None,
             reminder: None,
             project: None,
+            starts: None,
+            ends: None,
+            max_occurrences: None,
+            pause: false,
+            resume: false,
         };
-        let result = handle_updatecmd(&conn, &update_cmd);
+        let result = handle_updatecmd(&conn, &ctx, &update_cmd);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_handle_updatecmd_recurring_bounds_and_pause() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let task_id = insert_recurring_task(&conn, "work", "Daily standup", "Daily 9AM");
+        let items = query_items(&conn, &ItemQuery::new().with_action(RECURRING_TASK)).unwrap();
+        cache::store(&conn, &items).unwrap();
+
+        let bounds_cmd = UpdateCommand {
+            index: 1,
+            target_time: None,
+            category: None,
+            content: None,
+            add_content: None,
+            status: None,
+            reminder: None,
+            project: None,
+            starts: Some("2025-01-01".to_string()),
+            ends: Some("2099-03-31".to_string()),
+            max_occurrences: Some(10),
+            pause: false,
+            resume: false,
+        };
+        handle_updatecmd(&conn, &ctx, &bounds_cmd).unwrap();
+        let item = get_item(&conn, task_id).unwrap();
+        assert_eq!(item.starts_at, Some(timestr::since_to_unix_epoch("2025-01-01").unwrap()));
+        assert_eq!(item.ends_at, Some(timestr::to_unix_epoch("2099-03-31").unwrap()));
+        assert_eq!(item.max_occurrences, Some(10));
+
+        // "none" and 0 lift the bounds again
+        let lift_cmd = UpdateCommand {
+            starts: Some("none".to_string()),
+            ends: Some("none".to_string()),
+            max_occurrences: Some(0),
+            ..bounds_cmd
+        };
+        handle_updatecmd(&conn, &ctx, &lift_cmd).unwrap();
+        let item = get_item(&conn, task_id).unwrap();
+        assert_eq!((item.starts_at, item.ends_at, item.max_occurrences), (None, None, None));
+
+        let huge_cmd = UpdateCommand {
+            starts: None,
+            ends: None,
+            max_occurrences: Some(1_000_000_000),
+            ..lift_cmd
+        };
+        assert_eq!(handle_updatecmd(&conn, &ctx, &huge_cmd).unwrap_err(), "Occurrence limit cannot exceed 1000");
+        assert_eq!(get_item(&conn, task_id).unwrap().max_occurrences, None);
+
+        // A paused task cannot be done until it is resumed
+        let pause_cmd = UpdateCommand {
+            max_occurrences: None,
+            pause: true,
+            ..huge_cmd
+        };
+        handle_updatecmd(&conn, &ctx, &pause_cmd).unwrap();
+        assert!(get_item(&conn, task_id).unwrap().paused_at.is_some());
+        let done_cmd = DoneCommand {
+            index: 1,
+            status: 1,
+            comment: None,
+            close_issue: false,
+        };
+        assert_eq!(
+            handle_donecmd(&conn, &ctx, &done_cmd).unwrap_err(),
+            "Recurring task is paused, resume it first"
+        );
+        assert!(handle_updatecmd(&conn, &ctx, &pause_cmd).unwrap_err().contains("already paused"));
+
+        let resume_cmd = UpdateCommand {
+            pause: false,
+            resume: true,
+            ..pause_cmd
+        };
+        handle_updatecmd(&conn, &ctx, &resume_cmd).unwrap();
+        assert!(get_item(&conn, task_id).unwrap().paused_at.is_none());
+        handle_donecmd(&conn, &ctx, &done_cmd).unwrap();
+    }
+
+    #[test]
+    fn test_handle_updatecmd_bounds_need_recurring_task() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        insert_task(&conn, "work", "finish report", "tomorrow");
+        let items = query_items(&conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        cache::store(&conn, &items).unwrap();
+
+        let update_cmd = UpdateCommand {
+            index: 1,
+            target_time: None,
+            category: None,
+            content: None,
+            add_content: None,
+            status: None,
+            reminder: None,
+            project: None,
+            starts: None,
+            ends: None,
+            max_occurrences: None,
+            pause: true,
+            resume: false,
+        };
+        let result = handle_updatecmd(&conn, &ctx, &update_cmd);
+        assert!(result.unwrap_err().contains("only apply to recurring tasks"));
+    }
 }
@@ -5,9 +5,10 @@ use crate::{
     args::parser::NoteCommand,
     context::Context,
     db::{
+        audit::{log_change, Change, AUDIT_CREATE, TABLE_NOTES},
         cache,
         crud::get_item,
-        item::{RECORD, RECURRING_TASK_RECORD},
+        item::{Item, RECORD, RECURRING_TASK_RECORD},
         note::add_note,
     },
 };
@@ -17,11 +18,7 @@ pub fn handle_notecmd(conn: &Connection, ctx: &Context, cmd: &NoteCommand) -> Re
     let row_id = get_rowid_from_cache(conn, cmd.index)?;
 
     let item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {:?}", e))?;
-    if item.action == RECORD || item.action == RECURRING_TASK_RECORD {
-        return Err("Cannot add notes to records".to_string());
-    }
-
-    let note_id = add_note(conn, row_id, &cmd.content, Some(ctx.current_user_id))?;
+    let note_id = add_item_note(conn, ctx, &item, &cmd.content)?;
 
     display::print_bold(&format!("Added note #{} to task:", note_id));
     display::print_items(&[item], false, false);
@@ -30,6 +27,19 @@ pub fn handle_notecmd(conn: &Connection, ctx: &Context, cmd: &NoteCommand) -> Re
     Ok(())
 }
 
+/// Adds a note by the current user to a task. Returns the note's ID.
+pub fn add_item_note(conn: &Connection, ctx: &Context, item: &Item, content: &str) -> Result<i64, String> {
+    let row_id = item.id.ok_or_else(|| "Cannot add notes to an item without id".to_string())?;
+    if item.action == RECORD || item.action == RECURRING_TASK_RECORD {
+        return Err("Cannot add notes to records".to_string());
+    }
+
+    let note_id = add_note(conn, row_id, content, Some(ctx.current_user_id))?;
+    let change = Change::new(TABLE_NOTES, AUDIT_CREATE).with_new_value(content);
+    log_change(conn, row_id, item.namespace_id, ctx.current_user_id, change)?;
+    Ok(note_id)
+}
+
 fn validate_cache(conn: &Connection) -> Result<(), String> {
     match cache::validate_cache(conn) {
         Ok(true) => Ok(()),
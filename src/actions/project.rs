@@ -0,0 +1,253 @@
+use chrono::{Local, TimeZone};
+use rusqlite::Connection;
+
+use crate::{
+    actions::{
+        display,
+        list::OPEN_STATUS_CODES,
+    },
+    args::parser::{
+        ProjectAddCommand,
+        ProjectArchiveCommand,
+        ProjectCommand,
+        ProjectListCommand,
+        ProjectRenameCommand,
+        ProjectShowCommand,
+    },
+    config::get_project_config,
+    context::Context,
+    db::project::{
+        count_project_tasks,
+        create_project,
+        get_project,
+        list_projects,
+        rename_project,
+        set_project_archived,
+        Project,
+    },
+};
+
+pub fn handle_project_cmd(conn: &Connection, ctx: &Context, cmd: ProjectCommand) -> Result<(), String> {
+    match cmd {
+        ProjectCommand::Add(add_cmd) => handle_project_add(conn, ctx, &add_cmd),
+        ProjectCommand::List(list_cmd) => handle_project_list(conn, ctx, &list_cmd),
+        ProjectCommand::Show(show_cmd) => handle_project_show(conn, ctx, &show_cmd),
+        ProjectCommand::Rename(rename_cmd) => handle_project_rename(conn, ctx, &rename_cmd),
+        ProjectCommand::Archive(archive_cmd) => handle_project_archive(conn, ctx, &archive_cmd),
+    }
+}
+
+fn handle_project_add(conn: &Connection, ctx: &Context, cmd: &ProjectAddCommand) -> Result<(), String> {
+    if cmd.name.trim().is_empty() {
+        return Err("Project name cannot be empty".to_string());
+    }
+    let project = Project {
+        namespace_id: ctx.current_namespace_id,
+        name: cmd.name.clone(),
+        description: cmd.description.clone(),
+        path: cmd.path.clone(),
+        category: cmd.category.clone(),
+        prompt_template: cmd.prompt_template.clone(),
+        created_by: Some(ctx.current_user_id),
+        ..Default::default()
+    };
+    create_project(conn, &project)?;
+    println!("Added project '{}' to namespace '{}'", cmd.name, ctx.current_namespace_name);
+    Ok(())
+}
+
+fn handle_project_list(conn: &Connection, ctx: &Context, cmd: &ProjectListCommand) -> Result<(), String> {
+    let projects = list_projects(conn, ctx.current_namespace_id, cmd.all)?;
+    if projects.is_empty() {
+        println!("No projects in namespace '{}'.", ctx.current_namespace_name);
+        return Ok(());
+    }
+
+    println!("{:<20} {:<12} {:<10} {:<40}", "Name", "Category", "Status", "Path");
+    println!("{}", "-".repeat(84));
+    for project in projects {
+        let path = local_path(ctx, &project).unwrap_or_else(|| "-".to_string());
+        let status = if project.is_archived() { "archived" } else { "active" };
+        let category = project.category.as_deref().unwrap_or("-");
+        println!("{:<20} {:<12} {:<10} {:<40}", project.name, category, status, path);
+    }
+    Ok(())
+}
+
+fn handle_project_show(conn: &Connection, ctx: &Context, cmd: &ProjectShowCommand) -> Result<(), String> {
+    let project = get_project(conn, ctx.current_namespace_id, &cmd.name)?
+        .ok_or_else(|| format!("Project '{}' not found", cmd.name))?;
+    println!();
+    for line in project_lines(conn, ctx, &project)? {
+        println!("{}", line);
+    }
+    println!();
+    Ok(())
+}
+
+fn handle_project_rename(conn: &Connection, ctx: &Context, cmd: &ProjectRenameCommand) -> Result<(), String> {
+    let moved = rename_project(conn, ctx.current_namespace_id, &cmd.name, &cmd.new_name, ctx.current_user_id)?;
+    println!("Renamed project '{}' to '{}' ({} task(s) moved)", cmd.name, cmd.new_name, moved);
+    if get_project_config(&cmd.name, &ctx.current_namespace_name).is_some() {
+        println!(
+            "Rename projects.{} to projects.{} in ~/.config/ctm/config.json to keep your local settings",
+            cmd.name, cmd.new_name
+        );
+    }
+    Ok(())
+}
+
+fn handle_project_archive(conn: &Connection, ctx: &Context, cmd: &ProjectArchiveCommand) -> Result<(), String> {
+    set_project_archived(conn, ctx.current_namespace_id, &cmd.name, !cmd.restore, ctx.current_user_id)?;
+    if cmd.restore {
+        println!("Restored project '{}'", cmd.name);
+    } else {
+        println!("Archived project '{}'; its tasks are kept", cmd.name);
+    }
+    Ok(())
+}
+
+// The path this user works in: their config's, else the shared one
+fn local_path(ctx: &Context, project: &Project) -> Option<String> {
+    get_project_config(&project.name, &ctx.current_namespace_name)
+        .map(|local| local.path)
+        .filter(|path| !path.is_empty())
+        .or_else(|| project.path.clone())
+}
+
+/// The lines of `ctm project show`: shared settings, local overrides and task counts
+fn project_lines(conn: &Connection, ctx: &Context, project: &Project) -> Result<Vec<String>, String> {
+    let local = get_project_config(&project.name, &ctx.current_namespace_name);
+    let mut lines = vec![
+        display::paint(
+            &format!("Project {} ({})", project.name, ctx.current_namespace_name),
+            display::Style::Heading,
+        ),
+        "━".repeat(50),
+    ];
+    if let Some(description) = &project.description {
+        lines.push(format!("  Description: {}", description));
+    }
+
+    let local_path = local.as_ref().map(|l| l.path.as_str()).filter(|p| !p.is_empty());
+    match (&project.path, local_path) {
+        (Some(shared), Some(path)) if shared != path => {
+            lines.push(format!("  Path:        {} (shared: {})", path, shared))
+        }
+        (_, Some(path)) => lines.push(format!("  Path:        {}", path)),
+        (Some(shared), None) => lines.push(format!("  Path:        {}", shared)),
+        (None, None) => lines.push("  Path:        - (set projects.<name>.path in config)".to_string()),
+    }
+    let category = local.as_ref().and_then(|l| l.category.clone()).or_else(|| project.category.clone());
+    if let Some(category) = category {
+        lines.push(format!("  Category:    {}", category));
+    }
+    let prompt = local
+        .as_ref()
+        .and_then(|l| l.prompt_template.clone())
+        .or_else(|| project.prompt_template.clone());
+    if let Some(prompt) = prompt {
+        lines.push(format!("  Prompt:      {}", prompt));
+    }
+    if let Some(env) = local.as_ref().and_then(|l| l.conda_env.as_deref()) {
+        lines.push(format!("  Conda env:   {}", env));
+    }
+    if let Some(flags) = local.as_ref().and_then(|l| l.claude_flags.as_deref()) {
+        lines.push(format!("  Claude flags: {}", flags));
+    }
+
+    if let Some(archived_at) = project.archived_at {
+        let date = Local
+            .timestamp_opt(archived_at, 0)
+            .single()
+            .map(|dt| dt.format("%Y-%m-%d").to_string())
+            .unwrap_or_default();
+        lines.push(format!("  Archived:    {}", date));
+    }
+    let (open, closed) = count_project_tasks(conn, project, OPEN_STATUS_CODES)?;
+    lines.push(format!("  Tasks:       {} open, {} closed", open, closed));
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        actions::addition::handle_taskcmd,
+        args::parser::TaskCommand,
+        db::{
+            crud::{get_item, update_item},
+            item::ItemQuery,
+        },
+        tests::get_test_conn,
+    };
+
+    fn add_cmd(name: &str) -> ProjectAddCommand {
+        ProjectAddCommand {
+            name: name.to_string(),
+            description: Some("The public API".to_string()),
+            path: Some("/srv/api".to_string()),
+            category: Some("backend".to_string()),
+            prompt_template: None,
+        }
+    }
+
+    fn task_cmd(content: &str, project: &str) -> TaskCommand {
+        TaskCommand {
+            content: content.to_string(),
+            timestr: Some("tomorrow".to_string()),
+            category: None,
+            reminder: None,
+            project: Some(project.to_string()),
+            assignee: None,
+            priority: None,
+            estimate: None,
+            from_issue: None,
+        }
+    }
+
+    #[test]
+    fn test_tasks_take_project_category() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        handle_project_add(&conn, &ctx, &add_cmd("api")).unwrap();
+        assert!(handle_project_add(&conn, &ctx, &add_cmd("api")).is_err());
+
+        handle_taskcmd(&conn, &ctx, &task_cmd("Add rate limits", "api")).unwrap();
+        let tasks = crate::db::crud::query_items(&conn, &ItemQuery::new().with_project("api")).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].category, "backend");
+
+        let err = handle_taskcmd(&conn, &ctx, &task_cmd("Nope", "web")).unwrap_err();
+        assert!(err.contains("Project 'web' not found in namespace 'default'"));
+    }
+
+    #[test]
+    fn test_project_show_and_archive() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        handle_project_add(&conn, &ctx, &add_cmd("api")).unwrap();
+        handle_taskcmd(&conn, &ctx, &task_cmd("Add rate limits", "api")).unwrap();
+        handle_taskcmd(&conn, &ctx, &task_cmd("Fix pagination", "api")).unwrap();
+        let tasks = crate::db::crud::query_items(&conn, &ItemQuery::new().with_project("api")).unwrap();
+        let mut done = get_item(&conn, tasks[0].id.unwrap()).unwrap();
+        done.status = 1;
+        update_item(&conn, &done).unwrap();
+
+        let project = get_project(&conn, ctx.current_namespace_id, "api").unwrap().unwrap();
+        let lines = project_lines(&conn, &ctx, &project).unwrap().join("\n");
+        assert!(lines.contains("Description: The public API"));
+        assert!(lines.contains("Path:        /srv/api"));
+        assert!(lines.contains("Tasks:       1 open, 1 closed"));
+
+        let archive = ProjectArchiveCommand {
+            name: "api".to_string(),
+            restore: false,
+        };
+        handle_project_archive(&conn, &ctx, &archive).unwrap();
+        let err = handle_taskcmd(&conn, &ctx, &task_cmd("More", "api")).unwrap_err();
+        assert_eq!(err, "Project 'api' is archived");
+        let project = get_project(&conn, ctx.current_namespace_id, "api").unwrap().unwrap();
+        assert!(project_lines(&conn, &ctx, &project).unwrap().iter().any(|l| l.contains("Archived:")));
+    }
+}
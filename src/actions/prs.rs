@@ -0,0 +1,407 @@
+use chrono::Local;
+use rusqlite::Connection;
+use serde_json::{json, Value};
+
+use crate::{
+    actions::{
+        display::{self, paint, paint_opt, Style},
+        list::OPEN_STATUS_CODES,
+    },
+    args::parser::PrsCommand,
+    config::{get_github_config, get_pr_status_ttl},
+    context::Context,
+    db::{
+        cache,
+        crud::query_items,
+        item::{Item, ItemQuery, TASK},
+        link::{get_links_by_type, LINK_TYPE_PR},
+        pr_status::{get_pr_status, save_pr_status, CachedPrStatus},
+    },
+    forge::parse_forge_ref,
+    github::{forge_client, ChecksState, ForgeClient, PrStatus},
+};
+
+/// When a cached pull request status is good enough
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Freshness {
+    // Fetch when the cached status is older than this many seconds
+    MaxAge(i64),
+    // Always fetch
+    Refresh,
+}
+
+/// A task's linked pull requests, each with its status if one is known
+pub type TaskPrs = Vec<(String, Option<CachedPrStatus>)>;
+
+/// Handles the prs command - tasks with linked pull requests and where those stand
+pub fn handle_prscmd(conn: &Connection, ctx: &Context, cmd: &PrsCommand) -> Result<(), String> {
+    let freshness = if cmd.refresh {
+        Freshness::Refresh
+    } else {
+        Freshness::MaxAge(get_pr_status_ttl())
+    };
+    // Without a way to reach GitHub, cached statuses are all there is
+    let client = if cmd.offline {
+        None
+    } else {
+        forge_client(&get_github_config()).ok()
+    };
+    let rows = build_pr_rows(
+        conn,
+        ctx.current_namespace_id,
+        client.as_deref(),
+        cmd.all,
+        freshness,
+        Local::now().timestamp(),
+    )?;
+
+    if rows.is_empty() {
+        display::print_bold("No tasks with linked pull requests");
+        return Ok(());
+    }
+
+    // Index the tasks so that `ctm done` and `ctm show` work on this view
+    let items: Vec<Item> = rows.iter().map(|(item, _)| item.clone()).collect();
+    cache::clear(conn).map_err(|e| e.to_string())?;
+    cache::store(conn, &items).map_err(|e| e.to_string())?;
+
+    if cmd.json {
+        print_prs_json(&rows);
+    } else {
+        print_prs_text(&rows);
+    }
+    Ok(())
+}
+
+/// Tasks in the namespace with at least one linked pull request, open tasks
+/// only unless `all`
+pub fn build_pr_rows(
+    conn: &Connection,
+    namespace_id: i64,
+    client: Option<&dyn ForgeClient>,
+    all: bool,
+    freshness: Freshness,
+    now: i64,
+) -> Result<Vec<(Item, TaskPrs)>, String> {
+    let mut query = ItemQuery::new().with_action(TASK).with_namespace_id(namespace_id);
+    if !all {
+        query = query.with_statuses(OPEN_STATUS_CODES.to_vec());
+    }
+    let tasks = query_items(conn, &query).map_err(|e| format!("Failed to query tasks: {:?}", e))?;
+
+    let mut rows = Vec::new();
+    for item in tasks {
+        let prs = task_pr_statuses(conn, client, item.id.unwrap(), freshness, now)?;
+        if !prs.is_empty() {
+            rows.push((item, prs));
+        }
+    }
+    Ok(rows)
+}
+
+/// The statuses of the pull requests linked to a task
+pub fn task_pr_statuses(
+    conn: &Connection,
+    client: Option<&dyn ForgeClient>,
+    item_id: i64,
+    freshness: Freshness,
+    now: i64,
+) -> Result<TaskPrs, String> {
+    get_links_by_type(conn, item_id, LINK_TYPE_PR)?
+        .into_iter()
+        .map(|link| {
+            let status = pr_status(conn, client, &link.reference, freshness, now)?;
+            Ok((link.reference, status))
+        })
+        .collect()
+}
+
+/// The status of a pull request, fetched when the cached one is not fresh
+/// enough. With no client, or if fetching fails, e.g. offline, the cached
+/// one is used however old it is.
+pub fn pr_status(
+    conn: &Connection,
+    client: Option<&dyn ForgeClient>,
+    reference: &str,
+    freshness: Freshness,
+    now: i64,
+) -> Result<Option<CachedPrStatus>, String> {
+    let cached = get_pr_status(conn, reference)?;
+    let fresh = match freshness {
+        Freshness::MaxAge(ttl) => cached.as_ref().is_some_and(|c| now - c.fetched_at < ttl),
+        Freshness::Refresh => false,
+    };
+    let Some(client) = client.filter(|_| !fresh) else {
+        return Ok(cached);
+    };
+    // Only GitHub pull requests have a status to look up
+    let Ok(pr_ref) = parse_forge_ref(reference).and_then(|r| r.to_issue_ref()) else {
+        return Ok(cached);
+    };
+    match client.get_pr_status(&pr_ref) {
+        Ok(status) => {
+            let fetched = CachedPrStatus {
+                reference: reference.to_string(),
+                status,
+                fetched_at: now,
+            };
+            save_pr_status(conn, &fetched)?;
+            Ok(Some(fetched))
+        }
+        Err(_) => Ok(cached),
+    }
+}
+
+/// An open task whose pull requests have all merged or closed, at least one merged
+pub fn ready_to_close(item: &Item, prs: &TaskPrs) -> bool {
+    let statuses: Vec<&PrStatus> = prs.iter().filter_map(|(_, c)| c.as_ref().map(|c| &c.status)).collect();
+    OPEN_STATUS_CODES.contains(&item.status)
+        && statuses.len() == prs.len()
+        && statuses.iter().any(|s| s.is_merged())
+        && !statuses.iter().any(|s| s.is_open())
+}
+
+/// One line on where a pull request stands, e.g.
+/// "open · approved · checks passing · mergeable"
+pub fn describe_pr_status(status: &PrStatus) -> String {
+    let state = status.state.to_lowercase();
+    let mut parts = vec![match state.as_str() {
+        "merged" => paint(&state, Style::StatusDone),
+        "closed" => paint(&state, Style::StatusInactive),
+        _ => state.clone(),
+    }];
+    if let Some(decision) = &status.review_decision {
+        let text = decision.to_lowercase().replace('_', " ");
+        let style = match decision.as_str() {
+            "APPROVED" => Some(Style::StatusDone),
+            "CHANGES_REQUESTED" => Some(Style::Warning),
+            _ => None,
+        };
+        parts.push(paint_opt(&text, style));
+    }
+    let checks = format!("checks {}", status.checks.as_str());
+    parts.push(match status.checks {
+        ChecksState::Passing => paint(&checks, Style::StatusDone),
+        ChecksState::Failing => paint(&checks, Style::Error),
+        ChecksState::Pending => checks,
+        ChecksState::None => "no checks".to_string(),
+    });
+    // Mergeability only matters while the pull request is open
+    if status.is_open() {
+        match status.mergeable.as_str() {
+            "MERGEABLE" => parts.push("mergeable".to_string()),
+            "CONFLICTING" => parts.push(paint("conflicting", Style::Warning)),
+            _ => {}
+        }
+    }
+    parts.join(" · ")
+}
+
+/// How long ago a status was fetched, e.g. "5m ago"
+fn describe_age(fetched_at: i64, now: i64) -> String {
+    let age = (now - fetched_at).max(0);
+    if age < 60 {
+        "just now".to_string()
+    } else if age < 3600 {
+        format!("{}m ago", age / 60)
+    } else if age < 86400 {
+        format!("{}h ago", age / 3600)
+    } else {
+        format!("{}d ago", age / 86400)
+    }
+}
+
+fn print_prs_text(rows: &[(Item, TaskPrs)]) {
+    let now = Local::now().timestamp();
+    for (index, (item, prs)) in rows.iter().enumerate() {
+        let title = item.content.lines().next().unwrap_or("");
+        let mut header = format!("{:>3}  {}", index + 1, title);
+        if ready_to_close(item, prs) {
+            header.push_str(&format!("  {}", paint("ready to close", Style::StatusDone)));
+        }
+        println!("{}", header);
+        for (reference, cached) in prs {
+            let line = match cached {
+                Some(cached) => format!(
+                    "{}  {}",
+                    describe_pr_status(&cached.status),
+                    paint(&format!("({})", describe_age(cached.fetched_at, now)), Style::Label)
+                ),
+                None => paint("status unknown", Style::StatusInactive),
+            };
+            println!("     {} {}", paint_opt(reference, Some(Style::LinkPr)), line);
+        }
+    }
+}
+
+fn print_prs_json(rows: &[(Item, TaskPrs)]) {
+    let output: Vec<Value> = rows
+        .iter()
+        .enumerate()
+        .map(|(index, (item, prs))| {
+            let ready = ready_to_close(item, prs);
+            let prs: Vec<Value> = prs
+                .iter()
+                .map(|(reference, cached)| match cached {
+                    Some(cached) => json!({
+                        "reference": reference,
+                        "title": cached.status.title,
+                        "url": cached.status.url,
+                        "state": cached.status.state,
+                        "review_decision": cached.status.review_decision,
+                        "checks": cached.status.checks.as_str(),
+                        "mergeable": cached.status.mergeable,
+                        "fetched_at": cached.fetched_at,
+                    }),
+                    None => json!({ "reference": reference }),
+                })
+                .collect();
+            json!({
+                "index": index + 1,
+                "id": item.id,
+                "content": item.content,
+                "status": item.status,
+                "ready_to_close": ready,
+                "prs": prs,
+            })
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{link::add_link, namespace::create_namespace},
+        tests::{get_test_conn, insert_task, update_status, StubGh},
+    };
+
+    fn client(stub: &StubGh) -> Option<&dyn ForgeClient> {
+        Some(&stub.gh)
+    }
+
+    fn pr_json(state: &str, checks: &str) -> String {
+        format!(
+            r#"{{"title": "PR", "url": "u", "state": "{}", "reviewDecision": "APPROVED",
+                 "mergeable": "MERGEABLE", "statusCheckRollup": [{}]}}"#,
+            state, checks
+        )
+    }
+
+    #[test]
+    fn test_pr_status_uses_cache_within_ttl() {
+        let (conn, _temp_file) = get_test_conn();
+        let stub = StubGh::default();
+        stub.respond("pr", 7, &pr_json("OPEN", r#"{"status": "IN_PROGRESS", "conclusion": ""}"#));
+
+        let fetched = pr_status(&conn, client(&stub), "o/r#7", Freshness::MaxAge(300), 1000).unwrap().unwrap();
+        assert_eq!(fetched.status.checks, ChecksState::Pending);
+        assert_eq!(stub.calls().len(), 1);
+
+        // Fresh enough: no second call, even though the PR moved on
+        stub.respond("pr", 7, &pr_json("MERGED", r#"{"status": "COMPLETED", "conclusion": "SUCCESS"}"#));
+        let cached = pr_status(&conn, client(&stub), "o/r#7", Freshness::MaxAge(300), 1200).unwrap().unwrap();
+        assert_eq!(cached.status.state, "OPEN");
+        assert_eq!(stub.calls().len(), 1);
+        assert_eq!(pr_status(&conn, None, "o/r#7", Freshness::Refresh, 5000).unwrap(), Some(cached));
+
+        // Stale: fetched again
+        let refreshed = pr_status(&conn, client(&stub), "o/r#7", Freshness::MaxAge(300), 1400).unwrap().unwrap();
+        assert!(refreshed.status.is_merged());
+        assert_eq!(refreshed.status.checks, ChecksState::Passing);
+        assert_eq!(stub.calls().len(), 2);
+    }
+
+    #[test]
+    fn test_pr_status_falls_back_to_stale_cache() {
+        let (conn, _temp_file) = get_test_conn();
+        let stub = StubGh::default();
+        assert_eq!(pr_status(&conn, client(&stub), "o/r#7", Freshness::Refresh, 1000).unwrap(), None);
+
+        stub.respond("pr", 7, &pr_json("OPEN", ""));
+        pr_status(&conn, client(&stub), "o/r#7", Freshness::Refresh, 1000).unwrap();
+        // gh fails from now on, as when offline
+        std::fs::remove_file(stub.dir.path().join("pr-7.json")).unwrap();
+        let stale = pr_status(&conn, client(&stub), "o/r#7", Freshness::Refresh, 9000).unwrap().unwrap();
+        assert_eq!(stale.fetched_at, 1000);
+    }
+
+    #[test]
+    fn test_ready_to_close() {
+        let (conn, _temp_file) = get_test_conn();
+        let stub = StubGh::default();
+        let merged = insert_task(&conn, "work", "Merged", "today");
+        add_link(&conn, merged, LINK_TYPE_PR, "o/r#1", None, None).unwrap();
+        add_link(&conn, merged, LINK_TYPE_PR, "o/r#2", None, None).unwrap();
+        let waiting = insert_task(&conn, "work", "Waiting", "today");
+        add_link(&conn, waiting, LINK_TYPE_PR, "o/r#3", None, None).unwrap();
+        insert_task(&conn, "work", "No PR", "today");
+        let done = insert_task(&conn, "work", "Done", "today");
+        add_link(&conn, done, LINK_TYPE_PR, "o/r#4", None, None).unwrap();
+        update_status(&conn, done, 1);
+        let ctx = Context::default_from_db(&conn).unwrap();
+        conn.execute("UPDATE items SET namespace_id = ?1", [ctx.current_namespace_id])
+            .unwrap();
+
+        stub.respond("pr", 1, &pr_json("MERGED", ""));
+        stub.respond("pr", 2, &pr_json("CLOSED", ""));
+        stub.respond("pr", 3, &pr_json("OPEN", ""));
+
+        let ns = ctx.current_namespace_id;
+        let rows = build_pr_rows(&conn, ns, client(&stub), false, Freshness::MaxAge(300), 1000).unwrap();
+        let summary: Vec<(&str, bool)> = rows
+            .iter()
+            .map(|(item, prs)| (item.content.as_str(), ready_to_close(item, prs)))
+            .collect();
+        assert_eq!(summary.len(), 2);
+        assert!(summary.contains(&("Merged", true)));
+        assert!(summary.contains(&("Waiting", false)));
+        assert_eq!(build_pr_rows(&conn, ns, None, true, Freshness::Refresh, 1000).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_pr_rows_scoped_to_namespace() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let other = create_namespace(&conn, "other", None, ctx.current_user_id).unwrap();
+        let mine = insert_task(&conn, "work", "Mine", "today");
+        add_link(&conn, mine, LINK_TYPE_PR, "o/r#1", None, None).unwrap();
+        let theirs = insert_task(&conn, "work", "Theirs", "today");
+        add_link(&conn, theirs, LINK_TYPE_PR, "o/r#2", None, None).unwrap();
+        conn.execute(
+            "UPDATE items SET namespace_id = ?1 WHERE id = ?2",
+            rusqlite::params![ctx.current_namespace_id, mine],
+        )
+        .unwrap();
+        conn.execute("UPDATE items SET namespace_id = ?1 WHERE id = ?2", rusqlite::params![other, theirs])
+            .unwrap();
+
+        let rows = build_pr_rows(&conn, ctx.current_namespace_id, None, true, Freshness::Refresh, 1000).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].0.content, "Mine");
+        let rows = build_pr_rows(&conn, other, None, true, Freshness::Refresh, 1000).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].0.content, "Theirs");
+    }
+
+    #[test]
+    fn test_describe_pr_status() {
+        let status = PrStatus {
+            title: "Add SSO".to_string(),
+            url: String::new(),
+            state: "OPEN".to_string(),
+            review_decision: Some("CHANGES_REQUESTED".to_string()),
+            checks: ChecksState::Failing,
+            mergeable: "CONFLICTING".to_string(),
+        };
+        assert_eq!(describe_pr_status(&status), "open · changes requested · checks failing · conflicting");
+        let merged = PrStatus {
+            state: "MERGED".to_string(),
+            review_decision: None,
+            checks: ChecksState::None,
+            ..status
+        };
+        assert_eq!(describe_pr_status(&merged), "merged · no checks");
+    }
+}
@@ -0,0 +1,563 @@
+use chrono::Local;
+use rusqlite::Connection;
+
+use crate::{
+    actions::display::{self, timestamp_to_display_string},
+    args::{cron, parser::SkipCommand},
+    context::Context,
+    db::{
+        audit::{log_change, Change, AUDIT_UPDATE, TABLE_ITEMS},
+        cache,
+        crud::{get_item, insert_item, query_items},
+        item::{Item, ItemQuery, RECURRING_TASK, RECURRING_TASK_RECORD, SKIPPED_RECORD_STATUS},
+        metrics::RecurringHistory,
+    },
+};
+
+/// How one interval of a recurring task turned out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntervalState {
+    Fulfilled,
+    Skipped,
+    Missed,
+    // The current interval, not yet fulfilled but not missed either
+    Due,
+}
+
+/// An interval of a recurring task, from one occurrence to the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval {
+    pub start: i64,
+    pub end: i64,
+    pub state: IntervalState,
+}
+
+/// Where a recurring task stands against its bounds and pause
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleState {
+    Active,
+    Paused,
+    // The current interval began before the start date
+    NotStarted,
+    // The current interval began after the final occurrence
+    Ended,
+}
+
+/// Runs of fulfilled intervals; skipped intervals neither extend nor break a run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Streaks {
+    pub current: usize,
+    pub best: usize,
+}
+
+/// Handles the skip command - passes on the current interval of a recurring task
+pub fn handle_skipcmd(conn: &Connection, ctx: &Context, cmd: &SkipCommand) -> Result<(), String> {
+    let row_id = cache::rowid_for_index(conn, cmd.index)?;
+
+    let item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {:?}", e))?;
+    let record = skip_item(conn, ctx, &item)?;
+
+    display::print_bold(&format!(
+        "Skipped Recurring Task until {}:",
+        timestamp_to_display_string(record.good_until.unwrap(), false)
+    ));
+    display::print_items(&[item], false, false);
+    Ok(())
+}
+
+/// Writes a skip record covering the current interval of a recurring task
+pub fn skip_item(conn: &Connection, ctx: &Context, item: &Item) -> Result<Item, String> {
+    if item.action != RECURRING_TASK {
+        return Err("Only recurring tasks can be skipped".to_string());
+    }
+    let row_id = item.id.ok_or_else(|| "Cannot skip an item without id".to_string())?;
+    let cron_schedule = item
+        .cron_schedule
+        .as_ref()
+        .ok_or_else(|| "Recurring task missing cron schedule".to_string())?;
+    ensure_active(item, Local::now().timestamp())?;
+
+    let last_occurrence = cron::get_last_occurrence(cron_schedule)?;
+    let existing_records = query_items(
+        conn,
+        &ItemQuery::new()
+            .with_action(RECURRING_TASK_RECORD)
+            .with_recurring_task_id(row_id)
+            .with_good_until_min(last_occurrence),
+    )
+    .map_err(|e| format!("Failed to query existing records: {:?}", e))?;
+    if !existing_records.is_empty() {
+        return Err("This recurring task has already been completed or skipped for this iteration".to_string());
+    }
+
+    let next_occurrence = cron::get_next_occurrence(cron_schedule)?;
+    let mut skip_record = Item::create_recurring_record(
+        item.category.clone(),
+        format!("Skipped Recurring Task: {}", item.content),
+        row_id,
+        next_occurrence,
+    );
+    skip_record.status = SKIPPED_RECORD_STATUS;
+    skip_record.owner_id = item.owner_id;
+    skip_record.namespace_id = item.namespace_id;
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    insert_item(&tx, &skip_record).map_err(|e| format!("Failed to create skip record: {:?}", e))?;
+
+    let change = Change::new(TABLE_ITEMS, AUDIT_UPDATE).with_field(
+        "skipped_until",
+        None,
+        Some(next_occurrence.to_string()),
+    );
+    log_change(&tx, row_id, item.namespace_id, ctx.current_user_id, change)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(skip_record)
+}
+
+/// Pauses a recurring task; until resumed it has no intervals to miss
+pub fn pause_item(item: &mut Item, now: i64) -> Result<(), String> {
+    if item.paused_at.is_some() {
+        return Err("Recurring task is already paused".to_string());
+    }
+    item.paused_at = Some(now);
+    Ok(())
+}
+
+/// Resumes a paused recurring task. The intervals that ended while it was
+/// paused get skip records, so they stay out of streaks and adherence.
+/// The caller saves the task in the same transaction as the records.
+pub fn resume_item(conn: &Connection, item: &mut Item, now: i64) -> Result<(), String> {
+    let paused_at = item
+        .paused_at
+        .ok_or_else(|| "Recurring task is not paused".to_string())?;
+    let history = load_history(conn, item, paused_at)?;
+    for interval in interval_history(&history, paused_at, now + 1, now)? {
+        if interval.end > now {
+            continue;
+        }
+        let mut skip_record = Item::create_recurring_record(
+            item.category.clone(),
+            format!("Paused Recurring Task: {}", item.content),
+            history.id,
+            interval.end,
+        );
+        skip_record.status = SKIPPED_RECORD_STATUS;
+        skip_record.owner_id = item.owner_id;
+        skip_record.namespace_id = item.namespace_id;
+        insert_item(conn, &skip_record).map_err(|e| format!("Failed to create skip record: {:?}", e))?;
+    }
+    item.paused_at = None;
+    Ok(())
+}
+
+pub fn schedule_state(item: &Item, now: i64) -> Result<ScheduleState, String> {
+    if item.paused_at.is_some() {
+        return Ok(ScheduleState::Paused);
+    }
+    let cron_schedule = item
+        .cron_schedule
+        .as_ref()
+        .ok_or_else(|| "Recurring task missing cron schedule".to_string())?;
+    let last_occurrence = cron::get_occurrence_before(cron_schedule, now + 1)?;
+    if item.starts_at.is_some_and(|starts_at| last_occurrence < starts_at) {
+        return Ok(ScheduleState::NotStarted);
+    }
+    let final_occurrence = final_occurrence(
+        cron_schedule,
+        item.starts_at.unwrap_or(item.create_time),
+        item.ends_at,
+        item.max_occurrences,
+    )?;
+    if final_occurrence.is_some_and(|f| last_occurrence > f) {
+        return Ok(ScheduleState::Ended);
+    }
+    Ok(ScheduleState::Active)
+}
+
+/// Only an active recurring task has a current interval to complete or skip
+pub fn ensure_active(item: &Item, now: i64) -> Result<(), String> {
+    match schedule_state(item, now)? {
+        ScheduleState::Active => Ok(()),
+        ScheduleState::Paused => Err("Recurring task is paused, resume it first".to_string()),
+        ScheduleState::NotStarted => Err("Recurring task has not started yet".to_string()),
+        ScheduleState::Ended => Err("Recurring task has ended".to_string()),
+    }
+}
+
+/// The last occurrence allowed by the end date and the occurrence limit,
+/// counting occurrences from `start`. None when the task runs forever.
+pub fn final_occurrence(
+    cron_schedule: &str,
+    start: i64,
+    ends_at: Option<i64>,
+    max_occurrences: Option<i64>,
+) -> Result<Option<i64>, String> {
+    let mut last = match ends_at {
+        Some(ends_at) => Some(cron::get_occurrence_before(cron_schedule, ends_at + 1)?),
+        None => None,
+    };
+    if let Some(max_occurrences) = max_occurrences {
+        let mut occurrence = start - 1;
+        // Limits stored before they were bounded are capped like listed windows
+        for _ in 0..max_occurrences.min(cron::MAX_OCCURRENCES as i64) {
+            occurrence = cron::get_occurrence_after(cron_schedule, occurrence)?;
+        }
+        last = Some(last.map_or(occurrence, |last| last.min(occurrence)));
+    }
+    Ok(last)
+}
+
+/// Occurrences from `start` (inclusive) to `end` (exclusive) within the
+/// bounds of a recurring task, leaving out those since it was paused
+pub fn bounded_occurrences(item: &Item, start: i64, end: i64) -> Result<Vec<i64>, String> {
+    let cron_schedule = item
+        .cron_schedule
+        .as_ref()
+        .ok_or_else(|| "Recurring task missing cron schedule".to_string())?;
+    let final_occurrence = final_occurrence(
+        cron_schedule,
+        item.starts_at.unwrap_or(item.create_time),
+        item.ends_at,
+        item.max_occurrences,
+    )?;
+    let start = start.max(item.starts_at.unwrap_or(start));
+    let mut end = end.min(item.paused_at.unwrap_or(end));
+    if let Some(final_occurrence) = final_occurrence {
+        end = end.min(final_occurrence + 1);
+    }
+    if start >= end {
+        return Ok(Vec::new());
+    }
+    cron::get_occurrences_between(cron_schedule, start, end)
+}
+
+/// Walks the intervals of a recurring task that start in the period. An interval
+/// runs from one occurrence to the next and is fulfilled or skipped by a record
+/// whose `good_until` is its end. Intervals outside the task's life and bounds
+/// are left out, and those since it was paused count as skipped.
+pub fn interval_history(history: &RecurringHistory, from: i64, to: i64, now: i64) -> Result<Vec<Interval>, String> {
+    let start = from.max(history.create_time).max(history.starts_at.unwrap_or(from));
+    let mut end = to.min(now + 1);
+    let final_occurrence = final_occurrence(
+        &history.cron_schedule,
+        history.starts_at.unwrap_or(history.create_time),
+        history.ends_at,
+        history.max_occurrences,
+    )?;
+    if let Some(final_occurrence) = final_occurrence {
+        end = end.min(final_occurrence + 1);
+    }
+    let mut intervals = Vec::new();
+    if start >= end {
+        return Ok(intervals);
+    }
+
+    let mut occurrence = cron::get_occurrence_after(&history.cron_schedule, start - 1)?;
+    while occurrence < end {
+        let next = cron::get_occurrence_after(&history.cron_schedule, occurrence)?;
+        let covers = |until: &i64| *until > occurrence && *until <= next;
+        let state = if history.fulfilled_until.iter().any(covers) {
+            IntervalState::Fulfilled
+        } else if history.skipped_until.iter().any(covers)
+            || history.paused_at.is_some_and(|paused_at| occurrence >= paused_at)
+        {
+            IntervalState::Skipped
+        } else if next > now {
+            IntervalState::Due
+        } else {
+            IntervalState::Missed
+        };
+        intervals.push(Interval {
+            start: occurrence,
+            end: next,
+            state,
+        });
+        occurrence = next;
+    }
+    Ok(intervals)
+}
+
+/// Every interval of a recurring task since it was created, up to the current one
+pub fn task_intervals(conn: &Connection, item: &Item, now: i64) -> Result<Vec<Interval>, String> {
+    let history = load_history(conn, item, item.create_time)?;
+    interval_history(&history, item.create_time, now + 1, now)
+}
+
+/// Whether the interval before the current one ended without a completion or skip
+pub fn missed_last_interval(conn: &Connection, item: &Item, now: i64) -> Result<bool, String> {
+    let cron_schedule = item
+        .cron_schedule
+        .as_ref()
+        .ok_or_else(|| "Recurring task missing cron schedule".to_string())?;
+    let last_occurrence = cron::get_occurrence_before(cron_schedule, now + 1)?;
+    let previous = cron::get_occurrence_before(cron_schedule, last_occurrence)?;
+
+    let history = load_history(conn, item, previous)?;
+    let intervals = interval_history(&history, previous, last_occurrence, now)?;
+    Ok(intervals.iter().any(|i| i.state == IntervalState::Missed))
+}
+
+pub fn streaks(intervals: &[Interval]) -> Streaks {
+    let mut streaks = Streaks::default();
+    for interval in intervals {
+        match interval.state {
+            IntervalState::Fulfilled => {
+                streaks.current += 1;
+                streaks.best = streaks.best.max(streaks.current);
+            }
+            IntervalState::Missed => streaks.current = 0,
+            IntervalState::Skipped | IntervalState::Due => {}
+        }
+    }
+    streaks
+}
+
+// Reads the records of one recurring task whose interval ends after `from`
+fn load_history(conn: &Connection, item: &Item, from: i64) -> Result<RecurringHistory, String> {
+    let id = item.id.ok_or_else(|| "Recurring task without id".to_string())?;
+    let cron_schedule = item
+        .cron_schedule
+        .clone()
+        .ok_or_else(|| "Recurring task missing cron schedule".to_string())?;
+    let records = query_items(
+        conn,
+        &ItemQuery::new()
+            .with_action(RECURRING_TASK_RECORD)
+            .with_recurring_task_id(id)
+            .with_good_until_min(from),
+    )
+    .map_err(|e| e.to_string())?;
+
+    let mut history = RecurringHistory {
+        id,
+        content: item.content.clone(),
+        category: item.category.clone(),
+        cron_schedule,
+        create_time: item.create_time,
+        starts_at: item.starts_at,
+        ends_at: item.ends_at,
+        max_occurrences: item.max_occurrences,
+        paused_at: item.paused_at,
+        fulfilled_until: Vec::new(),
+        skipped_until: Vec::new(),
+    };
+    for record in records {
+        let Some(good_until) = record.good_until else {
+            continue;
+        };
+        if record.status == SKIPPED_RECORD_STATUS {
+            history.skipped_until.push(good_until);
+        } else {
+            history.fulfilled_until.push(good_until);
+        }
+    }
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        actions::modify::complete_item,
+        tests::{get_test_conn, insert_recurring_record, insert_recurring_task, insert_task},
+    };
+
+    fn history(cron_schedule: &str, fulfilled_until: Vec<i64>, skipped_until: Vec<i64>) -> RecurringHistory {
+        RecurringHistory {
+            id: 1,
+            content: "standup".to_string(),
+            category: "work".to_string(),
+            cron_schedule: cron_schedule.to_string(),
+            create_time: 0,
+            starts_at: None,
+            ends_at: None,
+            max_occurrences: None,
+            paused_at: None,
+            fulfilled_until,
+            skipped_until,
+        }
+    }
+
+    #[test]
+    fn test_interval_history_and_streaks() {
+        let now = Local::now().timestamp();
+        let last = cron::get_occurrence_before("0 9 * * *", now + 1).unwrap();
+        let from = last - 5 * 86400;
+        let ends: Vec<i64> = (1..=5).map(|i| from + i * 86400).collect();
+
+        // Done, done, missed, skipped, done, then the current interval
+        let h = history("0 9 * * *", vec![ends[0], ends[1], ends[4]], vec![ends[3]]);
+        let intervals = interval_history(&h, from, now + 1, now).unwrap();
+        let states: Vec<IntervalState> = intervals.iter().map(|i| i.state).collect();
+        assert_eq!(
+            states,
+            vec![
+                IntervalState::Fulfilled,
+                IntervalState::Fulfilled,
+                IntervalState::Missed,
+                IntervalState::Skipped,
+                IntervalState::Fulfilled,
+                IntervalState::Due,
+            ]
+        );
+        assert_eq!(streaks(&intervals), Streaks { current: 1, best: 2 });
+
+        // Intervals before the task was created are left out
+        let h = RecurringHistory { create_time: last - 60, ..h };
+        let intervals = interval_history(&h, from, now + 1, now).unwrap();
+        assert_eq!(intervals.len(), 1);
+        assert_eq!(intervals[0].state, IntervalState::Due);
+    }
+
+    #[test]
+    fn test_skip_covers_current_interval() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let id = insert_recurring_task(&conn, "work", "standup", "daily 9am");
+        conn.execute(
+            "UPDATE items SET owner_id = ?1, namespace_id = ?2 WHERE id = ?3",
+            rusqlite::params![ctx.current_user_id, ctx.current_namespace_id, id],
+        )
+        .unwrap();
+        let item = get_item(&conn, id).unwrap();
+
+        let record = skip_item(&conn, &ctx, &item).unwrap();
+        assert_eq!(record.status, SKIPPED_RECORD_STATUS);
+        let records = query_items(
+            &conn,
+            &ItemQuery::new().with_action(RECURRING_TASK_RECORD).with_recurring_task_id(id),
+        )
+        .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].status, SKIPPED_RECORD_STATUS);
+        assert_eq!(records[0].owner_id, Some(ctx.current_user_id));
+        assert_eq!(records[0].namespace_id, Some(ctx.current_namespace_id));
+
+        // The interval is taken care of, so neither skip nor done applies again
+        assert!(skip_item(&conn, &ctx, &item).unwrap_err().contains("already"));
+        assert!(complete_item(&conn, &ctx, item, 1, None).unwrap_err().contains("already"));
+    }
+
+    #[test]
+    fn test_skip_rejects_plain_task() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let id = insert_task(&conn, "work", "report", "tomorrow");
+        let item = get_item(&conn, id).unwrap();
+        assert_eq!(skip_item(&conn, &ctx, &item).unwrap_err(), "Only recurring tasks can be skipped");
+    }
+
+    #[test]
+    fn test_missed_last_interval() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_recurring_task(&conn, "work", "standup", "daily 9am");
+        let now = Local::now().timestamp();
+        conn.execute("UPDATE items SET create_time = ?1 WHERE id = ?2", rusqlite::params![now - 10 * 86400, id])
+            .unwrap();
+        let item = get_item(&conn, id).unwrap();
+        assert!(missed_last_interval(&conn, &item, now).unwrap());
+
+        // A record for the previous interval ends at the last occurrence
+        let last = cron::get_occurrence_before(item.cron_schedule.as_ref().unwrap(), now + 1).unwrap();
+        insert_recurring_record(&conn, "work", "done", id, last);
+        assert!(!missed_last_interval(&conn, &item, now).unwrap());
+
+        // A task created during the current interval has missed nothing
+        let fresh = insert_recurring_task(&conn, "work", "retro", "daily 9am");
+        let fresh = get_item(&conn, fresh).unwrap();
+        assert!(!missed_last_interval(&conn, &fresh, now).unwrap());
+    }
+
+    #[test]
+    fn test_final_occurrence() {
+        let day = 86400;
+        let start = cron::get_occurrence_before("0 9 * * *", Local::now().timestamp()).unwrap();
+
+        // The earlier of the end date and the last counted occurrence
+        assert_eq!(final_occurrence("0 9 * * *", start, None, None).unwrap(), None);
+        assert_eq!(final_occurrence("0 9 * * *", start, None, Some(3)).unwrap(), Some(start + 2 * day));
+        assert_eq!(final_occurrence("0 9 * * *", start, Some(start + day + 60), None).unwrap(), Some(start + day));
+        assert_eq!(
+            final_occurrence("0 9 * * *", start, Some(start + day + 60), Some(3)).unwrap(),
+            Some(start + day)
+        );
+
+        // A stored limit beyond the bound counts no further than the bound
+        let capped = final_occurrence("0 9 * * *", start, None, Some(i64::MAX)).unwrap().unwrap();
+        assert!(capped > start + 990 * day && capped < start + 1010 * day);
+    }
+
+    #[test]
+    fn test_schedule_state_and_bounded_occurrences() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_recurring_task(&conn, "work", "standup", "daily 9am");
+        let now = Local::now().timestamp();
+        let last = cron::get_occurrence_before("0 9 * * *", now + 1).unwrap();
+        let mut item = get_item(&conn, id).unwrap();
+        item.create_time = last - 10 * 86400;
+        assert_eq!(schedule_state(&item, now).unwrap(), ScheduleState::Active);
+        assert_eq!(bounded_occurrences(&item, last - 3 * 86400, now).unwrap().len(), 4);
+
+        // Two occurrences from three days ago: both are over
+        item.max_occurrences = Some(2);
+        item.starts_at = Some(last - 3 * 86400);
+        assert_eq!(schedule_state(&item, now).unwrap(), ScheduleState::Ended);
+        assert_eq!(
+            bounded_occurrences(&item, last - 10 * 86400, now).unwrap(),
+            vec![last - 3 * 86400, last - 2 * 86400]
+        );
+
+        item.max_occurrences = None;
+        item.starts_at = Some(last + 86400);
+        assert_eq!(schedule_state(&item, now).unwrap(), ScheduleState::NotStarted);
+
+        // Nothing occurs from the moment it is paused
+        item.starts_at = None;
+        pause_item(&mut item, last - 86400 + 60).unwrap();
+        assert_eq!(schedule_state(&item, now).unwrap(), ScheduleState::Paused);
+        assert_eq!(bounded_occurrences(&item, last - 3 * 86400, now).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_resume_skips_paused_intervals() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let id = insert_recurring_task(&conn, "work", "standup", "daily 9am");
+        let now = Local::now().timestamp();
+        let last = cron::get_occurrence_before("0 9 * * *", now + 1).unwrap();
+        conn.execute(
+            "UPDATE items SET create_time = ?1, owner_id = ?2, namespace_id = ?3 WHERE id = ?4",
+            rusqlite::params![last - 10 * 86400, ctx.current_user_id, ctx.current_namespace_id, id],
+        )
+        .unwrap();
+        let mut item = get_item(&conn, id).unwrap();
+
+        // Paused just before three occurrences ago; three intervals have ended since
+        pause_item(&mut item, last - 3 * 86400 - 60).unwrap();
+        let intervals = task_intervals(&conn, &item, now).unwrap();
+        assert_eq!(intervals.iter().filter(|i| i.state == IntervalState::Skipped).count(), 4);
+
+        resume_item(&conn, &mut item, now).unwrap();
+        assert!(item.paused_at.is_none());
+        let records = query_items(
+            &conn,
+            &ItemQuery::new().with_action(RECURRING_TASK_RECORD).with_recurring_task_id(id),
+        )
+        .unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(records.iter().all(|r| r.owner_id == Some(ctx.current_user_id)));
+        assert!(records.iter().all(|r| r.namespace_id == Some(ctx.current_namespace_id)));
+        let intervals = task_intervals(&conn, &item, now).unwrap();
+        let states: Vec<IntervalState> = intervals[intervals.len() - 5..].iter().map(|i| i.state).collect();
+        assert_eq!(
+            states,
+            vec![
+                IntervalState::Missed,
+                IntervalState::Skipped,
+                IntervalState::Skipped,
+                IntervalState::Skipped,
+                IntervalState::Due,
+            ]
+        );
+        assert!(resume_item(&conn, &mut item, now).unwrap_err().contains("not paused"));
+    }
+}
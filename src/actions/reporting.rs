@@ -1,15 +1,19 @@
 use std::collections::HashMap;
 
-use chrono::Local;
+use chrono::{Local, TimeZone};
 use rusqlite::Connection;
 use serde_json::json;
 
 use crate::{
+    actions::display::{paint, print_bold, Style},
+    actions::recurring::{interval_history, IntervalState},
     args::estimate::format_estimate,
     args::parser::{StatsCommand, TeamCommand, WorkloadCommand},
+    args::timestr,
     context::Context,
     db::{
         crud::query_items,
+        metrics::{period_stats, recurring_history, MetricsScope, PeriodStats, RecurringHistory},
         item::{ItemQuery, TASK},
         user::{get_user_by_name, list_users, User},
     },
@@ -28,7 +32,6 @@ struct UserStats {
 /// Workload stats for reporting
 #[derive(Debug)]
 struct WorkloadStats {
-    user_id: i64,
     user_name: String,
     display_name: String,
     task_count: i64,
@@ -120,7 +123,7 @@ fn print_team_text(stats: &[UserStats]) {
     let total: i64 = total_open + total_done;
 
     println!();
-    println!("\x1b[1mTeam Overview\x1b[0m");
+    print_bold("Team Overview");
     println!("{}", "━".repeat(50));
     println!("{:<20} {:>8} {:>8} {:>8}", "User", "Open", "Done", "Total");
     println!("{}", "━".repeat(50));
@@ -134,7 +137,8 @@ fn print_team_text(stats: &[UserStats]) {
         };
 
         if stat.user_id.is_none() {
-            println!("\x1b[33m{:<20}\x1b[0m {:>8} {:>8} {:>8}", name, stat.open_count, "-", total);
+            let name = paint(&format!("{:<20}", name), Style::Warning);
+            println!("{} {:>8} {:>8} {:>8}", name, stat.open_count, "-", total);
         } else {
             println!("{:<20} {:>8} {:>8} {:>8}", name, stat.open_count, stat.done_count, total);
         }
@@ -222,7 +226,6 @@ pub fn handle_workload(conn: &Connection, _ctx: &Context, cmd: &WorkloadCommand)
         }
 
         workload_stats.push(WorkloadStats {
-            user_id: user.id,
             user_name: user.name.clone(),
             display_name: user.display_name.as_ref().unwrap_or(&user.name).clone(),
             task_count: user_tasks.len() as i64,
@@ -231,7 +234,7 @@ pub fn handle_workload(conn: &Connection, _ctx: &Context, cmd: &WorkloadCommand)
     }
 
     // Sort by workload descending
-    workload_stats.sort_by(|a, b| b.total_minutes.cmp(&a.total_minutes));
+    workload_stats.sort_by_key(|w| std::cmp::Reverse(w.total_minutes));
 
     if cmd.json {
         print_workload_json(&workload_stats);
@@ -249,7 +252,7 @@ fn print_workload_text(stats: &[WorkloadStats]) {
     let total_minutes: i64 = stats.iter().map(|s| s.total_minutes).sum();
 
     println!();
-    println!("\x1b[1mWorkload Summary\x1b[0m");
+    print_bold("Workload Summary");
     println!("{}", "━".repeat(50));
     println!("{:<20} {:>10} {:>15}", "User", "Tasks", "Estimated");
     println!("{}", "━".repeat(50));
@@ -312,180 +315,310 @@ fn print_workload_markdown(stats: &[WorkloadStats]) {
     println!("| **Total** | **{}** | **{}** |", total_tasks, format_estimate(Some(total_minutes)));
 }
 
+/// Fulfilled and missed intervals of one recurring task in the period
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Adherence {
+    content: String,
+    fulfilled: usize,
+    missed: usize,
+    // Skipped on purpose, left out of the adherence rate
+    skipped: usize,
+    // The current interval, not yet fulfilled but not missed either
+    due: usize,
+}
+
 /// Handles the stats command - shows completion rates and overdue analysis
-pub fn handle_stats(conn: &Connection, _ctx: &Context, cmd: &StatsCommand) -> Result<(), String> {
+pub fn handle_stats(conn: &Connection, ctx: &Context, cmd: &StatsCommand) -> Result<(), String> {
     let now = Local::now().timestamp();
-    let cutoff = now - (cmd.days * 86400);
+    let from = match &cmd.from {
+        Some(from) => timestr::since_to_unix_epoch(from)?,
+        None => now - (cmd.days * 86400),
+    };
+    // The end is exclusive in queries, one second past the inclusive end
+    let to = match &cmd.to {
+        Some(to) => timestr::to_unix_epoch(to)? + 1,
+        None => now + 1,
+    };
+    if from >= to {
+        return Err("Stats period must start before it ends".to_string());
+    }
 
-    // Query all tasks
-    let all_tasks = query_items(conn, &ItemQuery::new().with_action(TASK))
-        .map_err(|e| format!("Failed to query tasks: {:?}", e))?;
+    let assignee_id = match &cmd.user {
+        Some(name) => Some(
+            get_user_by_name(conn, name)?
+                .ok_or_else(|| format!("User '{}' not found", name))?
+                .id,
+        ),
+        None => None,
+    };
+    let scope = MetricsScope {
+        namespace_id: ctx.current_namespace_id,
+        assignee_id,
+        category: None,
+    };
+    let (total, categories) = period_stats(conn, &scope, from, to, now)?;
+    let adherence = recurring_history(conn, &scope, from)?
+        .iter()
+        .map(|history| recurring_adherence(history, from, to, now))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let period = if cmd.from.is_some() || cmd.to.is_some() {
+        format!("{} - {}", format_date(from), format_date(to - 1))
+    } else {
+        format!("last {} days", cmd.days)
+    };
+    if cmd.json {
+        print_stats_json(&period, from, to, &total, &categories, &adherence);
+    } else if cmd.md {
+        print_stats_markdown(&period, &total, &categories, &adherence);
+    } else {
+        print_stats_text(&period, &total, &categories, &adherence);
+    }
 
-    // Calculate stats
-    let created_in_period: usize = all_tasks.iter()
-        .filter(|t| t.create_time >= cutoff)
-        .count();
+    Ok(())
+}
 
-    let completed_in_period: usize = all_tasks.iter()
-        .filter(|t| t.status == 1 && t.create_time >= cutoff)
-        .count();
+// Counts how the intervals of a recurring task in the period turned out
+fn recurring_adherence(history: &RecurringHistory, from: i64, to: i64, now: i64) -> Result<Adherence, String> {
+    let mut adherence = Adherence {
+        content: history.content.clone(),
+        fulfilled: 0,
+        missed: 0,
+        skipped: 0,
+        due: 0,
+    };
+    for interval in interval_history(history, from, to, now)? {
+        match interval.state {
+            IntervalState::Fulfilled => adherence.fulfilled += 1,
+            IntervalState::Missed => adherence.missed += 1,
+            IntervalState::Skipped => adherence.skipped += 1,
+            IntervalState::Due => adherence.due += 1,
+        }
+    }
+    Ok(adherence)
+}
 
-    let completion_rate = if created_in_period > 0 {
-        (completed_in_period as f64 / created_in_period as f64 * 100.0) as i64
+// Share of the work in play during the period that got done
+fn completion_rate(stats: &PeriodStats) -> i64 {
+    let in_play = stats.completed + stats.open_at_end;
+    if in_play > 0 {
+        (stats.completed as f64 / in_play as f64 * 100.0).round() as i64
     } else {
         0
-    };
-
-    // Overdue: open tasks with target_time < now
-    let overdue: usize = all_tasks.iter()
-        .filter(|t| {
-            (t.status == 0 || t.status == 4 || t.status == 6) &&
-            t.target_time.map(|tt| tt < now).unwrap_or(false)
-        })
-        .count();
+    }
+}
 
-    // High priority open tasks
-    let high_priority: usize = all_tasks.iter()
-        .filter(|t| {
-            (t.status == 0 || t.status == 4 || t.status == 6) &&
-            t.priority == Some(0)
-        })
-        .count();
+// Fulfilled share of the intervals that have ended
+fn adherence_rate(fulfilled: usize, missed: usize) -> Option<i64> {
+    let ended = fulfilled + missed;
+    (ended > 0).then(|| (fulfilled as f64 / ended as f64 * 100.0).round() as i64)
+}
 
-    // Status breakdown
-    let ongoing: usize = all_tasks.iter().filter(|t| t.status == 0).count();
-    let pending: usize = all_tasks.iter().filter(|t| t.status == 6).count();
-    let suspended: usize = all_tasks.iter().filter(|t| t.status == 4).count();
-    let done: usize = all_tasks.iter().filter(|t| t.status == 1).count();
-    let cancelled: usize = all_tasks.iter().filter(|t| t.status == 2).count();
+fn format_rate(rate: Option<i64>) -> String {
+    rate.map_or("-".to_string(), |r| format!("{}%", r))
+}
 
-    if cmd.json {
-        print_stats_json(cmd.days, created_in_period, completed_in_period, completion_rate,
-                         overdue, high_priority, ongoing, pending, suspended, done, cancelled);
-    } else if cmd.md {
-        print_stats_markdown(cmd.days, created_in_period, completed_in_period, completion_rate,
-                            overdue, high_priority, ongoing, pending, suspended, done, cancelled);
-    } else {
-        print_stats_text(cmd.days, created_in_period, completed_in_period, completion_rate,
-                        overdue, high_priority, ongoing, pending, suspended, done, cancelled);
-    }
+fn format_date(timestamp: i64) -> String {
+    Local.timestamp_opt(timestamp, 0).unwrap().format("%Y/%m/%d").to_string()
+}
 
-    Ok(())
+fn adherence_totals(adherence: &[Adherence]) -> (usize, usize, usize, usize) {
+    adherence.iter().fold((0, 0, 0, 0), |(f, m, s, d), a| {
+        (f + a.fulfilled, m + a.missed, s + a.skipped, d + a.due)
+    })
 }
 
-#[allow(clippy::too_many_arguments)]
-fn print_stats_text(
-    days: i64,
-    created: usize,
-    completed: usize,
-    completion_rate: i64,
-    overdue: usize,
-    high_priority: usize,
-    ongoing: usize,
-    pending: usize,
-    suspended: usize,
-    done: usize,
-    cancelled: usize,
-) {
+fn print_stats_text(period: &str, total: &PeriodStats, categories: &[(String, PeriodStats)], adherence: &[Adherence]) {
     println!();
-    println!("\x1b[1mTask Statistics (last {} days)\x1b[0m", days);
+    print_bold(&format!("Task Statistics ({})", period));
     println!("{}", "━".repeat(40));
-    println!("Created:        {}", created);
-    println!("Completed:      {}", completed);
-    println!("Completion:     {}%", completion_rate);
+    println!("Created:        {}", total.created);
+    println!("Completed:      {}", total.completed);
+    println!("Open at end:    {}", total.open_at_end);
+    println!("Completion:     {}%", completion_rate(total));
+    println!("Records:        {}", total.records);
     println!("{}", "━".repeat(40));
 
-    if overdue > 0 {
-        println!("Overdue:        \x1b[91m{}\x1b[0m", overdue);
+    if total.overdue > 0 {
+        println!("Overdue:        {}", paint(&total.overdue.to_string(), Style::Overdue));
     } else {
-        println!("Overdue:        {}", overdue);
+        println!("Overdue:        {}", total.overdue);
     }
 
-    if high_priority > 0 {
-        println!("High Priority:  \x1b[91m{}\x1b[0m", high_priority);
+    if total.high_priority > 0 {
+        println!("High Priority:  {}", paint(&total.high_priority.to_string(), Style::PriorityHigh));
     } else {
-        println!("High Priority:  {}", high_priority);
+        println!("High Priority:  {}", total.high_priority);
     }
 
     println!("{}", "━".repeat(40));
     println!("By Status:");
-    println!("  ongoing       {}", ongoing);
-    println!("  pending       {}", pending);
-    println!("  suspended     {}", suspended);
-    println!("  done          {}", done);
-    println!("  cancelled     {}", cancelled);
+    println!("  ongoing       {}", total.ongoing);
+    println!("  pending       {}", total.pending);
+    println!("  suspended     {}", total.suspended);
+    println!("  done          {}", total.done);
+    println!("  cancelled     {}", total.cancelled);
+
+    if !categories.is_empty() {
+        println!("{}", "━".repeat(60));
+        println!("{:<16} {:>8} {:>8} {:>8} {:>8} {:>8}", "By Category:", "Created", "Done", "Open", "Overdue", "Records");
+        for (name, c) in categories {
+            println!(
+                "  {:<14} {:>8} {:>8} {:>8} {:>8} {:>8}",
+                name, c.created, c.completed, c.open_at_end, c.overdue, c.records
+            );
+        }
+    }
+
+    if !adherence.is_empty() {
+        let (fulfilled, missed, skipped, _) = adherence_totals(adherence);
+        println!("{}", "━".repeat(60));
+        println!(
+            "Recurring:      {} fulfilled, {} missed, {} skipped ({})",
+            fulfilled,
+            missed,
+            skipped,
+            format_rate(adherence_rate(fulfilled, missed))
+        );
+        for a in adherence {
+            let due = if a.due > 0 { " (due now)" } else { "" };
+            println!(
+                "  {:<30} {:>3}/{:<3} {:>5}{}",
+                a.content.lines().next().unwrap_or_default(),
+                a.fulfilled,
+                a.fulfilled + a.missed,
+                format_rate(adherence_rate(a.fulfilled, a.missed)),
+                due
+            );
+        }
+    }
     println!();
 }
 
-#[allow(clippy::too_many_arguments)]
+fn stats_json(stats: &PeriodStats) -> serde_json::Value {
+    json!({
+        "created": stats.created,
+        "completed": stats.completed,
+        "open_at_end": stats.open_at_end,
+        "completion_rate": completion_rate(stats),
+        "records": stats.records,
+        "overdue": stats.overdue,
+        "high_priority": stats.high_priority,
+    })
+}
+
 fn print_stats_json(
-    days: i64,
-    created: usize,
-    completed: usize,
-    completion_rate: i64,
-    overdue: usize,
-    high_priority: usize,
-    ongoing: usize,
-    pending: usize,
-    suspended: usize,
-    done: usize,
-    cancelled: usize,
+    period: &str,
+    from: i64,
+    to: i64,
+    total: &PeriodStats,
+    categories: &[(String, PeriodStats)],
+    adherence: &[Adherence],
 ) {
-    let output = json!({
-        "period_days": days,
-        "created": created,
-        "completed": completed,
-        "completion_rate": completion_rate,
-        "overdue": overdue,
-        "high_priority": high_priority,
-        "by_status": {
-            "ongoing": ongoing,
-            "pending": pending,
-            "suspended": suspended,
-            "done": done,
-            "cancelled": cancelled
-        }
-    });
-
+    let output = stats_output(period, from, to, total, categories, adherence);
     println!("{}", serde_json::to_string_pretty(&output).unwrap());
 }
 
-#[allow(clippy::too_many_arguments)]
-fn print_stats_markdown(
-    days: i64,
-    created: usize,
-    completed: usize,
-    completion_rate: i64,
-    overdue: usize,
-    high_priority: usize,
-    ongoing: usize,
-    pending: usize,
-    suspended: usize,
-    done: usize,
-    cancelled: usize,
-) {
-    println!("# Task Statistics (last {} days)\n", days);
+fn stats_output(
+    period: &str,
+    from: i64,
+    to: i64,
+    total: &PeriodStats,
+    categories: &[(String, PeriodStats)],
+    adherence: &[Adherence],
+) -> serde_json::Value {
+    let (fulfilled, missed, skipped, due) = adherence_totals(adherence);
+    let mut output = stats_json(total);
+    output["period"] = json!(period);
+    output["period_days"] = json!((to - from) / 86400);
+    output["from"] = json!(from);
+    output["to"] = json!(to - 1);
+    output["by_status"] = json!({
+        "ongoing": total.ongoing,
+        "pending": total.pending,
+        "suspended": total.suspended,
+        "done": total.done,
+        "cancelled": total.cancelled
+    });
+    output["by_category"] = categories
+        .iter()
+        .map(|(name, c)| (name.clone(), stats_json(c)))
+        .collect::<serde_json::Map<_, _>>()
+        .into();
+    output["recurring"] = json!({
+        "fulfilled": fulfilled,
+        "missed": missed,
+        "skipped": skipped,
+        "due": due,
+        "adherence_rate": adherence_rate(fulfilled, missed),
+        "tasks": adherence.iter().map(|a| json!({
+            "content": a.content,
+            "fulfilled": a.fulfilled,
+            "missed": a.missed,
+            "skipped": a.skipped,
+            "due": a.due,
+            "adherence_rate": adherence_rate(a.fulfilled, a.missed),
+        })).collect::<Vec<_>>(),
+    });
+    output
+}
+
+fn print_stats_markdown(period: &str, total: &PeriodStats, categories: &[(String, PeriodStats)], adherence: &[Adherence]) {
+    println!("# Task Statistics ({})\n", period);
     println!("| Metric | Value |");
     println!("|--------|-------|");
-    println!("| Created | {} |", created);
-    println!("| Completed | {} |", completed);
-    println!("| Completion Rate | {}% |", completion_rate);
-    println!("| Overdue | {} |", overdue);
-    println!("| High Priority | {} |", high_priority);
+    println!("| Created | {} |", total.created);
+    println!("| Completed | {} |", total.completed);
+    println!("| Open at End | {} |", total.open_at_end);
+    println!("| Completion Rate | {}% |", completion_rate(total));
+    println!("| Records | {} |", total.records);
+    println!("| Overdue | {} |", total.overdue);
+    println!("| High Priority | {} |", total.high_priority);
     println!("\n## By Status\n");
     println!("| Status | Count |");
     println!("|--------|-------|");
-    println!("| ongoing | {} |", ongoing);
-    println!("| pending | {} |", pending);
-    println!("| suspended | {} |", suspended);
-    println!("| done | {} |", done);
-    println!("| cancelled | {} |", cancelled);
+    println!("| ongoing | {} |", total.ongoing);
+    println!("| pending | {} |", total.pending);
+    println!("| suspended | {} |", total.suspended);
+    println!("| done | {} |", total.done);
+    println!("| cancelled | {} |", total.cancelled);
+
+    if !categories.is_empty() {
+        println!("\n## By Category\n");
+        println!("| Category | Created | Done | Open | Overdue | Records |");
+        println!("|----------|---------|------|------|---------|---------|");
+        for (name, c) in categories {
+            println!(
+                "| {} | {} | {} | {} | {} | {} |",
+                name, c.created, c.completed, c.open_at_end, c.overdue, c.records
+            );
+        }
+    }
+
+    if !adherence.is_empty() {
+        println!("\n## Recurring Tasks\n");
+        println!("| Task | Fulfilled | Missed | Skipped | Adherence |");
+        println!("|------|-----------|--------|---------|-----------|");
+        for a in adherence {
+            println!(
+                "| {} | {} | {} | {} | {} |",
+                a.content.lines().next().unwrap_or_default(),
+                a.fulfilled,
+                a.missed,
+                a.skipped,
+                format_rate(adherence_rate(a.fulfilled, a.missed))
+            );
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tests::{get_test_conn, insert_task};
+    use crate::{
+        args::cron,
+        tests::{get_test_conn, insert_recurring_record, insert_recurring_task, insert_task},
+    };
 
     #[test]
     fn test_handle_team() {
@@ -569,7 +702,7 @@ mod tests {
         insert_task(&conn, "work", "Task 1", "today");
         insert_task(&conn, "work", "Task 2", "tomorrow");
 
-        let cmd = StatsCommand { days: 30, json: false, md: false };
+        let cmd = StatsCommand { days: 30, from: None, to: None, user: None, json: false, md: false };
         let result = handle_stats(&conn, &ctx, &cmd);
         assert!(result.is_ok());
     }
@@ -581,18 +714,135 @@ mod tests {
 
         insert_task(&conn, "work", "Task 1", "today");
 
-        let cmd = StatsCommand { days: 30, json: true, md: false };
+        let cmd = StatsCommand { days: 30, from: None, to: None, user: None, json: true, md: false };
         let result = handle_stats(&conn, &ctx, &cmd);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_stats_output() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let (from, to, now) = (86400, 8 * 86400, 9 * 86400);
+        // (category, created, completed): created before and completed in the
+        // period, done in it, open at its end, done after it, and created after it
+        for (category, created, completed) in [
+            ("work", 0, Some(2 * 86400)),
+            ("work", 3 * 86400, Some(4 * 86400)),
+            ("work", 5 * 86400, None),
+            ("home", 6 * 86400, Some(8 * 86400 + 1)),
+            ("home", 8 * 86400 + 10, None),
+        ] {
+            let id = insert_task(&conn, category, "task", "today");
+            conn.execute(
+                "UPDATE items SET namespace_id = ?1, create_time = ?2, completed_at = ?3,
+                 status = CASE WHEN ?3 IS NULL THEN 0 ELSE 1 END WHERE id = ?4",
+                rusqlite::params![ctx.current_namespace_id, created, completed, id],
+            )
+            .unwrap();
+        }
+        let scope = MetricsScope {
+            namespace_id: ctx.current_namespace_id,
+            assignee_id: None,
+            category: None,
+        };
+
+        let (total, categories) = period_stats(&conn, &scope, from, to, now).unwrap();
+        let output = stats_output("week", from, to, &total, &categories, &[]);
+        assert_eq!(output["created"], 3);
+        assert_eq!(output["completed"], 2);
+        // The task done after the period was still open at its end
+        assert_eq!(output["open_at_end"], 2);
+        assert_eq!(output["completion_rate"], 50);
+        assert_eq!(output["period_days"], 7);
+        assert_eq!((output["from"].clone(), output["to"].clone()), (json!(from), json!(to - 1)));
+        assert_eq!(output["by_status"]["done"], 3);
+        assert_eq!(output["by_category"]["work"]["completion_rate"], 67);
+        assert_eq!(output["by_category"]["home"]["completed"], 0);
+        assert_eq!(output["recurring"]["adherence_rate"], serde_json::Value::Null);
+
+        // Nothing in play is a 0% rate, not a division by zero
+        assert_eq!(completion_rate(&PeriodStats::default()), 0);
+    }
+
     #[test]
     fn test_handle_stats_custom_days() {
         let (conn, _temp_file) = get_test_conn();
         let ctx = Context::default_from_db(&conn).unwrap();
 
-        let cmd = StatsCommand { days: 7, json: false, md: false };
+        let cmd = StatsCommand { days: 7, from: None, to: None, user: None, json: false, md: false };
         let result = handle_stats(&conn, &ctx, &cmd);
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_handle_stats_period() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+
+        let cmd = StatsCommand {
+            days: 30,
+            from: Some("2025-03-01".to_string()),
+            to: Some("2025-03-31".to_string()),
+            user: None,
+            json: true,
+            md: false,
+        };
+        assert!(handle_stats(&conn, &ctx, &cmd).is_ok());
+
+        let cmd = StatsCommand { from: Some("2025-04-01".to_string()), ..cmd };
+        assert_eq!(handle_stats(&conn, &ctx, &cmd).unwrap_err(), "Stats period must start before it ends");
+        // A single day is a valid period, but a period ending before the default start is not
+        let day = StatsCommand {
+            days: 30,
+            from: Some("2025-03-31".to_string()),
+            to: Some("2025-03-31".to_string()),
+            user: None,
+            json: true,
+            md: false,
+        };
+        assert!(handle_stats(&conn, &ctx, &day).is_ok());
+        let cmd = StatsCommand { from: None, to: Some("2025-03-31".to_string()), ..cmd };
+        assert_eq!(handle_stats(&conn, &ctx, &cmd).unwrap_err(), "Stats period must start before it ends");
+        let cmd = StatsCommand { from: None, to: None, user: Some("nobody".to_string()), ..cmd };
+        assert_eq!(handle_stats(&conn, &ctx, &cmd).unwrap_err(), "User 'nobody' not found");
+    }
+
+    #[test]
+    fn test_recurring_adherence() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_recurring_task(&conn, "work", "standup", "daily 9am");
+        let task = crate::db::crud::get_item(&conn, id).unwrap();
+        let cron_schedule = task.cron_schedule.unwrap();
+
+        // Four whole days ending at the last 9am, then the current interval
+        let now = Local::now().timestamp();
+        let last = cron::get_last_occurrence(&cron_schedule).unwrap();
+        let from = last - 4 * 86400;
+        // Fulfil the first and third intervals and skip the second; a record ends where its interval does
+        insert_recurring_record(&conn, "work", "done", id, from + 86400);
+        insert_recurring_record(&conn, "work", "done", id, from + 3 * 86400);
+
+        let history = RecurringHistory {
+            id,
+            content: "standup".to_string(),
+            category: "work".to_string(),
+            cron_schedule,
+            create_time: 0,
+            starts_at: None,
+            ends_at: None,
+            max_occurrences: None,
+            paused_at: None,
+            fulfilled_until: vec![from + 86400, from + 3 * 86400],
+            skipped_until: vec![from + 2 * 86400],
+        };
+        let adherence = recurring_adherence(&history, from, now + 1, now).unwrap();
+        assert_eq!((adherence.fulfilled, adherence.missed, adherence.skipped, adherence.due), (2, 1, 1, 1));
+        assert_eq!(adherence_rate(adherence.fulfilled, adherence.missed), Some(67));
+
+        // Intervals before the task was created do not count as missed
+        let history = RecurringHistory { create_time: last - 86400 - 60, ..history };
+        let adherence = recurring_adherence(&history, from, now + 1, now).unwrap();
+        assert_eq!((adherence.fulfilled, adherence.missed, adherence.skipped, adherence.due), (0, 1, 0, 1));
+    }
 }
@@ -0,0 +1,118 @@
+use rusqlite::Connection;
+
+use crate::{
+    actions::display::print_bold,
+    args::parser::{
+        DbCommand,
+        DbMigrateCommand,
+    },
+    db::{
+        conn::{
+            config_projects,
+            upgrade,
+        },
+        migration::{
+            current_version,
+            pending,
+            MIGRATIONS,
+            SCHEMA_VERSION,
+        },
+    },
+};
+
+pub fn handle_db_cmd(conn: &Connection, cmd: DbCommand) -> Result<(), String> {
+    match cmd {
+        DbCommand::Migrate(migrate_cmd) => handle_db_migrate(conn, migrate_cmd),
+    }
+}
+
+fn handle_db_migrate(conn: &Connection, cmd: DbMigrateCommand) -> Result<(), String> {
+    if cmd.status {
+        return print_migration_status(conn);
+    }
+
+    let from = current_version(conn)?;
+    let applied = upgrade(conn, config_projects())?;
+    if applied == 0 {
+        println!("Schema is up to date (version {}).", from);
+    } else {
+        println!(
+            "Applied {} migration(s): version {} -> {}.",
+            applied, from, SCHEMA_VERSION
+        );
+    }
+    Ok(())
+}
+
+fn print_migration_status(conn: &Connection) -> Result<(), String> {
+    let (heading, lines) = migration_status(conn)?;
+    print_bold(&heading);
+    for line in lines {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+// A database written by a newer binary is reported, not refused, so the
+// user can see which versions are involved.
+fn migration_status(conn: &Connection) -> Result<(String, Vec<String>), String> {
+    let version = current_version(conn)?;
+    let heading = format!(
+        "Schema version {} (binary supports {})",
+        version, SCHEMA_VERSION
+    );
+    let mut lines: Vec<String> = MIGRATIONS
+        .iter()
+        .map(|step| {
+            let state = if step.version <= version {
+                "applied"
+            } else {
+                "pending"
+            };
+            format!("  v{:<3} {:<8} {}", step.version, state, step.description)
+        })
+        .collect();
+    if version > SCHEMA_VERSION {
+        lines.push(format!(
+            "The database is newer than this binary (found {}, supported {}); upgrade ctm to use it.",
+            version, SCHEMA_VERSION
+        ));
+        return Ok((heading, lines));
+    }
+    let pending_steps = pending(conn)?;
+    if !pending_steps.is_empty() {
+        lines.push(format!(
+            "{} pending migration(s); run `ctm db migrate` to apply.",
+            pending_steps.len()
+        ));
+    }
+    Ok((heading, lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::get_test_conn;
+
+    #[test]
+    fn test_migration_status() {
+        let (conn, _temp_file) = get_test_conn();
+        let (heading, lines) = migration_status(&conn).unwrap();
+        assert_eq!(
+            heading,
+            format!("Schema version {0} (binary supports {0})", SCHEMA_VERSION)
+        );
+        assert_eq!(lines.len(), MIGRATIONS.len());
+        assert!(lines.iter().all(|line| line.contains("applied")));
+
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+        let (heading, lines) = migration_status(&conn).unwrap();
+        assert!(heading.starts_with(&format!("Schema version {}", SCHEMA_VERSION + 1)));
+        assert!(lines.last().unwrap().contains(&format!(
+            "found {}, supported {}",
+            SCHEMA_VERSION + 1,
+            SCHEMA_VERSION
+        )));
+    }
+}
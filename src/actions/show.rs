@@ -2,17 +2,27 @@ use chrono::{Local, TimeZone};
 use rusqlite::Connection;
 
 use crate::{
+    actions::{
+        display::{link_style, paint, paint_opt, priority_style, status_style, Style},
+        prs::{describe_pr_status, ready_to_close, task_pr_statuses, Freshness},
+        recurring::{streaks, task_intervals, IntervalState},
+    },
     args::estimate::format_estimate,
+    config::{get_github_config, get_pr_status_ttl},
     db::{
         cache,
         crud::get_item,
         item::{Item, RECORD, RECURRING_TASK, RECURRING_TASK_RECORD, TASK},
-        link::get_links_for_item,
+        link::{get_links_by_type, get_links_for_item, LINK_TYPE_PR},
         note::get_notes_for_item,
         user::get_user_by_id,
     },
+    github::forge_client,
 };
 
+// Intervals shown in the recent history of a recurring task
+const RECENT_INTERVALS: usize = 14;
+
 /// Handles the show command - displays detailed view of a task
 pub fn handle_showcmd(conn: &Connection, index: usize) -> Result<(), String> {
     validate_cache(conn)?;
@@ -20,12 +30,32 @@ pub fn handle_showcmd(conn: &Connection, index: usize) -> Result<(), String> {
 
     let item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {:?}", e))?;
 
+    // Bring stale pull request statuses up to date; the view reads the cache.
+    // Tasks without pull requests need no client.
+    if !get_links_by_type(conn, row_id, LINK_TYPE_PR)?.is_empty() {
+        if let Ok(client) = forge_client(&get_github_config()) {
+            let freshness = Freshness::MaxAge(get_pr_status_ttl());
+            task_pr_statuses(conn, Some(client.as_ref()), row_id, freshness, Local::now().timestamp())?;
+        }
+    }
+
     print_detailed_view(conn, &item, index)?;
 
     Ok(())
 }
 
 fn print_detailed_view(conn: &Connection, item: &Item, index: usize) -> Result<(), String> {
+    println!();
+    for line in detail_lines(conn, item, index)? {
+        println!("{}", line);
+    }
+    println!();
+    Ok(())
+}
+
+/// The lines of the detailed view: fields, full content, notes and links
+pub fn detail_lines(conn: &Connection, item: &Item, index: usize) -> Result<Vec<String>, String> {
+    let mut lines = Vec::new();
     let action_label = match item.action.as_str() {
         TASK => "Task",
         RECURRING_TASK => "Recurring Task",
@@ -35,20 +65,26 @@ fn print_detailed_view(conn: &Connection, item: &Item, index: usize) -> Result<(
     };
 
     // Header
-    println!();
-    println!("\x1b[1m{} #{}: {}\x1b[0m", action_label, index, item.content.lines().next().unwrap_or(&item.content));
-    println!("{}", "━".repeat(50));
+    let title = format!("{} #{}: {}", action_label, index, item.content.lines().next().unwrap_or(&item.content));
+    lines.push(paint(&title, Style::Heading));
+    lines.push("━".repeat(50));
 
     // Basic fields
-    println!("  \x1b[90mPriority:\x1b[0m   {}", format_priority_colored(item.priority));
-    println!("  \x1b[90mStatus:\x1b[0m     {}", format_status(item.status));
-    println!("  \x1b[90mCategory:\x1b[0m   {}", item.category);
+    lines.push(format!("  {}   {}", label("Priority:"), format_priority_colored(item.priority)));
+    lines.push(format!("  {}     {}", label("Status:"), paint_opt(format_status(item.status), status_style(item.status))));
+    lines.push(format!("  {}   {}", label("Category:"), item.category));
+
+    // Linked pull requests, as last fetched
+    let prs = task_pr_statuses(conn, None, item.id.unwrap(), Freshness::Refresh, 0)?;
+    if ready_to_close(item, &prs) {
+        lines.push(format!("  {}      {}", label("Ready:"), paint("all linked PRs merged, ready to close", Style::StatusDone)));
+    }
 
     // Owner
     if let Some(owner_id) = item.owner_id {
         if let Ok(Some(user)) = get_user_by_id(conn, owner_id) {
             let display = user.display_name.as_ref().unwrap_or(&user.name);
-            println!("  \x1b[90mOwner:\x1b[0m      {}", display);
+            lines.push(format!("  {}      {}", label("Owner:"), display));
         }
     }
 
@@ -56,60 +92,80 @@ fn print_detailed_view(conn: &Connection, item: &Item, index: usize) -> Result<(
     if let Some(assignee_id) = item.assignee_id {
         if let Ok(Some(user)) = get_user_by_id(conn, assignee_id) {
             let display = user.display_name.as_ref().unwrap_or(&user.name);
-            println!("  \x1b[90mAssignee:\x1b[0m   {}", display);
+            lines.push(format!("  {}   {}", label("Assignee:"), display));
         }
     } else {
-        println!("  \x1b[90mAssignee:\x1b[0m   \x1b[33munassigned\x1b[0m");
+        lines.push(format!("  {}   {}", label("Assignee:"), paint("unassigned", Style::Warning)));
     }
 
     // Project
     if let Some(ref project) = item.project {
-        println!("  \x1b[90mProject:\x1b[0m    {}", project);
+        lines.push(format!("  {}    {}", label("Project:"), project));
     }
 
     // Due date / Schedule
     if item.action == RECURRING_TASK {
         if let Some(ref schedule) = item.human_schedule {
-            println!("  \x1b[90mSchedule:\x1b[0m   {}", schedule);
+            lines.push(format!("  {}   {}", label("Schedule:"), schedule));
+        }
+        if let Some(starts_at) = item.starts_at {
+            lines.push(format!("  {}     {}", label("Starts:"), format_timestamp(starts_at)));
+        }
+        if let Some(ends_at) = item.ends_at {
+            lines.push(format!("  {}       {}", label("Ends:"), format_timestamp(ends_at)));
         }
+        if let Some(max_occurrences) = item.max_occurrences {
+            lines.push(format!("  {}      {} occurrences", label("Limit:"), max_occurrences));
+        }
+        if let Some(paused_at) = item.paused_at {
+            lines.push(format!(
+                "  {}     {}",
+                label("Paused:"),
+                paint(&format!("since {}", format_timestamp(paused_at)), Style::Warning)
+            ));
+        }
+        lines.extend(streak_lines(conn, item)?);
     } else if let Some(target_time) = item.target_time {
-        println!("  \x1b[90mDue:\x1b[0m        {}", format_timestamp_relative(target_time));
+        lines.push(format!("  {}        {}", label("Due:"), format_timestamp_relative(target_time)));
     }
 
     // Estimate
     let estimate_str = format_estimate(item.estimate_minutes);
     if estimate_str != "-" {
-        println!("  \x1b[90mEstimate:\x1b[0m   {}", estimate_str);
+        lines.push(format!("  {}   {}", label("Estimate:"), estimate_str));
     }
 
     // Reminder
     if let Some(reminder) = item.reminder_days {
-        println!("  \x1b[90mReminder:\x1b[0m   {} days before", reminder);
+        lines.push(format!("  {}   {} days before", label("Reminder:"), reminder));
     }
 
     // Created / Modified
-    println!("  \x1b[90mCreated:\x1b[0m    {}", format_timestamp(item.create_time));
+    lines.push(format!("  {}    {}", label("Created:"), format_timestamp(item.create_time)));
     if let Some(modify_time) = item.modify_time {
         if modify_time != item.create_time {
-            println!("  \x1b[90mModified:\x1b[0m   {}", format_timestamp(modify_time));
+            lines.push(format!("  {}   {}", label("Modified:"), format_timestamp(modify_time)));
         }
     }
+    if let Some(completed_at) = item.completed_at {
+        lines.push(format!("  {}  {}", label("Completed:"), format_timestamp(completed_at)));
+    }
 
     // Full content if multiline
-    let lines: Vec<&str> = item.content.lines().collect();
-    if lines.len() > 1 {
-        println!();
-        println!("\x1b[90mContent:\x1b[0m");
-        for line in lines {
-            println!("  {}", line);
+    let content_lines: Vec<&str> = item.content.lines().collect();
+    if content_lines.len() > 1 {
+        lines.push(String::new());
+        lines.push(label("Content:"));
+        for line in content_lines {
+            lines.push(format!("  {}", line));
         }
     }
 
     // Notes
     let notes = get_notes_for_item(conn, item.id.unwrap())?;
     if !notes.is_empty() {
-        println!();
-        println!("\x1b[90mNotes:\x1b[0m");
+        lines.push(String::new());
+        lines.push(label("Notes:"));
         for note in &notes {
             let timestamp = format_timestamp_short(note.created_at);
             let author = if let Some(created_by) = note.created_by {
@@ -121,41 +177,73 @@ fn print_detailed_view(conn: &Connection, item: &Item, index: usize) -> Result<(
             } else {
                 String::new()
             };
-            println!("  \x1b[90m[{}{}]\x1b[0m {}", timestamp, author, note.content);
+            let stamp = format!("[{}{}]", timestamp, author);
+            lines.push(format!("  {} {}", paint(&stamp, Style::Label), note.content));
         }
     }
 
     // Links
     let links = get_links_for_item(conn, item.id.unwrap())?;
     if !links.is_empty() {
-        println!();
-        println!("\x1b[90mLinks:\x1b[0m");
+        lines.push(String::new());
+        lines.push(label("Links:"));
         for link in &links {
-            let type_colored = match link.link_type.as_str() {
-                "commit" => "\x1b[33mcommit\x1b[0m",
-                "issue" => "\x1b[32missue\x1b[0m",
-                "pr" => "\x1b[35mpr\x1b[0m",
-                "url" => "\x1b[36murl\x1b[0m",
-                _ => &link.link_type,
-            };
+            let type_colored = paint_opt(&link.link_type, link_style(&link.link_type));
             match &link.title {
-                Some(title) => println!("  [{}] {} - {}", type_colored, link.reference, title),
-                None => println!("  [{}] {}", type_colored, link.reference),
+                Some(title) => lines.push(format!("  [{}] {} - {}", type_colored, link.reference, title)),
+                None => lines.push(format!("  [{}] {}", type_colored, link.reference)),
+            }
+            if let Some((_, Some(cached))) = prs.iter().find(|(reference, _)| *reference == link.reference) {
+                lines.push(format!("       {}", describe_pr_status(&cached.status)));
             }
         }
     }
 
-    println!();
-    Ok(())
+    Ok(lines)
 }
 
-fn format_priority_colored(priority: Option<u8>) -> String {
-    match priority {
-        Some(0) => "\x1b[91mHIGH\x1b[0m".to_string(),
-        Some(1) => "normal".to_string(),
-        Some(2) => "\x1b[90mlow\x1b[0m".to_string(),
-        _ => "-".to_string(),
+// Streaks and the most recent intervals of a recurring task, oldest first
+fn streak_lines(conn: &Connection, item: &Item) -> Result<Vec<String>, String> {
+    let intervals = task_intervals(conn, item, Local::now().timestamp())?;
+    if intervals.is_empty() {
+        return Ok(Vec::new());
     }
+    let streaks = streaks(&intervals);
+    let count = |state| intervals.iter().filter(|i| i.state == state).count();
+    let recent: Vec<String> = intervals[intervals.len().saturating_sub(RECENT_INTERVALS)..]
+        .iter()
+        .map(|i| match i.state {
+            IntervalState::Fulfilled => paint("✓", Style::StatusDone),
+            IntervalState::Missed => paint("✗", Style::Overdue),
+            IntervalState::Skipped => paint("↷", Style::StatusInactive),
+            IntervalState::Due => "·".to_string(),
+        })
+        .collect();
+    Ok(vec![
+        format!("  {}     {} current, {} best", label("Streak:"), streaks.current, streaks.best),
+        format!(
+            "  {}  {} fulfilled, {} missed, {} skipped",
+            label("Intervals:"),
+            count(IntervalState::Fulfilled),
+            count(IntervalState::Missed),
+            count(IntervalState::Skipped)
+        ),
+        format!("  {}     {}", label("Recent:"), recent.join(" ")),
+    ])
+}
+
+fn format_priority_colored(priority: Option<u8>) -> String {
+    let text = match priority {
+        Some(0) => "HIGH",
+        Some(1) => "normal",
+        Some(2) => "low",
+        _ => "-",
+    };
+    paint_opt(text, priority_style(priority))
+}
+
+fn label(text: &str) -> String {
+    paint(text, Style::Label)
 }
 
 fn format_status(status: u8) -> &'static str {
@@ -202,14 +290,15 @@ fn format_timestamp_relative(timestamp: i64) -> String {
     if diff < 0 {
         let past_days = (-diff) / 86400;
         if past_days == 0 {
-            format!("{} (\x1b[91moverdue today\x1b[0m)", date_str)
+            format!("{} ({})", date_str, paint("overdue today", Style::Overdue))
         } else if past_days == 1 {
-            format!("{} (\x1b[91m1 day overdue\x1b[0m)", date_str)
+            format!("{} ({})", date_str, paint("1 day overdue", Style::Overdue))
         } else {
-            format!("{} (\x1b[91m{} days overdue\x1b[0m)", date_str, past_days)
+            let overdue = format!("{} days overdue", past_days);
+            format!("{} ({})", date_str, paint(&overdue, Style::Overdue))
         }
     } else if days == 0 {
-        format!("{} (\x1b[93mtoday\x1b[0m)", date_str)
+        format!("{} ({})", date_str, paint("today", Style::DueToday))
     } else if days == 1 {
         format!("{} (tomorrow)", date_str)
     } else if days <= 7 {
@@ -245,8 +334,10 @@ mod tests {
             item::ItemQuery,
             link::add_link,
             note::add_note,
+            pr_status::{save_pr_status, CachedPrStatus},
         },
-        tests::{get_test_conn, insert_task},
+        github::{ChecksState, PrStatus},
+        tests::{get_test_conn, insert_recurring_task, insert_task},
     };
 
     #[test]
@@ -282,6 +373,33 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_detail_lines_show_cached_pr_status() {
+        let (conn, _temp_file) = get_test_conn();
+        let task_id = insert_task(&conn, "work", "Add SSO", "today");
+        add_link(&conn, task_id, "pr", "o/r#7", None, None).unwrap();
+        let item = get_item(&conn, task_id).unwrap();
+        let joined = |lines: Vec<String>| lines.join("\n");
+
+        let lines = joined(detail_lines(&conn, &item, 1).unwrap());
+        assert!(lines.contains("o/r#7"));
+        assert!(!lines.contains("ready to close"));
+
+        let status = PrStatus {
+            title: "Add SSO".to_string(),
+            url: String::new(),
+            state: "MERGED".to_string(),
+            review_decision: Some("APPROVED".to_string()),
+            checks: ChecksState::Passing,
+            mergeable: "UNKNOWN".to_string(),
+        };
+        let cached = CachedPrStatus { reference: "o/r#7".to_string(), status, fetched_at: 0 };
+        save_pr_status(&conn, &cached).unwrap();
+        let lines = joined(detail_lines(&conn, &item, 1).unwrap());
+        assert!(lines.contains("merged · approved · checks passing"));
+        assert!(lines.contains("ready to close"));
+    }
+
     #[test]
     fn test_handle_showcmd_invalid_index() {
         let (conn, _temp_file) = get_test_conn();
@@ -295,6 +413,20 @@ mod tests {
         assert!(result.unwrap_err().contains("does not exist"));
     }
 
+    #[test]
+    fn test_detail_lines_show_streaks() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_recurring_task(&conn, "work", "standup", "daily 9am");
+        let now = Local::now().timestamp();
+        conn.execute("UPDATE items SET create_time = ?1 WHERE id = ?2", rusqlite::params![now - 3 * 86400, id])
+            .unwrap();
+
+        let item = get_item(&conn, id).unwrap();
+        let lines = detail_lines(&conn, &item, 1).unwrap();
+        assert!(lines.iter().any(|l| l.contains("0 current, 0 best")));
+        assert!(lines.iter().any(|l| l.contains("0 fulfilled, 2 missed, 0 skipped")));
+    }
+
     #[test]
     fn test_format_status() {
         assert_eq!(format_status(0), "ongoing");
@@ -0,0 +1,181 @@
+use chrono::Local;
+use rusqlite::Connection;
+
+use crate::{
+    actions::display::{self, timestamp_to_display_string},
+    args::{parser::SnoozeCommand, timestr},
+    context::Context,
+    db::{
+        audit::log_item_changes,
+        cache,
+        crud::{describe_update_error, get_item, query_items, update_item},
+        item::{Item, ItemQuery, TASK},
+        note::add_note,
+    },
+};
+
+/// Status a task holds while it is snoozed
+const SNOOZED_STATUS: u8 = 4;
+
+/// Handles the snooze command - hides a task until the given time
+pub fn handle_snoozecmd(conn: &Connection, ctx: &Context, cmd: &SnoozeCommand) -> Result<(), String> {
+    let row_id = cache::rowid_for_index(conn, cmd.index)?;
+
+    let item = get_item(conn, row_id).map_err(|e| format!("Failed to get item: {:?}", e))?;
+    let wake_time = timestr::since_to_unix_epoch(&cmd.timestr)?;
+    let item = snooze_item(conn, ctx, item, wake_time)?;
+
+    display::print_bold(&format!(
+        "Snoozed task until {}:",
+        timestamp_to_display_string(wake_time, false)
+    ));
+    display::print_items(&[item], false, false);
+
+    Ok(())
+}
+
+/// Hides an open task until the wake time
+pub fn snooze_item(conn: &Connection, ctx: &Context, mut item: Item, wake_time: i64) -> Result<Item, String> {
+    if item.action != TASK {
+        return Err("Only tasks can be snoozed".to_string());
+    }
+    if item.status != 0 && item.status != SNOOZED_STATUS {
+        return Err(format!(
+            "Cannot snooze a {} task",
+            display::translate_status(item.status)
+        ));
+    }
+
+    if wake_time <= Local::now().timestamp() {
+        return Err(format!(
+            "Wake time {} is not in the future",
+            timestamp_to_display_string(wake_time, false)
+        ));
+    }
+
+    let before = item.clone();
+    item.status = SNOOZED_STATUS;
+    item.snooze_until = Some(wake_time);
+    update_item(conn, &item).map_err(describe_update_error)?;
+    log_item_changes(conn, &before, &item, ctx.current_user_id)?;
+    Ok(item)
+}
+
+/// Returns snoozed tasks whose wake time has passed to ongoing, leaving a
+/// note on each. Returns the number of tasks woken.
+pub fn wake_snoozed_tasks(conn: &Connection) -> Result<usize, String> {
+    let now = Local::now().timestamp();
+    let query = ItemQuery::new().with_action(TASK).with_snoozed_only();
+    let due: Vec<_> = query_items(conn, &query)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|item| item.snooze_until.is_some_and(|t| t <= now))
+        .collect();
+
+    let mut woken = 0;
+    for mut item in due {
+        let (Some(id), Some(wake_time)) = (item.id, item.snooze_until) else {
+            continue;
+        };
+        let before = item.clone();
+        if item.status == SNOOZED_STATUS {
+            item.status = 0;
+        }
+        item.snooze_until = None;
+        match update_item(conn, &item) {
+            Ok(()) => {}
+            // Another process woke it first
+            Err(rusqlite::Error::StatementChangedRows(0)) => continue,
+            Err(e) => return Err(describe_update_error(e)),
+        }
+        if let Some(owner_id) = item.owner_id {
+            log_item_changes(conn, &before, &item, owner_id)?;
+        }
+        let note = format!(
+            "Back to ongoing: snoozed until {}",
+            timestamp_to_display_string(wake_time, false)
+        );
+        add_note(conn, id, &note, None)?;
+        woken += 1;
+    }
+    Ok(woken)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::note::get_notes_for_item,
+        tests::{get_test_conn, insert_task},
+    };
+
+    fn cache_tasks(conn: &Connection) {
+        let items = query_items(conn, &ItemQuery::new().with_action(TASK)).unwrap();
+        cache::store(conn, &items).unwrap();
+    }
+
+    #[test]
+    fn test_snooze_hides_task_until_wake() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let task_id = insert_task(&conn, "work", "Follow up with vendor", "tomorrow");
+        cache_tasks(&conn);
+
+        let cmd = SnoozeCommand {
+            index: 1,
+            timestr: "tomorrow".to_string(),
+        };
+        handle_snoozecmd(&conn, &ctx, &cmd).unwrap();
+
+        let item = get_item(&conn, task_id).unwrap();
+        assert_eq!(item.status, SNOOZED_STATUS);
+        assert!(item.snooze_until.unwrap() > Local::now().timestamp());
+
+        let now = Local::now().timestamp();
+        let awake = query_items(&conn, &ItemQuery::new().with_action(TASK).with_awake_at(now)).unwrap();
+        assert!(awake.is_empty());
+        let snoozed =
+            query_items(&conn, &ItemQuery::new().with_action(TASK).with_snoozed_only()).unwrap();
+        assert_eq!(snoozed.len(), 1);
+
+        // Not yet time to wake
+        assert_eq!(wake_snoozed_tasks(&conn).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_snooze_rejects_past_time() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        insert_task(&conn, "work", "Some task", "tomorrow");
+        cache_tasks(&conn);
+
+        let cmd = SnoozeCommand {
+            index: 1,
+            timestr: "yesterday".to_string(),
+        };
+        let result = handle_snoozecmd(&conn, &ctx, &cmd);
+        assert!(result.unwrap_err().contains("not in the future"));
+    }
+
+    #[test]
+    fn test_wake_snoozed_tasks() {
+        let (conn, _temp_file) = get_test_conn();
+        let task_id = insert_task(&conn, "work", "Renew certificate", "tomorrow");
+        let mut item = get_item(&conn, task_id).unwrap();
+        item.status = SNOOZED_STATUS;
+        item.snooze_until = Some(Local::now().timestamp() - 60);
+        update_item(&conn, &item).unwrap();
+
+        assert_eq!(wake_snoozed_tasks(&conn).unwrap(), 1);
+
+        let item = get_item(&conn, task_id).unwrap();
+        assert_eq!(item.status, 0);
+        assert!(item.snooze_until.is_none());
+        let notes = get_notes_for_item(&conn, task_id).unwrap();
+        assert_eq!(notes.len(), 1);
+        assert!(notes[0].content.starts_with("Back to ongoing: snoozed until"));
+
+        // Nothing left to wake
+        assert_eq!(wake_snoozed_tasks(&conn).unwrap(), 0);
+    }
+}
@@ -0,0 +1,307 @@
+use chrono::{
+    Datelike,
+    Duration,
+    Local,
+    NaiveDate,
+    TimeZone,
+};
+use rusqlite::Connection;
+use serde_json::json;
+
+use crate::{
+    actions::display::{
+        bar,
+        print_bold,
+        sparkline,
+    },
+    args::{
+        estimate::format_estimate,
+        parser::{
+            BurndownCommand,
+            VelocityCommand,
+        },
+        timestr::parse_flexible_timestr,
+    },
+    context::Context,
+    db::{
+        metrics::{
+            task_series,
+            Bucket,
+            MetricsScope,
+        },
+        user::get_user_by_name,
+    },
+};
+
+const DEFAULT_BURNDOWN_DAYS: i64 = 14;
+const BAR_WIDTH: usize = 30;
+
+/// Shows created and completed tasks per day, and the open work left at the end of each day
+pub fn handle_burndown(conn: &Connection, ctx: &Context, cmd: &BurndownCommand) -> Result<(), String> {
+    let today = Local::now().date_naive();
+    let first = match &cmd.since {
+        Some(since) => parse_flexible_timestr(since)?.date(),
+        None => today - Duration::days(DEFAULT_BURNDOWN_DAYS - 1),
+    };
+    if first > today {
+        return Err("Burndown must start today or earlier".to_string());
+    }
+    let days: Vec<NaiveDate> = first.iter_days().take_while(|d| *d <= today + Duration::days(1)).collect();
+    let boundaries: Vec<i64> = days.iter().map(|d| start_of(*d)).collect();
+
+    let assignee_id = resolve_user(conn, cmd.user.as_deref())?;
+    let scope = MetricsScope {
+        namespace_id: ctx.current_namespace_id,
+        assignee_id,
+        category: cmd.category.as_deref(),
+    };
+    let series = task_series(conn, &scope, &boundaries)?;
+
+    if cmd.json {
+        print_series_json("days", "date", &series);
+    } else if cmd.csv {
+        print_series_csv("date", &series);
+    } else {
+        print_burndown_text(ctx, first, &series);
+    }
+    Ok(())
+}
+
+/// Shows tasks and estimate minutes completed per week
+pub fn handle_velocity(conn: &Connection, ctx: &Context, cmd: &VelocityCommand) -> Result<(), String> {
+    if cmd.weeks == 0 {
+        return Err("Velocity needs at least one week".to_string());
+    }
+    let today = Local::now().date_naive();
+    let this_monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let boundaries: Vec<i64> = (0..=cmd.weeks)
+        .rev()
+        .map(|i| start_of(this_monday + Duration::weeks(1 - i as i64)))
+        .collect();
+
+    let assignee_id = resolve_user(conn, cmd.user.as_deref())?;
+    let scope = MetricsScope {
+        namespace_id: ctx.current_namespace_id,
+        assignee_id,
+        category: cmd.category.as_deref(),
+    };
+    let series = task_series(conn, &scope, &boundaries)?;
+
+    if cmd.json {
+        print_series_json("weeks", "week_start", &series);
+    } else if cmd.csv {
+        print_series_csv("week_start", &series);
+    } else {
+        print_velocity_text(ctx, &series);
+    }
+    Ok(())
+}
+
+fn resolve_user(conn: &Connection, username: Option<&str>) -> Result<Option<i64>, String> {
+    match username {
+        Some(name) => {
+            let user = get_user_by_name(conn, name)?
+                .ok_or_else(|| format!("User '{}' not found", name))?;
+            Ok(Some(user.id))
+        }
+        None => Ok(None),
+    }
+}
+
+fn start_of(date: NaiveDate) -> i64 {
+    Local
+        .from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap())
+        .earliest()
+        .map_or(0, |t| t.timestamp())
+}
+
+fn date_of(timestamp: i64) -> NaiveDate {
+    Local.timestamp_opt(timestamp, 0).unwrap().date_naive()
+}
+
+fn print_burndown_text(ctx: &Context, first: NaiveDate, series: &[Bucket]) {
+    println!();
+    print_bold(&format!(
+        "Burndown since {} ({})",
+        first.format("%Y/%m/%d"),
+        ctx.current_namespace_name
+    ));
+    println!("{}", "━".repeat(50 + BAR_WIDTH));
+    println!("{:<12} {:>8} {:>8} {:>8} {:>10}  Open", "Date", "Created", "Done", "Open", "Open Est.");
+    println!("{}", "━".repeat(50 + BAR_WIDTH));
+
+    let max_open = series.iter().map(|b| b.open).max().unwrap_or(0);
+    for bucket in series {
+        println!(
+            "{:<12} {:>8} {:>8} {:>8} {:>10}  {}",
+            date_of(bucket.start).format("%a %m/%d"),
+            bucket.created,
+            bucket.completed,
+            bucket.open,
+            format_estimate(Some(bucket.open_minutes)),
+            bar(bucket.open, max_open, BAR_WIDTH)
+        );
+    }
+
+    println!("{}", "━".repeat(50 + BAR_WIDTH));
+    let opens: Vec<i64> = series.iter().map(|b| b.open).collect();
+    let created: i64 = series.iter().map(|b| b.created).sum();
+    let completed: i64 = series.iter().map(|b| b.completed).sum();
+    println!("Open trend: {}", sparkline(&opens));
+    println!("Created {}, completed {}", created, completed);
+    println!();
+}
+
+fn print_velocity_text(ctx: &Context, series: &[Bucket]) {
+    println!();
+    print_bold(&format!(
+        "Velocity, last {} weeks ({})",
+        series.len(),
+        ctx.current_namespace_name
+    ));
+    println!("{}", "━".repeat(58 + BAR_WIDTH));
+    println!("{:<12} {:>8} {:>10} {:>8} {:>12}  Done", "Week Of", "Done", "Done Est.", "Created", "Created Est.");
+    println!("{}", "━".repeat(58 + BAR_WIDTH));
+
+    let max_done = series.iter().map(|b| b.completed).max().unwrap_or(0);
+    for bucket in series {
+        println!(
+            "{:<12} {:>8} {:>10} {:>8} {:>12}  {}",
+            date_of(bucket.start).format("%Y/%m/%d"),
+            bucket.completed,
+            format_estimate(Some(bucket.completed_minutes)),
+            bucket.created,
+            format_estimate(Some(bucket.created_minutes)),
+            bar(bucket.completed, max_done, BAR_WIDTH)
+        );
+    }
+
+    println!("{}", "━".repeat(58 + BAR_WIDTH));
+    let weeks = series.len().max(1) as i64;
+    let completed: i64 = series.iter().map(|b| b.completed).sum();
+    let minutes: i64 = series.iter().map(|b| b.completed_minutes).sum();
+    let done: Vec<i64> = series.iter().map(|b| b.completed).collect();
+    println!("Done trend: {}", sparkline(&done));
+    println!(
+        "Average {:.1} tasks and {} per week",
+        completed as f64 / weeks as f64,
+        format_estimate(Some(minutes / weeks))
+    );
+    println!();
+}
+
+fn print_series_json(key: &str, date_key: &str, series: &[Bucket]) {
+    let rows: Vec<_> = series
+        .iter()
+        .map(|b| {
+            json!({
+                date_key: date_of(b.start).format("%Y-%m-%d").to_string(),
+                "created": b.created,
+                "created_minutes": b.created_minutes,
+                "completed": b.completed,
+                "completed_minutes": b.completed_minutes,
+                "open": b.open,
+                "open_minutes": b.open_minutes,
+            })
+        })
+        .collect();
+    let output = json!({ key: rows });
+    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+}
+
+fn print_series_csv(date_key: &str, series: &[Bucket]) {
+    println!(
+        "{},created,created_minutes,completed,completed_minutes,open,open_minutes",
+        date_key
+    );
+    for b in series {
+        println!(
+            "{},{},{},{},{},{},{}",
+            date_of(b.start).format("%Y-%m-%d"),
+            b.created,
+            b.created_minutes,
+            b.completed,
+            b.completed_minutes,
+            b.open,
+            b.open_minutes
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::crud::{
+            get_item,
+            update_item,
+        },
+        tests::{
+            get_test_conn,
+            insert_task,
+        },
+    };
+
+    #[test]
+    fn test_burndown_and_velocity() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        for content in ["write docs", "fix bug"] {
+            let id = insert_task(&conn, "work", content, "today");
+            conn.execute(
+                "UPDATE items SET namespace_id = ?1, estimate_minutes = 30 WHERE id = ?2",
+                rusqlite::params![ctx.current_namespace_id, id],
+            )
+            .unwrap();
+        }
+        let mut done = get_item(&conn, 1).unwrap();
+        done.status = 1;
+        update_item(&conn, &done).unwrap();
+
+        let cmd = BurndownCommand {
+            since: Some("today".to_string()),
+            user: None,
+            category: None,
+            json: false,
+            csv: false,
+        };
+        handle_burndown(&conn, &ctx, &cmd).unwrap();
+        let cmd = VelocityCommand {
+            weeks: 4,
+            user: None,
+            category: Some("work".to_string()),
+            json: true,
+            csv: false,
+        };
+        handle_velocity(&conn, &ctx, &cmd).unwrap();
+
+        // Today's bucket sees both creations, one completion and one open task
+        let today = Local::now().date_naive();
+        let boundaries = [start_of(today), start_of(today + Duration::days(1))];
+        let scope = MetricsScope {
+            namespace_id: ctx.current_namespace_id,
+            assignee_id: None,
+            category: None,
+        };
+        let series = task_series(&conn, &scope, &boundaries).unwrap();
+        assert_eq!((series[0].created, series[0].completed, series[0].open), (2, 1, 1));
+        assert_eq!(series[0].completed_minutes, 30);
+
+        let cmd = BurndownCommand {
+            since: Some("tomorrow".to_string()),
+            user: None,
+            category: None,
+            json: false,
+            csv: true,
+        };
+        assert!(handle_burndown(&conn, &ctx, &cmd).is_err());
+        let cmd = VelocityCommand {
+            weeks: 1,
+            user: Some("nobody".to_string()),
+            category: None,
+            json: false,
+            csv: false,
+        };
+        assert_eq!(handle_velocity(&conn, &ctx, &cmd).unwrap_err(), "User 'nobody' not found");
+    }
+}
@@ -0,0 +1,520 @@
+use chrono::Local;
+use rusqlite::Connection;
+
+use super::terminal::Key;
+use crate::{
+    actions::{
+        claim::claim_item,
+        list::{
+            mark_recurring_task_by_completion,
+            CREATE_TIME_COL,
+            OPEN_STATUS_CODES,
+            TARGET_TIME_COL,
+        },
+        modify::complete_item,
+        recurring::{schedule_state, ScheduleState},
+        show::detail_lines,
+        snooze::snooze_item,
+    },
+    args::timestr,
+    context::Context,
+    db::{
+        audit::log_item_changes,
+        crud::{describe_update_error, query_items, update_item},
+        item::{Item, ItemQuery, RECORD, RECURRING_TASK, RECURRING_TASK_RECORD, TASK},
+    },
+};
+
+const PAGE_SIZE: usize = 10;
+const RECORD_LIMIT: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    Tasks,
+    Records,
+}
+
+/// What the line being typed at the bottom of the screen is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prompt {
+    Filter,
+    Snooze,
+    Edit,
+    Due,
+    Namespace,
+    User,
+}
+
+impl Prompt {
+    pub fn label(self) -> &'static str {
+        match self {
+            Prompt::Filter => "Filter",
+            Prompt::Snooze => "Snooze until",
+            Prompt::Edit => "Content",
+            Prompt::Due => "Due",
+            Prompt::Namespace => "Namespace",
+            Prompt::User => "User",
+        }
+    }
+}
+
+/// State of the triage screen. Every action goes through the same
+/// functions as the CLI commands, so it can be driven key by key in tests.
+pub struct App {
+    pub ctx: Context,
+    pub view: View,
+    pub items: Vec<Item>,
+    pub selected: usize,
+    pub filter: String,
+    pub show_closed: bool,
+    pub only_mine: bool,
+    pub input: Option<(Prompt, String)>,
+    pub message: Option<String>,
+    pub detail: Vec<String>,
+    pub quit: bool,
+}
+
+impl App {
+    pub fn new(conn: &Connection, ctx: Context, view: View) -> Result<Self, String> {
+        let mut app = App {
+            ctx,
+            view,
+            items: Vec::new(),
+            selected: 0,
+            filter: String::new(),
+            show_closed: false,
+            only_mine: false,
+            input: None,
+            message: None,
+            detail: Vec::new(),
+            quit: false,
+        };
+        app.reload(conn)?;
+        Ok(app)
+    }
+
+    pub fn selected_item(&self) -> Option<&Item> {
+        self.items.get(self.selected)
+    }
+
+    /// Re-reads the items for the current view, keeping the selection on the same item
+    pub fn reload(&mut self, conn: &Connection) -> Result<(), String> {
+        let selected_id = self.selected_item().and_then(|item| item.id);
+        let items = match self.view {
+            View::Tasks => self.load_tasks(conn)?,
+            View::Records => self.load_records(conn)?,
+        };
+        let filter = self.filter.to_lowercase();
+        self.items = items
+            .into_iter()
+            .filter(|item| !self.only_mine || item.assignee_id == Some(self.ctx.current_user_id))
+            .filter(|item| {
+                filter.is_empty()
+                    || item.content.to_lowercase().contains(&filter)
+                    || item.category.to_lowercase().contains(&filter)
+            })
+            .collect();
+        self.selected = selected_id
+            .and_then(|id| self.items.iter().position(|item| item.id == Some(id)))
+            .unwrap_or(self.selected)
+            .min(self.items.len().saturating_sub(1));
+        self.refresh_detail(conn)
+    }
+
+    fn load_tasks(&self, conn: &Connection) -> Result<Vec<Item>, String> {
+        let namespace_id = self.ctx.current_namespace_id;
+        let recurring = query_items(
+            conn,
+            &ItemQuery::new()
+                .with_action(RECURRING_TASK)
+                .with_namespace_id(namespace_id),
+        )
+        .map_err(|e| e.to_string())?;
+        let now = Local::now().timestamp();
+        let mut items = Vec::new();
+        for task in mark_recurring_task_by_completion(conn, recurring)? {
+            let active = schedule_state(&task, now)? == ScheduleState::Active;
+            if self.show_closed || (active && !task.recurring_interval_complete) {
+                items.push(task);
+            }
+        }
+
+        let mut query = ItemQuery::new()
+            .with_action(TASK)
+            .with_namespace_id(namespace_id)
+            .with_order_by(TARGET_TIME_COL);
+        if !self.show_closed {
+            query = query
+                .with_statuses(OPEN_STATUS_CODES.to_vec())
+                .with_awake_at(now);
+        }
+        items.extend(query_items(conn, &query).map_err(|e| e.to_string())?);
+        Ok(items)
+    }
+
+    fn load_records(&self, conn: &Connection) -> Result<Vec<Item>, String> {
+        let mut records = query_items(
+            conn,
+            &ItemQuery::new()
+                .with_actions(vec![RECORD, RECURRING_TASK_RECORD])
+                .with_namespace_id(self.ctx.current_namespace_id)
+                .with_order_by(CREATE_TIME_COL),
+        )
+        .map_err(|e| e.to_string())?;
+        // Newest first, and only the most recent ones
+        records.reverse();
+        records.truncate(RECORD_LIMIT);
+        Ok(records)
+    }
+
+    fn refresh_detail(&mut self, conn: &Connection) -> Result<(), String> {
+        self.detail = match self.selected_item() {
+            Some(item) => detail_lines(conn, item, self.selected + 1)?,
+            None => Vec::new(),
+        };
+        Ok(())
+    }
+
+    /// Applies one key press; failures are shown on the status line
+    pub fn handle_key(&mut self, conn: &Connection, key: Key) {
+        let result = match self.input.take() {
+            Some((prompt, buffer)) => self.handle_prompt_key(conn, prompt, buffer, key),
+            None => self.handle_normal_key(conn, key),
+        };
+        if let Err(e) = result {
+            self.message = Some(format!("Error: {}", e));
+        }
+    }
+
+    fn handle_normal_key(&mut self, conn: &Connection, key: Key) -> Result<(), String> {
+        self.message = None;
+        let last = self.items.len().saturating_sub(1);
+        match key {
+            Key::Char('q') | Key::CtrlC => self.quit = true,
+            Key::Esc if !self.filter.is_empty() => {
+                self.filter.clear();
+                return self.reload(conn);
+            }
+            Key::Esc => self.quit = true,
+            Key::Char('j') | Key::Down => self.select(conn, (self.selected + 1).min(last))?,
+            Key::Char('k') | Key::Up => self.select(conn, self.selected.saturating_sub(1))?,
+            Key::PageDown => self.select(conn, (self.selected + PAGE_SIZE).min(last))?,
+            Key::PageUp => self.select(conn, self.selected.saturating_sub(PAGE_SIZE))?,
+            Key::Char('g') | Key::Home => self.select(conn, 0)?,
+            Key::Char('G') | Key::End => self.select(conn, last)?,
+            Key::Tab => {
+                self.view = match self.view {
+                    View::Tasks => View::Records,
+                    View::Records => View::Tasks,
+                };
+                self.selected = 0;
+                self.items.clear();
+                return self.reload(conn);
+            }
+            Key::Char('a') => {
+                self.show_closed = !self.show_closed;
+                return self.reload(conn);
+            }
+            Key::Char('m') => {
+                self.only_mine = !self.only_mine;
+                return self.reload(conn);
+            }
+            Key::Char('r') => return self.reload(conn),
+            Key::Char('/') => self.input = Some((Prompt::Filter, self.filter.clone())),
+            Key::Char('n') => self.input = Some((Prompt::Namespace, String::new())),
+            Key::Char('u') => self.input = Some((Prompt::User, String::new())),
+            Key::Char('d') => {
+                let item = self.require_selected()?;
+                let done = complete_item(conn, &self.ctx, item, 1, None)?;
+                self.message = Some(format!("Completed: {}", first_line(&done.content)));
+                return self.reload(conn);
+            }
+            Key::Char('c') => {
+                let item = self.require_selected()?;
+                let claimed = claim_item(conn, &self.ctx, item)?;
+                self.message = Some(format!("Claimed: {}", first_line(&claimed.content)));
+                return self.reload(conn);
+            }
+            Key::Char('z') => {
+                self.require_selected()?;
+                self.input = Some((Prompt::Snooze, String::new()));
+            }
+            Key::Char('e') => {
+                let item = self.require_selected()?;
+                self.input = Some((Prompt::Edit, first_line(&item.content).to_string()));
+            }
+            Key::Char('t') => {
+                let item = self.require_selected()?;
+                if item.action == RECORD || item.action == RECURRING_TASK_RECORD {
+                    return Err("Records have no due time".to_string());
+                }
+                self.input = Some((Prompt::Due, item.human_schedule.unwrap_or_default()));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_prompt_key(
+        &mut self,
+        conn: &Connection,
+        prompt: Prompt,
+        mut buffer: String,
+        key: Key,
+    ) -> Result<(), String> {
+        match key {
+            Key::Esc | Key::CtrlC => {
+                if prompt == Prompt::Filter {
+                    self.filter.clear();
+                    return self.reload(conn);
+                }
+                Ok(())
+            }
+            Key::Enter => self.submit(conn, prompt, buffer.trim()),
+            Key::Backspace => {
+                buffer.pop();
+                self.edit_prompt(conn, prompt, buffer)
+            }
+            Key::Char(c) => {
+                buffer.push(c);
+                self.edit_prompt(conn, prompt, buffer)
+            }
+            _ => {
+                self.input = Some((prompt, buffer));
+                Ok(())
+            }
+        }
+    }
+
+    // The filter applies as it is typed
+    fn edit_prompt(&mut self, conn: &Connection, prompt: Prompt, buffer: String) -> Result<(), String> {
+        if prompt == Prompt::Filter {
+            self.filter = buffer.clone();
+            self.input = Some((prompt, buffer));
+            return self.reload(conn);
+        }
+        self.input = Some((prompt, buffer));
+        Ok(())
+    }
+
+    fn submit(&mut self, conn: &Connection, prompt: Prompt, value: &str) -> Result<(), String> {
+        match prompt {
+            Prompt::Filter => {
+                self.filter = value.to_string();
+                self.reload(conn)
+            }
+            _ if value.is_empty() => Ok(()),
+            Prompt::Snooze => {
+                let item = self.require_selected()?;
+                let wake_time = timestr::since_to_unix_epoch(value)?;
+                let snoozed = snooze_item(conn, &self.ctx, item, wake_time)?;
+                self.message = Some(format!("Snoozed: {}", first_line(&snoozed.content)));
+                self.reload(conn)
+            }
+            Prompt::Edit => {
+                self.update_selected(conn, |item| {
+                    // Only the first line is edited; further lines are kept
+                    let rest: Vec<&str> = item.content.lines().skip(1).collect();
+                    let mut content = value.to_string();
+                    for line in rest {
+                        content.push('\n');
+                        content.push_str(line);
+                    }
+                    item.content = content;
+                    Ok(())
+                })?;
+                self.message = Some("Updated content".to_string());
+                self.reload(conn)
+            }
+            Prompt::Due => {
+                self.update_selected(conn, |item| {
+                    if item.action == RECURRING_TASK {
+                        item.cron_schedule = Some(timestr::parse_recurring_timestr(value)?);
+                        item.human_schedule = Some(value.to_string());
+                    } else {
+                        item.target_time = Some(timestr::to_unix_epoch(value)?);
+                    }
+                    Ok(())
+                })?;
+                self.message = Some(format!("Due {}", value));
+                self.reload(conn)
+            }
+            Prompt::Namespace => {
+                let user = self.ctx.current_user_name.clone();
+                self.switch_context(conn, &user, value)
+            }
+            Prompt::User => {
+                let namespace = self.ctx.current_namespace_name.clone();
+                self.switch_context(conn, value, &namespace)
+            }
+        }
+    }
+
+    /// Resolves identity the same way as `--as` and `--ns`
+    fn switch_context(&mut self, conn: &Connection, user: &str, namespace: &str) -> Result<(), String> {
+        self.ctx = Context::resolve(conn, Some(user), Some(namespace))?;
+        self.message = Some(format!(
+            "Now {} in '{}'",
+            self.ctx.current_user_name, self.ctx.current_namespace_name
+        ));
+        self.selected = 0;
+        self.reload(conn)
+    }
+
+    fn update_selected(
+        &mut self,
+        conn: &Connection,
+        change: impl FnOnce(&mut Item) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let mut item = self.require_selected()?;
+        let before = item.clone();
+        change(&mut item)?;
+        update_item(conn, &item).map_err(describe_update_error)?;
+        log_item_changes(conn, &before, &item, self.ctx.current_user_id)
+    }
+
+    fn select(&mut self, conn: &Connection, index: usize) -> Result<(), String> {
+        self.selected = index;
+        self.refresh_detail(conn)
+    }
+
+    fn require_selected(&self) -> Result<Item, String> {
+        self.selected_item()
+            .cloned()
+            .ok_or_else(|| "Nothing selected".to_string())
+    }
+}
+
+pub fn first_line(content: &str) -> &str {
+    content.lines().next().unwrap_or(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{
+            conn::init_table,
+            crud::get_item,
+            namespace::add_user_to_namespace,
+            user::create_user,
+        },
+        tests::insert_task,
+    };
+
+    // The TUI runs against an in-memory database here, not a temp file
+    fn setup() -> (Connection, Context) {
+        let conn = Connection::open_in_memory().unwrap();
+        init_table(&conn).unwrap();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        (conn, ctx)
+    }
+
+    fn add_task(conn: &Connection, ctx: &Context, content: &str, timestr: &str) -> i64 {
+        let id = insert_task(conn, "work", content, timestr);
+        conn.execute(
+            "UPDATE items SET namespace_id = ?1 WHERE id = ?2",
+            rusqlite::params![ctx.current_namespace_id, id],
+        )
+        .unwrap();
+        id
+    }
+
+    fn press(app: &mut App, conn: &Connection, keys: &str) {
+        for c in keys.chars() {
+            let key = if c == '\n' { Key::Enter } else { Key::Char(c) };
+            app.handle_key(conn, key);
+        }
+    }
+
+    #[test]
+    fn test_navigate_and_done() {
+        let (conn, ctx) = setup();
+        add_task(&conn, &ctx, "first", "today");
+        let second_id = add_task(&conn, &ctx, "second", "tomorrow");
+        let mut app = App::new(&conn, ctx, View::Tasks).unwrap();
+        assert_eq!(app.items.len(), 2);
+        assert!(app.detail[0].contains("first"));
+
+        press(&mut app, &conn, "jj");
+        assert_eq!(app.selected, 1);
+        assert!(app.detail[0].contains("second"));
+
+        press(&mut app, &conn, "d");
+        assert_eq!(get_item(&conn, second_id).unwrap().status, 1);
+        assert_eq!(app.items.len(), 1);
+        assert_eq!(app.message.as_deref(), Some("Completed: second"));
+
+        // Closed tasks come back when showing all
+        press(&mut app, &conn, "a");
+        assert_eq!(app.items.len(), 2);
+    }
+
+    #[test]
+    fn test_filter_claim_and_edit() {
+        let (conn, ctx) = setup();
+        add_task(&conn, &ctx, "write docs", "today");
+        let bug_id = add_task(&conn, &ctx, "fix login bug", "tomorrow");
+        let mut app = App::new(&conn, ctx.clone(), View::Tasks).unwrap();
+
+        press(&mut app, &conn, "/login");
+        assert_eq!(app.items.len(), 1);
+        press(&mut app, &conn, "\n");
+        assert!(app.input.is_none());
+        assert_eq!(app.filter, "login");
+
+        press(&mut app, &conn, "c");
+        assert_eq!(get_item(&conn, bug_id).unwrap().assignee_id, Some(ctx.current_user_id));
+        press(&mut app, &conn, "m");
+        assert_eq!(app.items.len(), 1);
+
+        app.handle_key(&conn, Key::Char('e'));
+        for _ in 0.."fix login bug".len() {
+            app.handle_key(&conn, Key::Backspace);
+        }
+        press(&mut app, &conn, "fix signup bug\n");
+        assert_eq!(get_item(&conn, bug_id).unwrap().content, "fix signup bug");
+        // The filter no longer matches the edited task
+        assert!(app.items.is_empty());
+
+        app.handle_key(&conn, Key::Esc);
+        assert!(app.filter.is_empty());
+    }
+
+    #[test]
+    fn test_snooze_and_errors() {
+        let (conn, ctx) = setup();
+        let id = add_task(&conn, &ctx, "follow up", "today");
+        let mut app = App::new(&conn, ctx, View::Tasks).unwrap();
+
+        press(&mut app, &conn, "ztomorrow\n");
+        assert!(get_item(&conn, id).unwrap().snooze_until.is_some());
+        assert!(app.items.is_empty());
+
+        // Errors land on the status line instead of ending the session
+        press(&mut app, &conn, "d");
+        assert_eq!(app.message.as_deref(), Some("Error: Nothing selected"));
+        assert!(!app.quit);
+        press(&mut app, &conn, "q");
+        assert!(app.quit);
+    }
+
+    #[test]
+    fn test_switch_user_and_namespace() {
+        let (conn, ctx) = setup();
+        add_task(&conn, &ctx, "default ns task", "today");
+        create_user(&conn, "sarah", None, None).unwrap();
+        add_user_to_namespace(&conn, "default", "sarah", "member").unwrap();
+        let mut app = App::new(&conn, ctx, View::Tasks).unwrap();
+
+        press(&mut app, &conn, "nmissing\n");
+        assert!(app.message.as_deref().unwrap().contains("not found"));
+
+        press(&mut app, &conn, "usarah\n");
+        assert_eq!(app.ctx.current_user_name, "sarah");
+        assert_eq!(app.items.len(), 1);
+
+        app.handle_key(&conn, Key::Tab);
+        assert_eq!(app.view, View::Records);
+    }
+}
@@ -0,0 +1,42 @@
+mod app;
+mod render;
+mod terminal;
+
+use rusqlite::Connection;
+
+use crate::{
+    args::parser::TuiCommand,
+    context::Context,
+};
+use app::{
+    App,
+    View,
+};
+use terminal::RawTerminal;
+
+/// Runs the full-screen triage view until the user quits
+pub fn handle_tuicmd(conn: &Connection, ctx: &Context, cmd: &TuiCommand) -> Result<(), String> {
+    let view = if cmd.records { View::Records } else { View::Tasks };
+    let mut app = App::new(conn, ctx.clone(), view)?;
+    let terminal = RawTerminal::enter()?;
+
+    let mut size = terminal.size();
+    terminal.draw(&render::render(&app, size.0, size.1))?;
+    while !app.quit {
+        let keys = terminal.read_keys()?;
+        let new_size = terminal.size();
+        // Only redraw on input or when the terminal was resized
+        if keys.is_empty() && new_size == size {
+            continue;
+        }
+        size = new_size;
+        for key in keys {
+            app.handle_key(conn, key);
+            if app.quit {
+                break;
+            }
+        }
+        terminal.draw(&render::render(&app, size.0, size.1))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,216 @@
+use unicode_width::UnicodeWidthChar;
+
+use super::app::{
+    first_line,
+    App,
+    View,
+};
+use crate::{
+    actions::display::{
+        paint,
+        paint_opt,
+        status_style,
+        DisplayRow,
+        Style,
+    },
+    args::columns::Column,
+    db::item::{
+        RECORD,
+        RECURRING_TASK_RECORD,
+    },
+};
+
+const HELP: &str = "j/k move  / filter  d done  c claim  z snooze  e edit  t due  \
+                    a all  m mine  n ns  u user  tab view  r refresh  q quit";
+const SEPARATOR: &str = " │ ";
+
+/// Draws the whole screen as `height` lines of at most `width` columns.
+/// Kept free of terminal I/O so frames can be checked in tests.
+pub fn render(app: &App, width: usize, height: usize) -> Vec<String> {
+    let mut lines = Vec::with_capacity(height);
+    lines.push(fit(&paint(&header(app), Style::Heading), width));
+    lines.push("─".repeat(width));
+
+    // Header, separator and footer take three lines
+    let body_height = height.saturating_sub(3);
+    let list_width = (width * 45 / 100).max(20).min(width);
+    let detail_width = width.saturating_sub(list_width + SEPARATOR.chars().count());
+
+    let offset = scroll_offset(app.selected, app.items.len(), body_height);
+    for row in 0..body_height {
+        let left = match app.items.get(offset + row) {
+            Some(_) => list_line(app, offset + row, list_width),
+            None if row == 0 => fit(&paint("Nothing to show", Style::Label), list_width),
+            None => " ".repeat(list_width),
+        };
+        let right = app.detail.get(row).map_or("", String::as_str);
+        if detail_width == 0 {
+            lines.push(left);
+        } else {
+            lines.push(format!("{}{}{}", left, SEPARATOR, fit(right, detail_width)));
+        }
+    }
+
+    lines.push(fit(&footer(app), width));
+    lines.truncate(height);
+    lines
+}
+
+fn header(app: &App) -> String {
+    let view = match app.view {
+        View::Tasks => "tasks",
+        View::Records => "records",
+    };
+    let mut header = format!(
+        "ctm  {}@{}  {} ({})",
+        app.ctx.current_user_name,
+        app.ctx.current_namespace_name,
+        view,
+        app.items.len()
+    );
+    if !app.filter.is_empty() {
+        header.push_str(&format!("  filter: {}", app.filter));
+    }
+    if app.show_closed {
+        header.push_str("  [all]");
+    }
+    if app.only_mine {
+        header.push_str("  [mine]");
+    }
+    header
+}
+
+fn footer(app: &App) -> String {
+    if let Some((prompt, buffer)) = &app.input {
+        return format!("{}: {}█", prompt.label(), buffer);
+    }
+    match &app.message {
+        Some(message) if message.starts_with("Error:") => paint(message, Style::Error),
+        Some(message) => message.clone(),
+        None => paint(HELP, Style::Label),
+    }
+}
+
+fn list_line(app: &App, index: usize, width: usize) -> String {
+    let item = &app.items[index];
+    let is_record = item.action == RECORD || item.action == RECURRING_TASK_RECORD;
+    let row = if is_record {
+        DisplayRow::from_record((index + 1).to_string(), item)
+    } else {
+        DisplayRow::from_task((index + 1).to_string(), item)
+    };
+
+    let time = row.cell(Column::Time);
+    // The time is dropped on narrow screens to leave room for the content
+    let time_width = if width >= 40 { time.chars().count().min(width / 3) } else { 0 };
+    let text_width = width.saturating_sub(time_width + 6);
+    let text = format!(
+        "{:>3} {} {}",
+        row.index,
+        fit(first_line(&row.content), text_width),
+        fit(time, time_width)
+    );
+    let line = fit(&text, width);
+
+    if index == app.selected {
+        paint(&line, Style::Selected)
+    } else if row.is_overdue {
+        paint(&line, Style::Overdue)
+    } else {
+        paint_opt(&line, status_style(row.status_code))
+    }
+}
+
+// First visible row, so that the selection stays on screen
+fn scroll_offset(selected: usize, len: usize, height: usize) -> usize {
+    if height == 0 || len <= height {
+        return 0;
+    }
+    (selected + 1).saturating_sub(height).min(len - height)
+}
+
+/// Cuts or pads a line to exactly `width` columns.
+/// Escape sequences are copied through and do not count towards the width.
+pub fn fit(s: &str, width: usize) -> String {
+    let mut out = String::new();
+    let mut used = 0;
+    let mut styled = false;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            out.push(c);
+            for c in chars.by_ref() {
+                out.push(c);
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+            styled = true;
+            continue;
+        }
+        let w = c.width().unwrap_or(0);
+        if used + w > width {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    if styled {
+        out.push_str("\x1b[0m");
+    }
+    out.push_str(&" ".repeat(width - used));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::Connection;
+
+    use super::*;
+    use crate::{
+        context::Context,
+        db::conn::init_table,
+        tests::insert_task,
+    };
+
+    #[test]
+    fn test_fit() {
+        assert_eq!(fit("hello", 8), "hello   ");
+        assert_eq!(fit("hello world", 5), "hello");
+        assert_eq!(fit("日本語", 5), "日本 ");
+        assert_eq!(fit("\x1b[1mbold\x1b[0m", 6), "\x1b[1mbold\x1b[0m\x1b[0m  ");
+    }
+
+    #[test]
+    fn test_scroll_offset() {
+        assert_eq!(scroll_offset(3, 5, 10), 0);
+        assert_eq!(scroll_offset(9, 50, 10), 0);
+        assert_eq!(scroll_offset(10, 50, 10), 1);
+        assert_eq!(scroll_offset(49, 50, 10), 40);
+    }
+
+    #[test]
+    fn test_render_frame() {
+        let conn = Connection::open_in_memory().unwrap();
+        init_table(&conn).unwrap();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let id = insert_task(&conn, "work", "review the release notes", "tomorrow");
+        conn.execute(
+            "UPDATE items SET namespace_id = ?1 WHERE id = ?2",
+            rusqlite::params![ctx.current_namespace_id, id],
+        )
+        .unwrap();
+        let app = App::new(&conn, ctx, View::Tasks).unwrap();
+
+        let frame = render(&app, 100, 12);
+        assert_eq!(frame.len(), 12);
+        assert!(frame[0].contains("tasks (1)"));
+        assert!(frame[2].contains("review the release notes"));
+        assert!(frame[11].contains("j/k move"));
+
+        // The list still fits on a narrow terminal
+        let frame = render(&app, 30, 6);
+        assert_eq!(frame.len(), 6);
+        assert!(frame[2].contains("review"));
+    }
+}
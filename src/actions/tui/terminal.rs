@@ -0,0 +1,178 @@
+use std::{
+    io::{
+        self,
+        IsTerminal,
+        Read,
+        Write,
+    },
+    process::{
+        Command,
+        Stdio,
+    },
+};
+
+use terminal_size::{
+    terminal_size,
+    Height,
+    Width,
+};
+
+/// A key press, decoded from the raw bytes the terminal sends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Enter,
+    Esc,
+    Backspace,
+    Tab,
+    CtrlC,
+}
+
+/// Puts the terminal in raw mode on the alternate screen, restoring it on drop.
+/// Raw mode is set with `stty` so no terminal library is needed.
+pub struct RawTerminal {
+    saved_mode: String,
+}
+
+impl RawTerminal {
+    pub fn enter() -> Result<Self, String> {
+        if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+            return Err("ctm tui needs an interactive terminal".to_string());
+        }
+        let saved_mode = stty(&["-g"])?;
+        // Reads return after 0.1s even without input, so a lone Esc can be told
+        // apart from an escape sequence and the screen can follow resizes
+        stty(&["raw", "-echo", "min", "0", "time", "1"])?;
+        let terminal = RawTerminal {
+            saved_mode: saved_mode.trim().to_string(),
+        };
+        terminal.write("\x1b[?1049h\x1b[?25l")?;
+        Ok(terminal)
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        match terminal_size() {
+            Some((Width(w), Height(h))) => (w as usize, h as usize),
+            None => (80, 24),
+        }
+    }
+
+    pub fn draw(&self, lines: &[String]) -> Result<(), String> {
+        let mut frame = String::from("\x1b[H\x1b[2J");
+        frame.push_str(&lines.join("\r\n"));
+        self.write(&frame)
+    }
+
+    /// Waits briefly for input; returns no keys when none arrived
+    pub fn read_keys(&self) -> Result<Vec<Key>, String> {
+        let mut buf = [0u8; 64];
+        let n = io::stdin()
+            .read(&mut buf)
+            .map_err(|e| format!("Failed to read from terminal: {}", e))?;
+        Ok(parse_keys(&buf[..n]))
+    }
+
+    fn write(&self, s: &str) -> Result<(), String> {
+        let mut stdout = io::stdout();
+        stdout
+            .write_all(s.as_bytes())
+            .and_then(|_| stdout.flush())
+            .map_err(|e| format!("Failed to write to terminal: {}", e))
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = self.write("\x1b[?25h\x1b[?1049l");
+        let _ = stty(&[self.saved_mode.as_str()]);
+    }
+}
+
+fn stty(args: &[&str]) -> Result<String, String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()
+        .map_err(|e| format!("Failed to run stty: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "stty failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Decodes raw terminal input into keys, including arrow and paging sequences
+pub fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+    let chars: Vec<char> = String::from_utf8_lossy(bytes).chars().collect();
+    let mut keys = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        let key = match c {
+            '\x1b' => match (chars.get(i), chars.get(i + 1)) {
+                (Some('[') | Some('O'), Some(code)) => {
+                    i += 2;
+                    match code {
+                        'A' => Key::Up,
+                        'B' => Key::Down,
+                        'C' => Key::Right,
+                        'D' => Key::Left,
+                        'H' => Key::Home,
+                        'F' => Key::End,
+                        digit if digit.is_ascii_digit() => {
+                            // e.g. ESC [ 5 ~; skip to the terminating '~'
+                            while i < chars.len() && chars[i] != '~' {
+                                i += 1;
+                            }
+                            i += 1;
+                            match digit {
+                                '1' | '7' => Key::Home,
+                                '4' | '8' => Key::End,
+                                '5' => Key::PageUp,
+                                '6' => Key::PageDown,
+                                _ => continue,
+                            }
+                        }
+                        _ => continue,
+                    }
+                }
+                _ => Key::Esc,
+            },
+            '\r' | '\n' => Key::Enter,
+            '\x7f' | '\x08' => Key::Backspace,
+            '\t' => Key::Tab,
+            '\x03' => Key::CtrlC,
+            c if c.is_control() => continue,
+            c => Key::Char(c),
+        };
+        keys.push(key);
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(parse_keys(b"jk"), vec![Key::Char('j'), Key::Char('k')]);
+        assert_eq!(parse_keys(b"\x1b[A\x1b[B"), vec![Key::Up, Key::Down]);
+        assert_eq!(parse_keys(b"\x1b[5~\x1b[6~"), vec![Key::PageUp, Key::PageDown]);
+        assert_eq!(parse_keys(b"\x1bOH"), vec![Key::Home]);
+        assert_eq!(parse_keys(b"\x1b"), vec![Key::Esc]);
+        assert_eq!(parse_keys(b"\r\x7f\t\x03"), vec![Key::Enter, Key::Backspace, Key::Tab, Key::CtrlC]);
+        assert_eq!(parse_keys("é".as_bytes()), vec![Key::Char('é')]);
+    }
+}
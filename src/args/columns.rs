@@ -0,0 +1,116 @@
+/// A column that can be shown in task and record tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    Index,
+    Category,
+    Content,
+    Time,
+    Status,
+    Priority,
+    Estimate,
+    Assignee,
+    Project,
+    Links,
+    Notes,
+}
+
+/// Columns shown when neither `--columns` nor config say otherwise
+pub const DEFAULT_COLUMNS: &[Column] = &[Column::Index, Column::Category, Column::Content, Column::Time];
+
+/// Every column with the name used for it in `--columns` and config
+const COLUMN_NAMES: &[(&str, Column)] = &[
+    ("index", Column::Index),
+    ("category", Column::Category),
+    ("content", Column::Content),
+    ("time", Column::Time),
+    ("status", Column::Status),
+    ("priority", Column::Priority),
+    ("estimate", Column::Estimate),
+    ("assignee", Column::Assignee),
+    ("project", Column::Project),
+    ("links", Column::Links),
+    ("notes", Column::Notes),
+];
+
+impl Column {
+    pub fn header(self, is_record: bool) -> &'static str {
+        match self {
+            Column::Index => "Index",
+            Column::Category => "Category",
+            Column::Content => "Content",
+            Column::Time if is_record => "Created At",
+            Column::Time => "Deadline",
+            Column::Status => "Status",
+            Column::Priority => "Priority",
+            Column::Estimate => "Estimate",
+            Column::Assignee => "Assignee",
+            Column::Project => "Project",
+            Column::Links => "Links",
+            Column::Notes => "Notes",
+        }
+    }
+
+    /// Preferred and minimum width; content takes whatever space is left.
+    pub fn width(self) -> (usize, usize) {
+        match self {
+            Column::Index => (7, 7),
+            Column::Category => (20, 8),
+            Column::Content => (0, 10),
+            Column::Time => (20, 10),
+            Column::Status => (10, 9),
+            Column::Priority => (9, 8),
+            Column::Estimate => (9, 6),
+            Column::Assignee => (12, 6),
+            Column::Project => (14, 6),
+            Column::Links => (6, 6),
+            Column::Notes => (6, 6),
+        }
+    }
+}
+
+/// Parse a comma separated column list, e.g. "index,priority,content,time".
+/// `deadline` and `created` are accepted as aliases for `time`.
+pub fn parse_columns(s: &str) -> Result<Vec<Column>, String> {
+    let mut columns = Vec::new();
+    for name in s.split(',').map(|n| n.trim().to_lowercase()).filter(|n| !n.is_empty()) {
+        let name = match name.as_str() {
+            "deadline" | "created" => "time",
+            other => other,
+        };
+        let column = COLUMN_NAMES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, c)| *c)
+            .ok_or_else(|| {
+                let valid: Vec<&str> = COLUMN_NAMES.iter().map(|(n, _)| *n).collect();
+                format!("Invalid column '{}'. Valid columns: {}", name, valid.join(", "))
+            })?;
+        if !columns.contains(&column) {
+            columns.push(column);
+        }
+    }
+    if !columns.contains(&Column::Content) {
+        return Err("Columns must include 'content'".to_string());
+    }
+    Ok(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_columns() {
+        assert_eq!(
+            parse_columns("index, Priority,content,deadline").unwrap(),
+            vec![Column::Index, Column::Priority, Column::Content, Column::Time]
+        );
+        // Duplicates are dropped
+        assert_eq!(
+            parse_columns("content,notes,notes").unwrap(),
+            vec![Column::Content, Column::Notes]
+        );
+        assert!(parse_columns("index,owner,content").unwrap_err().contains("Invalid column 'owner'"));
+        assert!(parse_columns("index,time").unwrap_err().contains("must include 'content'"));
+    }
+}
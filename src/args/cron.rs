@@ -6,6 +6,10 @@ use chrono::{
     Timelike,
 };
 
+// Upper bound on occurrences listed for one window, e.g. a minute schedule over a year,
+// and on a recurring task's occurrence limit
+pub const MAX_OCCURRENCES: usize = 1000;
+
 // Parse a cron string and return the next or last occurrence timestamp
 // The cron implementation is specific to this project
 // avoiding additional dependency while implementing specific
@@ -18,6 +22,43 @@ pub fn get_last_occurrence(cron_str: &str) -> Result<i64, String> {
     get_occurrence_from(cron_str, Local::now(), false)
 }
 
+/// The first occurrence of the schedule strictly after `time`
+pub fn get_occurrence_after(cron_str: &str, time: i64) -> Result<i64, String> {
+    let current = Local
+        .timestamp_opt(time, 0)
+        .single()
+        .ok_or_else(|| format!("Invalid timestamp: {}", time))?;
+    get_occurrence_from(cron_str, current, true)
+}
+
+/// The last occurrence of the schedule strictly before `time`
+pub fn get_occurrence_before(cron_str: &str, time: i64) -> Result<i64, String> {
+    let current = Local
+        .timestamp_opt(time, 0)
+        .single()
+        .ok_or_else(|| format!("Invalid timestamp: {}", time))?;
+    get_occurrence_from(cron_str, current, false)
+}
+
+/// Every occurrence of the schedule from `start` (inclusive) to `end` (exclusive), in order
+pub fn get_occurrences_between(cron_str: &str, start: i64, end: i64) -> Result<Vec<i64>, String> {
+    let mut occurrences = Vec::new();
+    let mut now = start - 1;
+    while occurrences.len() < MAX_OCCURRENCES {
+        let current = Local
+            .timestamp_opt(now, 0)
+            .single()
+            .ok_or_else(|| format!("Invalid timestamp: {}", now))?;
+        let next = get_occurrence_from(cron_str, current, true)?;
+        if next >= end {
+            break;
+        }
+        occurrences.push(next);
+        now = next;
+    }
+    Ok(occurrences)
+}
+
 // Underlying implementation to allow for testing
 fn get_occurrence_from(cron_str: &str, now: chrono::DateTime<Local>, forward: bool) -> Result<i64, String> {
     let parts: Vec<&str> = cron_str.split_whitespace().collect();
@@ -171,19 +212,24 @@ fn calculate_monthly(
     }
 
     if forward {
-        // Try next month
-        month += 1;
-        if month > 12 {
-            month = 1;
-            year += 1;
-        }
-        let dt = Local
-            .with_ymd_and_hms(year, month, day, hour, minute, 0)
-            .earliest();
-        match dt {
-            Some(dt) => Ok(dt.timestamp()),
-            None => Err(format!("Day {} does not exist in month {}", day, month)),
+        // Try next months (up to 12 months ahead) to find valid day
+        for _ in 0..12 {
+            if month == 12 {
+                month = 1;
+                year += 1;
+            } else {
+                month += 1;
+            }
+
+            if let Some(dt) = Local
+                .with_ymd_and_hms(year, month, day, hour, minute, 0)
+                .earliest()
+            {
+                return Ok(dt.timestamp());
+            }
         }
+
+        Err(format!("Day {} does not exist in any month", day))
     } else {
         // Try previous months (up to 12 months back) to find valid day
         for _ in 0..12 {
@@ -280,6 +326,7 @@ mod tests {
             ("2024-03-15 10:00", "0 9 15 * *", "2024-04-15 09:00"), // Same day after time
             // Monthly edge case - Feb 30 doesn't exist
             ("2024-02-15 10:00", "0 9 30 * *", "2024-03-30 09:00"), // Skip Feb
+            ("2024-01-31 10:00", "0 9 31 * *", "2024-03-31 09:00"), // Skip Feb from a 31st
             // Yearly tests
             ("2024-03-15 10:00", "0 9 25 12 *", "2024-12-25 09:00"), // Same year
             ("2024-12-26 10:00", "0 9 25 12 *", "2025-12-25 09:00"), // Next year
@@ -388,4 +435,43 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_occurrences_between() {
+        let at = |s: &str| {
+            let naive = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+            Local.from_local_datetime(&naive).unwrap().timestamp()
+        };
+        let format = |ts: &[i64]| -> Vec<String> {
+            ts.iter()
+                .map(|t| Local.timestamp_opt(*t, 0).unwrap().format("%m-%d %H:%M").to_string())
+                .collect()
+        };
+
+        // Weekdays in one week; the start is inclusive and the end exclusive
+        let week = get_occurrences_between("0 9 * * 1-5", at("2024-03-18 09:00"), at("2024-03-22 09:00")).unwrap();
+        assert_eq!(format(&week), vec!["03-18 09:00", "03-19 09:00", "03-20 09:00", "03-21 09:00"]);
+
+        // Monthly on the 31st skips the short months
+        let months = get_occurrences_between("0 9 31 * *", at("2024-01-01 00:00"), at("2024-06-01 00:00")).unwrap();
+        assert_eq!(format(&months), vec!["01-31 09:00", "03-31 09:00", "05-31 09:00"]);
+
+        // Nothing in the window, and bad schedules are errors
+        assert!(get_occurrences_between("0 9 25 12 *", at("2024-01-01 00:00"), at("2024-02-01 00:00")).unwrap().is_empty());
+        assert!(get_occurrences_between("bad", at("2024-01-01 00:00"), at("2024-02-01 00:00")).is_err());
+    }
+
+    #[test]
+    fn test_occurrence_before_and_after() {
+        let at = |s: &str| {
+            let naive = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+            Local.from_local_datetime(&naive).unwrap().timestamp()
+        };
+
+        // Both are strict, so an occurrence itself steps to its neighbour
+        let monday = at("2024-03-18 09:00");
+        assert_eq!(get_occurrence_before("0 9 * * 1-5", monday).unwrap(), at("2024-03-15 09:00"));
+        assert_eq!(get_occurrence_after("0 9 * * 1-5", monday).unwrap(), at("2024-03-19 09:00"));
+        assert_eq!(get_occurrence_before("0 9 * * *", at("2024-03-18 12:00")).unwrap(), monday);
+    }
 }
@@ -23,7 +23,7 @@ pub fn parse_estimate(s: &str) -> Result<i64, String> {
             format!("Invalid hours in estimate: '{}'", parts[0])
         })?;
 
-        let min_str = parts[1].trim().trim_end_matches(|c| c == 'm' || c == 'i' || c == 'n');
+        let min_str = parts[1].trim().trim_end_matches(['m', 'i', 'n']);
         let mins: i64 = if min_str.is_empty() {
             0
         } else {
@@ -37,7 +37,7 @@ pub fn parse_estimate(s: &str) -> Result<i64, String> {
 
     // Handle hours: "2h", "2hr", "2hrs", "1.5h"
     if s.ends_with('h') || s.ends_with("hr") || s.ends_with("hrs") {
-        let num_str = s.trim_end_matches(|c| c == 'h' || c == 'r' || c == 's');
+        let num_str = s.trim_end_matches(['h', 'r', 's']);
         let hours: f64 = num_str.parse().map_err(|_| {
             format!("Invalid hours: '{}'", s)
         })?;
@@ -46,7 +46,7 @@ pub fn parse_estimate(s: &str) -> Result<i64, String> {
 
     // Handle minutes: "30m", "30min"
     if s.ends_with('m') || s.ends_with("min") {
-        let num_str = s.trim_end_matches(|c| c == 'm' || c == 'i' || c == 'n');
+        let num_str = s.trim_end_matches(['m', 'i', 'n']);
         let mins: i64 = num_str.parse().map_err(|_| {
             format!("Invalid minutes: '{}'", s)
         })?;
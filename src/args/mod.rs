@@ -1,3 +1,4 @@
+pub mod columns;
 pub mod cron;
 pub mod estimate;
 pub mod parser;
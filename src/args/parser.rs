@@ -3,7 +3,14 @@ use clap::{
     Parser,
     Subcommand,
 };
+use crate::actions::{
+    board::BoardGrouping,
+    calendar::CalendarSpan,
+    display::ColorMode,
+    gh::ConflictPolicy,
+};
 use crate::args::{
+    columns::parse_columns,
     estimate::parse_estimate,
     priority::parse_priority,
     timestr::{parse_flexible_timestr, parse_recurring_timestr},
@@ -24,6 +31,10 @@ pub struct CliArgs {
     #[arg(long = "ns", global = true)]
     pub namespace: Option<String>,
 
+    /// when to colour output: auto, always or never (auto honours NO_COLOR and CLICOLOR_FORCE)
+    #[arg(long, global = true, value_name = "WHEN", default_value = "auto", value_parser = parse_color_mode)]
+    pub color: ColorMode,
+
     #[command(subcommand)]
     pub arguments: Action,
 }
@@ -47,22 +58,61 @@ pub enum Action {
     Note(NoteCommand),
     /// show detailed view of a task
     Show(ShowCommand),
+    /// print everything known about a task as Markdown or JSON, for an AI session
+    Context(ContextCommand),
     /// claim an unassigned task
     Claim(ClaimCommand),
+    /// hide a task until a given time, then return it to ongoing
+    Snooze(SnoozeCommand),
+    /// skip the current interval of a recurring task without breaking its streak
+    Skip(SkipCommand),
     /// attach a link (commit, issue, PR, URL) to a task
     Link(LinkCommand),
+    /// create and check out a git branch for a task in its project
+    Branch(BranchCommand),
+    /// link commits to the tasks they mention with git hooks
+    #[command(subcommand)]
+    Git(GitCommand),
     /// manage users
     #[command(subcommand)]
     User(UserCommand),
     /// manage namespaces
     #[command(subcommand)]
     Ns(NamespaceCommand),
+    /// manage the projects of the namespace
+    #[command(subcommand)]
+    Project(ProjectCommand),
     /// show team task distribution
     Team(TeamCommand),
     /// show workload by user
     Workload(WorkloadCommand),
     /// show task statistics
     Stats(StatsCommand),
+    /// show trend reports: burndown, velocity and flow
+    #[command(subcommand)]
+    Report(ReportCommand),
+    /// show open tasks as a kanban board by status, assignee or priority
+    Board(BoardCommand),
+    /// show a week or month calendar of tasks and recurring occurrences
+    Cal(CalCommand),
+    /// show today's agenda: overdue, due today, reminders and recurring tasks
+    Today(TodayCommand),
+    /// show a standup report: done yesterday, planned today and blockers
+    Standup(StandupCommand),
+    /// show recent activity in the namespace
+    Feed(FeedCommand),
+    /// interactive full-screen triage of tasks and records
+    Tui(TuiCommand),
+    /// serve tasks to AI agents over the Model Context Protocol on stdin/stdout
+    Mcp,
+    /// sync tasks with their GitHub issues and pull requests
+    #[command(subcommand)]
+    Gh(GhCommand),
+    /// show tasks with linked pull requests and their review, CI and merge status
+    Prs(PrsCommand),
+    /// manage the database schema
+    #[command(subcommand)]
+    Db(DbCommand),
 }
 
 #[derive(Debug, Args)]
@@ -81,7 +131,7 @@ pub struct TaskCommand {
     /// defaults to 7 days when specified without value
     #[arg(short = 'r', long, default_missing_value = "7", num_args = 0..=1)]
     pub reminder: Option<i64>,
-    /// project name (must be defined in ~/.config/ctm/config.json)
+    /// project name (see `ctm project list`; default: the current directory's project)
     #[arg(short = 'p', long)]
     pub project: Option<String>,
     /// priority: high, normal (default), low (or h/n/l)
@@ -93,7 +143,7 @@ pub struct TaskCommand {
     /// assign task to a user (username)
     #[arg(long = "for")]
     pub assignee: Option<String>,
-    /// create task from GitHub issue (e.g., owner/repo#42)
+    /// create task from an issue, merge request or ticket (e.g., owner/repo#42, group/project!12, PROJ-123 or its URL)
     #[arg(long)]
     pub from_issue: Option<String>,
 }
@@ -123,7 +173,7 @@ pub struct DoneCommand {
     /// add comment to task content and completion record
     #[arg(short, long)]
     pub comment: Option<String>,
-    /// close linked GitHub issue when completing task
+    /// close the task's issue or ticket when completing task
     #[arg(long)]
     pub close_issue: bool,
 }
@@ -161,9 +211,24 @@ pub struct UpdateCommand {
     /// set reminder days before due date to show in today view
     #[arg(short = 'r', long)]
     pub reminder: Option<i64>,
-    /// update project association (must be defined in config)
+    /// update project association (see `ctm project list`)
     #[arg(short = 'p', long)]
     pub project: Option<String>,
+    /// first day a recurring task occurs, "none" to start right away
+    #[arg(long, value_parser = validate_bound)]
+    pub starts: Option<String>,
+    /// last day a recurring task occurs (inclusive), "none" to run forever
+    #[arg(long, value_parser = validate_bound)]
+    pub ends: Option<String>,
+    /// stop a recurring task after this many occurrences, 0 to lift the limit
+    #[arg(long, value_parser = clap::value_parser!(i64).range(0..))]
+    pub max_occurrences: Option<i64>,
+    /// pause a recurring task until resumed
+    #[arg(long, default_value_t = false, conflicts_with = "resume")]
+    pub pause: bool,
+    /// resume a paused recurring task; intervals missed while paused count as skipped
+    #[arg(long, default_value_t = false)]
+    pub resume: bool,
 }
 
 #[derive(Debug, Subcommand)]
@@ -203,6 +268,12 @@ pub struct ListTaskCommand {
     /// next page if the previous list command reached limit
     #[arg(short, long, default_value_t = false)]
     pub next_page: bool,
+    /// only show tasks in this project
+    #[arg(long)]
+    pub project: Option<String>,
+    /// only show tasks in the project of the current directory
+    #[arg(long, default_value_t = false, conflicts_with = "project")]
+    pub here: bool,
     /// search for tasks containing this text in their content
     #[arg(long)]
     pub search: Option<String>,
@@ -212,6 +283,19 @@ pub struct ListTaskCommand {
     /// show tasks for all users (ignores current user filter)
     #[arg(long, default_value_t = false)]
     pub all_users: bool,
+    /// only show tasks modified since this time, e.g. yesterday
+    #[arg(long, value_parser = validate_timestr)]
+    pub changed_since: Option<String>,
+    /// show snoozed tasks and when they wake, regardless of status or due time
+    #[arg(long, default_value_t = false)]
+    pub snoozed: bool,
+    /// show recurring tasks that were not done in their previous interval
+    #[arg(long, default_value_t = false, conflicts_with = "snoozed")]
+    pub missed: bool,
+    /// table columns, e.g. index,priority,content,time,assignee;
+    /// also: category, status, estimate, project, links, notes
+    #[arg(long, value_parser = validate_columns)]
+    pub columns: Option<String>,
 }
 
 #[derive(Debug, Args)]
@@ -241,6 +325,10 @@ pub struct ListRecordCommand {
     /// search for records containing this text in their content
     #[arg(long)]
     pub search: Option<String>,
+    /// table columns, e.g. index,priority,content,time,assignee;
+    /// also: category, status, estimate, project, links, notes
+    #[arg(long, value_parser = validate_columns)]
+    pub columns: Option<String>,
 }
 
 #[derive(Debug, Args)]
@@ -339,6 +427,68 @@ pub struct NamespaceMembersCommand {
     pub namespace: Option<String>,
 }
 
+#[derive(Debug, Subcommand)]
+pub enum ProjectCommand {
+    /// add a project to the current namespace
+    Add(ProjectAddCommand),
+    /// list the projects of the current namespace
+    List(ProjectListCommand),
+    /// show a project, its local settings and task counts
+    Show(ProjectShowCommand),
+    /// rename a project, moving its tasks along
+    Rename(ProjectRenameCommand),
+    /// archive a project so it takes no new tasks
+    Archive(ProjectArchiveCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct ProjectAddCommand {
+    /// project name, unique within the namespace
+    pub name: String,
+    /// description of the project
+    #[arg(short = 'd', long)]
+    pub description: Option<String>,
+    /// where the project is checked out; each user can override it in config
+    #[arg(long)]
+    pub path: Option<String>,
+    /// category for new tasks in the project
+    #[arg(short, long)]
+    pub category: Option<String>,
+    /// prompt for sessions on the project's tasks, e.g. "Work on: {content}"
+    #[arg(long)]
+    pub prompt_template: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct ProjectListCommand {
+    /// include archived projects
+    #[arg(short, long, default_value_t = false)]
+    pub all: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ProjectShowCommand {
+    /// project name
+    pub name: String,
+}
+
+#[derive(Debug, Args)]
+pub struct ProjectRenameCommand {
+    /// current project name
+    pub name: String,
+    /// new project name
+    pub new_name: String,
+}
+
+#[derive(Debug, Args)]
+pub struct ProjectArchiveCommand {
+    /// project name
+    pub name: String,
+    /// bring an archived project back
+    #[arg(long, default_value_t = false)]
+    pub restore: bool,
+}
+
 #[derive(Debug, Args)]
 pub struct NoteCommand {
     /// index from previous list command
@@ -355,6 +505,25 @@ pub struct ShowCommand {
     pub index: usize,
 }
 
+#[derive(Debug, Args)]
+pub struct ContextCommand {
+    /// index from previous list command
+    #[arg(value_parser = validate_index)]
+    pub index: usize,
+    /// output as JSON
+    #[arg(long, conflicts_with = "prompt")]
+    pub json: bool,
+    /// fill the project's prompt_template with the context
+    #[arg(long)]
+    pub prompt: bool,
+    /// cut the output down to about this many characters
+    #[arg(long, default_value_t = 16000)]
+    pub max_chars: usize,
+    /// do not fetch linked issues and pull requests
+    #[arg(long)]
+    pub offline: bool,
+}
+
 #[derive(Debug, Args)]
 pub struct ClaimCommand {
     /// index from previous list command
@@ -362,6 +531,24 @@ pub struct ClaimCommand {
     pub index: usize,
 }
 
+#[derive(Debug, Args)]
+pub struct SnoozeCommand {
+    /// index from previous list command
+    #[arg(value_parser = validate_index)]
+    pub index: usize,
+    /// time the task wakes up, e.g. monday, "tomorrow 9AM";
+    /// a bare date wakes at the start of that day
+    #[arg(value_parser = validate_timestr)]
+    pub timestr: String,
+}
+
+#[derive(Debug, Args)]
+pub struct SkipCommand {
+    /// index from previous list command
+    #[arg(value_parser = validate_index)]
+    pub index: usize,
+}
+
 #[derive(Debug, Args)]
 pub struct LinkCommand {
     /// index from previous list command
@@ -370,10 +557,10 @@ pub struct LinkCommand {
     /// attach a commit hash
     #[arg(long)]
     pub commit: Option<String>,
-    /// attach a GitHub issue (e.g., owner/repo#42)
+    /// attach an issue or Jira ticket (e.g., owner/repo#42, PROJ-123 or its URL)
     #[arg(long)]
     pub issue: Option<String>,
-    /// attach a pull request (e.g., owner/repo#43)
+    /// attach a pull or merge request (e.g., owner/repo#43, group/project!12 or its URL)
     #[arg(long)]
     pub pr: Option<String>,
     /// attach a URL
@@ -384,6 +571,58 @@ pub struct LinkCommand {
     pub title: Option<String>,
 }
 
+#[derive(Debug, Args)]
+pub struct BranchCommand {
+    /// index from previous list command
+    #[arg(value_parser = validate_index)]
+    pub index: usize,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum GitCommand {
+    /// manage the hooks that link commits to tasks
+    #[command(subcommand)]
+    Hook(GitHookCommand),
+}
+
+#[derive(Debug, Subcommand)]
+pub enum GitHookCommand {
+    /// install commit-msg and post-commit hooks that link commits mentioning ctm-<id> to the task
+    Install(GitHookInstallCommand),
+    /// run by the commit-msg hook
+    #[command(name = "commit-msg", hide = true)]
+    CommitMsg(GitCommitMsgCommand),
+    /// run by the post-commit hook
+    #[command(name = "post-commit", hide = true)]
+    PostCommit(GitPostCommitCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct GitHookInstallCommand {
+    /// repository to install into, default to the current directory
+    #[arg(long)]
+    pub dir: Option<String>,
+    /// complete tasks when a commit says "fixes ctm-<id>"
+    #[arg(long)]
+    pub close: bool,
+    /// replace existing hooks not installed by ctm
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct GitCommitMsgCommand {
+    /// file holding the commit message
+    pub file: String,
+}
+
+#[derive(Debug, Args)]
+pub struct GitPostCommitCommand {
+    /// complete tasks the commit says it fixes
+    #[arg(long)]
+    pub close: bool,
+}
+
 #[derive(Debug, Args)]
 pub struct TeamCommand {
     /// output as JSON
@@ -394,6 +633,26 @@ pub struct TeamCommand {
     pub md: bool,
 }
 
+#[derive(Debug, Args)]
+pub struct BoardCommand {
+    /// group columns by status (default), assignee or priority
+    #[arg(long, default_value = "status", value_parser = parse_board_grouping)]
+    pub by: BoardGrouping,
+    /// output as Markdown
+    #[arg(long)]
+    pub md: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct CalCommand {
+    /// period to show: week (default) or month
+    #[arg(default_value = "week", value_parser = parse_calendar_span)]
+    pub span: CalendarSpan,
+    /// a day in the period to show, default to today
+    #[arg(value_parser = validate_timestr)]
+    pub date: Option<String>,
+}
+
 #[derive(Debug, Args)]
 pub struct WorkloadCommand {
     /// filter to specific user
@@ -412,6 +671,115 @@ pub struct StatsCommand {
     /// time period in days (default 30)
     #[arg(short, long, default_value_t = 30)]
     pub days: i64,
+    /// start of the period, a date means the start of that day
+    #[arg(long, value_parser = validate_timestr, conflicts_with = "days")]
+    pub from: Option<String>,
+    /// end of the period, a date is inclusive; default to now
+    #[arg(long, value_parser = validate_timestr)]
+    pub to: Option<String>,
+    /// only count tasks assigned to, and records written by, this user
+    #[arg(short, long)]
+    pub user: Option<String>,
+    /// output as JSON
+    #[arg(long)]
+    pub json: bool,
+    /// output as Markdown
+    #[arg(long)]
+    pub md: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ReportCommand {
+    /// daily created and completed tasks, and the open work remaining
+    Burndown(BurndownCommand),
+    /// tasks and estimates completed per week
+    Velocity(VelocityCommand),
+    /// lead and cycle times, time in each status and the oldest open tasks
+    Flow(FlowCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct BurndownCommand {
+    /// first day of the report, default to 13 days ago
+    #[arg(long, value_parser = validate_timestr)]
+    pub since: Option<String>,
+    /// only count tasks assigned to this user
+    #[arg(short, long)]
+    pub user: Option<String>,
+    /// only count tasks in this category
+    #[arg(short, long)]
+    pub category: Option<String>,
+    /// output as JSON
+    #[arg(long)]
+    pub json: bool,
+    /// output as CSV
+    #[arg(long)]
+    pub csv: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct VelocityCommand {
+    /// number of weeks to report, including the current one
+    #[arg(short, long, default_value_t = 8)]
+    pub weeks: usize,
+    /// only count tasks assigned to this user
+    #[arg(short, long)]
+    pub user: Option<String>,
+    /// only count tasks in this category
+    #[arg(short, long)]
+    pub category: Option<String>,
+    /// output as JSON
+    #[arg(long)]
+    pub json: bool,
+    /// output as CSV
+    #[arg(long)]
+    pub csv: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct FlowCommand {
+    /// count tasks completed since then, default to 30 days ago
+    #[arg(long, value_parser = validate_timestr)]
+    pub since: Option<String>,
+    /// only count tasks assigned to this user
+    #[arg(short, long)]
+    pub user: Option<String>,
+    /// only count tasks in this category
+    #[arg(short, long)]
+    pub category: Option<String>,
+    /// number of oldest open tasks to show
+    #[arg(short, long, default_value_t = 5)]
+    pub limit: usize,
+    /// output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct TodayCommand {
+    /// only show tasks assigned to this user
+    #[arg(short, long)]
+    pub user: Option<String>,
+    /// table columns, e.g. index,priority,content,time,assignee;
+    /// also: category, status, estimate, project, links, notes
+    #[arg(long, value_parser = validate_columns)]
+    pub columns: Option<String>,
+    /// output as JSON
+    #[arg(long)]
+    pub json: bool,
+    /// output as Markdown
+    #[arg(long)]
+    pub md: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct StandupCommand {
+    /// only report on this user's records and tasks
+    #[arg(short, long)]
+    pub user: Option<String>,
+    /// report records made since this time, default to yesterday
+    #[arg(long, value_parser = validate_timestr)]
+    pub since: Option<String>,
     /// output as JSON
     #[arg(long)]
     pub json: bool,
@@ -420,6 +788,98 @@ pub struct StatsCommand {
     pub md: bool,
 }
 
+#[derive(Debug, Args)]
+pub struct TuiCommand {
+    /// start in the records view
+    #[arg(long)]
+    pub records: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct FeedCommand {
+    /// show activity since this time, default to the last 7 days
+    #[arg(long, value_parser = validate_timestr)]
+    pub since: Option<String>,
+    /// only show changes made by this user
+    #[arg(short, long)]
+    pub user: Option<String>,
+    /// limit the amount of entries returned
+    #[arg(short, long, default_value_t = 50, value_parser = validate_limit)]
+    pub limit: usize,
+    /// output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum GhCommand {
+    /// pull issue and PR state, titles and assignees, and push local completion or reopen
+    Sync(GhSyncCommand),
+    /// create tasks from the open issues of a repository
+    Import(GhImportCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct GhSyncCommand {
+    /// show what would change on either side without changing anything
+    #[arg(long)]
+    pub dry_run: bool,
+    /// when both sides changed: newest (default), local, remote or skip
+    #[arg(long, default_value = "newest", value_parser = parse_conflict_policy)]
+    pub on_conflict: ConflictPolicy,
+}
+
+#[derive(Debug, Args)]
+pub struct GhImportCommand {
+    /// repository to import from, as owner/repo
+    #[arg(long, value_parser = validate_repo)]
+    pub repo: String,
+    /// only issues with this label; repeat to require several
+    #[arg(long)]
+    pub label: Vec<String>,
+    /// only issues assigned to this GitHub login, or @me
+    #[arg(long)]
+    pub assignee: Option<String>,
+    /// only issues in this milestone
+    #[arg(long)]
+    pub milestone: Option<String>,
+    /// time the tasks are due, default to EOD
+    #[arg(long, value_parser = validate_timestr)]
+    pub due: Option<String>,
+    /// import at most this many issues
+    #[arg(short, long, default_value_t = 100, value_parser = validate_limit)]
+    pub limit: usize,
+}
+
+#[derive(Debug, Args)]
+pub struct PrsCommand {
+    /// include tasks that are already closed
+    #[arg(short, long)]
+    pub all: bool,
+    /// fetch every pull request now, even if its cached status is fresh
+    #[arg(long)]
+    pub refresh: bool,
+    /// only show cached statuses, never call gh
+    #[arg(long, conflicts_with = "refresh")]
+    pub offline: bool,
+    /// output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DbCommand {
+    /// apply pending schema migrations
+    Migrate(DbMigrateCommand),
+}
+
+#[derive(Debug, Args)]
+pub struct DbMigrateCommand {
+    /// show applied and pending migrations without applying them
+    #[arg(long)]
+    pub status: bool,
+}
+
 fn syntax_helper(cmd: &str, s: &str) -> Result<String, String> {
     if s == "list" {
         return Err(format!("Do you mean 'list {}' instead of '{} list'", cmd, cmd));
@@ -441,6 +901,47 @@ fn validate_limit(s: &str) -> Result<usize, String> {
     Ok(limit)
 }
 
+fn parse_color_mode(s: &str) -> Result<ColorMode, String> {
+    match s {
+        "auto" => Ok(ColorMode::Auto),
+        "always" => Ok(ColorMode::Always),
+        "never" => Ok(ColorMode::Never),
+        _ => Err(format!("Invalid color mode '{}'. Use auto, always or never", s)),
+    }
+}
+
+fn parse_board_grouping(s: &str) -> Result<BoardGrouping, String> {
+    match s {
+        "status" => Ok(BoardGrouping::Status),
+        "assignee" => Ok(BoardGrouping::Assignee),
+        "priority" => Ok(BoardGrouping::Priority),
+        _ => Err(format!("Invalid grouping '{}'. Use status, assignee or priority", s)),
+    }
+}
+
+fn parse_conflict_policy(s: &str) -> Result<ConflictPolicy, String> {
+    match s {
+        "newest" => Ok(ConflictPolicy::Newest),
+        "local" => Ok(ConflictPolicy::Local),
+        "remote" => Ok(ConflictPolicy::Remote),
+        "skip" => Ok(ConflictPolicy::Skip),
+        _ => Err(format!("Invalid conflict policy '{}'. Use newest, local, remote or skip", s)),
+    }
+}
+
+fn parse_calendar_span(s: &str) -> Result<CalendarSpan, String> {
+    match s {
+        "week" => Ok(CalendarSpan::Week),
+        "month" => Ok(CalendarSpan::Month),
+        _ => Err(format!("Invalid calendar span '{}'. Use week or month", s)),
+    }
+}
+
+fn validate_columns(s: &str) -> Result<String, String> {
+    parse_columns(s)?;
+    Ok(s.to_string())
+}
+
 fn validate_index(s: &str) -> Result<usize, String> {
     let index: usize = s.parse().map_err(|_| "Index must be a number".to_string())?;
     if index == 0 {
@@ -452,6 +953,15 @@ fn validate_index(s: &str) -> Result<usize, String> {
     Ok(index)
 }
 
+fn validate_repo(s: &str) -> Result<String, String> {
+    match s.split_once('/') {
+        Some((owner, repo)) if !owner.is_empty() && !repo.is_empty() && !repo.contains('/') => {
+            Ok(s.to_string())
+        }
+        _ => Err(format!("Invalid repository '{}'. Use format: owner/repo", s)),
+    }
+}
+
 fn validate_timestr(s: &str) -> Result<String, String> {
     match parse_flexible_timestr(s) {
         Ok(_) => Ok(s.to_string()),
@@ -464,7 +974,14 @@ fn validate_timestr(s: &str) -> Result<String, String> {
     }
 }
 
-fn parse_status(s: &str) -> Result<u8, String> {
+fn validate_bound(s: &str) -> Result<String, String> {
+    if s.eq_ignore_ascii_case("none") {
+        return Ok(s.to_string());
+    }
+    parse_flexible_timestr(s).map(|_| s.to_string())
+}
+
+pub fn parse_status(s: &str) -> Result<u8, String> {
     match s.to_lowercase().as_str() {
         "ongoing" => Ok(0),
         "done" | "complete" | "completed" => Ok(1),
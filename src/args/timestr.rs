@@ -31,6 +31,20 @@ pub fn to_unix_epoch(s: &str) -> Result<i64, String> {
         .map(|dt| dt.timestamp())
 }
 
+/// Like `to_unix_epoch`, but a bare date means the start of that day,
+/// so "since yesterday" covers all of yesterday.
+pub fn since_to_unix_epoch(s: &str) -> Result<i64, String> {
+    let today = Local::now().date_naive();
+    match parse_date_portion(s.trim(), today) {
+        Ok(date) => Local
+            .from_local_datetime(&date.and_time(NaiveTime::MIN))
+            .earliest()
+            .ok_or_else(|| String::from("cannot parse timestr into unix epoch"))
+            .map(|dt| dt.timestamp()),
+        Err(_) => to_unix_epoch(s),
+    }
+}
+
 pub fn parse_flexible_timestr(s: &str) -> Result<NaiveDateTime, String> {
     let s = s.trim();
     let now = Local::now().naive_local();
@@ -347,7 +361,7 @@ fn parse_ordinal_day(s: &str) -> Option<u8> {
             &s
         };
 
-    day_str.parse::<u8>().ok().filter(|&d| d >= 1 && d <= 31)
+    day_str.parse::<u8>().ok().filter(|d| (1..=31).contains(d))
 }
 
 // Parse month/day patterns like "2/14"
@@ -380,6 +394,24 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_since_to_unix_epoch() {
+        let today_start = Local
+            .from_local_datetime(&Local::now().date_naive().and_time(NaiveTime::MIN))
+            .earliest()
+            .unwrap()
+            .timestamp();
+        assert_eq!(since_to_unix_epoch("today").unwrap(), today_start);
+        let yesterday = since_to_unix_epoch("yesterday").unwrap();
+        assert!(yesterday < today_start && yesterday >= today_start - 90000);
+        // Times keep their exact value
+        assert_eq!(
+            since_to_unix_epoch("2025-10-15 14:30").unwrap(),
+            to_unix_epoch("2025-10-15 14:30").unwrap()
+        );
+        assert!(since_to_unix_epoch("daily").is_err());
+    }
+
     #[test]
     fn test_valid_inputs() {
         // Collection of inputs that should be successfully parsed
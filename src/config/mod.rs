@@ -1,7 +1,10 @@
+
 use std::{
     collections::HashMap,
     fs,
     path::PathBuf,
+    sync::OnceLock,
+    time::Duration,
 };
 
 use nanoserde::DeJson;
@@ -9,17 +12,90 @@ use nanoserde::DeJson;
 const DB_NAME: &str = "ctm.db";
 const DEFAULT_DATA_DIR: &[&str] = &[".local", "share", "ctm"];
 const CONFIG_PATH: &[&str] = &[".config", "ctm", "config.json"];
+const DEFAULT_BUSY_TIMEOUT_MS: u64 = 5000;
+const DEFAULT_PR_STATUS_TTL_SECS: i64 = 300;
+
+/// Default for optional config fields. With a plain `#[nserde(default)]` the
+/// DeJson derive falls back to a literal `None`, which clippy's question_mark
+/// lint flags in the generated code.
+fn absent<T>() -> Option<T> {
+    None
+}
 
+/// This user's settings for a project: where it is checked out and how
+/// sessions start in it. path, category and prompt_template override the
+/// project's shared settings.
 #[derive(Default, DeJson, Clone)]
+#[allow(dead_code)]
 pub struct ProjectConfig {
     #[nserde(default)]
     pub path: String,
-    #[nserde(default)]
+    #[nserde(default_with = "absent")]
     pub conda_env: Option<String>,
-    #[nserde(default)]
+    #[nserde(default_with = "absent")]
     pub claude_flags: Option<String>,
-    #[nserde(default)]
+    #[nserde(default_with = "absent")]
     pub prompt_template: Option<String>,
+    /// Namespace of the project the entry is for (default: any)
+    #[nserde(default_with = "absent")]
+    pub namespace: Option<String>,
+    /// Category for new tasks in the project, unless --category is given
+    #[nserde(default_with = "absent")]
+    pub category: Option<String>,
+}
+
+/// How GitHub issues map onto tasks, for `gh sync` and `gh import`
+#[derive(Default, DeJson, Clone)]
+pub struct GitHubConfig {
+    /// GitHub login to ctm user name; unlisted logins match a user of the same name
+    #[nserde(default_with = "absent")]
+    pub users: Option<HashMap<String, String>>,
+    /// Label to priority (high, normal, low)
+    #[nserde(default_with = "absent")]
+    pub priority_labels: Option<HashMap<String, String>>,
+    /// Label to category
+    #[nserde(default_with = "absent")]
+    pub category_labels: Option<HashMap<String, String>>,
+    /// "gh" or "http"; by default the API is used when a token is set, else gh
+    #[nserde(default_with = "absent")]
+    pub client: Option<String>,
+    /// API base URL, e.g. https://github.example.com/api/v3 (default: https://api.github.com)
+    #[nserde(default_with = "absent")]
+    pub api_url: Option<String>,
+    /// API token, if neither GH_TOKEN nor GITHUB_TOKEN is set
+    #[nserde(default_with = "absent")]
+    pub token: Option<String>,
+}
+
+/// A GitLab, Gitea, Jira or GitHub instance that issue references resolve to.
+/// A forge scoped to namespaces or projects is only used for those; the
+/// most specific scope wins.
+#[derive(Default, DeJson, Clone)]
+pub struct ForgeConfig {
+    /// github, gitlab, gitea or jira
+    #[nserde(default)]
+    pub kind: String,
+    /// Web address, e.g. https://gitlab.example.com (default for github: https://api.github.com)
+    #[nserde(default_with = "absent")]
+    pub url: Option<String>,
+    /// Environment variable holding the API token
+    #[nserde(default_with = "absent")]
+    pub token_env: Option<String>,
+    /// API token, if token_env is not set
+    #[nserde(default_with = "absent")]
+    pub token: Option<String>,
+    /// Jira account email; with a token, authenticates with basic auth
+    #[nserde(default_with = "absent")]
+    pub user: Option<String>,
+    /// Jira transition that closes a ticket (default: the first into a done status)
+    #[nserde(default_with = "absent")]
+    pub close_transition: Option<String>,
+    /// Only for tasks in these namespaces
+    #[nserde(default_with = "absent")]
+    pub namespaces: Option<Vec<String>>,
+    /// Only for tasks in these projects
+    #[nserde(default_with = "absent")]
+    pub projects: Option<Vec<String>>,
 }
 
 #[derive(Default, DeJson)]
@@ -28,11 +104,28 @@ pub struct Config {
     #[nserde(default)]
     pub data_dir: String,
     /// Terminal profile name for Windows Terminal (default: Ubuntu)
-    #[nserde(default)]
+    #[nserde(default_with = "absent")]
+    #[allow(dead_code)]
     pub terminal_profile: Option<String>,
     /// Project configurations keyed by name
-    #[nserde(default)]
+    #[nserde(default_with = "absent")]
     pub projects: Option<HashMap<String, ProjectConfig>>,
+    /// How long to wait on a locked database before failing (default: 5000)
+    #[nserde(default_with = "absent")]
+    pub busy_timeout_ms: Option<u64>,
+    /// Table columns per view ("list_task", "list_record", "today"),
+    /// e.g. "index,priority,content,time"
+    #[nserde(default_with = "absent")]
+    pub columns: Option<HashMap<String, String>>,
+    /// GitHub login and label mappings
+    #[nserde(default_with = "absent")]
+    pub github: Option<GitHubConfig>,
+    /// How long a fetched pull request status is used before fetching it again (default: 300)
+    #[nserde(default_with = "absent")]
+    pub pr_status_ttl_secs: Option<i64>,
+    /// Forges keyed by name, for references other than GitHub's
+    #[nserde(default_with = "absent")]
+    pub forges: Option<HashMap<String, ForgeConfig>>,
 }
 
 pub fn get_data_path() -> Result<PathBuf, String> {
@@ -67,8 +160,14 @@ fn get_config_data_dir(home_dir: PathBuf) -> Option<String> {
     }
 }
 
-/// Load the full config from ~/.config/ctm/config.json
-pub fn load_config() -> Option<Config> {
+static CONFIG: OnceLock<Option<Config>> = OnceLock::new();
+
+/// The config from ~/.config/ctm/config.json, read once per run
+pub fn load_config() -> Option<&'static Config> {
+    CONFIG.get_or_init(read_config).as_ref()
+}
+
+fn read_config() -> Option<Config> {
     let home_dir = home::home_dir()?;
     let config_path = CONFIG_PATH.iter().fold(home_dir, |p, d| p.join(d));
     if !config_path.exists() {
@@ -78,29 +177,66 @@ pub fn load_config() -> Option<Config> {
     DeJson::deserialize_json(&config_content).ok()
 }
 
-/// Get project configuration by name
-pub fn get_project(name: &str) -> Option<ProjectConfig> {
-    let config = load_config()?;
-    let projects = config.projects?;
-    projects.get(name).cloned()
+/// Get this user's settings for a project in a namespace
+pub fn get_project_config(name: &str, namespace: &str) -> Option<ProjectConfig> {
+    load_config()?
+        .projects
+        .as_ref()?
+        .get(name)
+        .filter(|p| p.namespace.as_deref().is_none_or(|ns| ns == namespace))
+        .cloned()
+}
+
+/// Get busy timeout for database locks, overridable via CTM_BUSY_TIMEOUT_MS
+pub fn get_busy_timeout() -> Duration {
+    let ms = std::env::var("CTM_BUSY_TIMEOUT_MS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .or_else(|| load_config().and_then(|c| c.busy_timeout_ms))
+        .unwrap_or(DEFAULT_BUSY_TIMEOUT_MS);
+    Duration::from_millis(ms)
+}
+
+/// Get how long cached pull request statuses stay fresh, in seconds
+pub fn get_pr_status_ttl() -> i64 {
+    load_config()
+        .and_then(|c| c.pr_status_ttl_secs)
+        .unwrap_or(DEFAULT_PR_STATUS_TTL_SECS)
+}
+
+/// Get the configured table columns for a view, e.g. "list_task"
+pub fn get_columns(view: &str) -> Option<String> {
+    load_config()?.columns.as_ref()?.get(view).cloned()
+}
+
+/// Get the GitHub mappings, empty when not configured
+pub fn get_github_config() -> GitHubConfig {
+    load_config().and_then(|c| c.github.clone()).unwrap_or_default()
+}
+
+/// Get the configured forges keyed by name
+pub fn get_forges() -> HashMap<String, ForgeConfig> {
+    load_config().and_then(|c| c.forges.clone()).unwrap_or_default()
 }
 
 /// Get terminal profile name (default: "Ubuntu")
+#[allow(dead_code)]
 pub fn get_terminal_profile() -> String {
     load_config()
-        .and_then(|c| c.terminal_profile)
+        .and_then(|c| c.terminal_profile.clone())
         .unwrap_or_else(|| "Ubuntu".to_string())
 }
 
 /// List all configured project names
+#[allow(dead_code)]
 pub fn list_projects() -> Vec<String> {
     load_config()
-        .and_then(|c| c.projects)
+        .and_then(|c| c.projects.as_ref())
         .map(|p| p.keys().cloned().collect())
         .unwrap_or_default()
 }
 
-fn str_to_pathbuf(dir_path: String) -> Result<PathBuf, String> {
+pub fn str_to_pathbuf(dir_path: String) -> Result<PathBuf, String> {
     if dir_path.starts_with("~") {
         // We have already executed home_dir previously
         let mut path_buf = home::home_dir().unwrap();
@@ -140,4 +276,20 @@ mod tests {
             .unwrap_err()
             .contains("must be absolute or home relative"));
     }
+
+    #[test]
+    fn test_parse_forges() {
+        let config: Config = DeJson::deserialize_json(
+            r#"{"forges": {
+                "corp": {"kind": "gitlab", "url": "https://gitlab.corp", "token_env": "GITLAB_TOKEN", "namespaces": ["work"]},
+                "jira": {"kind": "jira", "url": "https://corp.atlassian.net", "user": "me@corp.com", "close_transition": "Done"}
+            }}"#,
+        )
+        .unwrap();
+        let forges = config.forges.unwrap();
+        assert_eq!(forges["corp"].kind, "gitlab");
+        assert_eq!(forges["corp"].namespaces, Some(vec!["work".to_string()]));
+        assert_eq!(forges["corp"].projects, None);
+        assert_eq!(forges["jira"].close_transition.as_deref(), Some("Done"));
+    }
 }
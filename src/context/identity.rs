@@ -1,5 +1,13 @@
 use rusqlite::Connection;
 
+use crate::{
+    context::project::current_projects,
+    db::{
+        namespace::get_namespace_by_id,
+        project::Project,
+    },
+};
+
 /// Runtime context containing the current user and namespace.
 /// This is resolved at startup and passed through to all command handlers.
 #[derive(Debug, Clone)]
@@ -8,6 +16,8 @@ pub struct Context {
     pub current_user_name: String,
     pub current_namespace_id: i64,
     pub current_namespace_name: String,
+    /// The namespace's project the current directory belongs to
+    pub current_project: Option<Project>,
 }
 
 impl Context {
@@ -43,10 +53,15 @@ impl Context {
             )
         })?;
 
-        // Resolve namespace with priority: --ns flag > CTM_NAMESPACE env > "default"
+        // Detected once, as it may start git; a failure only means no project
+        let projects = current_projects(conn, user_id).unwrap_or_default();
+
+        // Resolve namespace with priority: --ns flag > CTM_NAMESPACE env >
+        // the current directory's project > "default"
         let ns_name = namespace
             .map(|s| s.to_string())
             .or_else(|| std::env::var("CTM_NAMESPACE").ok())
+            .or_else(|| project_namespace(conn, &projects))
             .unwrap_or_else(|| "default".to_string());
 
         // Look up namespace and verify user has access
@@ -70,16 +85,24 @@ impl Context {
             current_user_name: user_name,
             current_namespace_id: namespace_id,
             current_namespace_name: ns_name,
+            current_project: projects.into_iter().find(|p| p.namespace_id == namespace_id),
         })
     }
 
     /// Get the default context (for backwards compatibility or tests).
     /// This assumes the auto-setup has created the default user and namespace.
+    #[allow(dead_code)]
     pub fn default_from_db(conn: &Connection) -> Result<Self, String> {
         Self::resolve(conn, None, None)
     }
 }
 
+// The namespace of the deepest project the current directory belongs to
+fn project_namespace(conn: &Connection, projects: &[Project]) -> Option<String> {
+    let project = projects.first()?;
+    get_namespace_by_id(conn, project.namespace_id).ok()?.map(|ns| ns.name)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
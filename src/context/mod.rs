@@ -1,3 +1,4 @@
 mod identity;
+pub mod project;
 
 pub use identity::Context;
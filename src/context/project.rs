@@ -0,0 +1,190 @@
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use rusqlite::Connection;
+
+use crate::{
+    config::{
+        get_project_config,
+        str_to_pathbuf,
+    },
+    context::Context,
+    db::{
+        namespace::get_namespace_by_id,
+        project::{
+            get_project,
+            list_user_projects,
+            Project,
+        },
+    },
+    utils::git::Git,
+};
+
+/// A project of a namespace, with this user's config applied
+pub fn find_project(conn: &Connection, namespace_id: i64, name: &str) -> Result<Option<Project>, String> {
+    match get_project(conn, namespace_id, name)? {
+        Some(mut project) => {
+            apply_local_config(conn, &mut project)?;
+            Ok(Some(project))
+        }
+        None => Ok(None),
+    }
+}
+
+/// A project of the current namespace that tasks can be added to
+pub fn active_project(conn: &Connection, ctx: &Context, name: &str) -> Result<Project, String> {
+    let project = find_project(conn, ctx.current_namespace_id, name)?.ok_or_else(|| {
+        format!(
+            "Project '{}' not found in namespace '{}'. Add it with 'ctm project add {}'",
+            name, ctx.current_namespace_name, name
+        )
+    })?;
+    if project.is_archived() {
+        return Err(format!("Project '{}' is archived", name));
+    }
+    Ok(project)
+}
+
+/// The user's projects the current directory belongs to, deepest first
+pub fn current_projects(conn: &Connection, user_id: i64) -> Result<Vec<Project>, String> {
+    match std::env::current_dir() {
+        Ok(dir) => detect_projects(conn, user_id, &dir),
+        Err(_) => Ok(Vec::new()),
+    }
+}
+
+/// The user's projects whose path holds `dir`, or holds the main working
+/// tree of the repository `dir` is in, so linked worktrees count.
+/// The deepest path comes first.
+pub fn detect_projects(conn: &Connection, user_id: i64, dir: &Path) -> Result<Vec<Project>, String> {
+    let mut candidates: Vec<(PathBuf, Project)> = Vec::new();
+    for mut project in list_user_projects(conn, user_id)? {
+        apply_local_config(conn, &mut project)?;
+        let path = project
+            .path
+            .clone()
+            .and_then(|p| str_to_pathbuf(p).ok())
+            .and_then(|p| p.canonicalize().ok());
+        if let Some(path) = path {
+            candidates.push((path, project));
+        }
+    }
+    // Most users have no project paths; don't start git for them
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut dirs = vec![dir.to_path_buf()];
+    dirs.extend(Git::new(dir).main_toplevel());
+    let dirs: Vec<PathBuf> = dirs.iter().filter_map(|d| d.canonicalize().ok()).collect();
+
+    candidates.retain(|(path, _)| dirs.iter().any(|d| d.starts_with(path)));
+    candidates.sort_by_key(|(path, _)| std::cmp::Reverse(path.components().count()));
+    Ok(candidates.into_iter().map(|(_, project)| project).collect())
+}
+
+// path, category and prompt_template in the user's config override the
+// shared ones
+fn apply_local_config(conn: &Connection, project: &mut Project) -> Result<(), String> {
+    let namespace = get_namespace_by_id(conn, project.namespace_id)?
+        .map(|ns| ns.name)
+        .unwrap_or_default();
+    if let Some(local) = get_project_config(&project.name, &namespace) {
+        if !local.path.is_empty() {
+            project.path = Some(local.path);
+        }
+        if local.category.is_some() {
+            project.category = local.category;
+        }
+        if local.prompt_template.is_some() {
+            project.prompt_template = local.prompt_template;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{
+        db::{
+            namespace::create_namespace,
+            project::{create_project, set_project_archived},
+        },
+        tests::{get_test_conn, GitRepo},
+    };
+
+    fn add_project(conn: &Connection, namespace_id: i64, name: &str, path: &Path) {
+        let project = Project {
+            namespace_id,
+            name: name.to_string(),
+            path: Some(path.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        create_project(conn, &project).unwrap();
+    }
+
+    #[test]
+    fn test_detect_deepest_project() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let work = create_namespace(&conn, "work", None, ctx.current_user_id).unwrap();
+        let root = tempfile::tempdir().unwrap();
+        let app = root.path().join("app");
+        let nested = app.join("vendor").join("lib");
+        fs::create_dir_all(&nested).unwrap();
+        add_project(&conn, ctx.current_namespace_id, "app", &app);
+        add_project(&conn, work, "lib", &nested);
+
+        let detect = |ns: Option<i64>, dir: &Path| {
+            detect_projects(&conn, ctx.current_user_id, dir)
+                .unwrap()
+                .into_iter()
+                .find(|p| ns.is_none_or(|id| id == p.namespace_id))
+                .map(|p| p.name)
+        };
+        assert_eq!(detect(None, &app.join("vendor")).as_deref(), Some("app"));
+        assert_eq!(detect(None, &nested).as_deref(), Some("lib"));
+        assert_eq!(detect(Some(ctx.current_namespace_id), &nested).as_deref(), Some("app"));
+        assert_eq!(detect(None, root.path()), None);
+
+        set_project_archived(&conn, ctx.current_namespace_id, "app", true, ctx.current_user_id).unwrap();
+        assert_eq!(detect(None, &app), None);
+    }
+
+    #[test]
+    fn test_detect_project_from_worktree() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let repo = GitRepo::init();
+        add_project(&conn, ctx.current_namespace_id, "repo", repo.dir.path());
+
+        let worktrees = tempfile::tempdir().unwrap();
+        let worktree = worktrees.path().join("feature");
+        repo.git
+            .run(&["worktree", "add", "--quiet", "-b", "feature", &worktree.to_string_lossy()])
+            .unwrap();
+        let found = detect_projects(&conn, ctx.current_user_id, &worktree).unwrap();
+        assert_eq!(found.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(), vec!["repo"]);
+        assert!(detect_projects(&conn, ctx.current_user_id, worktrees.path())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_active_project() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        add_project(&conn, ctx.current_namespace_id, "api", Path::new("/srv/api"));
+        assert_eq!(active_project(&conn, &ctx, "api").unwrap().name, "api");
+
+        let err = active_project(&conn, &ctx, "web").unwrap_err();
+        assert!(err.contains("ctm project add web"));
+        set_project_archived(&conn, ctx.current_namespace_id, "api", true, ctx.current_user_id).unwrap();
+        assert_eq!(active_project(&conn, &ctx, "api").unwrap_err(), "Project 'api' is archived");
+    }
+}
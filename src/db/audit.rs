@@ -0,0 +1,282 @@
+use rusqlite::Connection;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::db::item::Item;
+
+/// Audit actions, matching the CHECK constraint on audit_log.action
+pub const AUDIT_CREATE: &str = "create";
+pub const AUDIT_UPDATE: &str = "update";
+pub const AUDIT_DELETE: &str = "delete";
+pub const AUDIT_COMPLETE: &str = "complete";
+
+/// Tables an audit entry can refer to
+pub const TABLE_ITEMS: &str = "items";
+pub const TABLE_NOTES: &str = "task_notes";
+pub const TABLE_LINKS: &str = "task_links";
+pub const TABLE_PROJECTS: &str = "projects";
+
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    #[allow(dead_code)]
+    pub id: i64,
+    pub item_id: Option<i64>,
+    #[allow(dead_code)]
+    pub namespace_id: Option<i64>,
+    pub table_name: String,
+    pub action: String,
+    pub field_name: Option<String>,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub created_at: i64,
+    pub created_by: Option<i64>,
+    /// Name of the acting user, joined from users
+    pub user_name: Option<String>,
+    /// Current content of the item, joined from items (None once deleted)
+    pub item_content: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn from_row(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        Ok(AuditEntry {
+            id: row.get("id")?,
+            item_id: row.get("item_id")?,
+            namespace_id: row.get("namespace_id")?,
+            table_name: row.get("table_name")?,
+            action: row.get("action")?,
+            field_name: row.get("field_name")?,
+            old_value: row.get("old_value")?,
+            new_value: row.get("new_value")?,
+            created_at: row.get("created_at")?,
+            created_by: row.get("created_by")?,
+            user_name: row.get("user_name")?,
+            item_content: row.get("item_content")?,
+        })
+    }
+}
+
+/// A single change to be recorded against an item.
+#[derive(Debug, Clone)]
+pub struct Change<'a> {
+    pub table_name: &'a str,
+    pub action: &'a str,
+    pub field_name: Option<&'a str>,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+impl<'a> Change<'a> {
+    pub fn new(table_name: &'a str, action: &'a str) -> Self {
+        Change {
+            table_name,
+            action,
+            field_name: None,
+            old_value: None,
+            new_value: None,
+        }
+    }
+
+    pub fn with_field(
+        mut self,
+        field_name: &'a str,
+        old_value: Option<String>,
+        new_value: Option<String>,
+    ) -> Self {
+        self.field_name = Some(field_name);
+        self.old_value = old_value;
+        self.new_value = new_value;
+        self
+    }
+
+    pub fn with_old_value(mut self, old_value: &str) -> Self {
+        self.old_value = Some(old_value.to_string());
+        self
+    }
+
+    pub fn with_new_value(mut self, new_value: &str) -> Self {
+        self.new_value = Some(new_value.to_string());
+        self
+    }
+}
+
+/// Records a change made by a user to an item in a namespace.
+pub fn log_change(
+    conn: &Connection,
+    item_id: i64,
+    namespace_id: Option<i64>,
+    user_id: i64,
+    change: Change,
+) -> Result<(), String> {
+    insert_entry(conn, Some(item_id), namespace_id, user_id, change)
+}
+
+/// Records a change made by a user to a namespace's project, which has no item.
+pub fn log_project_change(conn: &Connection, namespace_id: i64, user_id: i64, change: Change) -> Result<(), String> {
+    insert_entry(conn, None, Some(namespace_id), user_id, change)
+}
+
+fn insert_entry(
+    conn: &Connection,
+    item_id: Option<i64>,
+    namespace_id: Option<i64>,
+    user_id: i64,
+    change: Change,
+) -> Result<(), String> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+
+    conn.execute(
+        "INSERT INTO audit_log (item_id, namespace_id, table_name, action, field_name, old_value, new_value, created_at, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        rusqlite::params![
+            item_id,
+            namespace_id,
+            change.table_name,
+            change.action,
+            change.field_name,
+            change.old_value,
+            change.new_value,
+            now,
+            user_id
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Records one entry per field that differs between two versions of an item.
+/// A transition to done is recorded as a completion rather than an update.
+pub fn log_item_changes(
+    conn: &Connection,
+    before: &Item,
+    after: &Item,
+    user_id: i64,
+) -> Result<(), String> {
+    let item_id = after
+        .id
+        .ok_or_else(|| "Cannot audit an item without id".to_string())?;
+    let fields: [(&str, Option<String>, Option<String>); 16] = [
+        ("content", Some(before.content.clone()), Some(after.content.clone())),
+        ("category", Some(before.category.clone()), Some(after.category.clone())),
+        ("status", Some(before.status.to_string()), Some(after.status.to_string())),
+        ("target_time", before.target_time.map(|t| t.to_string()), after.target_time.map(|t| t.to_string())),
+        ("human_schedule", before.human_schedule.clone(), after.human_schedule.clone()),
+        ("priority", before.priority.map(|p| p.to_string()), after.priority.map(|p| p.to_string())),
+        ("assignee_id", before.assignee_id.map(|a| a.to_string()), after.assignee_id.map(|a| a.to_string())),
+        ("estimate_minutes", before.estimate_minutes.map(|e| e.to_string()), after.estimate_minutes.map(|e| e.to_string())),
+        ("reminder_days", before.reminder_days.map(|r| r.to_string()), after.reminder_days.map(|r| r.to_string())),
+        ("project", before.project.clone(), after.project.clone()),
+        ("github_issue", before.github_issue.clone(), after.github_issue.clone()),
+        ("snooze_until", before.snooze_until.map(|s| s.to_string()), after.snooze_until.map(|s| s.to_string())),
+        ("starts_at", before.starts_at.map(|s| s.to_string()), after.starts_at.map(|s| s.to_string())),
+        ("ends_at", before.ends_at.map(|e| e.to_string()), after.ends_at.map(|e| e.to_string())),
+        ("max_occurrences", before.max_occurrences.map(|m| m.to_string()), after.max_occurrences.map(|m| m.to_string())),
+        ("paused_at", before.paused_at.map(|p| p.to_string()), after.paused_at.map(|p| p.to_string())),
+    ];
+
+    for (field, old_value, new_value) in fields {
+        if old_value == new_value {
+            continue;
+        }
+        let action = if field == "status" && after.status == 1 {
+            AUDIT_COMPLETE
+        } else {
+            AUDIT_UPDATE
+        };
+        let change = Change::new(TABLE_ITEMS, action).with_field(field, old_value, new_value);
+        log_change(conn, item_id, after.namespace_id, user_id, change)?;
+    }
+    Ok(())
+}
+
+/// Gets the activity in a namespace since a point in time, newest first.
+pub fn get_feed(
+    conn: &Connection,
+    namespace_id: i64,
+    since: i64,
+    user_id: Option<i64>,
+    limit: usize,
+) -> Result<Vec<AuditEntry>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT a.*, u.name AS user_name, i.content AS item_content
+             FROM audit_log a
+             LEFT JOIN users u ON u.id = a.created_by
+             LEFT JOIN items i ON i.id = a.item_id
+             WHERE a.namespace_id = ?1
+               AND a.created_at >= ?2
+               AND (?3 IS NULL OR a.created_by = ?3)
+             ORDER BY a.created_at DESC, a.id DESC
+             LIMIT ?4",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let entries = stmt
+        .query_map(
+            rusqlite::params![namespace_id, since, user_id, limit as i64],
+            AuditEntry::from_row,
+        )
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        context::Context,
+        db::crud::get_item,
+        tests::{get_test_conn, insert_task},
+    };
+
+    #[test]
+    fn test_log_change_and_feed() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let task_id = insert_task(&conn, "work", "Write docs", "today");
+
+        let change = Change::new(TABLE_NOTES, AUDIT_CREATE).with_new_value("first draft");
+        log_change(&conn, task_id, Some(ctx.current_namespace_id), ctx.current_user_id, change)
+            .unwrap();
+
+        let feed = get_feed(&conn, ctx.current_namespace_id, 0, None, 10).unwrap();
+        assert_eq!(feed.len(), 1);
+        assert_eq!(feed[0].table_name, TABLE_NOTES);
+        assert_eq!(feed[0].new_value.as_deref(), Some("first draft"));
+        assert_eq!(feed[0].user_name.as_deref(), Some(ctx.current_user_name.as_str()));
+        assert_eq!(feed[0].item_content.as_deref(), Some("Write docs"));
+
+        // Other namespaces and users see nothing
+        assert!(get_feed(&conn, ctx.current_namespace_id + 1, 0, None, 10).unwrap().is_empty());
+        assert!(get_feed(&conn, ctx.current_namespace_id, 0, Some(-1), 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_log_item_changes() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let task_id = insert_task(&conn, "work", "Ship release", "today");
+        let mut before = get_item(&conn, task_id).unwrap();
+        before.namespace_id = Some(ctx.current_namespace_id);
+        before.priority = Some(1);
+
+        let mut after = before.clone();
+        after.priority = Some(0);
+        after.status = 1;
+        log_item_changes(&conn, &before, &after, ctx.current_user_id).unwrap();
+
+        let feed = get_feed(&conn, ctx.current_namespace_id, 0, None, 10).unwrap();
+        assert_eq!(feed.len(), 2);
+        let completion = feed.iter().find(|e| e.action == AUDIT_COMPLETE).unwrap();
+        assert_eq!(completion.field_name.as_deref(), Some("status"));
+        let update = feed.iter().find(|e| e.action == AUDIT_UPDATE).unwrap();
+        assert_eq!(update.field_name.as_deref(), Some("priority"));
+        assert_eq!(update.old_value.as_deref(), Some("1"));
+        assert_eq!(update.new_value.as_deref(), Some("0"));
+    }
+}
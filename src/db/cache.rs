@@ -57,6 +57,21 @@ pub fn validate_cache(conn: &Connection) -> Result<bool> {
     Ok(true)
 }
 
+/// Resolves an index shown by the last list command to the item's row ID,
+/// failing when the listing has expired or has no such index.
+pub fn rowid_for_index(conn: &Connection, index: usize) -> std::result::Result<i64, String> {
+    match validate_cache(conn) {
+        Ok(true) => {}
+        Ok(false) => return Err("Cache is not valid, consider running list command first".to_string()),
+        Err(_) => return Err("Cannot connect to cache".to_string()),
+    }
+    let index = index as i64;
+    match read(conn, index).map_err(|e| format!("Failed to read cache table: {:?}", e))? {
+        Some(id) => Ok(id),
+        None => Err(format!("index {} does not exist", index)),
+    }
+}
+
 fn store_kv(conn: &Connection, kv: Vec<(i64, i64)>) -> Result<()> {
     let tx = conn.unchecked_transaction()?;
     {
@@ -204,4 +219,16 @@ mod tests {
         store_with_next(&conn, &items).expect("Failed to store items in cache");
         assert_eq!(get_next_index(&conn).unwrap(), Some(3));
     }
+
+    #[test]
+    fn test_rowid_for_index() {
+        let (conn, _temp_file) = get_test_conn();
+        assert!(rowid_for_index(&conn, 1).unwrap_err().contains("not valid"));
+
+        let mut item = Item::new("task".to_string(), "work".to_string(), "cached".to_string());
+        item.id = Some(42);
+        store(&conn, &[item]).unwrap();
+        assert_eq!(rowid_for_index(&conn, 1).unwrap(), 42);
+        assert_eq!(rowid_for_index(&conn, 2).unwrap_err(), "index 2 does not exist");
+    }
 }
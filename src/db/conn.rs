@@ -1,284 +1,68 @@
-use rusqlite::Connection;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-use crate::config::get_data_path;
-
-// Going forward, all schema changes require toggling
-// this DB_VERSION to a higher number.
-const SCHEMA_VERSION: i32 = 5;
-
-pub fn init_table(conn: &Connection) -> Result<(), rusqlite::Error> {
-    let current_version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
-
-    if current_version == SCHEMA_VERSION {
-        return Ok(());
-    }
-
-    // Single polymorphic table
-    // Supports task, record, recurring_task, recurring_task_record
-    // distinguished via field "action"
-    // common fields: id; action; category; content; create_time; modify_time; status;
-    // target_time is specific for type task
-    // cron_schedule; human_schedule is specific for type recurring_task
-    // recurring_task_id; good_until is for type recurring task record
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS items (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            action TEXT NOT NULL,
-            category TEXT NOT NULL,
-            content TEXT NOT NULL,
-            create_time INTEGER NOT NULL,
-            target_time INTEGER,
-            modify_time INTEGER,
-            status INTEGER DEFAULT 0,
-            cron_schedule TEXT,
-            human_schedule TEXT,
-            recurring_task_id INTEGER,
-            good_until INTEGER,
-            reminder_days INTEGER,
-            project TEXT,
-            owner_id INTEGER REFERENCES users(id),
-            assignee_id INTEGER REFERENCES users(id),
-            namespace_id INTEGER REFERENCES namespaces(id),
-            priority INTEGER DEFAULT 1,
-            estimate_minutes INTEGER,
-            github_issue TEXT
-        )",
-        [],
-    )?;
-
-    conn.execute("CREATE INDEX IF NOT EXISTS idx_action ON items(action)", [])?;
-
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_create_time ON items(create_time)",
-        [],
-    )?;
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_target_time ON items(target_time)",
-        [],
-    )?;
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_category ON items(category)",
-        [],
-    )?;
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_category_create_time ON items(category, create_time)",
-        [],
-    )?;
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_category_target_time ON items(category, target_time)",
-        [],
-    )?;
-
-    // Create cache table for list commands
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS cache (
-            key INTEGER PRIMARY KEY,
-            value INTEGER NOT NULL
-        )",
-        [],
-    )?;
-
-    // Users table (team members you track)
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS users (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT NOT NULL UNIQUE,
-            display_name TEXT,
-            created_at INTEGER NOT NULL,
-            created_by INTEGER REFERENCES users(id)
-        )",
-        [],
-    )?;
-
-    // Namespaces table (work, personal, team-x)
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS namespaces (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT NOT NULL UNIQUE,
-            description TEXT,
-            created_at INTEGER NOT NULL,
-            created_by INTEGER REFERENCES users(id)
-        )",
-        [],
-    )?;
-
-    // User-namespace membership with roles
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS user_namespaces (
-            user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
-            namespace_id INTEGER NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
-            role TEXT NOT NULL CHECK(role IN ('owner', 'admin', 'member', 'viewer')),
-            created_at INTEGER NOT NULL,
-            PRIMARY KEY (user_id, namespace_id)
-        )",
-        [],
-    )?;
-
-    // Task links (commits, issues, PRs)
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS task_links (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
-            link_type TEXT NOT NULL CHECK(link_type IN ('commit', 'issue', 'pr', 'url')),
-            reference TEXT NOT NULL,
-            title TEXT,
-            created_at INTEGER NOT NULL,
-            created_by INTEGER REFERENCES users(id)
-        )",
-        [],
-    )?;
-
-    // Task notes (append-only)
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS task_notes (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
-            content TEXT NOT NULL,
-            created_at INTEGER NOT NULL,
-            created_by INTEGER REFERENCES users(id)
-        )",
-        [],
-    )?;
+use std::{
+    collections::HashMap,
+    time::Duration,
+};
 
-    // Audit log
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS audit_log (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            item_id INTEGER REFERENCES items(id) ON DELETE SET NULL,
-            table_name TEXT NOT NULL,
-            action TEXT NOT NULL CHECK(action IN ('create', 'update', 'delete', 'complete')),
-            field_name TEXT,
-            old_value TEXT,
-            new_value TEXT,
-            created_at INTEGER NOT NULL,
-            created_by INTEGER REFERENCES users(id)
-        )",
-        [],
-    )?;
-
-    // Migrate from version 1 to 2 - add columns for recurring task support
-    if current_version < 2 && current_version > 0 {
-        conn.execute("ALTER TABLE items ADD COLUMN cron_schedule TEXT", [])?;
-        conn.execute("ALTER TABLE items ADD COLUMN human_schedule TEXT", [])?;
-        conn.execute("ALTER TABLE items ADD COLUMN recurring_task_id INTEGER", [])?;
-        conn.execute("ALTER TABLE items ADD COLUMN good_until INTEGER", [])?;
-    }
-
-    // Migrate from version 2 to 3 - add reminder_days column for early task reminders
-    if current_version < 3 && current_version > 0 {
-        conn.execute("ALTER TABLE items ADD COLUMN reminder_days INTEGER", [])?;
-    }
-
-    // Migrate from version 3 to 4 - add project column for project association
-    if current_version < 4 && current_version > 0 {
-        conn.execute("ALTER TABLE items ADD COLUMN project TEXT", [])?;
-    }
-
-    // Migrate from version 4 to 5 - add multi-tenant columns
-    if current_version < 5 && current_version > 0 {
-        conn.execute("ALTER TABLE items ADD COLUMN owner_id INTEGER REFERENCES users(id)", [])?;
-        conn.execute("ALTER TABLE items ADD COLUMN assignee_id INTEGER REFERENCES users(id)", [])?;
-        conn.execute("ALTER TABLE items ADD COLUMN namespace_id INTEGER REFERENCES namespaces(id)", [])?;
-        conn.execute("ALTER TABLE items ADD COLUMN priority INTEGER DEFAULT 1", [])?;
-        conn.execute("ALTER TABLE items ADD COLUMN estimate_minutes INTEGER", [])?;
-        conn.execute("ALTER TABLE items ADD COLUMN github_issue TEXT", [])?;
-    }
-
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS idx_recurring_task_id_good_until ON items(recurring_task_id, good_until)",
-        [],
-    )?;
-
-    // Indexes for multi-tenant columns
-    conn.execute("CREATE INDEX IF NOT EXISTS idx_owner_id ON items(owner_id)", [])?;
-    conn.execute("CREATE INDEX IF NOT EXISTS idx_assignee_id ON items(assignee_id)", [])?;
-    conn.execute("CREATE INDEX IF NOT EXISTS idx_namespace_id ON items(namespace_id)", [])?;
-    conn.execute("CREATE INDEX IF NOT EXISTS idx_priority ON items(priority)", [])?;
-
-    // Indexes for task_links and task_notes
-    conn.execute("CREATE INDEX IF NOT EXISTS idx_task_links_item_id ON task_links(item_id)", [])?;
-    conn.execute("CREATE INDEX IF NOT EXISTS idx_task_notes_item_id ON task_notes(item_id)", [])?;
-    conn.execute("CREATE INDEX IF NOT EXISTS idx_audit_log_item_id ON audit_log(item_id)", [])?;
-
-    // Auto-setup default user and namespace on first run or upgrade to v5
-    setup_default_user_and_namespace(conn, current_version)?;
-
-    conn.execute(&format!("PRAGMA user_version = {SCHEMA_VERSION}"), [])?;
+use rusqlite::Connection;
 
-    Ok(())
+use crate::{
+    config::{
+        get_busy_timeout,
+        get_data_path,
+        load_config,
+        ProjectConfig,
+    },
+    db::{
+        migration,
+        project::import_config_projects,
+    },
+};
+
+/// Brings the schema up to date by applying any pending migration steps,
+/// without reading the config file.
+#[cfg(test)]
+pub fn init_table(conn: &Connection) -> Result<(), String> {
+    upgrade(conn, None).map(|_| ())
 }
 
-/// Creates default user (from system $USER) and namespace on first run or v5 upgrade.
-/// Migrates existing items to the default user/namespace.
-fn setup_default_user_and_namespace(conn: &Connection, from_version: i32) -> Result<(), rusqlite::Error> {
-    // Check if users table is empty (first run or fresh v5 install)
-    let user_count: i64 = conn.query_row(
-        "SELECT COUNT(*) FROM users",
-        [],
-        |row| row.get(0)
-    )?;
-
-    if user_count > 0 {
-        // Already set up
-        return Ok(());
+/// Applies pending migration steps, returning how many were applied. When
+/// they create the projects table, the projects of the config file are
+/// imported into it.
+pub fn upgrade(conn: &Connection, configured: Option<&HashMap<String, ProjectConfig>>) -> Result<usize, String> {
+    let from = migration::current_version(conn)?;
+    let applied = migration::migrate(conn)?;
+    if let Some(configured) = configured.filter(|_| from < migration::PROJECTS_VERSION) {
+        import_config_projects(conn, configured)?;
     }
+    Ok(applied)
+}
 
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs() as i64;
-
-    // Get username from environment, fall back to "default"
-    let username = std::env::var("USER")
-        .or_else(|_| std::env::var("USERNAME")) // Windows fallback
-        .unwrap_or_else(|_| "default".to_string());
-
-    // Create default user
-    conn.execute(
-        "INSERT INTO users (name, display_name, created_at, created_by) VALUES (?1, ?2, ?3, NULL)",
-        rusqlite::params![&username, &username, now],
-    )?;
-
-    let user_id: i64 = conn.query_row(
-        "SELECT id FROM users WHERE name = ?1",
-        [&username],
-        |row| row.get(0)
-    )?;
-
-    // Create default namespace
-    conn.execute(
-        "INSERT INTO namespaces (name, description, created_at, created_by) VALUES ('default', 'Default namespace', ?1, ?2)",
-        rusqlite::params![now, user_id],
-    )?;
-
-    let namespace_id: i64 = conn.query_row(
-        "SELECT id FROM namespaces WHERE name = 'default'",
-        [],
-        |row| row.get(0)
-    )?;
-
-    // Assign user as owner of default namespace
-    conn.execute(
-        "INSERT INTO user_namespaces (user_id, namespace_id, role, created_at) VALUES (?1, ?2, 'owner', ?3)",
-        rusqlite::params![user_id, namespace_id, now],
-    )?;
+/// The projects of the config file, for `upgrade`
+pub fn config_projects() -> Option<&'static HashMap<String, ProjectConfig>> {
+    load_config().and_then(|config| config.projects.as_ref())
+}
 
-    // If upgrading from v4 (has existing data), migrate items to default user/namespace
-    if from_version >= 1 {
-        conn.execute(
-            "UPDATE items SET owner_id = ?1, namespace_id = ?2 WHERE owner_id IS NULL",
-            rusqlite::params![user_id, namespace_id],
-        )?;
-    }
+/// Opens the database without touching the schema.
+pub fn open() -> Result<Connection, String> {
+    let db_path = get_data_path()?;
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+    configure(&conn, get_busy_timeout())?;
+    Ok(conn)
+}
 
+/// Sets up the connection for concurrent use by several processes:
+/// WAL lets readers proceed alongside a writer, and the busy timeout
+/// makes writers wait for a lock instead of failing immediately.
+pub fn configure(conn: &Connection, busy_timeout: Duration) -> Result<(), String> {
+    conn.busy_timeout(busy_timeout).map_err(|e| e.to_string())?;
+    conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?;
     Ok(())
 }
 
 pub fn connect() -> Result<Connection, String> {
-    let db_path = get_data_path()?;
-    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
-    init_table(&conn).map_err(|e| e.to_string())?;
+    let conn = open()?;
+    upgrade(&conn, config_projects())?;
 
     Ok(conn)
 }
@@ -288,7 +72,10 @@ mod tests {
     use rusqlite::Row;
 
     use super::*;
-    use crate::tests::get_test_conn;
+    use crate::{
+        db::migration::SCHEMA_VERSION,
+        tests::get_test_conn,
+    };
 
     #[test]
     fn test_init_table() {
@@ -324,6 +111,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_upgrade_imports_config_projects_once() {
+        let conn = Connection::open_in_memory().unwrap();
+        let configured = HashMap::from([(
+            "api".to_string(),
+            ProjectConfig {
+                path: "/srv/api".to_string(),
+                ..Default::default()
+            },
+        )]);
+        assert_eq!(upgrade(&conn, Some(&configured)).unwrap(), SCHEMA_VERSION as usize);
+        let count = |conn: &Connection| -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM projects WHERE path = '/srv/api'", [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(count(&conn), 1);
+
+        // Projects removed later are not brought back from the config
+        conn.execute("DELETE FROM projects", []).unwrap();
+        assert_eq!(upgrade(&conn, Some(&configured)).unwrap(), 0);
+        assert_eq!(count(&conn), 0);
+    }
+
+    #[test]
+    fn test_configure_enables_wal() {
+        let (conn, _temp_file) = get_test_conn();
+        configure(&conn, Duration::from_millis(250)).unwrap();
+
+        let journal_mode: String = conn
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(journal_mode, "wal");
+        let busy_timeout: i64 = conn
+            .query_row("PRAGMA busy_timeout", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(busy_timeout, 250);
+    }
+
     #[test]
     fn test_init_table_version_logic() {
         let (conn, _temp_file) = get_test_conn();
@@ -19,9 +19,14 @@ use crate::db::item::{
 const VALID_ORDER_COLUMNS: &[&str] = &["id", "create_time", "target_time"];
 
 pub fn insert_item(conn: &Connection, item: &Item) -> Result<i64> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+
     conn.execute(
-        "INSERT INTO items (action, category, content, create_time, target_time, cron_schedule, human_schedule, recurring_task_id, good_until, reminder_days, project, owner_id, assignee_id, namespace_id, priority, estimate_minutes, github_issue)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)",
+        "INSERT INTO items (action, category, content, create_time, target_time, cron_schedule, human_schedule, recurring_task_id, good_until, reminder_days, project, owner_id, assignee_id, namespace_id, priority, estimate_minutes, github_issue, snooze_until, modify_time, status, starts_at, ends_at, max_occurrences, paused_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24)",
         params![
             item.action,
             item.category,
@@ -39,20 +44,35 @@ pub fn insert_item(conn: &Connection, item: &Item) -> Result<i64> {
             item.namespace_id,
             item.priority,
             item.estimate_minutes,
-            item.github_issue
+            item.github_issue,
+            item.snooze_until,
+            now,
+            item.status,
+            item.starts_at,
+            item.ends_at,
+            item.max_occurrences,
+            item.paused_at
         ],
     )?;
 
     Ok(conn.last_insert_rowid())
 }
 
+/// Writes the item back only if the row still carries the `modify_time`
+/// it was read with. A concurrent change in between is reported as
+/// `StatementChangedRows(0)` rather than silently overwritten.
+/// `completed_at` follows the status: it is stamped when the item
+/// becomes done and cleared when it is reopened.
 pub fn update_item(conn: &Connection, item: &Item) -> Result<()> {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
         .as_secs() as i64;
+    // Always move forward, even within the same second,
+    // so that any other copy read before this write goes stale.
+    let modify_time = item.modify_time.map_or(now, |t| now.max(t + 1));
 
-    conn.execute(
+    let changed = conn.execute(
         "UPDATE items SET
             category = ?1,
             content = ?2,
@@ -70,13 +90,19 @@ pub fn update_item(conn: &Connection, item: &Item) -> Result<()> {
             namespace_id = ?14,
             priority = ?15,
             estimate_minutes = ?16,
-            github_issue = ?17
-        WHERE id = ?18",
+            github_issue = ?17,
+            snooze_until = ?18,
+            completed_at = CASE WHEN ?5 = 1 THEN COALESCE(completed_at, ?4) ELSE NULL END,
+            starts_at = ?21,
+            ends_at = ?22,
+            max_occurrences = ?23,
+            paused_at = ?24
+        WHERE id = ?19 AND modify_time IS ?20",
         params![
             item.category,
             item.content,
             item.target_time,
-            now,
+            modify_time,
             item.status,
             item.cron_schedule,
             item.human_schedule,
@@ -90,10 +116,48 @@ pub fn update_item(conn: &Connection, item: &Item) -> Result<()> {
             item.priority,
             item.estimate_minutes,
             item.github_issue,
-            item.id
+            item.snooze_until,
+            item.id,
+            item.modify_time,
+            item.starts_at,
+            item.ends_at,
+            item.max_occurrences,
+            item.paused_at
         ],
     )?;
 
+    if changed == 0 {
+        // Distinguish a missing row from a lost race
+        let id = item.id.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+        get_item(conn, id)?;
+        return Err(rusqlite::Error::StatementChangedRows(0));
+    }
+
+    Ok(())
+}
+
+/// Formats an `update_item` failure, calling out concurrent modification.
+pub fn describe_update_error(e: rusqlite::Error) -> String {
+    match e {
+        rusqlite::Error::StatementChangedRows(0) => {
+            "Item was modified by another process since it was read; nothing was changed, please retry".to_string()
+        }
+        e => format!("Failed to update item: {:?}", e),
+    }
+}
+
+/// Bumps `modify_time` of an item whose notes or links changed.
+pub fn touch_item(conn: &Connection, item_id: i64) -> Result<()> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+
+    conn.execute(
+        "UPDATE items SET modify_time = MAX(?1, COALESCE(modify_time, 0) + 1) WHERE id = ?2",
+        params![now, item_id],
+    )?;
+
     Ok(())
 }
 
@@ -139,6 +203,11 @@ pub fn query_items(
         params.push(c.to_string());
     }
 
+    if let Some(p) = item_query.project {
+        conditions.push("project = ?".to_string());
+        params.push(p.to_string());
+    }
+
     if let Some(content) = item_query.content_like {
         conditions.push("content LIKE ?".to_string());
         params.push(format!("%{}%", content));
@@ -169,6 +238,16 @@ pub fn query_items(
         params.push(ct_max.to_string());
     }
 
+    if let Some(mt_min) = item_query.modify_time_min {
+        conditions.push("modify_time >= ?".to_string());
+        params.push(mt_min.to_string());
+    }
+
+    if let Some(ca_min) = item_query.completed_at_min {
+        conditions.push("completed_at >= ?".to_string());
+        params.push(ca_min.to_string());
+    }
+
     if let Some(tt_max) = item_query.target_time_max {
         conditions.push("target_time <= ?".to_string());
         params.push(tt_max.to_string());
@@ -205,6 +284,15 @@ pub fn query_items(
         params.push(namespace_id.to_string());
     }
 
+    if let Some(time) = item_query.awake_at {
+        conditions.push("(snooze_until IS NULL OR snooze_until <= ?)".to_string());
+        params.push(time.to_string());
+    }
+
+    if item_query.snoozed_only {
+        conditions.push("snooze_until IS NOT NULL".to_string());
+    }
+
     if let Some(cc) = &item_query.statuses {
         let status_list = cc
             .iter()
@@ -350,6 +438,68 @@ mod tests {
         assert_eq!(updated_recurring.human_schedule, original_human);
     }
 
+    #[test]
+    fn test_update_item_detects_lost_update() {
+        let (conn, _temp_file) = get_test_conn();
+        let item_id = insert_task(&conn, "work", "contended", "today");
+
+        // Two writers read the same version of the row
+        let mut first = get_item(&conn, item_id).unwrap();
+        let mut second = get_item(&conn, item_id).unwrap();
+
+        first.content = "first writer".to_string();
+        update_item(&conn, &first).unwrap();
+
+        second.content = "second writer".to_string();
+        let result = update_item(&conn, &second);
+        assert!(matches!(result, Err(rusqlite::Error::StatementChangedRows(0))));
+        assert!(describe_update_error(result.unwrap_err()).contains("modified by another process"));
+        assert_eq!(get_item(&conn, item_id).unwrap().content, "first writer");
+
+        // A fresh read can be written again, even within the same second
+        let mut third = get_item(&conn, item_id).unwrap();
+        third.content = "third writer".to_string();
+        update_item(&conn, &third).unwrap();
+        let mut fourth = get_item(&conn, item_id).unwrap();
+        assert!(fourth.modify_time > third.modify_time);
+        fourth.status = 1;
+        update_item(&conn, &fourth).unwrap();
+    }
+
+    #[test]
+    fn test_update_item_tracks_completion() {
+        let (conn, _temp_file) = get_test_conn();
+        let item_id = insert_task(&conn, "work", "ship it", "today");
+        assert_eq!(get_item(&conn, item_id).unwrap().completed_at, None);
+
+        let mut item = get_item(&conn, item_id).unwrap();
+        item.status = 1;
+        update_item(&conn, &item).unwrap();
+        let completed_at = get_item(&conn, item_id).unwrap().completed_at;
+        assert!(completed_at.is_some());
+
+        // Further edits keep the original completion time
+        let mut item = get_item(&conn, item_id).unwrap();
+        item.content = "shipped".to_string();
+        update_item(&conn, &item).unwrap();
+        assert_eq!(get_item(&conn, item_id).unwrap().completed_at, completed_at);
+
+        // Reopening clears it
+        let mut item = get_item(&conn, item_id).unwrap();
+        item.status = 0;
+        update_item(&conn, &item).unwrap();
+        assert_eq!(get_item(&conn, item_id).unwrap().completed_at, None);
+    }
+
+    #[test]
+    fn test_update_item_missing_row() {
+        let (conn, _temp_file) = get_test_conn();
+        let mut item = get_test_item("task", "work", "ghost");
+        item.id = Some(9999);
+        let result = update_item(&conn, &item);
+        assert!(matches!(result, Err(rusqlite::Error::QueryReturnedNoRows)));
+    }
+
     #[test]
     fn test_delete_item() {
         let (conn, _temp_file) = get_test_conn();
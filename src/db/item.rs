@@ -16,7 +16,8 @@ pub struct Item {
     pub create_time: i64,
     // This field is dedicated for tasks (deadline)
     pub target_time: Option<i64>,
-    #[allow(dead_code)]
+    // Stamped on every mutation of the item or its notes and links,
+    // and used to detect concurrent updates.
     pub modify_time: Option<i64>,
     pub status: u8,
     // cron and human schedule are specific to recurring tasks.
@@ -44,6 +45,17 @@ pub struct Item {
     pub estimate_minutes: Option<i64>,
     // github_issue: linked GitHub issue (e.g., "owner/repo#42")
     pub github_issue: Option<String>,
+    // snooze_until: task is hidden from default views until this time (v7)
+    pub snooze_until: Option<i64>,
+    // completed_at: when the task was last marked done (v8), maintained by `update_item`
+    pub completed_at: Option<i64>,
+    // Bounds of a recurring task (v10): first and last time it may occur,
+    // and how many times it occurs counting from its start
+    pub starts_at: Option<i64>,
+    pub ends_at: Option<i64>,
+    pub max_occurrences: Option<i64>,
+    // paused_at: a paused recurring task does not occur until resumed (v10)
+    pub paused_at: Option<i64>,
     // Runtime-only field applicable to recurring task, not persisted to db
     // Computed at application layer indicating if a recurring_task is completed.
     pub recurring_interval_complete: bool,
@@ -54,6 +66,9 @@ pub const RECORD: &str = "record";
 pub const RECURRING_TASK: &str = "recurring_task";
 pub const RECURRING_TASK_RECORD: &str = "recurring_task_record";
 
+// A recurring task record with this status skips its interval instead of fulfilling it
+pub const SKIPPED_RECORD_STATUS: u8 = 2;
+
 impl Item {
     pub fn new(action: String, category: String, content: String) -> Self {
         let now = SystemTime::now()
@@ -82,6 +97,12 @@ impl Item {
             priority: None,
             estimate_minutes: None,
             github_issue: None,
+            snooze_until: None,
+            completed_at: None,
+            starts_at: None,
+            ends_at: None,
+            max_occurrences: None,
+            paused_at: None,
             recurring_interval_complete: false,
         }
     }
@@ -155,6 +176,12 @@ impl Item {
             priority: row.get("priority").ok(),
             estimate_minutes: row.get("estimate_minutes").ok(),
             github_issue: row.get("github_issue").ok(),
+            snooze_until: row.get("snooze_until").ok(),
+            completed_at: row.get("completed_at").ok(),
+            starts_at: row.get("starts_at").ok(),
+            ends_at: row.get("ends_at").ok(),
+            max_occurrences: row.get("max_occurrences").ok(),
+            paused_at: row.get("paused_at").ok(),
             recurring_interval_complete: false,
         })
     }
@@ -165,9 +192,12 @@ impl Item {
 pub struct ItemQuery<'a> {
     pub actions: Option<Vec<&'a str>>,
     pub category: Option<&'a str>,
+    pub project: Option<&'a str>,
     pub content_like: Option<&'a str>,
     pub create_time_min: Option<i64>,
     pub create_time_max: Option<i64>,
+    pub modify_time_min: Option<i64>,
+    pub completed_at_min: Option<i64>,
     pub target_time_min: Option<i64>,
     pub target_time_max: Option<i64>,
     pub good_until_min: Option<i64>,
@@ -181,6 +211,9 @@ pub struct ItemQuery<'a> {
     pub assignee_id: Option<i64>,
     pub owner_id: Option<i64>,
     pub namespace_id: Option<i64>,
+    // Snooze filters: hide tasks still asleep at a time, or show only snoozed tasks
+    pub awake_at: Option<i64>,
+    pub snoozed_only: bool,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -198,9 +231,12 @@ impl<'a> ItemQuery<'a> {
         ItemQuery {
             actions: None,
             category: None,
+            project: None,
             content_like: None,
             create_time_min: None,
             create_time_max: None,
+            modify_time_min: None,
+            completed_at_min: None,
             target_time_min: None,
             target_time_max: None,
             good_until_min: None,
@@ -213,6 +249,8 @@ impl<'a> ItemQuery<'a> {
             assignee_id: None,
             owner_id: None,
             namespace_id: None,
+            awake_at: None,
+            snoozed_only: false,
         }
     }
 
@@ -231,6 +269,11 @@ impl<'a> ItemQuery<'a> {
         self
     }
 
+    pub fn with_project(mut self, project: &'a str) -> Self {
+        self.project = Some(project);
+        self
+    }
+
     pub fn with_content_like(mut self, content: &'a str) -> Self {
         self.content_like = Some(content);
         self
@@ -258,6 +301,16 @@ impl<'a> ItemQuery<'a> {
         self
     }
 
+    pub fn with_modify_time_min(mut self, modify_time_min: i64) -> Self {
+        self.modify_time_min = Some(modify_time_min);
+        self
+    }
+
+    pub fn with_completed_at_min(mut self, completed_at_min: i64) -> Self {
+        self.completed_at_min = Some(completed_at_min);
+        self
+    }
+
     pub fn with_target_time_min(mut self, target_time_min: i64) -> Self {
         self.target_time_min = Some(target_time_min);
         self
@@ -323,6 +376,16 @@ impl<'a> ItemQuery<'a> {
         self.namespace_id = Some(namespace_id);
         self
     }
+
+    pub fn with_awake_at(mut self, time: i64) -> Self {
+        self.awake_at = Some(time);
+        self
+    }
+
+    pub fn with_snoozed_only(mut self) -> Self {
+        self.snoozed_only = true;
+        self
+    }
 }
 
 #[cfg(test)]
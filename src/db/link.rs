@@ -1,15 +1,21 @@
 use rusqlite::Connection;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::db::crud::touch_item;
+
 /// Link types for task associations
 pub const LINK_TYPE_COMMIT: &str = "commit";
 pub const LINK_TYPE_ISSUE: &str = "issue";
 pub const LINK_TYPE_PR: &str = "pr";
 pub const LINK_TYPE_URL: &str = "url";
+pub const LINK_TYPE_MR: &str = "mr";
+pub const LINK_TYPE_TICKET: &str = "ticket";
 
 #[derive(Debug, Clone)]
 pub struct TaskLink {
+    #[allow(dead_code)]
     pub id: i64,
+    #[allow(dead_code)]
     pub item_id: i64,
     pub link_type: String,
     pub reference: String,
@@ -43,9 +49,9 @@ impl TaskLink {
 /// Validates a link type.
 pub fn validate_link_type(link_type: &str) -> Result<(), String> {
     match link_type {
-        LINK_TYPE_COMMIT | LINK_TYPE_ISSUE | LINK_TYPE_PR | LINK_TYPE_URL => Ok(()),
+        LINK_TYPE_COMMIT | LINK_TYPE_ISSUE | LINK_TYPE_PR | LINK_TYPE_URL | LINK_TYPE_MR | LINK_TYPE_TICKET => Ok(()),
         _ => Err(format!(
-            "Invalid link type '{}'. Valid types: commit, issue, pr, url",
+            "Invalid link type '{}'. Valid types: commit, issue, pr, mr, ticket, url",
             link_type
         )),
     }
@@ -75,6 +81,7 @@ pub fn add_link(
     .map_err(|e| e.to_string())?;
 
     let link_id = conn.last_insert_rowid();
+    touch_item(conn, item_id).map_err(|e| e.to_string())?;
     Ok(link_id)
 }
 
@@ -99,6 +106,7 @@ pub fn get_links_for_item(conn: &Connection, item_id: i64) -> Result<Vec<TaskLin
 }
 
 /// Gets links for a task filtered by type.
+#[allow(dead_code)]
 pub fn get_links_by_type(
     conn: &Connection,
     item_id: i64,
@@ -123,19 +131,21 @@ pub fn get_links_by_type(
 }
 
 /// Deletes a specific link by ID.
+#[allow(dead_code)]
 pub fn delete_link(conn: &Connection, link_id: i64) -> Result<(), String> {
-    let deleted = conn
-        .execute("DELETE FROM task_links WHERE id = ?1", [link_id])
-        .map_err(|e| e.to_string())?;
+    let item_id: i64 = conn
+        .query_row("SELECT item_id FROM task_links WHERE id = ?1", [link_id], |row| row.get(0))
+        .map_err(|_| format!("Link {} not found", link_id))?;
 
-    if deleted == 0 {
-        return Err(format!("Link {} not found", link_id));
-    }
+    conn.execute("DELETE FROM task_links WHERE id = ?1", [link_id])
+        .map_err(|e| e.to_string())?;
+    touch_item(conn, item_id).map_err(|e| e.to_string())?;
 
     Ok(())
 }
 
 /// Gets the count of links for a task.
+#[allow(dead_code)]
 pub fn count_links_for_item(conn: &Connection, item_id: i64) -> Result<i64, String> {
     conn.query_row(
         "SELECT COUNT(*) FROM task_links WHERE item_id = ?1",
@@ -158,6 +168,17 @@ pub fn link_exists(conn: &Connection, item_id: i64, reference: &str) -> Result<b
     Ok(count > 0)
 }
 
+/// Checks if any task tracks an issue, as its own issue or through an issue link.
+pub fn issue_tracked(conn: &Connection, reference: &str) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM items WHERE github_issue = ?1)
+             OR EXISTS (SELECT 1 FROM task_links WHERE link_type = ?2 AND reference = ?1)",
+        rusqlite::params![reference, LINK_TYPE_ISSUE],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,489 @@
+use rusqlite::Connection;
+
+use crate::db::item::SKIPPED_RECORD_STATUS;
+
+/// Narrows report queries to a namespace, and optionally to an assignee and a category
+#[derive(Debug, Clone, Copy)]
+pub struct MetricsScope<'a> {
+    pub namespace_id: i64,
+    pub assignee_id: Option<i64>,
+    pub category: Option<&'a str>,
+}
+
+/// Task counts and estimate minutes for one period
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bucket {
+    pub start: i64,
+    pub end: i64,
+    pub created: i64,
+    pub created_minutes: i64,
+    pub completed: i64,
+    pub completed_minutes: i64,
+    // Still open at the end of the period
+    pub open: i64,
+    pub open_minutes: i64,
+}
+
+/// Aggregates tasks into consecutive periods, one per pair of adjacent boundaries.
+/// Completion is by `completed_at`, so a task counts in the period it was done
+/// whenever it was created. Cancelled, duplicate and removed tasks are left out.
+pub fn task_series(
+    conn: &Connection,
+    scope: &MetricsScope,
+    boundaries: &[i64],
+) -> Result<Vec<Bucket>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT
+                COUNT(CASE WHEN create_time >= ?1 AND create_time < ?2 THEN 1 END),
+                COALESCE(SUM(CASE WHEN create_time >= ?1 AND create_time < ?2 THEN estimate_minutes END), 0),
+                COUNT(CASE WHEN completed_at >= ?1 AND completed_at < ?2 THEN 1 END),
+                COALESCE(SUM(CASE WHEN completed_at >= ?1 AND completed_at < ?2 THEN estimate_minutes END), 0),
+                COUNT(CASE WHEN create_time < ?2 AND (completed_at IS NULL OR completed_at >= ?2) THEN 1 END),
+                COALESCE(SUM(CASE WHEN create_time < ?2 AND (completed_at IS NULL OR completed_at >= ?2)
+                    THEN estimate_minutes END), 0)
+             FROM items
+             WHERE action = 'task'
+               AND status NOT IN (2, 3, 5)
+               AND namespace_id = ?3
+               AND (?4 IS NULL OR assignee_id = ?4)
+               AND (?5 IS NULL OR category = ?5)",
+        )
+        .map_err(|e| e.to_string())?;
+
+    boundaries
+        .windows(2)
+        .map(|pair| {
+            let (start, end) = (pair[0], pair[1]);
+            stmt.query_row(
+                rusqlite::params![start, end, scope.namespace_id, scope.assignee_id, scope.category],
+                |row| {
+                    Ok(Bucket {
+                        start,
+                        end,
+                        created: row.get(0)?,
+                        created_minutes: row.get(1)?,
+                        completed: row.get(2)?,
+                        completed_minutes: row.get(3)?,
+                        open: row.get(4)?,
+                        open_minutes: row.get(5)?,
+                    })
+                },
+            )
+            .map_err(|e| e.to_string())
+        })
+        .collect()
+}
+
+/// A task with its status history, the input of the flow report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlowTask {
+    pub id: i64,
+    pub content: String,
+    pub category: String,
+    pub priority: Option<u8>,
+    pub assignee: Option<String>,
+    pub status: u8,
+    pub create_time: i64,
+    pub completed_at: Option<i64>,
+    /// (old status, new status, time) in the order they happened
+    pub transitions: Vec<(u8, u8, i64)>,
+}
+
+/// Tasks completed at or after `since`, and tasks still open (ongoing,
+/// suspended or pending), each with the status changes recorded for it
+pub fn flow_tasks(conn: &Connection, scope: &MetricsScope, since: i64) -> Result<Vec<FlowTask>, String> {
+    let filter = "i.action = 'task'
+        AND i.namespace_id = ?1
+        AND (?2 IS NULL OR i.assignee_id = ?2)
+        AND (?3 IS NULL OR i.category = ?3)
+        AND ((i.status = 1 AND i.completed_at >= ?4) OR i.status IN (0, 4, 6))";
+    let params = rusqlite::params![scope.namespace_id, scope.assignee_id, scope.category, since];
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT i.id, i.content, i.category, i.priority, u.name, i.status, i.create_time, i.completed_at
+             FROM items i
+             LEFT JOIN users u ON u.id = i.assignee_id
+             WHERE {}
+             ORDER BY i.id",
+            filter
+        ))
+        .map_err(|e| e.to_string())?;
+    let mut tasks: Vec<FlowTask> = stmt
+        .query_map(params, |row| {
+            Ok(FlowTask {
+                id: row.get(0)?,
+                content: row.get(1)?,
+                category: row.get(2)?,
+                priority: row.get(3)?,
+                assignee: row.get(4)?,
+                status: row.get(5)?,
+                create_time: row.get(6)?,
+                completed_at: row.get(7)?,
+                transitions: Vec::new(),
+            })
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT s.item_id, s.old_status, s.new_status, s.changed_at
+             FROM status_changes s
+             INNER JOIN items i ON i.id = s.item_id
+             WHERE {}
+             ORDER BY s.item_id, s.changed_at, s.id",
+            filter
+        ))
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params, |row| Ok((row.get::<_, i64>(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+        .map_err(|e| e.to_string())?;
+    for row in rows {
+        let (item_id, old, new, at) = row.map_err(|e| e.to_string())?;
+        // Both queries are ordered by id, so the task is found by binary search
+        if let Ok(i) = tasks.binary_search_by_key(&item_id, |t| t.id) {
+            tasks[i].transitions.push((old, new, at));
+        }
+    }
+    Ok(tasks)
+}
+
+/// Task and record totals for a period, plus the current state of open tasks
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeriodStats {
+    pub created: i64,
+    // Completed in the period, whenever the task was created
+    pub completed: i64,
+    // Still open at the end of the period
+    pub open_at_end: i64,
+    pub records: i64,
+    pub overdue: i64,
+    pub high_priority: i64,
+    pub ongoing: i64,
+    pub pending: i64,
+    pub suspended: i64,
+    pub done: i64,
+    pub cancelled: i64,
+}
+
+/// Period totals over all categories, followed by one row per category
+pub fn period_stats(
+    conn: &Connection,
+    scope: &MetricsScope,
+    from: i64,
+    to: i64,
+    now: i64,
+) -> Result<(PeriodStats, Vec<(String, PeriodStats)>), String> {
+    // Tasks count for their assignee, records for the user who wrote them
+    let sql = "SELECT category,
+            COUNT(CASE WHEN action = 'task' AND status NOT IN (3, 5) AND create_time >= ?1 AND create_time < ?2 THEN 1 END),
+            COUNT(CASE WHEN action = 'task' AND completed_at >= ?1 AND completed_at < ?2 THEN 1 END),
+            COUNT(CASE WHEN action = 'task' AND status NOT IN (2, 3, 5) AND create_time < ?2
+                AND (completed_at IS NULL OR completed_at >= ?2) THEN 1 END),
+            COUNT(CASE WHEN action = 'record' AND create_time >= ?1 AND create_time < ?2 THEN 1 END),
+            COUNT(CASE WHEN action = 'task' AND status IN (0, 4, 6) AND target_time < ?3 THEN 1 END),
+            COUNT(CASE WHEN action = 'task' AND status IN (0, 4, 6) AND priority = 0 THEN 1 END),
+            COUNT(CASE WHEN action = 'task' AND status = 0 THEN 1 END),
+            COUNT(CASE WHEN action = 'task' AND status = 6 THEN 1 END),
+            COUNT(CASE WHEN action = 'task' AND status = 4 THEN 1 END),
+            COUNT(CASE WHEN action = 'task' AND status = 1 THEN 1 END),
+            COUNT(CASE WHEN action = 'task' AND status = 2 THEN 1 END)
+         FROM items
+         WHERE action IN ('task', 'record')
+           AND namespace_id = ?4
+           AND (?5 IS NULL OR (action = 'task' AND assignee_id = ?5) OR (action = 'record' AND owner_id = ?5))
+           AND (?6 IS NULL OR category = ?6)
+         GROUP BY category
+         ORDER BY category";
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let categories: Vec<(String, PeriodStats)> = stmt
+        .query_map(
+            rusqlite::params![from, to, now, scope.namespace_id, scope.assignee_id, scope.category],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    PeriodStats {
+                        created: row.get(1)?,
+                        completed: row.get(2)?,
+                        open_at_end: row.get(3)?,
+                        records: row.get(4)?,
+                        overdue: row.get(5)?,
+                        high_priority: row.get(6)?,
+                        ongoing: row.get(7)?,
+                        pending: row.get(8)?,
+                        suspended: row.get(9)?,
+                        done: row.get(10)?,
+                        cancelled: row.get(11)?,
+                    },
+                ))
+            },
+        )
+        .map_err(|e| e.to_string())?
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut total = PeriodStats::default();
+    for (_, c) in &categories {
+        total.created += c.created;
+        total.completed += c.completed;
+        total.open_at_end += c.open_at_end;
+        total.records += c.records;
+        total.overdue += c.overdue;
+        total.high_priority += c.high_priority;
+        total.ongoing += c.ongoing;
+        total.pending += c.pending;
+        total.suspended += c.suspended;
+        total.done += c.done;
+        total.cancelled += c.cancelled;
+    }
+    Ok((total, categories))
+}
+
+/// A recurring task with the intervals its completion records cover
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurringHistory {
+    pub id: i64,
+    pub content: String,
+    pub category: String,
+    pub cron_schedule: String,
+    pub create_time: i64,
+    pub starts_at: Option<i64>,
+    pub ends_at: Option<i64>,
+    pub max_occurrences: Option<i64>,
+    pub paused_at: Option<i64>,
+    /// `good_until` of each completion record, i.e. the end of the interval it fulfilled
+    pub fulfilled_until: Vec<i64>,
+    /// `good_until` of each skip record
+    pub skipped_until: Vec<i64>,
+}
+
+/// Active recurring tasks in scope, with completion and skip records whose interval ends after `from`
+pub fn recurring_history(conn: &Connection, scope: &MetricsScope, from: i64) -> Result<Vec<RecurringHistory>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.id, t.content, t.category, t.cron_schedule, t.create_time,
+                t.starts_at, t.ends_at, t.max_occurrences, t.paused_at, r.good_until, r.status
+             FROM items t
+             LEFT JOIN items r ON r.recurring_task_id = t.id
+                AND r.action = 'recurring_task_record' AND r.good_until > ?1
+             WHERE t.action = 'recurring_task'
+               AND t.status NOT IN (2, 3, 5)
+               AND t.cron_schedule IS NOT NULL
+               AND t.namespace_id = ?2
+               AND (?3 IS NULL OR t.assignee_id = ?3)
+               AND (?4 IS NULL OR t.category = ?4)
+             ORDER BY t.id, r.good_until",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(
+            rusqlite::params![from, scope.namespace_id, scope.assignee_id, scope.category],
+            |row| {
+                Ok((
+                    RecurringHistory {
+                        id: row.get(0)?,
+                        content: row.get(1)?,
+                        category: row.get(2)?,
+                        cron_schedule: row.get(3)?,
+                        create_time: row.get(4)?,
+                        starts_at: row.get(5)?,
+                        ends_at: row.get(6)?,
+                        max_occurrences: row.get(7)?,
+                        paused_at: row.get(8)?,
+                        fulfilled_until: Vec::new(),
+                        skipped_until: Vec::new(),
+                    },
+                    row.get::<_, Option<i64>>(9)?,
+                    row.get::<_, Option<u8>>(10)?,
+                ))
+            },
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut history: Vec<RecurringHistory> = Vec::new();
+    for row in rows {
+        let (task, good_until, status) = row.map_err(|e| e.to_string())?;
+        if history.last().map(|h| h.id) != Some(task.id) {
+            history.push(task);
+        }
+        let last = history.last_mut().unwrap();
+        match (good_until, status) {
+            (Some(good_until), Some(SKIPPED_RECORD_STATUS)) => last.skipped_until.push(good_until),
+            (Some(good_until), _) => last.fulfilled_until.push(good_until),
+            _ => {}
+        }
+    }
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        context::Context,
+        db::crud::{
+            get_item,
+            update_item,
+        },
+        tests::{
+            get_test_conn,
+            insert_record,
+            insert_recurring_record,
+            insert_recurring_task,
+            insert_task,
+        },
+    };
+
+    // Sets the fields the metrics depend on directly, to place tasks in time
+    fn place(conn: &Connection, id: i64, ns: i64, created: i64, completed: Option<i64>, minutes: i64) {
+        conn.execute(
+            "UPDATE items SET namespace_id = ?1, create_time = ?2, completed_at = ?3,
+             status = CASE WHEN ?3 IS NULL THEN 0 ELSE 1 END, estimate_minutes = ?4 WHERE id = ?5",
+            rusqlite::params![ns, created, completed, minutes, id],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_task_series() {
+        let (conn, _temp_file) = get_test_conn();
+        let ns = Context::default_from_db(&conn).unwrap().current_namespace_id;
+        let a = insert_task(&conn, "work", "a", "today");
+        let b = insert_task(&conn, "work", "b", "today");
+        let c = insert_task(&conn, "home", "c", "today");
+        let cancelled = insert_task(&conn, "work", "cancelled", "today");
+        place(&conn, a, ns, 5, Some(15), 30);
+        place(&conn, b, ns, 12, Some(25), 60);
+        place(&conn, c, ns, 18, None, 90);
+        place(&conn, cancelled, ns, 12, None, 10);
+        let mut item = get_item(&conn, cancelled).unwrap();
+        item.status = 2;
+        update_item(&conn, &item).unwrap();
+
+        let scope = MetricsScope {
+            namespace_id: ns,
+            assignee_id: None,
+            category: None,
+        };
+        let series = task_series(&conn, &scope, &[10, 20, 30]).unwrap();
+        assert_eq!(series.len(), 2);
+        // a was created before the window but completed in the first period
+        assert_eq!(
+            series[0],
+            Bucket {
+                start: 10,
+                end: 20,
+                created: 2,
+                created_minutes: 150,
+                completed: 1,
+                completed_minutes: 30,
+                open: 2,
+                open_minutes: 150,
+            }
+        );
+        assert_eq!((series[1].completed, series[1].open), (1, 1));
+
+        let scope = MetricsScope {
+            category: Some("home"),
+            ..scope
+        };
+        let series = task_series(&conn, &scope, &[10, 20]).unwrap();
+        assert_eq!((series[0].created, series[0].open), (1, 1));
+    }
+
+    #[test]
+    fn test_flow_tasks() {
+        let (conn, _temp_file) = get_test_conn();
+        let ns = Context::default_from_db(&conn).unwrap().current_namespace_id;
+        let old = insert_task(&conn, "work", "done long ago", "today");
+        let recent = insert_task(&conn, "work", "done recently", "today");
+        let open = insert_task(&conn, "home", "still open", "today");
+        place(&conn, old, ns, 5, Some(15), 0);
+        place(&conn, recent, ns, 5, Some(50), 0);
+        place(&conn, open, ns, 5, None, 0);
+        // The trigger records every status change made above
+        conn.execute("UPDATE items SET status = 6, modify_time = 60 WHERE id = ?1", [open]).unwrap();
+
+        let scope = MetricsScope {
+            namespace_id: ns,
+            assignee_id: None,
+            category: None,
+        };
+        let tasks = flow_tasks(&conn, &scope, 40).unwrap();
+        let ids: Vec<i64> = tasks.iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![recent, open]);
+        assert_eq!(tasks[0].completed_at, Some(50));
+        assert_eq!(tasks[0].transitions.len(), 1);
+        assert_eq!(tasks[1].transitions.last(), Some(&(0, 6, 60)));
+
+        let scope = MetricsScope {
+            category: Some("home"),
+            ..scope
+        };
+        assert_eq!(flow_tasks(&conn, &scope, 40).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_period_stats() {
+        let (conn, _temp_file) = get_test_conn();
+        let ns = Context::default_from_db(&conn).unwrap().current_namespace_id;
+        // Created long before the period but completed in it
+        let old = insert_task(&conn, "work", "old", "today");
+        let new = insert_task(&conn, "work", "new", "today");
+        let home = insert_task(&conn, "home", "home", "today");
+        let note = insert_record(&conn, "home", "note", "today");
+        place(&conn, old, ns, 1, Some(150), 0);
+        place(&conn, new, ns, 120, None, 0);
+        place(&conn, home, ns, 130, Some(300), 0);
+        conn.execute(
+            "UPDATE items SET namespace_id = ?1, create_time = 140, priority = 0 WHERE id IN (?2, ?3)",
+            rusqlite::params![ns, note, new],
+        )
+        .unwrap();
+
+        let scope = MetricsScope {
+            namespace_id: ns,
+            assignee_id: None,
+            category: None,
+        };
+        let (total, categories) = period_stats(&conn, &scope, 100, 200, 1000).unwrap();
+        assert_eq!(
+            (total.created, total.completed, total.open_at_end, total.records),
+            (2, 1, 2, 1)
+        );
+        // Only `new` is open now; its deadline is in the future relative to 1000
+        assert_eq!((total.ongoing, total.done, total.high_priority), (1, 2, 1));
+        let names: Vec<&str> = categories.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["home", "work"]);
+        assert_eq!((categories[1].1.created, categories[1].1.completed), (1, 1));
+    }
+
+    #[test]
+    fn test_recurring_history() {
+        let (conn, _temp_file) = get_test_conn();
+        let ns = Context::default_from_db(&conn).unwrap().current_namespace_id;
+        let standup = insert_recurring_task(&conn, "work", "standup", "daily 9am");
+        let review = insert_recurring_task(&conn, "work", "review", "weekly friday 3pm");
+        conn.execute("UPDATE items SET namespace_id = ?1", [ns]).unwrap();
+        insert_recurring_record(&conn, "work", "done", standup, 50);
+        insert_recurring_record(&conn, "work", "done", standup, 200);
+        insert_recurring_record(&conn, "work", "done", standup, 300);
+        let skip = insert_recurring_record(&conn, "work", "skipped", standup, 400);
+        conn.execute("UPDATE items SET status = ?1 WHERE id = ?2", rusqlite::params![SKIPPED_RECORD_STATUS, skip])
+            .unwrap();
+
+        let scope = MetricsScope {
+            namespace_id: ns,
+            assignee_id: None,
+            category: None,
+        };
+        let history = recurring_history(&conn, &scope, 100).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!((history[0].id, history[0].fulfilled_until.clone()), (standup, vec![200, 300]));
+        assert_eq!(history[0].skipped_until, vec![400]);
+        assert_eq!((history[1].id, history[1].fulfilled_until.len()), (review, 0));
+    }
+}
@@ -0,0 +1,925 @@
+use std::time::{
+    SystemTime,
+    UNIX_EPOCH,
+};
+
+use rusqlite::Connection;
+
+// Going forward, every schema change is a new step appended to MIGRATIONS,
+// and SCHEMA_VERSION is bumped to the version of that step.
+pub const SCHEMA_VERSION: i32 = 14;
+
+/// The step that creates the projects table
+pub const PROJECTS_VERSION: i32 = 14;
+
+/// A single schema change. Steps are applied in order, each inside its own
+/// transaction, and the applied version is tracked with PRAGMA user_version.
+pub struct Migration {
+    pub version: i32,
+    pub description: &'static str,
+    pub apply: fn(&Connection) -> Result<(), rusqlite::Error>,
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "items and cache tables",
+        apply: v1_base_tables,
+    },
+    Migration {
+        version: 2,
+        description: "recurring task schedule and records",
+        apply: v2_recurring_tasks,
+    },
+    Migration {
+        version: 3,
+        description: "task reminder days",
+        apply: v3_reminder_days,
+    },
+    Migration {
+        version: 4,
+        description: "task project association",
+        apply: v4_project,
+    },
+    Migration {
+        version: 5,
+        description: "users, namespaces, notes, links and audit log",
+        apply: v5_multi_tenant,
+    },
+    Migration {
+        version: 6,
+        description: "activity feed scoped by namespace",
+        apply: v6_activity_feed,
+    },
+    Migration {
+        version: 7,
+        description: "task snooze",
+        apply: v7_snooze,
+    },
+    Migration {
+        version: 8,
+        description: "task completion time",
+        apply: v8_completed_at,
+    },
+    Migration {
+        version: 9,
+        description: "task status history",
+        apply: v9_status_changes,
+    },
+    Migration {
+        version: 10,
+        description: "recurring task bounds and pause",
+        apply: v10_recurring_bounds,
+    },
+    Migration {
+        version: 11,
+        description: "github sync state",
+        apply: v11_github_sync,
+    },
+    Migration {
+        version: 12,
+        description: "pull request status cache",
+        apply: v12_pr_status,
+    },
+    Migration {
+        version: 13,
+        description: "task link types checked in code",
+        apply: v13_link_types,
+    },
+    Migration {
+        version: 14,
+        description: "projects scoped to namespaces",
+        apply: v14_projects,
+    },
+];
+
+/// Reads the schema version currently recorded in the database.
+pub fn current_version(conn: &Connection) -> Result<i32, String> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| e.to_string())
+}
+
+/// Returns the steps not yet applied to the database.
+/// Fails if the database was written by a newer binary.
+pub fn pending(conn: &Connection) -> Result<Vec<&'static Migration>, String> {
+    let version = current_version(conn)?;
+    if version > SCHEMA_VERSION {
+        return Err(format!(
+            "Database schema version {} is newer than this binary supports ({}). Upgrade ctm before using this database.",
+            version, SCHEMA_VERSION
+        ));
+    }
+    Ok(MIGRATIONS.iter().filter(|m| m.version > version).collect())
+}
+
+/// Applies all pending steps in order, returning how many were applied.
+pub fn migrate(conn: &Connection) -> Result<usize, String> {
+    let steps = pending(conn)?;
+    for step in &steps {
+        apply(conn, step)?;
+    }
+    Ok(steps.len())
+}
+
+/// Applies one step atomically: either the schema change and the version
+/// bump both land, or neither does.
+pub fn apply(conn: &Connection, migration: &Migration) -> Result<(), String> {
+    let describe = |e: rusqlite::Error| {
+        format!(
+            "Migration v{} ({}) failed: {}",
+            migration.version, migration.description, e
+        )
+    };
+    let tx = conn.unchecked_transaction().map_err(describe)?;
+    (migration.apply)(&tx).map_err(describe)?;
+    tx.pragma_update(None, "user_version", migration.version)
+        .map_err(describe)?;
+    tx.commit().map_err(describe)
+}
+
+// Steps must tolerate columns that already exist, as older binaries created
+// the full items table up front and user_version may have been reset.
+fn add_column(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), rusqlite::Error> {
+    let exists: bool = conn.query_row(
+        &format!(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('{}') WHERE name = ?1",
+            table
+        ),
+        [column],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// Single polymorphic table
+// Supports task, record, recurring_task, recurring_task_record
+// distinguished via field "action"
+// common fields: id; action; category; content; create_time; modify_time; status;
+// target_time is specific for type task
+fn v1_base_tables(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS items (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            action TEXT NOT NULL,
+            category TEXT NOT NULL,
+            content TEXT NOT NULL,
+            create_time INTEGER NOT NULL,
+            target_time INTEGER,
+            modify_time INTEGER,
+            status INTEGER DEFAULT 0
+        )",
+        [],
+    )?;
+
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_action ON items(action)", [])?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_create_time ON items(create_time)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_target_time ON items(target_time)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_category ON items(category)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_category_create_time ON items(category, create_time)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_category_target_time ON items(category, target_time)",
+        [],
+    )?;
+
+    // Create cache table for list commands
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS cache (
+            key INTEGER PRIMARY KEY,
+            value INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+// cron_schedule; human_schedule is specific for type recurring_task
+// recurring_task_id; good_until is for type recurring task record
+fn v2_recurring_tasks(conn: &Connection) -> Result<(), rusqlite::Error> {
+    add_column(conn, "items", "cron_schedule", "TEXT")?;
+    add_column(conn, "items", "human_schedule", "TEXT")?;
+    add_column(conn, "items", "recurring_task_id", "INTEGER")?;
+    add_column(conn, "items", "good_until", "INTEGER")?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_recurring_task_id_good_until ON items(recurring_task_id, good_until)",
+        [],
+    )?;
+    Ok(())
+}
+
+// reminder_days for early task reminders
+fn v3_reminder_days(conn: &Connection) -> Result<(), rusqlite::Error> {
+    add_column(conn, "items", "reminder_days", "INTEGER")
+}
+
+// project for project association
+fn v4_project(conn: &Connection) -> Result<(), rusqlite::Error> {
+    add_column(conn, "items", "project", "TEXT")
+}
+
+fn v5_multi_tenant(conn: &Connection) -> Result<(), rusqlite::Error> {
+    // Users table (team members you track)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS users (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            display_name TEXT,
+            created_at INTEGER NOT NULL,
+            created_by INTEGER REFERENCES users(id)
+        )",
+        [],
+    )?;
+
+    // Namespaces table (work, personal, team-x)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS namespaces (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            description TEXT,
+            created_at INTEGER NOT NULL,
+            created_by INTEGER REFERENCES users(id)
+        )",
+        [],
+    )?;
+
+    // User-namespace membership with roles
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS user_namespaces (
+            user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+            namespace_id INTEGER NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+            role TEXT NOT NULL CHECK(role IN ('owner', 'admin', 'member', 'viewer')),
+            created_at INTEGER NOT NULL,
+            PRIMARY KEY (user_id, namespace_id)
+        )",
+        [],
+    )?;
+
+    // Task links (commits, issues, PRs)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS task_links (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+            link_type TEXT NOT NULL CHECK(link_type IN ('commit', 'issue', 'pr', 'url')),
+            reference TEXT NOT NULL,
+            title TEXT,
+            created_at INTEGER NOT NULL,
+            created_by INTEGER REFERENCES users(id)
+        )",
+        [],
+    )?;
+
+    // Task notes (append-only)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS task_notes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+            content TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            created_by INTEGER REFERENCES users(id)
+        )",
+        [],
+    )?;
+
+    // Audit log
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            item_id INTEGER REFERENCES items(id) ON DELETE SET NULL,
+            table_name TEXT NOT NULL,
+            action TEXT NOT NULL CHECK(action IN ('create', 'update', 'delete', 'complete')),
+            field_name TEXT,
+            old_value TEXT,
+            new_value TEXT,
+            created_at INTEGER NOT NULL,
+            created_by INTEGER REFERENCES users(id)
+        )",
+        [],
+    )?;
+
+    add_column(conn, "items", "owner_id", "INTEGER REFERENCES users(id)")?;
+    add_column(conn, "items", "assignee_id", "INTEGER REFERENCES users(id)")?;
+    add_column(conn, "items", "namespace_id", "INTEGER REFERENCES namespaces(id)")?;
+    add_column(conn, "items", "priority", "INTEGER DEFAULT 1")?;
+    add_column(conn, "items", "estimate_minutes", "INTEGER")?;
+    add_column(conn, "items", "github_issue", "TEXT")?;
+
+    // Indexes for multi-tenant columns
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_owner_id ON items(owner_id)", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_assignee_id ON items(assignee_id)", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_namespace_id ON items(namespace_id)", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_priority ON items(priority)", [])?;
+
+    // Indexes for task_links and task_notes
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_task_links_item_id ON task_links(item_id)", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_task_notes_item_id ON task_notes(item_id)", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_audit_log_item_id ON audit_log(item_id)", [])?;
+
+    setup_default_user_and_namespace(conn)
+}
+
+// audit_log rows outlive deleted items, so they carry their own namespace
+fn v6_activity_feed(conn: &Connection) -> Result<(), rusqlite::Error> {
+    add_column(conn, "audit_log", "namespace_id", "INTEGER REFERENCES namespaces(id)")?;
+    conn.execute(
+        "UPDATE audit_log SET namespace_id = (SELECT namespace_id FROM items WHERE items.id = audit_log.item_id)
+         WHERE namespace_id IS NULL",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_audit_log_namespace_created_at ON audit_log(namespace_id, created_at)",
+        [],
+    )?;
+    Ok(())
+}
+
+// snooze_until hides a suspended task until it wakes
+fn v7_snooze(conn: &Connection) -> Result<(), rusqlite::Error> {
+    add_column(conn, "items", "snooze_until", "INTEGER")?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_snooze_until ON items(snooze_until)",
+        [],
+    )?;
+    Ok(())
+}
+
+// completed_at is when a task was last marked done. Existing done tasks take
+// the time of their last completion in the audit log, or else their last change.
+fn v8_completed_at(conn: &Connection) -> Result<(), rusqlite::Error> {
+    add_column(conn, "items", "completed_at", "INTEGER")?;
+    conn.execute(
+        "UPDATE items SET completed_at = COALESCE(
+            (SELECT MAX(a.created_at) FROM audit_log a
+             WHERE a.item_id = items.id AND a.action = 'complete'),
+            modify_time,
+            create_time
+         )
+         WHERE status = 1 AND completed_at IS NULL",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_completed_at ON items(completed_at)",
+        [],
+    )?;
+    Ok(())
+}
+
+// status_changes keeps every status transition, which items.status overwrites.
+// A trigger records them so that no write path can skip it. History is
+// backfilled from the audit log, or a single completion for done tasks.
+fn v9_status_changes(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS status_changes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+            old_status INTEGER NOT NULL,
+            new_status INTEGER NOT NULL,
+            changed_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_status_changes_item_id ON status_changes(item_id, changed_at)",
+        [],
+    )?;
+
+    let history: i64 = conn.query_row("SELECT COUNT(*) FROM status_changes", [], |row| row.get(0))?;
+    if history == 0 {
+        conn.execute(
+            "INSERT INTO status_changes (item_id, old_status, new_status, changed_at)
+             SELECT a.item_id, CAST(a.old_value AS INTEGER), CAST(a.new_value AS INTEGER), a.created_at
+             FROM audit_log a
+             INNER JOIN items i ON i.id = a.item_id
+             WHERE a.table_name = 'items' AND a.field_name = 'status'
+               AND a.old_value IS NOT NULL AND a.new_value IS NOT NULL
+             ORDER BY a.created_at, a.id",
+            [],
+        )?;
+        conn.execute(
+            "INSERT INTO status_changes (item_id, old_status, new_status, changed_at)
+             SELECT id, 0, 1, completed_at FROM items
+             WHERE status = 1 AND completed_at IS NOT NULL
+               AND id NOT IN (SELECT item_id FROM status_changes)",
+            [],
+        )?;
+    }
+
+    conn.execute(
+        "CREATE TRIGGER IF NOT EXISTS trg_items_status_change
+         AFTER UPDATE OF status ON items
+         WHEN OLD.status IS NOT NEW.status
+         BEGIN
+            INSERT INTO status_changes (item_id, old_status, new_status, changed_at)
+            VALUES (NEW.id, OLD.status, NEW.status, COALESCE(NEW.modify_time, CAST(strftime('%s', 'now') AS INTEGER)));
+         END",
+        [],
+    )?;
+    Ok(())
+}
+
+// A recurring task occurs from starts_at to ends_at and at most
+// max_occurrences times, and not at all while paused_at is set.
+fn v10_recurring_bounds(conn: &Connection) -> Result<(), rusqlite::Error> {
+    add_column(conn, "items", "starts_at", "INTEGER")?;
+    add_column(conn, "items", "ends_at", "INTEGER")?;
+    add_column(conn, "items", "max_occurrences", "INTEGER")?;
+    add_column(conn, "items", "paused_at", "INTEGER")?;
+    Ok(())
+}
+
+// github_sync remembers what an issue or pull request looked like at the
+// last sync, so a later sync can tell which side changed since.
+fn v11_github_sync(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS github_sync (
+            item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+            reference TEXT NOT NULL,
+            state TEXT NOT NULL,
+            title TEXT NOT NULL,
+            assignees TEXT NOT NULL DEFAULT '',
+            synced_at INTEGER NOT NULL,
+            PRIMARY KEY (item_id, reference)
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+// pr_status caches what gh reported for each pull request, so views can
+// show it without a network round trip until it is older than the TTL.
+fn v12_pr_status(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS pr_status (
+            reference TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            url TEXT NOT NULL,
+            state TEXT NOT NULL,
+            review_decision TEXT,
+            checks TEXT NOT NULL,
+            mergeable TEXT NOT NULL,
+            fetched_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+// task_links.link_type had a CHECK for commit/issue/pr/url. SQLite cannot
+// drop a constraint, so the table is rebuilt; the valid types now live in
+// db::link, where new ones (mr, ticket) are added without a migration.
+fn v13_link_types(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE task_links_new (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            item_id INTEGER NOT NULL REFERENCES items(id) ON DELETE CASCADE,
+            link_type TEXT NOT NULL,
+            reference TEXT NOT NULL,
+            title TEXT,
+            created_at INTEGER NOT NULL,
+            created_by INTEGER REFERENCES users(id)
+        )",
+        [],
+    )?;
+    conn.execute(
+        "INSERT INTO task_links_new (id, item_id, link_type, reference, title, created_at, created_by)
+         SELECT id, item_id, link_type, reference, title, created_at, created_by FROM task_links",
+        [],
+    )?;
+    conn.execute("DROP TABLE task_links", [])?;
+    conn.execute("ALTER TABLE task_links_new RENAME TO task_links", [])?;
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_task_links_item_id ON task_links(item_id)", [])?;
+    Ok(())
+}
+
+// Projects lived only in each user's config. They move into the database,
+// taking along the projects of the upgrading user's config and any project
+// named by a task; the config entries stay as local overrides.
+fn v14_projects(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS projects (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            namespace_id INTEGER NOT NULL REFERENCES namespaces(id) ON DELETE CASCADE,
+            name TEXT NOT NULL,
+            description TEXT,
+            path TEXT,
+            category TEXT,
+            prompt_template TEXT,
+            created_at INTEGER NOT NULL,
+            created_by INTEGER REFERENCES users(id),
+            archived_at INTEGER,
+            UNIQUE (namespace_id, name)
+        )",
+        [],
+    )?;
+    // Projects from the config file are imported by `conn::upgrade`, as a
+    // migration must not depend on the user's files
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    conn.execute(
+        "INSERT OR IGNORE INTO projects (namespace_id, name, created_at)
+         SELECT DISTINCT namespace_id, project, ?1 FROM items
+         WHERE project IS NOT NULL AND project != '' AND namespace_id IS NOT NULL",
+        [now],
+    )?;
+    Ok(())
+}
+
+/// Creates default user (from system $USER) and namespace on first run or v5 upgrade.
+/// Migrates existing items to the default user/namespace.
+fn setup_default_user_and_namespace(conn: &Connection) -> Result<(), rusqlite::Error> {
+    // Check if users table is empty (first run or fresh v5 install)
+    let user_count: i64 = conn.query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0))?;
+
+    if user_count > 0 {
+        // Already set up
+        return Ok(());
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+
+    // Get username from environment, fall back to "default"
+    let username = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME")) // Windows fallback
+        .unwrap_or_else(|_| "default".to_string());
+
+    // Create default user
+    conn.execute(
+        "INSERT INTO users (name, display_name, created_at, created_by) VALUES (?1, ?2, ?3, NULL)",
+        rusqlite::params![&username, &username, now],
+    )?;
+
+    let user_id: i64 = conn.query_row(
+        "SELECT id FROM users WHERE name = ?1",
+        [&username],
+        |row| row.get(0),
+    )?;
+
+    // Create default namespace
+    conn.execute(
+        "INSERT INTO namespaces (name, description, created_at, created_by) VALUES ('default', 'Default namespace', ?1, ?2)",
+        rusqlite::params![now, user_id],
+    )?;
+
+    let namespace_id: i64 = conn.query_row(
+        "SELECT id FROM namespaces WHERE name = 'default'",
+        [],
+        |row| row.get(0),
+    )?;
+
+    // Assign user as owner of default namespace
+    conn.execute(
+        "INSERT INTO user_namespaces (user_id, namespace_id, role, created_at) VALUES (?1, ?2, 'owner', ?3)",
+        rusqlite::params![user_id, namespace_id, now],
+    )?;
+
+    // Items from before v5 (if any) move to the default user/namespace
+    conn.execute(
+        "UPDATE items SET owner_id = ?1, namespace_id = ?2 WHERE owner_id IS NULL",
+        rusqlite::params![user_id, namespace_id],
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(conn: &Connection, table: &str) -> Vec<String> {
+        let mut stmt = conn
+            .prepare(&format!("SELECT name FROM pragma_table_info('{}')", table))
+            .unwrap();
+        stmt.query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<String>, _>>()
+            .unwrap()
+    }
+
+    fn table_exists(conn: &Connection, table: &str) -> bool {
+        conn.query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name = ?1",
+            [table],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    // Brings a fresh database up to (and including) the given version.
+    fn migrate_to(conn: &Connection, version: i32) {
+        for step in MIGRATIONS.iter().filter(|m| m.version <= version) {
+            apply(conn, step).unwrap();
+        }
+    }
+
+    fn step(version: i32) -> &'static Migration {
+        MIGRATIONS.iter().find(|m| m.version == version).unwrap()
+    }
+
+    #[test]
+    fn test_migrations_are_ordered() {
+        let versions: Vec<i32> = MIGRATIONS.iter().map(|m| m.version).collect();
+        let expected: Vec<i32> = (1..=SCHEMA_VERSION).collect();
+        assert_eq!(versions, expected);
+    }
+
+    #[test]
+    fn test_v1_base_tables() {
+        let conn = Connection::open_in_memory().unwrap();
+        apply(&conn, step(1)).unwrap();
+        assert!(table_exists(&conn, "items"));
+        assert!(table_exists(&conn, "cache"));
+        assert!(!columns(&conn, "items").contains(&"cron_schedule".to_string()));
+        assert_eq!(current_version(&conn).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_v2_recurring_tasks() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 1);
+        apply(&conn, step(2)).unwrap();
+        let cols = columns(&conn, "items");
+        for col in ["cron_schedule", "human_schedule", "recurring_task_id", "good_until"] {
+            assert!(cols.contains(&col.to_string()), "missing column {}", col);
+        }
+    }
+
+    #[test]
+    fn test_v3_reminder_days() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 2);
+        apply(&conn, step(3)).unwrap();
+        assert!(columns(&conn, "items").contains(&"reminder_days".to_string()));
+    }
+
+    #[test]
+    fn test_v4_project() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 3);
+        apply(&conn, step(4)).unwrap();
+        assert!(columns(&conn, "items").contains(&"project".to_string()));
+    }
+
+    #[test]
+    fn test_v5_multi_tenant_adopts_existing_items() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 4);
+        conn.execute(
+            "INSERT INTO items (action, category, content, create_time) VALUES ('task', 'work', 'old task', 0)",
+            [],
+        )
+        .unwrap();
+
+        apply(&conn, step(5)).unwrap();
+        for table in ["users", "namespaces", "user_namespaces", "task_links", "task_notes", "audit_log"] {
+            assert!(table_exists(&conn, table), "missing table {}", table);
+        }
+        let (owner_id, namespace_id): (Option<i64>, Option<i64>) = conn
+            .query_row("SELECT owner_id, namespace_id FROM items", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert!(owner_id.is_some());
+        assert!(namespace_id.is_some());
+    }
+
+    #[test]
+    fn test_v6_activity_feed() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 5);
+        apply(&conn, step(6)).unwrap();
+        assert!(columns(&conn, "audit_log").contains(&"namespace_id".to_string()));
+    }
+
+    #[test]
+    fn test_v7_snooze() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 6);
+        apply(&conn, step(7)).unwrap();
+        assert!(columns(&conn, "items").contains(&"snooze_until".to_string()));
+    }
+
+    #[test]
+    fn test_v8_completed_at() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 7);
+        conn.execute(
+            "INSERT INTO items (action, category, content, create_time, status, modify_time)
+             VALUES ('task', 'work', 'audited', 100, 1, 300), ('task', 'work', 'unaudited', 100, 1, 400),
+                    ('task', 'work', 'open', 100, 0, 500)",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO audit_log (item_id, table_name, action, field_name, created_at)
+             VALUES (1, 'items', 'complete', 'status', 200)",
+            [],
+        )
+        .unwrap();
+        apply(&conn, step(8)).unwrap();
+
+        let completed: Vec<Option<i64>> = conn
+            .prepare("SELECT completed_at FROM items ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(completed, vec![Some(200), Some(400), None]);
+    }
+
+    #[test]
+    fn test_v9_status_changes() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 8);
+        conn.execute(
+            "INSERT INTO items (action, category, content, create_time, status, modify_time, completed_at)
+             VALUES ('task', 'work', 'audited', 100, 1, 300, 300), ('task', 'work', 'unaudited', 100, 1, 400, 400)",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO audit_log (item_id, table_name, action, field_name, old_value, new_value, created_at)
+             VALUES (1, 'items', 'update', 'status', '0', '6', 200), (1, 'items', 'complete', 'status', '6', '1', 300)",
+            [],
+        )
+        .unwrap();
+        apply(&conn, step(9)).unwrap();
+
+        let history = |conn: &Connection| -> Vec<(i64, u8, u8, i64)> {
+            conn.prepare("SELECT item_id, old_status, new_status, changed_at FROM status_changes ORDER BY id")
+                .unwrap()
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+        assert_eq!(history(&conn), vec![(1, 0, 6, 200), (1, 6, 1, 300), (2, 0, 1, 400)]);
+
+        // From now on the trigger records transitions, and only real ones
+        conn.execute("UPDATE items SET status = 0, modify_time = 500 WHERE id = 2", []).unwrap();
+        conn.execute("UPDATE items SET content = 'renamed', modify_time = 600 WHERE id = 2", []).unwrap();
+        assert_eq!(history(&conn).last(), Some(&(2, 1, 0, 500)));
+        assert_eq!(history(&conn).len(), 4);
+    }
+
+    #[test]
+    fn test_v10_recurring_bounds() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 9);
+        apply(&conn, step(10)).unwrap();
+        let columns = columns(&conn, "items");
+        for column in ["starts_at", "ends_at", "max_occurrences", "paused_at"] {
+            assert!(columns.contains(&column.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_v11_github_sync() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 10);
+        apply(&conn, step(11)).unwrap();
+        let columns = columns(&conn, "github_sync");
+        for column in ["item_id", "reference", "state", "title", "assignees", "synced_at"] {
+            assert!(columns.contains(&column.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_v12_pr_status() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 11);
+        apply(&conn, step(12)).unwrap();
+        let columns = columns(&conn, "pr_status");
+        for column in ["reference", "state", "review_decision", "checks", "mergeable", "fetched_at"] {
+            assert!(columns.contains(&column.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_v13_link_types() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 12);
+        conn.execute(
+            "INSERT INTO items (action, category, content, create_time, target_time) VALUES ('task', 'c', 'x', 0, 0)",
+            [],
+        )
+        .unwrap();
+        let insert = "INSERT INTO task_links (item_id, link_type, reference, created_at) VALUES (1, ?1, ?2, 0)";
+        conn.execute(insert, ["issue", "o/r#1"]).unwrap();
+        assert!(conn.execute(insert, ["ticket", "OPS-1"]).is_err());
+
+        apply(&conn, step(13)).unwrap();
+        conn.execute(insert, ["ticket", "OPS-1"]).unwrap();
+        let types: Vec<String> = conn
+            .prepare("SELECT link_type FROM task_links ORDER BY id")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(types, vec!["issue", "ticket"]);
+    }
+
+    #[test]
+    fn test_v14_projects() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 13);
+        conn.execute(
+            "INSERT INTO namespaces (name, created_at) VALUES ('work', 0)",
+            [],
+        )
+        .unwrap();
+        let insert = "INSERT INTO items (action, category, content, create_time, target_time, project, namespace_id)
+                      VALUES ('task', 'c', 'x', 0, 0, ?1, (SELECT id FROM namespaces WHERE name = ?2))";
+        conn.execute(insert, ["api", "work"]).unwrap();
+        conn.execute(insert, ["legacy", "default"]).unwrap();
+
+        apply(&conn, step(14)).unwrap();
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM projects", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 2);
+
+        let projects: Vec<(String, String)> = conn
+            .prepare(
+                "SELECT n.name, p.name FROM projects p
+                 JOIN namespaces n ON n.id = p.namespace_id ORDER BY p.name",
+            )
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let expected = vec![
+            ("work".to_string(), "api".to_string()),
+            ("default".to_string(), "legacy".to_string()),
+        ];
+        assert_eq!(projects, expected);
+    }
+
+    #[test]
+    fn test_migrate_applies_pending_only() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate_to(&conn, 2);
+        assert_eq!(pending(&conn).unwrap().len(), (SCHEMA_VERSION - 2) as usize);
+        assert_eq!(migrate(&conn).unwrap(), (SCHEMA_VERSION - 2) as usize);
+        assert!(pending(&conn).unwrap().is_empty());
+        assert_eq!(migrate(&conn).unwrap(), 0);
+        assert_eq!(current_version(&conn).unwrap(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_refuses_newer_schema() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn).unwrap();
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+        let result = migrate(&conn);
+        assert!(result.unwrap_err().contains("newer than this binary"));
+    }
+
+    #[test]
+    fn test_failed_step_rolls_back() {
+        fn broken(conn: &Connection) -> Result<(), rusqlite::Error> {
+            conn.execute("CREATE TABLE half_done (id INTEGER)", [])?;
+            conn.execute("ALTER TABLE missing ADD COLUMN x TEXT", [])?;
+            Ok(())
+        }
+        let conn = Connection::open_in_memory().unwrap();
+        migrate(&conn).unwrap();
+        let broken_step = Migration {
+            version: SCHEMA_VERSION + 1,
+            description: "broken",
+            apply: broken,
+        };
+
+        let result = apply(&conn, &broken_step);
+        assert!(result.unwrap_err().contains("broken"));
+        assert!(!table_exists(&conn, "half_done"));
+        assert_eq!(current_version(&conn).unwrap(), SCHEMA_VERSION);
+    }
+}
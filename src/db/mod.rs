@@ -1,8 +1,14 @@
+pub mod audit;
 pub mod cache;
 pub mod conn;
 pub mod crud;
 pub mod item;
 pub mod link;
+pub mod metrics;
+pub mod migration;
 pub mod namespace;
 pub mod note;
+pub mod pr_status;
+pub mod project;
+pub mod sync;
 pub mod user;
@@ -6,7 +6,9 @@ pub struct Namespace {
     pub id: i64,
     pub name: String,
     pub description: Option<String>,
+    #[allow(dead_code)]
     pub created_at: i64,
+    #[allow(dead_code)]
     pub created_by: Option<i64>,
 }
 
@@ -24,9 +26,12 @@ impl Namespace {
 
 #[derive(Debug, Clone)]
 pub struct NamespaceMembership {
+    #[allow(dead_code)]
     pub user_id: i64,
+    #[allow(dead_code)]
     pub namespace_id: i64,
     pub role: String,
+    #[allow(dead_code)]
     pub created_at: i64,
     pub user_name: Option<String>,
 }
@@ -88,6 +93,7 @@ pub fn get_namespace_by_name(conn: &Connection, name: &str) -> Result<Option<Nam
 }
 
 /// Retrieves a namespace by ID.
+#[allow(dead_code)]
 pub fn get_namespace_by_id(conn: &Connection, id: i64) -> Result<Option<Namespace>, String> {
     let mut stmt = conn
         .prepare("SELECT id, name, description, created_at, created_by FROM namespaces WHERE id = ?1")
@@ -258,6 +264,7 @@ pub fn remove_user_from_namespace(
 }
 
 /// Gets the role of a user in a namespace.
+#[allow(dead_code)]
 pub fn get_user_role(
     conn: &Connection,
     user_id: i64,
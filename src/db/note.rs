@@ -1,9 +1,13 @@
 use rusqlite::Connection;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::db::crud::touch_item;
+
 #[derive(Debug, Clone)]
 pub struct TaskNote {
+    #[allow(dead_code)]
     pub id: i64,
+    #[allow(dead_code)]
     pub item_id: i64,
     pub content: String,
     pub created_at: i64,
@@ -41,6 +45,7 @@ pub fn add_note(
     .map_err(|e| e.to_string())?;
 
     let note_id = conn.last_insert_rowid();
+    touch_item(conn, item_id).map_err(|e| e.to_string())?;
     Ok(note_id)
 }
 
@@ -65,19 +70,21 @@ pub fn get_notes_for_item(conn: &Connection, item_id: i64) -> Result<Vec<TaskNot
 }
 
 /// Deletes a specific note by ID.
+#[allow(dead_code)]
 pub fn delete_note(conn: &Connection, note_id: i64) -> Result<(), String> {
-    let deleted = conn
-        .execute("DELETE FROM task_notes WHERE id = ?1", [note_id])
-        .map_err(|e| e.to_string())?;
+    let item_id: i64 = conn
+        .query_row("SELECT item_id FROM task_notes WHERE id = ?1", [note_id], |row| row.get(0))
+        .map_err(|_| format!("Note {} not found", note_id))?;
 
-    if deleted == 0 {
-        return Err(format!("Note {} not found", note_id));
-    }
+    conn.execute("DELETE FROM task_notes WHERE id = ?1", [note_id])
+        .map_err(|e| e.to_string())?;
+    touch_item(conn, item_id).map_err(|e| e.to_string())?;
 
     Ok(())
 }
 
 /// Gets the count of notes for a task.
+#[allow(dead_code)]
 pub fn count_notes_for_item(conn: &Connection, item_id: i64) -> Result<i64, String> {
     conn.query_row(
         "SELECT COUNT(*) FROM task_notes WHERE item_id = ?1",
@@ -0,0 +1,103 @@
+use rusqlite::{
+    Connection,
+    OptionalExtension,
+};
+
+use crate::github::{
+    ChecksState,
+    PrStatus,
+};
+
+/// A pull request status as last fetched
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedPrStatus {
+    pub reference: String,
+    pub status: PrStatus,
+    pub fetched_at: i64,
+}
+
+impl CachedPrStatus {
+    pub fn from_row(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        let checks: String = row.get("checks")?;
+        Ok(CachedPrStatus {
+            reference: row.get("reference")?,
+            status: PrStatus {
+                title: row.get("title")?,
+                url: row.get("url")?,
+                state: row.get("state")?,
+                review_decision: row.get("review_decision")?,
+                checks: ChecksState::parse(&checks),
+                mergeable: row.get("mergeable")?,
+            },
+            fetched_at: row.get("fetched_at")?,
+        })
+    }
+}
+
+/// Gets the cached status of a pull request, however old.
+pub fn get_pr_status(conn: &Connection, reference: &str) -> Result<Option<CachedPrStatus>, String> {
+    conn.query_row(
+        "SELECT reference, title, url, state, review_decision, checks, mergeable, fetched_at
+         FROM pr_status
+         WHERE reference = ?1",
+        [reference],
+        CachedPrStatus::from_row,
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// Caches the status of a pull request, replacing any earlier one.
+pub fn save_pr_status(conn: &Connection, cached: &CachedPrStatus) -> Result<(), String> {
+    let status = &cached.status;
+    conn.execute(
+        "INSERT OR REPLACE INTO pr_status
+            (reference, title, url, state, review_decision, checks, mergeable, fetched_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            cached.reference,
+            status.title,
+            status.url,
+            status.state,
+            status.review_decision,
+            status.checks.as_str(),
+            status.mergeable,
+            cached.fetched_at
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::get_test_conn;
+
+    #[test]
+    fn test_save_and_get_pr_status() {
+        let (conn, _temp_file) = get_test_conn();
+        assert_eq!(get_pr_status(&conn, "o/r#7").unwrap(), None);
+
+        let mut cached = CachedPrStatus {
+            reference: "o/r#7".to_string(),
+            status: PrStatus {
+                title: "Add SSO".to_string(),
+                url: "https://github.com/o/r/pull/7".to_string(),
+                state: "OPEN".to_string(),
+                review_decision: Some("APPROVED".to_string()),
+                checks: ChecksState::Pending,
+                mergeable: "MERGEABLE".to_string(),
+            },
+            fetched_at: 100,
+        };
+        save_pr_status(&conn, &cached).unwrap();
+        assert_eq!(get_pr_status(&conn, "o/r#7").unwrap(), Some(cached.clone()));
+
+        cached.status.state = "MERGED".to_string();
+        cached.status.checks = ChecksState::Passing;
+        cached.fetched_at = 200;
+        save_pr_status(&conn, &cached).unwrap();
+        assert_eq!(get_pr_status(&conn, "o/r#7").unwrap(), Some(cached));
+    }
+}
@@ -0,0 +1,404 @@
+use rusqlite::{
+    Connection,
+    OptionalExtension,
+};
+use std::{
+    collections::HashMap,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    config::ProjectConfig,
+    db::audit::{
+        log_change,
+        log_project_change,
+        Change,
+        AUDIT_UPDATE,
+        TABLE_ITEMS,
+        TABLE_PROJECTS,
+    },
+};
+
+const PROJECT_COLUMNS: &str =
+    "id, namespace_id, name, description, path, category, prompt_template, created_at, created_by, archived_at";
+
+/// A project shared by everyone in its namespace. Tasks refer to it by name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Project {
+    pub id: i64,
+    pub namespace_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    /// Where the project is checked out; each user can override it in config
+    pub path: Option<String>,
+    /// Category for new tasks in the project
+    pub category: Option<String>,
+    pub prompt_template: Option<String>,
+    pub created_at: i64,
+    pub created_by: Option<i64>,
+    pub archived_at: Option<i64>,
+}
+
+impl Project {
+    pub fn from_row(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        Ok(Project {
+            id: row.get("id")?,
+            namespace_id: row.get("namespace_id")?,
+            name: row.get("name")?,
+            description: row.get("description")?,
+            path: row.get("path")?,
+            category: row.get("category")?,
+            prompt_template: row.get("prompt_template")?,
+            created_at: row.get("created_at")?,
+            created_by: row.get("created_by")?,
+            archived_at: row.get("archived_at")?,
+        })
+    }
+
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64
+}
+
+/// Creates a project in its namespace. Returns the new project's ID.
+pub fn create_project(conn: &Connection, project: &Project) -> Result<i64, String> {
+    conn.execute(
+        "INSERT INTO projects (namespace_id, name, description, path, category, prompt_template, created_at, created_by)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            project.namespace_id,
+            project.name,
+            project.description,
+            project.path,
+            project.category,
+            project.prompt_template,
+            now(),
+            project.created_by
+        ],
+    )
+    .map_err(|e| {
+        if e.to_string().contains("UNIQUE constraint failed") {
+            format!("Project '{}' already exists", project.name)
+        } else {
+            e.to_string()
+        }
+    })?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Retrieves a project by name within a namespace, archived or not.
+pub fn get_project(conn: &Connection, namespace_id: i64, name: &str) -> Result<Option<Project>, String> {
+    conn.query_row(
+        &format!(
+            "SELECT {} FROM projects WHERE namespace_id = ?1 AND name = ?2",
+            PROJECT_COLUMNS
+        ),
+        rusqlite::params![namespace_id, name],
+        Project::from_row,
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// Lists the projects of a namespace by name, with archived ones if asked.
+pub fn list_projects(
+    conn: &Connection,
+    namespace_id: i64,
+    include_archived: bool,
+) -> Result<Vec<Project>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM projects
+             WHERE namespace_id = ?1 AND (?2 OR archived_at IS NULL)
+             ORDER BY name",
+            PROJECT_COLUMNS
+        ))
+        .map_err(|e| e.to_string())?;
+    let projects = stmt
+        .query_map(rusqlite::params![namespace_id, include_archived], Project::from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(projects)
+}
+
+/// Lists the active projects in every namespace the user belongs to.
+pub fn list_user_projects(conn: &Connection, user_id: i64) -> Result<Vec<Project>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT p.id, p.namespace_id, p.name, p.description, p.path, p.category, p.prompt_template,
+                    p.created_at, p.created_by, p.archived_at
+             FROM projects p
+             INNER JOIN user_namespaces un ON un.namespace_id = p.namespace_id
+             WHERE un.user_id = ?1 AND p.archived_at IS NULL
+             ORDER BY p.namespace_id, p.name",
+        )
+        .map_err(|e| e.to_string())?;
+    let projects = stmt
+        .query_map([user_id], Project::from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(projects)
+}
+
+/// Renames a project and moves its tasks along with it, recording both in
+/// the audit log. Returns how many tasks were moved.
+pub fn rename_project(
+    conn: &Connection,
+    namespace_id: i64,
+    name: &str,
+    new_name: &str,
+    user_id: i64,
+) -> Result<usize, String> {
+    let project = get_project(conn, namespace_id, name)?
+        .ok_or_else(|| format!("Project '{}' not found", name))?;
+    if get_project(conn, namespace_id, new_name)?.is_some() {
+        return Err(format!("Project '{}' already exists", new_name));
+    }
+
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "UPDATE projects SET name = ?1 WHERE id = ?2",
+        rusqlite::params![new_name, project.id],
+    )
+    .map_err(|e| e.to_string())?;
+    let task_ids: Vec<i64> = tx
+        .prepare("SELECT id FROM items WHERE namespace_id = ?1 AND project = ?2")
+        .and_then(|mut stmt| {
+            stmt.query_map(rusqlite::params![namespace_id, name], |row| row.get(0))?
+                .collect()
+        })
+        .map_err(|e| e.to_string())?;
+    // modify_time only ever increases, so concurrent updates of a moved task conflict
+    tx.execute(
+        "UPDATE items SET project = ?1, modify_time = MAX(?2, COALESCE(modify_time, 0) + 1)
+         WHERE namespace_id = ?3 AND project = ?4",
+        rusqlite::params![new_name, now(), namespace_id, name],
+    )
+    .map_err(|e| e.to_string())?;
+    for &task_id in &task_ids {
+        let change = Change::new(TABLE_ITEMS, AUDIT_UPDATE).with_field(
+            "project",
+            Some(name.to_string()),
+            Some(new_name.to_string()),
+        );
+        log_change(&tx, task_id, Some(namespace_id), user_id, change)?;
+    }
+    let change = Change::new(TABLE_PROJECTS, AUDIT_UPDATE).with_field(
+        "name",
+        Some(name.to_string()),
+        Some(new_name.to_string()),
+    );
+    log_project_change(&tx, namespace_id, user_id, change)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(task_ids.len())
+}
+
+/// Archives a project, or restores it with `archived` false.
+/// Archived projects keep their tasks but take no new ones.
+pub fn set_project_archived(
+    conn: &Connection,
+    namespace_id: i64,
+    name: &str,
+    archived: bool,
+    user_id: i64,
+) -> Result<(), String> {
+    let archived_at = archived.then(now);
+    let updated = conn
+        .execute(
+            "UPDATE projects SET archived_at = ?1 WHERE namespace_id = ?2 AND name = ?3",
+            rusqlite::params![archived_at, namespace_id, name],
+        )
+        .map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(format!("Project '{}' not found", name));
+    }
+    let field = if archived { "archived" } else { "restored" };
+    let change = Change::new(TABLE_PROJECTS, AUDIT_UPDATE).with_field(field, None, Some(name.to_string()));
+    log_project_change(conn, namespace_id, user_id, change)
+}
+
+/// Adds the projects of the config file, where they were kept before the
+/// projects table. A project for a namespace not in this database goes to
+/// default; projects that already exist are left alone.
+pub fn import_config_projects(conn: &Connection, configured: &HashMap<String, ProjectConfig>) -> Result<(), String> {
+    let namespace_id = |name: &str| -> Result<Option<i64>, String> {
+        conn.query_row("SELECT id FROM namespaces WHERE name = ?1", [name], |row| row.get(0))
+            .optional()
+            .map_err(|e| e.to_string())
+    };
+    for (name, project) in configured {
+        let ns = match namespace_id(project.namespace.as_deref().unwrap_or("default"))? {
+            Some(id) => Some(id),
+            None => namespace_id("default")?,
+        };
+        let Some(ns) = ns else { continue };
+        let path = Some(&project.path).filter(|p| !p.is_empty());
+        conn.execute(
+            "INSERT OR IGNORE INTO projects (namespace_id, name, path, category, prompt_template, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            rusqlite::params![ns, name, path, project.category, project.prompt_template, now()],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Counts a project's open and closed tasks.
+pub fn count_project_tasks(conn: &Connection, project: &Project, open_statuses: &[u8]) -> Result<(i64, i64), String> {
+    let open_list = open_statuses
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    conn.query_row(
+        &format!(
+            "SELECT COALESCE(SUM(status IN ({0})), 0), COALESCE(SUM(status NOT IN ({0})), 0)
+             FROM items WHERE action = 'task' AND namespace_id = ?1 AND project = ?2",
+            open_list
+        ),
+        rusqlite::params![project.namespace_id, project.name],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        context::Context,
+        db::{
+            crud::{get_item, update_item},
+            namespace::{create_namespace, get_namespace_by_name},
+        },
+        tests::{get_test_conn, insert_task},
+    };
+
+    fn new_project(ctx: &Context, name: &str) -> Project {
+        Project {
+            namespace_id: ctx.current_namespace_id,
+            name: name.to_string(),
+            path: Some("/src/app".to_string()),
+            created_by: Some(ctx.current_user_id),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_create_and_list_projects() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        create_project(&conn, &new_project(&ctx, "web")).unwrap();
+        create_project(&conn, &new_project(&ctx, "api")).unwrap();
+
+        let err = create_project(&conn, &new_project(&ctx, "api")).unwrap_err();
+        assert_eq!(err, "Project 'api' already exists");
+
+        let names: Vec<String> = list_projects(&conn, ctx.current_namespace_id, false)
+            .unwrap()
+            .into_iter()
+            .map(|p| p.name)
+            .collect();
+        assert_eq!(names, vec!["api", "web"]);
+        assert_eq!(list_user_projects(&conn, ctx.current_user_id).unwrap().len(), 2);
+
+        let api = get_project(&conn, ctx.current_namespace_id, "api").unwrap().unwrap();
+        assert_eq!(api.path.as_deref(), Some("/src/app"));
+        assert!(get_project(&conn, ctx.current_namespace_id, "cli").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_rename_moves_tasks() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        create_project(&conn, &new_project(&ctx, "api")).unwrap();
+        create_project(&conn, &new_project(&ctx, "web")).unwrap();
+        let id = insert_task(&conn, "work", "Add rate limits", "tomorrow");
+        let mut task = get_item(&conn, id).unwrap();
+        task.project = Some("api".to_string());
+        task.namespace_id = Some(ctx.current_namespace_id);
+        update_item(&conn, &task).unwrap();
+
+        let err = rename_project(&conn, ctx.current_namespace_id, "api", "web", ctx.current_user_id).unwrap_err();
+        assert_eq!(err, "Project 'web' already exists");
+
+        // A modify_time ahead of the clock still moves forward
+        let ahead = now() + 1000;
+        conn.execute("UPDATE items SET modify_time = ?1 WHERE id = ?2", [ahead, id]).unwrap();
+        assert_eq!(rename_project(&conn, ctx.current_namespace_id, "api", "gateway", ctx.current_user_id).unwrap(), 1);
+        let moved = get_item(&conn, id).unwrap();
+        assert_eq!(moved.project.as_deref(), Some("gateway"));
+        assert_eq!(moved.modify_time, Some(ahead + 1));
+        assert!(get_project(&conn, ctx.current_namespace_id, "api").unwrap().is_none());
+        assert!(get_project(&conn, ctx.current_namespace_id, "gateway").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_archive_project() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        create_project(&conn, &new_project(&ctx, "api")).unwrap();
+
+        set_project_archived(&conn, ctx.current_namespace_id, "api", true, ctx.current_user_id).unwrap();
+        assert!(list_projects(&conn, ctx.current_namespace_id, false).unwrap().is_empty());
+        assert!(list_user_projects(&conn, ctx.current_user_id).unwrap().is_empty());
+        let api = &list_projects(&conn, ctx.current_namespace_id, true).unwrap()[0];
+        assert!(api.is_archived());
+
+        set_project_archived(&conn, ctx.current_namespace_id, "api", false, ctx.current_user_id).unwrap();
+        assert_eq!(list_projects(&conn, ctx.current_namespace_id, false).unwrap().len(), 1);
+        assert!(set_project_archived(&conn, ctx.current_namespace_id, "cli", true, ctx.current_user_id).is_err());
+    }
+
+    #[test]
+    fn test_import_config_projects() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        create_namespace(&conn, "work", None, ctx.current_user_id).unwrap();
+        create_project(&conn, &new_project(&ctx, "site")).unwrap();
+        let configured = HashMap::from([
+            (
+                "api".to_string(),
+                ProjectConfig {
+                    path: "~/src/api".to_string(),
+                    namespace: Some("work".to_string()),
+                    category: Some("backend".to_string()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "cli".to_string(),
+                ProjectConfig {
+                    namespace: Some("gone".to_string()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "site".to_string(),
+                ProjectConfig {
+                    path: "/srv/site".to_string(),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        import_config_projects(&conn, &configured).unwrap();
+
+        let work = get_namespace_by_name(&conn, "work").unwrap().unwrap().id;
+        let api = get_project(&conn, work, "api").unwrap().unwrap();
+        assert_eq!((api.path.as_deref(), api.category.as_deref()), (Some("~/src/api"), Some("backend")));
+        let cli = get_project(&conn, ctx.current_namespace_id, "cli").unwrap().unwrap();
+        assert_eq!(cli.path, None);
+        // The existing project keeps its own path
+        let site = get_project(&conn, ctx.current_namespace_id, "site").unwrap().unwrap();
+        assert_eq!(site.path.as_deref(), Some("/src/app"));
+    }
+}
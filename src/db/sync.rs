@@ -0,0 +1,119 @@
+use rusqlite::{
+    Connection,
+    OptionalExtension,
+};
+
+/// What an issue or pull request looked like when a task was last synced
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncState {
+    pub item_id: i64,
+    pub reference: String,
+    pub state: String,
+    pub title: String,
+    /// Assignee logins, sorted
+    pub assignees: Vec<String>,
+    pub synced_at: i64,
+}
+
+impl SyncState {
+    pub fn from_row(row: &rusqlite::Row) -> Result<Self, rusqlite::Error> {
+        let assignees: String = row.get("assignees")?;
+        Ok(SyncState {
+            item_id: row.get("item_id")?,
+            reference: row.get("reference")?,
+            state: row.get("state")?,
+            title: row.get("title")?,
+            assignees: assignees
+                .split(',')
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect(),
+            synced_at: row.get("synced_at")?,
+        })
+    }
+
+    /// Whether the remote side was open at the last sync
+    pub fn is_open(&self) -> bool {
+        self.state.eq_ignore_ascii_case("open")
+    }
+}
+
+/// Gets the sync state recorded for a task's reference, if it was ever synced.
+pub fn get_sync_state(
+    conn: &Connection,
+    item_id: i64,
+    reference: &str,
+) -> Result<Option<SyncState>, String> {
+    conn.query_row(
+        "SELECT item_id, reference, state, title, assignees, synced_at
+         FROM github_sync
+         WHERE item_id = ?1 AND reference = ?2",
+        rusqlite::params![item_id, reference],
+        SyncState::from_row,
+    )
+    .optional()
+    .map_err(|e| e.to_string())
+}
+
+/// Records the sync state for a task's reference, replacing any earlier one.
+pub fn save_sync_state(conn: &Connection, state: &SyncState) -> Result<(), String> {
+    let mut assignees = state.assignees.clone();
+    assignees.sort();
+    conn.execute(
+        "INSERT INTO github_sync (item_id, reference, state, title, assignees, synced_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT (item_id, reference) DO UPDATE SET
+            state = excluded.state,
+            title = excluded.title,
+            assignees = excluded.assignees,
+            synced_at = excluded.synced_at",
+        rusqlite::params![
+            state.item_id,
+            state.reference,
+            state.state,
+            state.title,
+            assignees.join(","),
+            state.synced_at
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        get_test_conn,
+        insert_task,
+    };
+
+    #[test]
+    fn test_save_and_get_sync_state() {
+        let (conn, _temp_file) = get_test_conn();
+        let id = insert_task(&conn, "work", "Fix login", "today");
+        assert_eq!(get_sync_state(&conn, id, "o/r#1").unwrap(), None);
+
+        let mut state = SyncState {
+            item_id: id,
+            reference: "o/r#1".to_string(),
+            state: "OPEN".to_string(),
+            title: "Fix login".to_string(),
+            assignees: vec!["bob".to_string(), "alice".to_string()],
+            synced_at: 100,
+        };
+        save_sync_state(&conn, &state).unwrap();
+        let saved = get_sync_state(&conn, id, "o/r#1").unwrap().unwrap();
+        assert_eq!(saved.assignees, vec!["alice", "bob"]);
+        assert!(saved.is_open());
+
+        state.state = "CLOSED".to_string();
+        state.assignees.clear();
+        state.synced_at = 200;
+        save_sync_state(&conn, &state).unwrap();
+        let saved = get_sync_state(&conn, id, "o/r#1").unwrap().unwrap();
+        assert!(!saved.is_open());
+        assert!(saved.assignees.is_empty());
+        assert_eq!(saved.synced_at, 200);
+    }
+}
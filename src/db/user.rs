@@ -6,7 +6,9 @@ pub struct User {
     pub id: i64,
     pub name: String,
     pub display_name: Option<String>,
+    #[allow(dead_code)]
     pub created_at: i64,
+    #[allow(dead_code)]
     pub created_by: Option<i64>,
 }
 
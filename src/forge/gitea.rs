@@ -0,0 +1,101 @@
+use serde_json::json;
+
+use crate::forge::{
+    provider::{ForgeIssue, IssueProvider},
+    reference::{ForgeKind, ForgeRef},
+    rest::RestClient,
+};
+
+/// Gitea (and Forgejo) issues and pull requests through the v1 REST API
+pub struct Gitea {
+    rest: RestClient,
+}
+
+impl Gitea {
+    /// `url` is the instance's web address, e.g. https://gitea.example.com
+    pub fn new(url: &str, token: &str, token_hint: String) -> Self {
+        let base_url = format!("{}/api/v1", url.trim_end_matches('/'));
+        Gitea {
+            rest: RestClient::new("Gitea", &base_url, format!("token {}", token), token_hint),
+        }
+    }
+
+    fn path(forge_ref: &ForgeRef) -> String {
+        let collection = if forge_ref.pull_request { "pulls" } else { "issues" };
+        format!("/repos/{}/{}/{}", forge_ref.project, collection, forge_ref.number)
+    }
+}
+
+impl IssueProvider for Gitea {
+    fn kind(&self) -> ForgeKind {
+        ForgeKind::Gitea
+    }
+
+    fn fetch(&self, forge_ref: &ForgeRef) -> Result<ForgeIssue, String> {
+        let json = self.rest.get(&Gitea::path(forge_ref))?;
+        let merged = json["merged"].as_bool().unwrap_or(false);
+        Ok(ForgeIssue {
+            title: json["title"].as_str().unwrap_or("").to_string(),
+            body: json["body"].as_str().map(|s| s.to_string()),
+            state: if merged {
+                "MERGED".to_string()
+            } else {
+                json["state"].as_str().unwrap_or("").to_uppercase()
+            },
+            url: json["html_url"].as_str().unwrap_or("").to_string(),
+            assignees: json["assignees"]
+                .as_array()
+                .map(|list| {
+                    list.iter()
+                        .filter_map(|a| a["login"].as_str().map(|s| s.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+    }
+
+    fn close(&self, forge_ref: &ForgeRef) -> Result<(), String> {
+        let body = json!({ "state": "closed" });
+        self.rest
+            .request("PATCH", &Gitea::path(forge_ref), Some(&body))
+            .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        forge::parse_forge_ref,
+        tests::MockApi,
+    };
+
+    #[test]
+    fn test_fetch_and_close_issue() {
+        let api = MockApi::start(vec![
+            (
+                "GET /api/v1/repos/o/r/issues/5",
+                200,
+                r#"{"number": 5, "title": "Broken link", "state": "open", "html_url": "https://gitea/o/r/issues/5",
+                    "assignees": null}"#
+                    .to_string(),
+            ),
+            ("PATCH /api/v1/repos/o/r/issues/5", 201, "{}".to_string()),
+            ("GET /api/v1/repos/o/r/pulls/6", 401, r#"{"message": "token is required"}"#.to_string()),
+        ]);
+        let gitea = Gitea::new(&api.url, "tok", "GITEA_TOKEN".to_string());
+
+        let issue_ref = parse_forge_ref("o/r#5").unwrap();
+        let issue = gitea.fetch(&issue_ref).unwrap();
+        assert!(issue.is_open());
+        assert!(issue.assignees.is_empty());
+        gitea.close(&issue_ref).unwrap();
+        assert_eq!(api.bodies(), vec![r#"{"state":"closed"}"#]);
+        assert_eq!(api.requests()[0], "GET /api/v1/repos/o/r/issues/5 token tok");
+
+        let err = gitea
+            .fetch(&parse_forge_ref("https://gitea/o/r/pulls/6").unwrap())
+            .unwrap_err();
+        assert!(err.contains("Gitea rejected the token (401). Check GITEA_TOKEN."));
+    }
+}
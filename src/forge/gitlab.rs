@@ -0,0 +1,109 @@
+use serde_json::{json, Value};
+
+use crate::forge::{
+    provider::{ForgeIssue, IssueProvider},
+    reference::{ForgeKind, ForgeRef},
+    rest::{encode_segment, RestClient},
+};
+
+/// GitLab issues and merge requests through the v4 REST API
+pub struct GitLab {
+    rest: RestClient,
+}
+
+impl GitLab {
+    /// `url` is the instance's web address, e.g. https://gitlab.com
+    pub fn new(url: &str, token: &str, token_hint: String) -> Self {
+        let base_url = format!("{}/api/v4", url.trim_end_matches('/'));
+        GitLab {
+            rest: RestClient::new("GitLab", &base_url, format!("Bearer {}", token), token_hint),
+        }
+    }
+
+    fn path(forge_ref: &ForgeRef) -> String {
+        let collection = if forge_ref.pull_request { "merge_requests" } else { "issues" };
+        format!(
+            "/projects/{}/{}/{}",
+            encode_segment(&forge_ref.project),
+            collection,
+            forge_ref.number
+        )
+    }
+}
+
+impl IssueProvider for GitLab {
+    fn kind(&self) -> ForgeKind {
+        ForgeKind::GitLab
+    }
+
+    fn fetch(&self, forge_ref: &ForgeRef) -> Result<ForgeIssue, String> {
+        let json = self.rest.get(&GitLab::path(forge_ref))?;
+        Ok(ForgeIssue {
+            title: json["title"].as_str().unwrap_or("").to_string(),
+            body: json["description"].as_str().map(|s| s.to_string()),
+            state: match json["state"].as_str().unwrap_or("") {
+                "opened" => "OPEN",
+                "merged" => "MERGED",
+                _ => "CLOSED",
+            }
+            .to_string(),
+            url: json["web_url"].as_str().unwrap_or("").to_string(),
+            assignees: usernames(&json["assignees"]),
+        })
+    }
+
+    fn close(&self, forge_ref: &ForgeRef) -> Result<(), String> {
+        let body = json!({ "state_event": "close" });
+        self.rest
+            .request("PUT", &GitLab::path(forge_ref), Some(&body))
+            .map(|_| ())
+    }
+}
+
+fn usernames(list: &Value) -> Vec<String> {
+    list.as_array()
+        .map(|list| {
+            list.iter()
+                .filter_map(|a| a["username"].as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        forge::parse_forge_ref,
+        tests::MockApi,
+    };
+
+    #[test]
+    fn test_fetch_and_close_merge_request() {
+        let api = MockApi::start(vec![
+            (
+                "GET /api/v4/projects/g%2Fsub%2Fp/merge_requests/12",
+                200,
+                r#"{"iid": 12, "title": "Add SSO", "state": "merged", "web_url": "https://gl/g/sub/p/-/merge_requests/12",
+                    "assignees": [{"username": "alice"}]}"#
+                    .to_string(),
+            ),
+            ("PUT /api/v4/projects/g%2Fsub%2Fp/issues/3", 200, "{}".to_string()),
+        ]);
+        let gitlab = GitLab::new(&api.url, "glpat", "GITLAB_TOKEN".to_string());
+
+        let mr = gitlab.fetch(&parse_forge_ref("g/sub/p!12").unwrap()).unwrap();
+        assert_eq!(mr.title, "Add SSO");
+        assert_eq!(mr.state, "MERGED");
+        assert_eq!(mr.assignees, vec!["alice"]);
+
+        let mut issue = parse_forge_ref("g/sub/p#3").unwrap();
+        issue.kind = Some(ForgeKind::GitLab);
+        gitlab.close(&issue).unwrap();
+        assert_eq!(api.bodies(), vec![r#"{"state_event":"close"}"#]);
+        assert_eq!(api.requests()[0], "GET /api/v4/projects/g%2Fsub%2Fp/merge_requests/12 Bearer glpat");
+
+        let err = gitlab.fetch(&parse_forge_ref("g/p!1").unwrap()).unwrap_err();
+        assert!(err.contains("Not found on GitLab"));
+    }
+}
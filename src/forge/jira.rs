@@ -0,0 +1,158 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde_json::{json, Value};
+
+use crate::forge::{
+    provider::{ForgeIssue, IssueProvider},
+    reference::{ForgeKind, ForgeRef},
+    rest::RestClient,
+};
+
+/// Jira tickets through the v2 REST API
+pub struct Jira {
+    rest: RestClient,
+    url: String,
+    // Name of the transition that closes a ticket; None takes the first
+    // transition into a done status
+    close_transition: Option<String>,
+}
+
+impl Jira {
+    /// Jira Cloud authenticates with the account email and an API token,
+    /// Data Center with a personal access token alone
+    pub fn new(url: &str, user: Option<&str>, token: &str, token_hint: String, close_transition: Option<String>) -> Self {
+        let url = url.trim_end_matches('/').to_string();
+        let authorization = match user {
+            Some(user) => format!("Basic {}", STANDARD.encode(format!("{}:{}", user, token))),
+            None => format!("Bearer {}", token),
+        };
+        Jira {
+            rest: RestClient::new("Jira", &format!("{}/rest/api/2", url), authorization, token_hint),
+            url,
+            close_transition,
+        }
+    }
+
+    fn path(forge_ref: &ForgeRef) -> String {
+        format!("/issue/{}-{}", forge_ref.project, forge_ref.number)
+    }
+
+    /// The transition that closes the ticket from its current status
+    fn close_transition_id(&self, forge_ref: &ForgeRef) -> Result<String, String> {
+        let reply = self.rest.get(&format!("{}/transitions", Jira::path(forge_ref)))?;
+        let transitions = reply["transitions"].as_array().cloned().unwrap_or_default();
+        let found = match &self.close_transition {
+            Some(name) => transitions
+                .iter()
+                .find(|t| t["name"].as_str().is_some_and(|n| n.eq_ignore_ascii_case(name))),
+            None => transitions.iter().find(|t| is_done(&t["to"])),
+        };
+        found
+            .and_then(|t| t["id"].as_str())
+            .map(|id| id.to_string())
+            .ok_or_else(|| match &self.close_transition {
+                Some(name) => format!("{} has no transition named '{}'", forge_ref, name),
+                None => format!(
+                    "{} has no transition to a done status. Set close_transition for the forge in the config.",
+                    forge_ref
+                ),
+            })
+    }
+}
+
+impl IssueProvider for Jira {
+    fn kind(&self) -> ForgeKind {
+        ForgeKind::Jira
+    }
+
+    fn fetch(&self, forge_ref: &ForgeRef) -> Result<ForgeIssue, String> {
+        let json = self
+            .rest
+            .get(&format!("{}?fields=summary,description,status,assignee", Jira::path(forge_ref)))?;
+        let fields = &json["fields"];
+        Ok(ForgeIssue {
+            title: fields["summary"].as_str().unwrap_or("").to_string(),
+            body: fields["description"].as_str().map(|s| s.to_string()),
+            state: if is_done(&fields["status"]) { "CLOSED" } else { "OPEN" }.to_string(),
+            url: format!("{}/browse/{}", self.url, forge_ref),
+            assignees: fields["assignee"]["displayName"]
+                .as_str()
+                .map(|name| vec![name.to_string()])
+                .unwrap_or_default(),
+        })
+    }
+
+    fn close(&self, forge_ref: &ForgeRef) -> Result<(), String> {
+        let id = self.close_transition_id(forge_ref)?;
+        let body = json!({ "transition": { "id": id } });
+        self.rest
+            .request("POST", &format!("{}/transitions", Jira::path(forge_ref)), Some(&body))
+            .map(|_| ())
+    }
+}
+
+// Statuses belong to the "new", "indeterminate" or "done" category
+fn is_done(status: &Value) -> bool {
+    status["statusCategory"]["key"].as_str() == Some("done")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        forge::parse_forge_ref,
+        tests::MockApi,
+    };
+
+    fn transitions() -> String {
+        r#"{"transitions": [
+            {"id": "11", "name": "Start", "to": {"statusCategory": {"key": "indeterminate"}}},
+            {"id": "31", "name": "Resolve", "to": {"statusCategory": {"key": "done"}}},
+            {"id": "41", "name": "Won't do", "to": {"statusCategory": {"key": "done"}}}
+        ]}"#
+        .to_string()
+    }
+
+    #[test]
+    fn test_fetch_ticket() {
+        let api = MockApi::start(vec![(
+            "GET /rest/api/2/issue/OPS-9?fields=summary,description,status,assignee",
+            200,
+            r#"{"key": "OPS-9", "fields": {"summary": "Rotate certs", "status": {"statusCategory": {"key": "new"}},
+                "assignee": {"displayName": "Alice"}}}"#
+                .to_string(),
+        )]);
+        let jira = Jira::new(&api.url, Some("me@example.com"), "tok", "JIRA_TOKEN".to_string(), None);
+        let ticket = jira.fetch(&parse_forge_ref("OPS-9").unwrap()).unwrap();
+        assert_eq!(ticket.title, "Rotate certs");
+        assert!(ticket.is_open());
+        assert_eq!(ticket.url, format!("{}/browse/OPS-9", api.url));
+        assert_eq!(ticket.assignees, vec!["Alice"]);
+        let auth = format!("Basic {}", STANDARD.encode("me@example.com:tok"));
+        assert!(api.requests()[0].ends_with(&auth));
+    }
+
+    #[test]
+    fn test_close_picks_transition() {
+        let api = MockApi::start(vec![
+            ("GET /rest/api/2/issue/OPS-9/transitions", 200, transitions()),
+            ("POST /rest/api/2/issue/OPS-9/transitions", 204, String::new()),
+        ]);
+        let ticket = parse_forge_ref("OPS-9").unwrap();
+
+        Jira::new(&api.url, None, "pat", "JIRA_TOKEN".to_string(), None)
+            .close(&ticket)
+            .unwrap();
+        Jira::new(&api.url, None, "pat", "JIRA_TOKEN".to_string(), Some("won't DO".to_string()))
+            .close(&ticket)
+            .unwrap();
+        assert_eq!(
+            api.bodies(),
+            vec![r#"{"transition":{"id":"31"}}"#, r#"{"transition":{"id":"41"}}"#]
+        );
+
+        let err = Jira::new(&api.url, None, "pat", "JIRA_TOKEN".to_string(), Some("Close".to_string()))
+            .close(&ticket)
+            .unwrap_err();
+        assert!(err.contains("no transition named 'Close'"));
+    }
+}
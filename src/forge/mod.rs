@@ -0,0 +1,9 @@
+pub mod gitea;
+pub mod gitlab;
+pub mod jira;
+pub mod provider;
+pub mod reference;
+pub mod rest;
+
+pub use provider::*;
+pub use reference::*;
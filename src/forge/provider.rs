@@ -0,0 +1,282 @@
+use std::collections::HashMap;
+
+use crate::{
+    config::{
+        get_forges,
+        get_github_config,
+        ForgeConfig,
+        GitHubConfig,
+    },
+    forge::{
+        gitea::Gitea,
+        gitlab::GitLab,
+        jira::Jira,
+        reference::{ForgeKind, ForgeRef},
+    },
+    github::{forge_client, ForgeClient},
+};
+
+/// An issue, pull request or ticket as any forge reports it
+#[derive(Debug, Clone)]
+pub struct ForgeIssue {
+    pub title: String,
+    pub body: Option<String>,
+    /// OPEN, CLOSED or MERGED
+    pub state: String,
+    pub url: String,
+    /// Logins of the assigned users
+    pub assignees: Vec<String>,
+}
+
+impl ForgeIssue {
+    pub fn is_open(&self) -> bool {
+        self.state == "OPEN"
+    }
+}
+
+/// Fetches and closes the issues of one forge
+pub trait IssueProvider {
+    fn kind(&self) -> ForgeKind;
+    fn fetch(&self, forge_ref: &ForgeRef) -> Result<ForgeIssue, String>;
+    fn close(&self, forge_ref: &ForgeRef) -> Result<(), String>;
+}
+
+/// GitHub through the gh CLI or the REST API, whichever `forge_client` picks
+pub struct GitHubProvider {
+    client: Box<dyn ForgeClient>,
+}
+
+impl GitHubProvider {
+    pub fn new(config: &GitHubConfig) -> Result<Self, String> {
+        let client = forge_client(config)?;
+        client.check_auth()?;
+        Ok(GitHubProvider { client })
+    }
+}
+
+impl IssueProvider for GitHubProvider {
+    fn kind(&self) -> ForgeKind {
+        ForgeKind::GitHub
+    }
+
+    fn fetch(&self, forge_ref: &ForgeRef) -> Result<ForgeIssue, String> {
+        let issue_ref = forge_ref.to_issue_ref()?;
+        let issue = if forge_ref.pull_request {
+            self.client.get_pull_request(&issue_ref)?
+        } else {
+            self.client.get_issue(&issue_ref)?
+        };
+        Ok(ForgeIssue {
+            title: issue.title,
+            body: issue.body,
+            state: issue.state.to_uppercase(),
+            url: issue.url,
+            assignees: issue.assignees,
+        })
+    }
+
+    fn close(&self, forge_ref: &ForgeRef) -> Result<(), String> {
+        self.client.close_issue(&forge_ref.to_issue_ref()?)
+    }
+}
+
+/// The namespace and project of a task, which decide the forge it uses
+#[derive(Debug, Clone, Copy)]
+pub struct ForgeScope<'a> {
+    pub namespace: &'a str,
+    pub project: Option<&'a str>,
+}
+
+/// The provider for a reference made from a task in this scope
+pub fn issue_provider(forge_ref: &ForgeRef, scope: ForgeScope) -> Result<Box<dyn IssueProvider>, String> {
+    let forges = get_forges();
+    match select_forge(&forges, forge_ref, scope)? {
+        Some((name, forge)) => build_provider(name, forge),
+        None if matches!(forge_ref.kind, None | Some(ForgeKind::GitHub)) => {
+            Ok(Box::new(GitHubProvider::new(&get_github_config())?))
+        }
+        None => Err(format!(
+            "No {} forge configured for {}. Add one under \"forges\" in ~/.config/ctm/config.json",
+            forge_ref.kind.map_or("", |k| k.name()),
+            forge_ref
+        )),
+    }
+}
+
+/// Where a reference made in this scope lives, without connecting to it
+pub fn resolve_kind(
+    forges: &HashMap<String, ForgeConfig>,
+    forge_ref: &ForgeRef,
+    scope: ForgeScope,
+) -> Result<ForgeKind, String> {
+    Ok(match select_forge(forges, forge_ref, scope)? {
+        Some((_, forge)) => ForgeKind::parse(&forge.kind)?,
+        None => forge_ref.kind.unwrap_or(ForgeKind::GitHub),
+    })
+}
+
+/// The configured forge for a reference: one of its kind (any but Jira for
+/// "owner/repo#N"), on its host for URLs, scoped to the task's project, else
+/// to its namespace, else unscoped. None leaves GitHub as the default.
+fn select_forge<'a>(
+    forges: &'a HashMap<String, ForgeConfig>,
+    forge_ref: &ForgeRef,
+    scope: ForgeScope,
+) -> Result<Option<(&'a str, &'a ForgeConfig)>, String> {
+    let mut candidates = Vec::new();
+    for (name, forge) in forges {
+        let kind = ForgeKind::parse(&forge.kind).map_err(|e| format!("forges.{}: {}", name, e))?;
+        let kind_matches = match forge_ref.kind {
+            Some(wanted) => kind == wanted,
+            None => kind != ForgeKind::Jira,
+        };
+        let listed = |list: &Option<Vec<String>>, value: &str| list.as_ref().is_some_and(|l| l.iter().any(|v| v == value));
+        let rank = if scope.project.is_some_and(|p| listed(&forge.projects, p)) {
+            0
+        } else if listed(&forge.namespaces, scope.namespace) {
+            1
+        } else if forge.projects.is_none() && forge.namespaces.is_none() {
+            2
+        } else {
+            continue;
+        };
+        if kind_matches {
+            candidates.push((rank, name.as_str(), forge));
+        }
+    }
+    // A URL names its host, which settles it when a forge is on that host
+    if let Some(host) = &forge_ref.host {
+        let on_host: Vec<_> = candidates
+            .iter()
+            .filter(|(_, _, forge)| forge.url.as_deref().and_then(url_host).as_deref() == Some(host.as_str()))
+            .cloned()
+            .collect();
+        if !on_host.is_empty() {
+            candidates = on_host;
+        }
+    }
+    candidates.sort_by_key(|(rank, name, _)| (*rank, *name));
+    Ok(candidates.first().map(|(_, name, forge)| (*name, *forge)))
+}
+
+fn build_provider(name: &str, forge: &ForgeConfig) -> Result<Box<dyn IssueProvider>, String> {
+    let kind = ForgeKind::parse(&forge.kind)?;
+    let token_hint = match &forge.token_env {
+        Some(var) => var.clone(),
+        None => format!("forges.{}.token in the config", name),
+    };
+    let token = forge
+        .token_env
+        .as_ref()
+        .and_then(|var| std::env::var(var).ok())
+        .or_else(|| forge.token.clone())
+        .filter(|token| !token.is_empty());
+
+    if kind == ForgeKind::GitHub {
+        let config = GitHubConfig {
+            client: token.as_ref().map(|_| "http".to_string()),
+            api_url: forge.url.clone(),
+            token,
+            ..get_github_config()
+        };
+        return Ok(Box::new(GitHubProvider::new(&config)?));
+    }
+
+    let url = forge
+        .url
+        .as_deref()
+        .ok_or_else(|| format!("forges.{} has no url", name))?;
+    let token = token.ok_or_else(|| format!("No token for forge '{}'. Set {}.", name, token_hint))?;
+    Ok(match kind {
+        ForgeKind::GitLab => Box::new(GitLab::new(url, &token, token_hint)),
+        ForgeKind::Gitea => Box::new(Gitea::new(url, &token, token_hint)),
+        ForgeKind::Jira => Box::new(Jira::new(
+            url,
+            forge.user.as_deref(),
+            &token,
+            token_hint,
+            forge.close_transition.clone(),
+        )),
+        ForgeKind::GitHub => unreachable!(),
+    })
+}
+
+// "https://host:port/path" -> "host:port"
+fn url_host(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split('/').next().map(|host| host.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::parse_forge_ref;
+
+    fn forge(kind: &str, url: &str, namespaces: &[&str], projects: &[&str]) -> ForgeConfig {
+        let list = |l: &[&str]| (!l.is_empty()).then(|| l.iter().map(|s| s.to_string()).collect());
+        ForgeConfig {
+            kind: kind.to_string(),
+            url: Some(url.to_string()),
+            namespaces: list(namespaces),
+            projects: list(projects),
+            ..Default::default()
+        }
+    }
+
+    fn selected(forges: &HashMap<String, ForgeConfig>, reference: &str, namespace: &str, project: Option<&str>) -> Option<String> {
+        let scope = ForgeScope { namespace, project };
+        select_forge(forges, &parse_forge_ref(reference).unwrap(), scope)
+            .unwrap()
+            .map(|(name, _)| name.to_string())
+    }
+
+    #[test]
+    fn test_select_forge_by_scope() {
+        let forges: HashMap<String, ForgeConfig> = [
+            ("corp-gitlab".to_string(), forge("gitlab", "https://gitlab.corp", &["work"], &[])),
+            ("api-gitea".to_string(), forge("gitea", "https://gitea.corp", &[], &["api"])),
+            ("jira".to_string(), forge("jira", "https://corp.atlassian.net", &[], &[])),
+        ]
+        .into();
+
+        // Shorthand follows the task's project, then its namespace, else GitHub
+        assert_eq!(selected(&forges, "o/r#1", "work", Some("api")).as_deref(), Some("api-gitea"));
+        assert_eq!(selected(&forges, "o/r#1", "work", None).as_deref(), Some("corp-gitlab"));
+        assert_eq!(selected(&forges, "o/r#1", "home", None), None);
+        let scope = ForgeScope { namespace: "home", project: None };
+        assert_eq!(resolve_kind(&forges, &parse_forge_ref("o/r#1").unwrap(), scope), Ok(ForgeKind::GitHub));
+
+        // Merge requests and tickets only go to a forge of their kind
+        assert_eq!(selected(&forges, "g/p!2", "work", None).as_deref(), Some("corp-gitlab"));
+        assert_eq!(selected(&forges, "g/p!2", "home", None), None);
+        assert_eq!(selected(&forges, "OPS-3", "home", None).as_deref(), Some("jira"));
+    }
+
+    #[test]
+    fn test_select_forge_by_url_host() {
+        let forges: HashMap<String, ForgeConfig> = [
+            ("a".to_string(), forge("gitlab", "https://gitlab.one.com", &[], &[])),
+            ("b".to_string(), forge("gitlab", "https://gitlab.two.com/", &[], &[])),
+        ]
+        .into();
+        assert_eq!(
+            selected(&forges, "https://gitlab.two.com/g/p/-/issues/1", "default", None).as_deref(),
+            Some("b")
+        );
+        // Unknown hosts fall back to the usual order
+        assert_eq!(
+            selected(&forges, "https://gitlab.other.com/g/p/-/issues/1", "default", None).as_deref(),
+            Some("a")
+        );
+    }
+
+    #[test]
+    fn test_select_forge_without_match() {
+        let scope = ForgeScope { namespace: "default", project: None };
+        let none = HashMap::new();
+        assert!(select_forge(&none, &parse_forge_ref("OPS-3").unwrap(), scope).unwrap().is_none());
+        let mut bad = HashMap::new();
+        bad.insert("x".to_string(), forge("svn", "https://svn", &[], &[]));
+        assert!(select_forge(&bad, &parse_forge_ref("OPS-3").unwrap(), scope).is_err());
+    }
+}
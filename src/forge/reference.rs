@@ -0,0 +1,260 @@
+use std::fmt;
+
+use crate::github::IssueRef;
+
+/// The kind of service an issue or pull request lives on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForgeKind {
+    GitHub,
+    GitLab,
+    Gitea,
+    Jira,
+}
+
+impl ForgeKind {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "github" => Ok(ForgeKind::GitHub),
+            "gitlab" => Ok(ForgeKind::GitLab),
+            "gitea" => Ok(ForgeKind::Gitea),
+            "jira" => Ok(ForgeKind::Jira),
+            _ => Err(format!(
+                "Invalid forge kind '{}'. Use github, gitlab, gitea or jira",
+                s
+            )),
+        }
+    }
+
+    /// Display name, e.g. "GitLab"
+    pub fn name(&self) -> &'static str {
+        match self {
+            ForgeKind::GitHub => "GitHub",
+            ForgeKind::GitLab => "GitLab",
+            ForgeKind::Gitea => "Gitea",
+            ForgeKind::Jira => "Jira",
+        }
+    }
+}
+
+/// An issue, pull request, merge request or ticket on some forge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForgeRef {
+    // None for "owner/repo#N", which GitHub, GitLab and Gitea all use;
+    // the forge configured for the task decides
+    pub kind: Option<ForgeKind>,
+    // Host of a URL reference, to pick among configured forges
+    pub host: Option<String>,
+    // owner/repo, group/subgroup/project or a Jira project key
+    pub project: String,
+    pub number: u32,
+    pub pull_request: bool,
+}
+
+/// Formats in the forge's own shorthand: "owner/repo#42",
+/// "group/project!12" for merge requests, or "PROJ-123"
+impl fmt::Display for ForgeRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            Some(ForgeKind::Jira) => write!(f, "{}-{}", self.project, self.number),
+            Some(ForgeKind::GitLab) if self.pull_request => write!(f, "{}!{}", self.project, self.number),
+            _ => write!(f, "{}#{}", self.project, self.number),
+        }
+    }
+}
+
+impl ForgeRef {
+    /// What the reference is called, e.g. "merge request"
+    pub fn noun(&self) -> &'static str {
+        match (self.kind, self.pull_request) {
+            (Some(ForgeKind::Jira), _) => "ticket",
+            (Some(ForgeKind::GitLab), true) => "merge request",
+            (_, true) => "pull request",
+            (_, false) => "issue",
+        }
+    }
+
+    /// The task link type for the reference
+    pub fn link_type(&self) -> &'static str {
+        match (self.kind, self.pull_request) {
+            (Some(ForgeKind::Jira), _) => "ticket",
+            (Some(ForgeKind::GitLab), true) => "mr",
+            (_, true) => "pr",
+            (_, false) => "issue",
+        }
+    }
+
+    /// The GitHub "owner/repo#N" form, for references that can be on GitHub
+    pub fn to_issue_ref(&self) -> Result<IssueRef, String> {
+        let github = matches!(self.kind, None | Some(ForgeKind::GitHub));
+        match self.project.split_once('/') {
+            Some((owner, repo)) if github && !repo.contains('/') => Ok(IssueRef {
+                owner: owner.to_string(),
+                repo: repo.to_string(),
+                number: self.number,
+            }),
+            _ => Err(format!("'{}' is not a GitHub reference", self)),
+        }
+    }
+}
+
+/// Parse an issue or pull request reference:
+/// "owner/repo#42", "group/project!12", "PROJ-123", or the URL of any of them
+pub fn parse_forge_ref(s: &str) -> Result<ForgeRef, String> {
+    let invalid = || {
+        format!(
+            "Invalid reference '{}'. Use owner/repo#N, group/project!N, PROJ-123 or a URL",
+            s
+        )
+    };
+    let s = s.trim();
+    if let Some(rest) = s.strip_prefix("https://").or_else(|| s.strip_prefix("http://")) {
+        return parse_url(rest).ok_or_else(invalid);
+    }
+
+    // Shorthand: the number follows the last '#' or '!'
+    if let Some(pos) = s.rfind(['#', '!']) {
+        let project = &s[..pos];
+        let number: u32 = s[pos + 1..].parse().map_err(|_| invalid())?;
+        if !valid_project_path(project) {
+            return Err(invalid());
+        }
+        let merge_request = s[pos..].starts_with('!');
+        return Ok(ForgeRef {
+            kind: merge_request.then_some(ForgeKind::GitLab),
+            host: None,
+            project: project.to_string(),
+            number,
+            pull_request: merge_request,
+        });
+    }
+
+    parse_jira_key(s).ok_or_else(invalid)
+}
+
+// Project paths have at least two segments and no empty ones
+fn valid_project_path(path: &str) -> bool {
+    let segments: Vec<&str> = path.split('/').collect();
+    segments.len() >= 2 && segments.iter().all(|s| !s.is_empty() && !s.contains(char::is_whitespace))
+}
+
+// "PROJ-123": an upper-case key starting with a letter, a dash and a number
+fn parse_jira_key(s: &str) -> Option<ForgeRef> {
+    let (key, number) = s.rsplit_once('-')?;
+    let number: u32 = number.parse().ok()?;
+    let valid_key = key.starts_with(|c: char| c.is_ascii_uppercase())
+        && key.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_');
+    valid_key.then(|| ForgeRef {
+        kind: Some(ForgeKind::Jira),
+        host: None,
+        project: key.to_string(),
+        number,
+        pull_request: false,
+    })
+}
+
+// The part of a URL after the scheme
+fn parse_url(rest: &str) -> Option<ForgeRef> {
+    let rest = rest.split(['?', '#']).next()?;
+    let (host, path) = rest.split_once('/')?;
+    let segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    let with_host = |r: ForgeRef| ForgeRef {
+        host: Some(host.to_lowercase()),
+        ..r
+    };
+
+    // Jira: /browse/PROJ-123
+    if let ["browse", key] = segments.as_slice() {
+        return parse_jira_key(key).map(with_host);
+    }
+
+    let (number, segments) = segments.split_last()?;
+    let number: u32 = number.parse().ok()?;
+    let (kind_segment, project) = segments.split_last()?;
+    let (kind, pull_request, project) = match (project.split_last(), *kind_segment) {
+        // GitLab: group/project/-/issues/N and group/project/-/merge_requests/N
+        (Some((&"-", project)), "issues") => (Some(ForgeKind::GitLab), false, project),
+        (Some((&"-", project)), "merge_requests") => (Some(ForgeKind::GitLab), true, project),
+        // Gitea: owner/repo/pulls/N
+        (_, "pulls") => (Some(ForgeKind::Gitea), true, project),
+        // GitHub, and Gitea issues: owner/repo/issues/N, owner/repo/pull/N
+        (_, "issues" | "pull") => {
+            let kind = (host == "github.com").then_some(ForgeKind::GitHub);
+            (kind, *kind_segment == "pull", project)
+        }
+        _ => return None,
+    };
+    let project = project.join("/");
+    if !valid_project_path(&project) {
+        return None;
+    }
+    Some(with_host(ForgeRef {
+        kind,
+        host: None,
+        project,
+        number,
+        pull_request,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shorthand() {
+        let r = parse_forge_ref("owner/repo#42").unwrap();
+        assert_eq!(r.kind, None);
+        assert_eq!((r.project.as_str(), r.number, r.pull_request), ("owner/repo", 42, false));
+        assert_eq!(r.to_string(), "owner/repo#42");
+        assert_eq!(r.to_issue_ref().unwrap().to_string(), "owner/repo#42");
+
+        let r = parse_forge_ref("group/sub/project!12").unwrap();
+        assert_eq!(r.kind, Some(ForgeKind::GitLab));
+        assert_eq!(r.project, "group/sub/project");
+        assert_eq!(r.link_type(), "mr");
+        assert_eq!(r.to_string(), "group/sub/project!12");
+        assert!(r.to_issue_ref().is_err());
+
+        let r = parse_forge_ref("PROJ-123").unwrap();
+        assert_eq!(r.kind, Some(ForgeKind::Jira));
+        assert_eq!((r.project.as_str(), r.number), ("PROJ", 123));
+        assert_eq!(r.link_type(), "ticket");
+        assert_eq!(r.to_string(), "PROJ-123");
+    }
+
+    #[test]
+    fn test_parse_urls() {
+        let r = parse_forge_ref("https://github.com/o/r/pull/7").unwrap();
+        assert_eq!(r.kind, Some(ForgeKind::GitHub));
+        assert_eq!(r.host.as_deref(), Some("github.com"));
+        assert_eq!(r.to_string(), "o/r#7");
+        assert_eq!(r.link_type(), "pr");
+
+        let r = parse_forge_ref("https://gitlab.example.com/g/sub/p/-/merge_requests/12?tab=diffs").unwrap();
+        assert_eq!(r.kind, Some(ForgeKind::GitLab));
+        assert_eq!(r.project, "g/sub/p");
+        assert!(r.pull_request);
+
+        let r = parse_forge_ref("https://gitlab.example.com/g/p/-/issues/3").unwrap();
+        assert_eq!((r.kind, r.pull_request), (Some(ForgeKind::GitLab), false));
+
+        let r = parse_forge_ref("https://gitea.example.com/o/r/pulls/5").unwrap();
+        assert_eq!((r.kind, r.pull_request), (Some(ForgeKind::Gitea), true));
+
+        // Self-hosted issue URLs look the same on GitHub Enterprise and Gitea
+        let r = parse_forge_ref("https://git.example.com/o/r/issues/5").unwrap();
+        assert_eq!(r.kind, None);
+        assert_eq!(r.host.as_deref(), Some("git.example.com"));
+
+        let r = parse_forge_ref("https://acme.atlassian.net/browse/OPS-9").unwrap();
+        assert_eq!(r.kind, Some(ForgeKind::Jira));
+        assert_eq!(r.to_string(), "OPS-9");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for s in ["", "repo#1", "o/r#x", "o//r#1", "proj-1", "https://example.com/docs", "42"] {
+            assert!(parse_forge_ref(s).is_err(), "parsed {:?}", s);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use serde_json::Value;
+
+/// A JSON API reached with one Authorization header, shared by the GitLab,
+/// Gitea and Jira providers
+pub struct RestClient {
+    // Forge name for messages, e.g. "GitLab"
+    name: &'static str,
+    base_url: String,
+    authorization: String,
+    // Where the token came from, to say what to check when it is rejected
+    token_hint: String,
+    agent: ureq::Agent,
+}
+
+impl RestClient {
+    pub fn new(name: &'static str, base_url: &str, authorization: String, token_hint: String) -> Self {
+        RestClient {
+            name,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            authorization,
+            token_hint,
+            agent: ureq::AgentBuilder::new().timeout(Duration::from_secs(30)).build(),
+        }
+    }
+
+    /// Sends one request and parses the JSON reply; an empty reply is `null`
+    pub fn request(&self, method: &str, path: &str, body: Option<&Value>) -> Result<Value, String> {
+        let request = self
+            .agent
+            .request(method, &format!("{}{}", self.base_url, path))
+            .set("Authorization", &self.authorization)
+            .set("Accept", "application/json")
+            .set("User-Agent", "ctm");
+        let result = match body {
+            Some(body) => request
+                .set("Content-Type", "application/json")
+                .send_string(&body.to_string()),
+            None => request.call(),
+        };
+
+        match result {
+            Ok(response) => {
+                let text = response
+                    .into_string()
+                    .map_err(|e| format!("Failed to read {} response: {}", self.name, e))?;
+                if text.trim().is_empty() {
+                    return Ok(Value::Null);
+                }
+                serde_json::from_str(&text).map_err(|e| format!("Failed to parse {} response: {}", self.name, e))
+            }
+            Err(ureq::Error::Status(code, response)) => {
+                let message = response
+                    .into_string()
+                    .ok()
+                    .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+                    .map(|json| error_message(&json))
+                    .unwrap_or_default();
+                Err(self.describe_status(code, &message, path))
+            }
+            Err(ureq::Error::Transport(e)) => Err(format!("Failed to reach {}: {}", self.base_url, e)),
+        }
+    }
+
+    pub fn get(&self, path: &str) -> Result<Value, String> {
+        self.request("GET", path, None)
+    }
+
+    /// Turns an API failure into what to do about it
+    fn describe_status(&self, code: u16, message: &str, path: &str) -> String {
+        match code {
+            401 => format!("{} rejected the token (401). Check {}.", self.name, self.token_hint),
+            403 => format!(
+                "{} refused the request (403): {}. The token may lack access to the project.",
+                self.name, message
+            ),
+            404 => format!(
+                "Not found on {}: {}. Check the reference, and that the token can see the project.",
+                self.name, path
+            ),
+            _ => format!("{} API error ({}): {}", self.name, code, message),
+        }
+    }
+}
+
+// GitLab and Gitea reply with "message" (GitLab sometimes "error"),
+// Jira with a list of "errorMessages"
+fn error_message(json: &Value) -> String {
+    json["message"]
+        .as_str()
+        .or_else(|| json["error"].as_str())
+        .or_else(|| json["errorMessages"][0].as_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Percent-encodes a path segment, e.g. a GitLab "group/project" id
+pub fn encode_segment(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
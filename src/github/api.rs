@@ -1,7 +1,13 @@
-use std::process::Command;
+use std::{
+    fmt,
+    process::Command,
+};
 
+use chrono::DateTime;
 use serde_json::Value;
 
+use crate::github::client::ForgeClient;
+
 /// Parsed GitHub issue reference (owner/repo#number)
 #[derive(Debug, Clone)]
 pub struct IssueRef {
@@ -10,20 +16,159 @@ pub struct IssueRef {
     pub number: u32,
 }
 
-impl IssueRef {
-    /// Format as "owner/repo#number"
-    pub fn to_string(&self) -> String {
-        format!("{}/{}#{}", self.owner, self.repo, self.number)
+/// Formats as "owner/repo#number"
+impl fmt::Display for IssueRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}#{}", self.owner, self.repo, self.number)
     }
 }
 
-/// GitHub issue data from gh CLI
+/// GitHub issue or pull request data from gh CLI
 #[derive(Debug, Clone)]
 pub struct GitHubIssue {
+    pub number: u32,
     pub title: String,
+    #[allow(dead_code)]
     pub body: Option<String>,
     pub state: String,
     pub url: String,
+    /// Logins of the assigned users
+    pub assignees: Vec<String>,
+    pub labels: Vec<String>,
+    /// Last remote update as a unix timestamp
+    pub updated_at: Option<i64>,
+}
+
+impl GitHubIssue {
+    /// Parse the JSON printed by `gh issue view` / `gh pr view`
+    pub(crate) fn from_json(json: &Value) -> Self {
+        let names = |field: &str, key: &str| -> Vec<String> {
+            json[field]
+                .as_array()
+                .map(|list| {
+                    list.iter()
+                        .filter_map(|a| a[key].as_str().map(|s| s.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        GitHubIssue {
+            number: json["number"].as_u64().unwrap_or(0) as u32,
+            title: json["title"].as_str().unwrap_or("").to_string(),
+            body: json["body"].as_str().map(|s| s.to_string()),
+            state: json["state"].as_str().unwrap_or("").to_string(),
+            url: json["url"].as_str().unwrap_or("").to_string(),
+            assignees: names("assignees", "login"),
+            labels: names("labels", "name"),
+            updated_at: json["updatedAt"]
+                .as_str()
+                .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                .map(|dt| dt.timestamp()),
+        }
+    }
+
+    /// Open issues and pull requests report "OPEN"; closed and merged ones do not
+    pub fn is_open(&self) -> bool {
+        self.state.eq_ignore_ascii_case("open")
+    }
+}
+
+/// Combined outcome of the CI checks on a pull request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksState {
+    Passing,
+    Failing,
+    Pending,
+    // No checks reported
+    None,
+}
+
+impl ChecksState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChecksState::Passing => "passing",
+            ChecksState::Failing => "failing",
+            ChecksState::Pending => "pending",
+            ChecksState::None => "none",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "passing" => ChecksState::Passing,
+            "failing" => ChecksState::Failing,
+            "pending" => ChecksState::Pending,
+            _ => ChecksState::None,
+        }
+    }
+
+    /// Rolls up `statusCheckRollup`, which mixes check runs (status and
+    /// conclusion) with commit statuses (state). Any failure fails the
+    /// rollup; otherwise anything unfinished leaves it pending.
+    pub(crate) fn rollup(checks: &[Value]) -> Self {
+        if checks.is_empty() {
+            return ChecksState::None;
+        }
+        let mut pending = false;
+        for check in checks {
+            let outcome = check["conclusion"]
+                .as_str()
+                .filter(|s| !s.is_empty())
+                .or_else(|| check["state"].as_str())
+                .unwrap_or("");
+            match outcome.to_uppercase().as_str() {
+                "FAILURE" | "ERROR" | "CANCELLED" | "TIMED_OUT" | "ACTION_REQUIRED" | "STARTUP_FAILURE" => {
+                    return ChecksState::Failing
+                }
+                "SUCCESS" | "NEUTRAL" | "SKIPPED" => {}
+                _ => pending = true,
+            }
+        }
+        if pending {
+            ChecksState::Pending
+        } else {
+            ChecksState::Passing
+        }
+    }
+}
+
+/// Review and merge status of a pull request from gh CLI
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrStatus {
+    pub title: String,
+    pub url: String,
+    // OPEN, CLOSED or MERGED
+    pub state: String,
+    // APPROVED, CHANGES_REQUESTED or REVIEW_REQUIRED; None when no review is required
+    pub review_decision: Option<String>,
+    pub checks: ChecksState,
+    // MERGEABLE, CONFLICTING or UNKNOWN
+    pub mergeable: String,
+}
+
+impl PrStatus {
+    fn from_json(json: &Value) -> Self {
+        let text = |field: &str| json[field].as_str().unwrap_or("").to_string();
+        PrStatus {
+            title: text("title"),
+            url: text("url"),
+            state: text("state"),
+            review_decision: json["reviewDecision"]
+                .as_str()
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string()),
+            checks: ChecksState::rollup(json["statusCheckRollup"].as_array().map_or(&[], |a| a.as_slice())),
+            mergeable: text("mergeable"),
+        }
+    }
+
+    pub fn is_merged(&self) -> bool {
+        self.state.eq_ignore_ascii_case("merged")
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.state.eq_ignore_ascii_case("open")
+    }
 }
 
 /// Parse "owner/repo#42" format into components
@@ -71,56 +216,134 @@ pub fn parse_issue_ref(s: &str) -> Result<IssueRef, String> {
     })
 }
 
-/// Check if gh CLI is available and authenticated
-pub fn is_gh_available() -> bool {
-    Command::new("gh")
-        .args(["auth", "status"])
-        .output()
-        .map(|o| o.status.success())
-        .unwrap_or(false)
+const ISSUE_FIELDS: &str = "number,title,body,state,url,assignees,labels,updatedAt";
+
+/// Narrows `gh issue list` to open issues matching every given criterion
+#[derive(Debug, Clone, Default)]
+pub struct IssueFilter {
+    pub labels: Vec<String>,
+    // A login, or "@me"
+    pub assignee: Option<String>,
+    pub milestone: Option<String>,
+    pub limit: usize,
 }
 
-/// Fetch issue details via gh CLI
-pub fn get_issue(issue_ref: &IssueRef) -> Result<GitHubIssue, String> {
-    let issue_arg = issue_ref.to_string();
+/// Runs the GitHub CLI. The program defaults to `gh` on PATH and can be
+/// overridden with `CTM_GH`, which is how tests substitute a stub script.
+#[derive(Debug, Clone)]
+pub struct GhCli {
+    program: String,
+}
 
-    let output = Command::new("gh")
-        .args(["issue", "view", &issue_arg, "--json", "title,body,state,url"])
-        .output()
-        .map_err(|e| format!("Failed to run gh: {}", e))?;
+impl Default for GhCli {
+    fn default() -> Self {
+        GhCli::new(std::env::var("CTM_GH").unwrap_or_else(|_| "gh".to_string()))
+    }
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("GitHub CLI error: {}", stderr.trim()));
+impl GhCli {
+    pub fn new(program: impl Into<String>) -> Self {
+        GhCli {
+            program: program.into(),
+        }
     }
 
-    // Parse JSON response
-    let json: Value = serde_json::from_slice(&output.stdout)
-        .map_err(|e| format!("Failed to parse gh output: {}", e))?;
+    /// Run gh with the given arguments and return its stdout
+    fn run(&self, args: &[&str]) -> Result<Vec<u8>, String> {
+        let output = Command::new(&self.program)
+            .args(args)
+            .output()
+            .map_err(|e| format!("Failed to run gh: {}", e))?;
 
-    Ok(GitHubIssue {
-        title: json["title"].as_str().unwrap_or("").to_string(),
-        body: json["body"].as_str().map(|s| s.to_string()),
-        state: json["state"].as_str().unwrap_or("").to_string(),
-        url: json["url"].as_str().unwrap_or("").to_string(),
-    })
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("GitHub CLI error: {}", stderr.trim()));
+        }
+
+        Ok(output.stdout)
+    }
+
+    /// Run a `gh <kind> <verb>` command against the reference's repository
+    fn run_on(&self, kind: &str, verb: &str, issue_ref: &IssueRef, extra: &[&str]) -> Result<Vec<u8>, String> {
+        let number = issue_ref.number.to_string();
+        let repo = format!("{}/{}", issue_ref.owner, issue_ref.repo);
+        let mut args = vec![kind, verb, number.as_str(), "-R", repo.as_str()];
+        args.extend_from_slice(extra);
+        self.run(&args)
+    }
+
+    fn view(&self, kind: &str, issue_ref: &IssueRef) -> Result<GitHubIssue, String> {
+        let stdout = self.run_on(kind, "view", issue_ref, &["--json", ISSUE_FIELDS])?;
+        let json: Value = serde_json::from_slice(&stdout)
+            .map_err(|e| format!("Failed to parse gh output: {}", e))?;
+        Ok(GitHubIssue::from_json(&json))
+    }
+
+    /// Whether the gh executable can be run at all
+    pub fn is_installed(&self) -> bool {
+        Command::new(&self.program).arg("--version").output().is_ok()
+    }
 }
 
-/// Close a GitHub issue via gh CLI
-pub fn close_issue(issue_ref: &IssueRef) -> Result<(), String> {
-    let issue_arg = issue_ref.to_string();
+impl ForgeClient for GhCli {
+    fn check_auth(&self) -> Result<(), String> {
+        if !self.is_installed() {
+            return Err(format!(
+                "GitHub CLI ({}) is not installed. Install it, or set GH_TOKEN or GITHUB_TOKEN to use the GitHub API directly.",
+                self.program
+            ));
+        }
+        self.run(&["auth", "status"]).map(|_| ()).map_err(|_| {
+            "GitHub CLI (gh) is not authenticated. Run 'gh auth login' first.".to_string()
+        })
+    }
+
+    fn get_issue(&self, issue_ref: &IssueRef) -> Result<GitHubIssue, String> {
+        self.view("issue", issue_ref)
+    }
+
+    fn get_pull_request(&self, pr_ref: &IssueRef) -> Result<GitHubIssue, String> {
+        self.view("pr", pr_ref)
+    }
+
+    fn list_issues(&self, repo: &str, filter: &IssueFilter) -> Result<Vec<GitHubIssue>, String> {
+        let limit = filter.limit.to_string();
+        let mut args = vec!["issue", "list", "-R", repo, "--state", "open", "--limit", &limit];
+        for label in &filter.labels {
+            args.extend(["--label", label.as_str()]);
+        }
+        if let Some(assignee) = &filter.assignee {
+            args.extend(["--assignee", assignee.as_str()]);
+        }
+        if let Some(milestone) = &filter.milestone {
+            args.extend(["--milestone", milestone.as_str()]);
+        }
+        args.extend(["--json", ISSUE_FIELDS]);
+
+        let stdout = self.run(&args)?;
+        let json: Value = serde_json::from_slice(&stdout)
+            .map_err(|e| format!("Failed to parse gh output: {}", e))?;
+        let issues = json
+            .as_array()
+            .ok_or_else(|| "Failed to parse gh output: expected a list of issues".to_string())?;
+        Ok(issues.iter().map(GitHubIssue::from_json).collect())
+    }
 
-    let output = Command::new("gh")
-        .args(["issue", "close", &issue_arg])
-        .output()
-        .map_err(|e| format!("Failed to run gh: {}", e))?;
+    fn get_pr_status(&self, pr_ref: &IssueRef) -> Result<PrStatus, String> {
+        let fields = "title,url,state,reviewDecision,statusCheckRollup,mergeable";
+        let stdout = self.run_on("pr", "view", pr_ref, &["--json", fields])?;
+        let json: Value = serde_json::from_slice(&stdout)
+            .map_err(|e| format!("Failed to parse gh output: {}", e))?;
+        Ok(PrStatus::from_json(&json))
+    }
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("GitHub CLI error: {}", stderr.trim()));
+    fn close_issue(&self, issue_ref: &IssueRef) -> Result<(), String> {
+        self.run_on("issue", "close", issue_ref, &[]).map(|_| ())
     }
 
-    Ok(())
+    fn reopen_issue(&self, issue_ref: &IssueRef) -> Result<(), String> {
+        self.run_on("issue", "reopen", issue_ref, &[]).map(|_| ())
+    }
 }
 
 #[cfg(test)]
@@ -183,6 +406,36 @@ mod tests {
         assert!(parse_issue_ref("owner/repo#abc").is_err());
     }
 
+    #[test]
+    fn test_pr_status_from_json() {
+        let json: Value = serde_json::from_str(
+            r#"{"title": "Add SSO", "url": "u", "state": "OPEN", "reviewDecision": "",
+                "mergeable": "CONFLICTING", "statusCheckRollup": []}"#,
+        )
+        .unwrap();
+        let status = PrStatus::from_json(&json);
+        assert_eq!(status.review_decision, None);
+        assert_eq!(status.checks, ChecksState::None);
+        assert_eq!(status.mergeable, "CONFLICTING");
+        assert!(status.is_open());
+    }
+
+    #[test]
+    fn test_checks_rollup() {
+        let rollup = |s: &str| ChecksState::rollup(serde_json::from_str::<Value>(s).unwrap().as_array().unwrap());
+        let run = |status: &str, conclusion: &str| format!(r#"{{"status": "{}", "conclusion": "{}"}}"#, status, conclusion);
+        let passed = run("COMPLETED", "SUCCESS");
+        let skipped = run("COMPLETED", "SKIPPED");
+        let running = run("IN_PROGRESS", "");
+        let failed = run("COMPLETED", "FAILURE");
+
+        assert_eq!(rollup(&format!("[{}, {}]", passed, skipped)), ChecksState::Passing);
+        assert_eq!(rollup(&format!("[{}, {}]", passed, running)), ChecksState::Pending);
+        assert_eq!(rollup(&format!("[{}, {}]", running, failed)), ChecksState::Failing);
+        assert_eq!(rollup(r#"[{"state": "PENDING"}]"#), ChecksState::Pending);
+        assert_eq!(rollup(r#"[{"state": "ERROR"}]"#), ChecksState::Failing);
+    }
+
     #[test]
     fn test_issue_ref_to_string() {
         let r = IssueRef {
@@ -0,0 +1,85 @@
+use crate::{
+    config::GitHubConfig,
+    github::{
+        api::{
+            GhCli,
+            GitHubIssue,
+            IssueFilter,
+            IssueRef,
+            PrStatus,
+        },
+        http::GitHubHttp,
+    },
+};
+
+/// Reads and updates issues and pull requests on a forge
+pub trait ForgeClient {
+    /// Fails with what to do about it when the client cannot authenticate
+    fn check_auth(&self) -> Result<(), String>;
+    fn get_issue(&self, issue_ref: &IssueRef) -> Result<GitHubIssue, String>;
+    fn get_pull_request(&self, pr_ref: &IssueRef) -> Result<GitHubIssue, String>;
+    /// Open issues of a repository ("owner/repo"), pull requests excluded
+    fn list_issues(&self, repo: &str, filter: &IssueFilter) -> Result<Vec<GitHubIssue>, String>;
+    fn get_pr_status(&self, pr_ref: &IssueRef) -> Result<PrStatus, String>;
+    fn close_issue(&self, issue_ref: &IssueRef) -> Result<(), String>;
+    fn reopen_issue(&self, issue_ref: &IssueRef) -> Result<(), String>;
+}
+
+/// Picks how to talk to GitHub. `github.client` in the config forces "gh"
+/// or "http"; otherwise a token means the API, and the gh CLI is the fallback.
+pub fn forge_client(config: &GitHubConfig) -> Result<Box<dyn ForgeClient>, String> {
+    let token = github_token(config);
+    match config.client.as_deref() {
+        Some("gh") => Ok(Box::new(GhCli::default())),
+        Some("http") => {
+            let token = token.ok_or_else(|| {
+                "github.client is \"http\" but no token is set. Set GH_TOKEN or GITHUB_TOKEN, or github.token in the config."
+                    .to_string()
+            })?;
+            Ok(Box::new(GitHubHttp::new(&github_api_url(config), &token)))
+        }
+        Some(other) => Err(format!("Invalid github.client '{}'. Use gh or http", other)),
+        None => {
+            if let Some(token) = token {
+                return Ok(Box::new(GitHubHttp::new(&github_api_url(config), &token)));
+            }
+            let gh = GhCli::default();
+            if gh.is_installed() {
+                return Ok(Box::new(gh));
+            }
+            Err("No way to reach GitHub: set GH_TOKEN or GITHUB_TOKEN (or github.token in the config) to use the API, or install the GitHub CLI and run 'gh auth login'."
+                .to_string())
+        }
+    }
+}
+
+/// The API token: GH_TOKEN, then GITHUB_TOKEN, then the config
+fn github_token(config: &GitHubConfig) -> Option<String> {
+    ["GH_TOKEN", "GITHUB_TOKEN"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok())
+        .or_else(|| config.token.clone())
+        .filter(|token| !token.is_empty())
+}
+
+/// The API base URL: CTM_GITHUB_API_URL, then the config, then github.com
+fn github_api_url(config: &GitHubConfig) -> String {
+    std::env::var("CTM_GITHUB_API_URL")
+        .ok()
+        .or_else(|| config.api_url.clone())
+        .unwrap_or_else(|| "https://api.github.com".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forge_client_rejects_unknown_client() {
+        let config = GitHubConfig {
+            client: Some("svn".to_string()),
+            ..Default::default()
+        };
+        assert!(forge_client(&config).is_err());
+    }
+}
@@ -0,0 +1,424 @@
+use std::{
+    collections::HashMap,
+    time::Duration,
+};
+
+use chrono::DateTime;
+use serde_json::{json, Value};
+
+use crate::github::{
+    api::{
+        ChecksState,
+        GitHubIssue,
+        IssueFilter,
+        IssueRef,
+        PrStatus,
+    },
+    client::ForgeClient,
+};
+
+// Largest page the REST API returns
+const PER_PAGE: usize = 100;
+
+/// Talks to the GitHub REST API with a token. The base URL can point at
+/// GitHub Enterprise (https://host/api/v3) or a local mock server.
+pub struct GitHubHttp {
+    base_url: String,
+    token: String,
+    agent: ureq::Agent,
+}
+
+impl GitHubHttp {
+    pub fn new(base_url: &str, token: &str) -> Self {
+        GitHubHttp {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: token.to_string(),
+            agent: ureq::AgentBuilder::new().timeout(Duration::from_secs(30)).build(),
+        }
+    }
+
+    /// Sends one request and parses the JSON reply; an empty reply is `null`
+    fn request(&self, method: &str, path: &str, query: &[(&str, String)], body: Option<&Value>) -> Result<Value, String> {
+        let mut request = self
+            .agent
+            .request(method, &format!("{}{}", self.base_url, path))
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .set("Accept", "application/vnd.github+json")
+            .set("X-GitHub-Api-Version", "2022-11-28")
+            .set("User-Agent", "ctm");
+        for (key, value) in query {
+            request = request.query(key, value);
+        }
+        let result = match body {
+            Some(body) => request
+                .set("Content-Type", "application/json")
+                .send_string(&body.to_string()),
+            None => request.call(),
+        };
+
+        match result {
+            Ok(response) => {
+                let text = response
+                    .into_string()
+                    .map_err(|e| format!("Failed to read GitHub response: {}", e))?;
+                if text.trim().is_empty() {
+                    return Ok(Value::Null);
+                }
+                serde_json::from_str(&text).map_err(|e| format!("Failed to parse GitHub response: {}", e))
+            }
+            Err(ureq::Error::Status(code, response)) => {
+                let message = response
+                    .into_string()
+                    .ok()
+                    .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+                    .and_then(|json| json["message"].as_str().map(|s| s.to_string()))
+                    .unwrap_or_default();
+                Err(describe_status(code, &message, path))
+            }
+            Err(ureq::Error::Transport(e)) => Err(format!("Failed to reach {}: {}", self.base_url, e)),
+        }
+    }
+
+    fn get(&self, path: &str, query: &[(&str, String)]) -> Result<Value, String> {
+        self.request("GET", path, query, None)
+    }
+
+    fn set_issue_state(&self, issue_ref: &IssueRef, state: &str) -> Result<(), String> {
+        let path = format!("{}/issues/{}", repo_path(issue_ref), issue_ref.number);
+        self.request("PATCH", &path, &[], Some(&json!({ "state": state })))
+            .map(|_| ())
+    }
+
+    fn current_login(&self) -> Result<String, String> {
+        let user = self.get("/user", &[])?;
+        user["login"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| "GitHub did not report the token's user".to_string())
+    }
+
+    /// The number of the milestone with this title, as the issues endpoint wants
+    fn milestone_number(&self, repo: &str, title: &str) -> Result<u64, String> {
+        let milestones = self.get(
+            &format!("/repos/{}/milestones", repo),
+            &[("state", "all".to_string()), ("per_page", PER_PAGE.to_string())],
+        )?;
+        milestones
+            .as_array()
+            .and_then(|list| list.iter().find(|m| m["title"].as_str() == Some(title)))
+            .and_then(|m| m["number"].as_u64())
+            .ok_or_else(|| format!("Milestone '{}' not found in {}", title, repo))
+    }
+}
+
+impl ForgeClient for GitHubHttp {
+    fn check_auth(&self) -> Result<(), String> {
+        self.current_login().map(|_| ())
+    }
+
+    fn get_issue(&self, issue_ref: &IssueRef) -> Result<GitHubIssue, String> {
+        let path = format!("{}/issues/{}", repo_path(issue_ref), issue_ref.number);
+        Ok(issue_from_rest(&self.get(&path, &[])?))
+    }
+
+    fn get_pull_request(&self, pr_ref: &IssueRef) -> Result<GitHubIssue, String> {
+        let path = format!("{}/pulls/{}", repo_path(pr_ref), pr_ref.number);
+        Ok(issue_from_rest(&self.get(&path, &[])?))
+    }
+
+    fn list_issues(&self, repo: &str, filter: &IssueFilter) -> Result<Vec<GitHubIssue>, String> {
+        let mut query = vec![("state", "open".to_string())];
+        if !filter.labels.is_empty() {
+            query.push(("labels", filter.labels.join(",")));
+        }
+        if let Some(assignee) = &filter.assignee {
+            let login = match assignee.as_str() {
+                "@me" => self.current_login()?,
+                login => login.to_string(),
+            };
+            query.push(("assignee", login));
+        }
+        if let Some(milestone) = &filter.milestone {
+            query.push(("milestone", self.milestone_number(repo, milestone)?.to_string()));
+        }
+        let per_page = filter.limit.clamp(1, PER_PAGE);
+        query.push(("per_page", per_page.to_string()));
+
+        let mut issues = Vec::new();
+        for page in 1.. {
+            let mut page_query = query.clone();
+            page_query.push(("page", page.to_string()));
+            let reply = self.get(&format!("/repos/{}/issues", repo), &page_query)?;
+            let items = reply.as_array().cloned().unwrap_or_default();
+            // The issues endpoint lists pull requests too
+            issues.extend(
+                items
+                    .iter()
+                    .filter(|item| item.get("pull_request").is_none())
+                    .map(issue_from_rest),
+            );
+            if items.len() < per_page || issues.len() >= filter.limit {
+                break;
+            }
+        }
+        issues.truncate(filter.limit);
+        Ok(issues)
+    }
+
+    fn get_pr_status(&self, pr_ref: &IssueRef) -> Result<PrStatus, String> {
+        let repo = repo_path(pr_ref);
+        let pr = self.get(&format!("{}/pulls/{}", repo, pr_ref.number), &[])?;
+        let page = [("per_page", PER_PAGE.to_string())];
+        let reviews = self.get(&format!("{}/pulls/{}/reviews", repo, pr_ref.number), &page)?;
+
+        // Check runs and commit statuses together make up the rollup
+        let sha = pr["head"]["sha"].as_str().unwrap_or("");
+        let mut checks = self.get(&format!("{}/commits/{}/check-runs", repo, sha), &page)?["check_runs"]
+            .as_array()
+            .cloned()
+            .unwrap_or_default();
+        let statuses = self.get(&format!("{}/commits/{}/status", repo, sha), &[])?;
+        checks.extend(statuses["statuses"].as_array().cloned().unwrap_or_default());
+
+        let issue = issue_from_rest(&pr);
+        Ok(PrStatus {
+            title: issue.title,
+            url: issue.url,
+            state: issue.state,
+            review_decision: review_decision(&pr, reviews.as_array().map_or(&[], |a| a.as_slice())),
+            checks: ChecksState::rollup(&checks),
+            mergeable: match pr["mergeable"].as_bool() {
+                Some(true) => "MERGEABLE",
+                Some(false) => "CONFLICTING",
+                None => "UNKNOWN",
+            }
+            .to_string(),
+        })
+    }
+
+    fn close_issue(&self, issue_ref: &IssueRef) -> Result<(), String> {
+        self.set_issue_state(issue_ref, "closed")
+    }
+
+    fn reopen_issue(&self, issue_ref: &IssueRef) -> Result<(), String> {
+        self.set_issue_state(issue_ref, "open")
+    }
+}
+
+fn repo_path(issue_ref: &IssueRef) -> String {
+    format!("/repos/{}/{}", issue_ref.owner, issue_ref.repo)
+}
+
+/// Turns an API failure into what to do about it
+fn describe_status(code: u16, message: &str, path: &str) -> String {
+    match code {
+        401 => "GitHub rejected the token (401). Check GH_TOKEN, GITHUB_TOKEN or github.token in the config."
+            .to_string(),
+        403 => format!(
+            "GitHub refused the request (403): {}. The token may lack access to the repository, or be rate limited.",
+            message
+        ),
+        404 => format!(
+            "Not found on GitHub: {}. Check the reference, and that the token can see the repository.",
+            path
+        ),
+        _ => format!("GitHub API error ({}): {}", code, message),
+    }
+}
+
+/// Maps an issue or pull request from the REST API onto what gh reports,
+/// e.g. upper-case states and MERGED for merged pull requests
+fn issue_from_rest(json: &Value) -> GitHubIssue {
+    let names = |field: &str, key: &str| -> Vec<String> {
+        json[field]
+            .as_array()
+            .map(|list| {
+                list.iter()
+                    .filter_map(|a| a[key].as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let merged = json["merged"].as_bool().unwrap_or(false) || json["merged_at"].is_string();
+    GitHubIssue {
+        number: json["number"].as_u64().unwrap_or(0) as u32,
+        title: json["title"].as_str().unwrap_or("").to_string(),
+        body: json["body"].as_str().map(|s| s.to_string()),
+        state: if merged {
+            "MERGED".to_string()
+        } else {
+            json["state"].as_str().unwrap_or("").to_uppercase()
+        },
+        url: json["html_url"].as_str().unwrap_or("").to_string(),
+        assignees: names("assignees", "login"),
+        labels: names("labels", "name"),
+        updated_at: json["updated_at"]
+            .as_str()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.timestamp()),
+    }
+}
+
+/// What gh calls the review decision, from each reviewer's latest verdict:
+/// any changes requested wins over approvals, and pending requests for
+/// review leave it at review required
+fn review_decision(pr: &Value, reviews: &[Value]) -> Option<String> {
+    let mut latest: HashMap<&str, &str> = HashMap::new();
+    for review in reviews {
+        let (Some(login), Some(state)) = (review["user"]["login"].as_str(), review["state"].as_str()) else {
+            continue;
+        };
+        if matches!(state, "APPROVED" | "CHANGES_REQUESTED" | "DISMISSED") {
+            latest.insert(login, state);
+        }
+    }
+    let requested = |field: &str| pr[field].as_array().is_some_and(|a| !a.is_empty());
+    if latest.values().any(|s| *s == "CHANGES_REQUESTED") {
+        Some("CHANGES_REQUESTED".to_string())
+    } else if requested("requested_reviewers") || requested("requested_teams") {
+        Some("REVIEW_REQUIRED".to_string())
+    } else if latest.values().any(|s| *s == "APPROVED") {
+        Some("APPROVED".to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        github::parse_issue_ref,
+        tests::MockApi,
+    };
+
+    fn client(api: &MockApi) -> GitHubHttp {
+        GitHubHttp::new(&api.url, "secret")
+    }
+
+    #[test]
+    fn test_get_issue() {
+        let api = MockApi::start(vec![(
+            "GET /repos/o/r/issues/42",
+            200,
+            r#"{"number": 42, "title": "Crash on save", "state": "open", "html_url": "https://github.com/o/r/issues/42",
+                "assignees": [{"login": "octocat"}], "labels": [{"name": "bug"}],
+                "updated_at": "2024-03-18T09:00:00Z"}"#
+                .to_string(),
+        )]);
+        let issue = client(&api).get_issue(&parse_issue_ref("o/r#42").unwrap()).unwrap();
+        assert_eq!(issue.number, 42);
+        assert_eq!(issue.state, "OPEN");
+        assert!(issue.is_open());
+        assert_eq!(issue.assignees, vec!["octocat"]);
+        assert_eq!(issue.labels, vec!["bug"]);
+        assert_eq!(issue.updated_at, Some(1710752400));
+        assert_eq!(api.requests(), vec!["GET /repos/o/r/issues/42 Bearer secret"]);
+    }
+
+    #[test]
+    fn test_get_pr_status() {
+        let api = MockApi::start(vec![
+            (
+                "GET /repos/o/r/pulls/7",
+                200,
+                r#"{"number": 7, "title": "Add SSO", "state": "open", "merged": false, "mergeable": false,
+                    "html_url": "u", "head": {"sha": "abc"}, "requested_reviewers": []}"#
+                    .to_string(),
+            ),
+            (
+                "GET /repos/o/r/pulls/7/reviews?per_page=100",
+                200,
+                r#"[{"user": {"login": "alice"}, "state": "CHANGES_REQUESTED"},
+                    {"user": {"login": "bob"}, "state": "APPROVED"},
+                    {"user": {"login": "alice"}, "state": "COMMENTED"},
+                    {"user": {"login": "alice"}, "state": "APPROVED"}]"#
+                    .to_string(),
+            ),
+            (
+                "GET /repos/o/r/commits/abc/check-runs?per_page=100",
+                200,
+                r#"{"check_runs": [{"status": "completed", "conclusion": "success"}]}"#.to_string(),
+            ),
+            (
+                "GET /repos/o/r/commits/abc/status",
+                200,
+                r#"{"statuses": [{"state": "pending"}]}"#.to_string(),
+            ),
+        ]);
+        let status = client(&api).get_pr_status(&parse_issue_ref("o/r#7").unwrap()).unwrap();
+        assert_eq!(status.state, "OPEN");
+        assert_eq!(status.review_decision.as_deref(), Some("APPROVED"));
+        assert_eq!(status.checks, ChecksState::Pending);
+        assert_eq!(status.mergeable, "CONFLICTING");
+    }
+
+    #[test]
+    fn test_review_decision() {
+        let pr = json!({ "requested_reviewers": [] });
+        let review = |login: &str, state: &str| json!({ "user": { "login": login }, "state": state });
+        assert_eq!(review_decision(&pr, &[]), None);
+        assert_eq!(
+            review_decision(&pr, &[review("a", "APPROVED"), review("b", "CHANGES_REQUESTED")]).as_deref(),
+            Some("CHANGES_REQUESTED")
+        );
+        let waiting = json!({ "requested_reviewers": [{ "login": "c" }] });
+        assert_eq!(review_decision(&waiting, &[review("a", "APPROVED")]).as_deref(), Some("REVIEW_REQUIRED"));
+        assert!(issue_from_rest(&json!({ "state": "closed", "merged_at": "2024-01-01T00:00:00Z" })).state == "MERGED");
+    }
+
+    #[test]
+    fn test_list_issues_resolves_filters_and_pages() {
+        let page = |numbers: std::ops::RangeInclusive<u32>| {
+            let items: Vec<String> = numbers
+                .map(|n| format!(r#"{{"number": {}, "title": "Issue {}", "state": "open"}}"#, n, n))
+                .collect();
+            format!("[{}]", items.join(", "))
+        };
+        let api = MockApi::start(vec![
+            ("GET /user", 200, r#"{"login": "octocat"}"#.to_string()),
+            (
+                "GET /repos/o/r/milestones?state=all&per_page=100",
+                200,
+                r#"[{"number": 3, "title": "v1"}, {"number": 4, "title": "v2 beta"}]"#.to_string(),
+            ),
+            (
+                "GET /repos/o/r/issues?state=open&labels=bug%2Ctriage&assignee=octocat&milestone=4&per_page=2&page=1",
+                200,
+                r#"[{"number": 1, "title": "One", "state": "open"},
+                    {"number": 2, "title": "A pull request", "state": "open", "pull_request": {}}]"#
+                    .to_string(),
+            ),
+            (
+                "GET /repos/o/r/issues?state=open&labels=bug%2Ctriage&assignee=octocat&milestone=4&per_page=2&page=2",
+                200,
+                page(3..=4),
+            ),
+        ]);
+        let filter = IssueFilter {
+            labels: vec!["bug".to_string(), "triage".to_string()],
+            assignee: Some("@me".to_string()),
+            milestone: Some("v2 beta".to_string()),
+            limit: 2,
+        };
+        let issues = client(&api).list_issues("o/r", &filter).unwrap();
+        let numbers: Vec<u32> = issues.iter().map(|i| i.number).collect();
+        assert_eq!(numbers, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_close_issue_and_auth_errors() {
+        let api = MockApi::start(vec![
+            ("PATCH /repos/o/r/issues/42", 200, "{}".to_string()),
+            ("GET /user", 401, r#"{"message": "Bad credentials"}"#.to_string()),
+        ]);
+        let client = client(&api);
+        client.close_issue(&parse_issue_ref("o/r#42").unwrap()).unwrap();
+        assert_eq!(api.bodies(), vec![r#"{"state":"closed"}"#]);
+
+        let err = client.check_auth().unwrap_err();
+        assert!(err.contains("rejected the token"));
+        let err = client.get_issue(&parse_issue_ref("o/r#9").unwrap()).unwrap_err();
+        assert!(err.contains("Not found on GitHub: /repos/o/r/issues/9"));
+    }
+}
@@ -1,3 +1,6 @@
 pub mod api;
+pub mod client;
+pub mod http;
 
 pub use api::*;
+pub use client::*;
@@ -3,18 +3,39 @@ mod args;
 mod config;
 mod context;
 mod db;
+mod forge;
 mod github;
+mod mcp;
 mod utils;
 
 use std::process::exit;
 
-use actions::display::print_red;
-use args::parser::CliArgs;
+use actions::display::{
+    init_color,
+    print_red,
+};
+use args::parser::{
+    Action,
+    CliArgs,
+};
 use clap::Parser;
 use context::Context;
 
 fn main() {
     let cli_args = CliArgs::parse();
+    init_color(cli_args.color);
+
+    // Schema commands run before auto-migration so pending
+    // steps can be inspected rather than silently applied.
+    if let Action::Db(cmd) = cli_args.arguments {
+        let result = db::conn::open().and_then(|conn| actions::schema::handle_db_cmd(&conn, cmd));
+        if let Err(err) = result {
+            print_red(&format!("Error: {}", err));
+            exit(1)
+        }
+        return;
+    }
+
     let conn = match db::conn::connect() {
         Ok(conn) => conn,
         Err(err) => {
@@ -37,8 +58,8 @@ fn main() {
     };
 
     let result = actions::handler::handle_commands(&conn, &ctx, cli_args);
-    if result.is_err() {
-        print_red(&format!("Error: {}", result.unwrap_err()));
+    if let Err(err) = result {
+        print_red(&format!("Error: {}", err));
         exit(1)
     }
 }
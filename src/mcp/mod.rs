@@ -0,0 +1,5 @@
+pub mod resources;
+pub mod server;
+pub mod tools;
+
+pub use server::*;
@@ -0,0 +1,85 @@
+use rusqlite::Connection;
+use serde_json::{json, Value};
+
+use crate::{
+    actions::agenda::{build_agenda, today_json},
+    context::Context,
+    mcp::tools::{lookup_task, parse_task_id, task_detail},
+};
+
+const TODAY_URI: &str = "ctm://today";
+const TASK_URI_PREFIX: &str = "ctm://task/";
+
+/// The fixed resources, for resources/list
+pub fn definitions() -> Value {
+    json!([{
+        "uri": TODAY_URI,
+        "name": "today",
+        "description": "Today's agenda: overdue, due today, recurring and upcoming tasks of the namespace",
+        "mimeType": "application/json",
+    }])
+}
+
+/// Resources addressed by ID, for resources/templates/list
+pub fn templates() -> Value {
+    json!([{
+        "uriTemplate": format!("{}{{id}}", TASK_URI_PREFIX),
+        "name": "task",
+        "description": "A task with its notes and links, by ID like 42 or ctm-42",
+        "mimeType": "application/json",
+    }])
+}
+
+/// The contents of a resource, or None when there is no such resource
+pub fn read(conn: &Connection, ctx: &Context, uri: &str) -> Result<Option<Value>, String> {
+    if uri == TODAY_URI {
+        let agenda = build_agenda(conn, ctx.current_namespace_id, None)?;
+        return Ok(Some(today_json(&agenda)));
+    }
+    let Some(id) = uri.strip_prefix(TASK_URI_PREFIX).and_then(parse_task_id) else {
+        return Ok(None);
+    };
+    match lookup_task(conn, ctx, id) {
+        Ok(item) => task_detail(conn, &item).map(Some),
+        Err(_) => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{
+            crud::{get_item, update_item},
+            namespace::create_namespace,
+        },
+        tests::{get_test_conn, insert_task},
+    };
+
+    #[test]
+    fn test_read_resources() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let overdue = insert_task(&conn, "work", "File the expense report", "yesterday");
+        let mut task = get_item(&conn, overdue).unwrap();
+        task.namespace_id = Some(ctx.current_namespace_id);
+        update_item(&conn, &task).unwrap();
+        let mut other = get_item(&conn, insert_task(&conn, "work", "Someone else's", "yesterday")).unwrap();
+        other.namespace_id = Some(create_namespace(&conn, "work", None, ctx.current_user_id).unwrap());
+        update_item(&conn, &other).unwrap();
+
+        let today = read(&conn, &ctx, TODAY_URI).unwrap().unwrap();
+        let overdue_tasks = today["overdue"].as_array().unwrap();
+        assert_eq!(overdue_tasks.len(), 1);
+        assert_eq!(overdue_tasks[0]["id"], overdue);
+
+        let task = read(&conn, &ctx, &format!("ctm://task/ctm-{}", overdue)).unwrap().unwrap();
+        assert_eq!(task["task"]["content"], "File the expense report");
+        assert!(task["task"]["notes"].as_array().unwrap().is_empty());
+
+        let hidden = format!("ctm://task/{}", other.id.unwrap());
+        assert!(read(&conn, &ctx, &hidden).unwrap().is_none());
+        assert!(read(&conn, &ctx, "ctm://task/abc").unwrap().is_none());
+        assert!(read(&conn, &ctx, "ctm://calendar").unwrap().is_none());
+    }
+}
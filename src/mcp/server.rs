@@ -0,0 +1,265 @@
+use std::io::{
+    self,
+    BufRead,
+    Write,
+};
+
+use rusqlite::Connection;
+use serde_json::{json, Value};
+
+use crate::{
+    actions::snooze::wake_snoozed_tasks,
+    context::Context,
+    mcp::{
+        resources,
+        tools,
+    },
+};
+
+// Protocol revisions we speak, newest first
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+const RESOURCE_NOT_FOUND: i64 = -32002;
+
+#[derive(Debug)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// Handles `ctm mcp`: serves stdin and stdout until stdin closes
+pub fn handle_mcpcmd(conn: &Connection, ctx: &Context) -> Result<(), String> {
+    serve(conn, ctx, io::stdin().lock(), io::stdout().lock())
+}
+
+/// Answers JSON-RPC messages read from `input`, one per line, with one line
+/// each on `output`. Notifications get no answer.
+pub fn serve(conn: &Connection, ctx: &Context, input: impl BufRead, mut output: impl Write) -> Result<(), String> {
+    for line in input.lines() {
+        let line = line.map_err(|e| format!("Failed to read request: {}", e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_message(conn, ctx, &line) {
+            writeln!(output, "{}", response).map_err(|e| format!("Failed to write response: {}", e))?;
+            output.flush().map_err(|e| format!("Failed to write response: {}", e))?;
+        }
+    }
+    Ok(())
+}
+
+/// The response to one JSON-RPC message, or None for a notification
+pub fn handle_message(conn: &Connection, ctx: &Context, message: &str) -> Option<Value> {
+    let request: Value = match serde_json::from_str(message) {
+        Ok(request) => request,
+        Err(e) => return Some(error_response(Value::Null, RpcError::new(PARSE_ERROR, e.to_string()))),
+    };
+    let id = request.get("id").cloned();
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return Some(error_response(
+            id.unwrap_or(Value::Null),
+            RpcError::new(INVALID_REQUEST, "Request has no method"),
+        ));
+    };
+    let params = request.get("params").cloned().unwrap_or_else(|| json!({}));
+
+    let result = dispatch(conn, ctx, method, &params);
+    let id = id?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => error_response(id, err),
+    })
+}
+
+fn dispatch(conn: &Connection, ctx: &Context, method: &str, params: &Value) -> Result<Value, RpcError> {
+    match method {
+        "initialize" => Ok(initialize(ctx, params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools::definitions() })),
+        "tools/call" => {
+            let name = params
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Tool call has no name"))?;
+            let args = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+            wake(conn)?;
+            match tools::call(conn, ctx, name, &args) {
+                Some(Ok(value)) => Ok(json!({
+                    "content": [{ "type": "text", "text": serde_json::to_string_pretty(&value).unwrap() }],
+                    "structuredContent": value,
+                    "isError": false,
+                })),
+                // Failed calls are results, so the agent sees why
+                Some(Err(message)) => Ok(json!({
+                    "content": [{ "type": "text", "text": message }],
+                    "isError": true,
+                })),
+                None => Err(RpcError::new(INVALID_PARAMS, format!("Unknown tool '{}'", name))),
+            }
+        }
+        "resources/list" => Ok(json!({ "resources": resources::definitions() })),
+        "resources/templates/list" => Ok(json!({ "resourceTemplates": resources::templates() })),
+        "resources/read" => {
+            let uri = params
+                .get("uri")
+                .and_then(Value::as_str)
+                .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Resource read has no uri"))?;
+            wake(conn)?;
+            let contents = resources::read(conn, ctx, uri)
+                .map_err(|e| RpcError::new(INTERNAL_ERROR, e))?
+                .ok_or_else(|| RpcError::new(RESOURCE_NOT_FOUND, format!("Resource '{}' not found", uri)))?;
+            Ok(json!({
+                "contents": [{
+                    "uri": uri,
+                    "mimeType": "application/json",
+                    "text": serde_json::to_string_pretty(&contents).unwrap(),
+                }]
+            }))
+        }
+        // Notifications such as notifications/initialized need no action
+        _ if method.starts_with("notifications/") => Ok(Value::Null),
+        _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("Method '{}' not found", method))),
+    }
+}
+
+fn initialize(ctx: &Context, params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|v| PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {}, "resources": {} },
+        "serverInfo": { "name": "ctm", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": format!(
+            "Tasks of namespace '{}', acting as user '{}'. Tasks are named by ID: 42 or ctm-42.",
+            ctx.current_namespace_name, ctx.current_user_name
+        ),
+    })
+}
+
+// A long-running server wakes snoozed tasks before each call, as every command does
+fn wake(conn: &Connection) -> Result<(), RpcError> {
+    wake_snoozed_tasks(conn)
+        .map(|_| ())
+        .map_err(|e| RpcError::new(INTERNAL_ERROR, e))
+}
+
+fn error_response(id: Value, err: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": err.code, "message": err.message },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::get_test_conn;
+
+    // Pipes the input through the server and parses its answers
+    fn run(conn: &Connection, ctx: &Context, input: &str) -> Vec<Value> {
+        let mut output = Vec::new();
+        serve(conn, ctx, input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn lines(requests: &[Value]) -> String {
+        requests.iter().map(|r| format!("{}\n", r)).collect()
+    }
+
+    fn call(id: i64, name: &str, arguments: Value) -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "tools/call",
+            "params": { "name": name, "arguments": arguments },
+        })
+    }
+
+    #[test]
+    fn test_session() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let input = lines(&[
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize",
+                    "params": { "protocolVersion": "2024-11-05", "capabilities": {} } }),
+            json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+            call(3, "add_task", json!({ "content": "Write the release notes", "due": "tomorrow", "priority": "high" })),
+            call(4, "list_tasks", json!({})),
+            json!({ "jsonrpc": "2.0", "id": 5, "method": "resources/read", "params": { "uri": "ctm://today" } }),
+        ]);
+        let responses = run(&conn, &ctx, &input);
+        // The notification is not answered
+        assert_eq!(responses.len(), 5);
+
+        assert_eq!(responses[0]["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(responses[0]["result"]["serverInfo"]["name"], "ctm");
+
+        let names: Vec<&str> = responses[1]["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert!(names.contains(&"add_task") && names.contains(&"search"));
+
+        let added = &responses[2]["result"];
+        assert_eq!(added["isError"], false);
+        assert_eq!(added["structuredContent"]["task"]["content"], "Write the release notes");
+        assert_eq!(added["structuredContent"]["task"]["priority"], "HIGH");
+        let text: Value = serde_json::from_str(added["content"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(text, added["structuredContent"]);
+
+        let tasks = responses[3]["result"]["structuredContent"]["tasks"].as_array().unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0]["ref"], added["structuredContent"]["task"]["ref"]);
+
+        let contents = &responses[4]["result"]["contents"][0];
+        assert_eq!(contents["uri"], "ctm://today");
+        let today: Value = serde_json::from_str(contents["text"].as_str().unwrap()).unwrap();
+        assert!(today["due_today"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_errors() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let input = lines(&[
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "tasks/delete" }),
+            call(2, "drop_table", json!({})),
+            call(3, "show_task", json!({ "id": "ctm-99" })),
+            json!({ "jsonrpc": "2.0", "id": 4, "method": "resources/read", "params": { "uri": "ctm://task/99" } }),
+        ]);
+        let responses = run(&conn, &ctx, &format!("not json\n{}", input));
+
+        assert_eq!(responses[0]["id"], Value::Null);
+        assert_eq!(responses[0]["error"]["code"], PARSE_ERROR);
+        assert_eq!(responses[1]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(responses[2]["error"]["code"], INVALID_PARAMS);
+        assert_eq!(responses[3]["result"]["isError"], true);
+        assert_eq!(responses[3]["result"]["content"][0]["text"], "Task ctm-99 not found");
+        assert_eq!(responses[4]["error"]["code"], RESOURCE_NOT_FOUND);
+    }
+}
@@ -0,0 +1,464 @@
+use chrono::Local;
+use rusqlite::Connection;
+use serde_json::{json, Value};
+
+use crate::{
+    actions::{
+        addition::add_task,
+        agenda::{items_json, records_json},
+        claim::claim_item,
+        git::task_id_from_branch,
+        link::{add_item_link, resolve_link_type},
+        list::{CLOSED_STATUS_CODES, CREATE_TIME_COL, OPEN_STATUS_CODES, TARGET_TIME_COL},
+        modify::complete_item,
+        note::add_item_note,
+    },
+    args::{
+        estimate::parse_estimate,
+        parser::{parse_status, TaskCommand},
+        priority::parse_priority,
+    },
+    context::Context,
+    db::{
+        crud::{get_item, query_items},
+        item::{Item, ItemQuery, RECORD, RECURRING_TASK, RECURRING_TASK_RECORD, TASK},
+        link::get_links_for_item,
+        note::get_notes_for_item,
+        user::{get_user_by_id, get_user_by_name},
+    },
+};
+
+const DEFAULT_LIMIT: usize = 50;
+
+/// The tools with JSON schemas of their arguments, for tools/list
+pub fn definitions() -> Value {
+    let id = json!({ "type": ["string", "integer"], "description": "Task ID: 42 or ctm-42" });
+    json!([
+        tool(
+            "add_task",
+            "Add a task; a schedule like 'daily 9am' as due makes it recurring",
+            json!({
+                "content": string("What needs doing"),
+                "due": string("When it is due, like 'friday 3pm' or '2025-01-15'; default today"),
+                "category": string("Category; default the project's, else 'default'"),
+                "project": string("Project name"),
+                "assignee": string("Username to assign the task to"),
+                "priority": string("high, normal or low"),
+                "estimate": string("Time estimate, like 30m, 2h or 1h30m"),
+            }),
+            &["content"],
+        ),
+        tool(
+            "list_tasks",
+            "List tasks of the namespace in due order, snoozed ones left out",
+            json!({
+                "status": string("open (default), closed, all, or a status like ongoing or done"),
+                "category": string("Only this category"),
+                "project": string("Only this project"),
+                "assignee": string("Only tasks assigned to this username, or 'me'"),
+                "limit": { "type": "integer", "description": "At most this many tasks; default 50" },
+            }),
+            &[],
+        ),
+        tool(
+            "show_task",
+            "A task with its notes and links",
+            json!({ "id": id }),
+            &["id"],
+        ),
+        tool(
+            "complete_task",
+            "Complete a task, or the current interval of a recurring one, and record it",
+            json!({
+                "id": id,
+                "status": string("done (default), cancelled, duplicate or removed"),
+                "comment": string("Comment added to the task and its record"),
+            }),
+            &["id"],
+        ),
+        tool(
+            "add_note",
+            "Add a progress note to a task",
+            json!({ "id": id, "content": string("The note") }),
+            &["id", "content"],
+        ),
+        tool(
+            "add_link",
+            "Attach a commit, issue, pull request or URL to a task",
+            json!({
+                "id": id,
+                "kind": { "type": "string", "enum": ["commit", "issue", "pr", "url"] },
+                "reference": string("Commit SHA, owner/repo#N, group/project!N, PROJ-123 or URL"),
+                "title": string("Optional title"),
+            }),
+            &["id", "kind", "reference"],
+        ),
+        tool(
+            "claim_task",
+            "Assign an unassigned task to yourself",
+            json!({ "id": id }),
+            &["id"],
+        ),
+        tool(
+            "search",
+            "Find tasks and records of the namespace whose content contains the query",
+            json!({
+                "query": string("Text to look for"),
+                "status": string("Task status: all (default), open, closed, or a status like ongoing"),
+                "limit": { "type": "integer", "description": "At most this many tasks and records each; default 50" },
+            }),
+            &["query"],
+        ),
+    ])
+}
+
+fn tool(name: &str, description: &str, properties: Value, required: &[&str]) -> Value {
+    json!({
+        "name": name,
+        "description": description,
+        "inputSchema": { "type": "object", "properties": properties, "required": required },
+    })
+}
+
+fn string(description: &str) -> Value {
+    json!({ "type": "string", "description": description })
+}
+
+/// Runs a tool, or None when there is no tool of that name
+pub fn call(conn: &Connection, ctx: &Context, name: &str, args: &Value) -> Option<Result<Value, String>> {
+    let result = match name {
+        "add_task" => add(conn, ctx, args),
+        "list_tasks" => list(conn, ctx, args),
+        "show_task" => find_task(conn, ctx, args).and_then(|item| task_detail(conn, &item)),
+        "complete_task" => complete(conn, ctx, args),
+        "add_note" => note(conn, ctx, args),
+        "add_link" => link(conn, ctx, args),
+        "claim_task" => claim(conn, ctx, args),
+        "search" => search(conn, ctx, args),
+        _ => return None,
+    };
+    Some(result)
+}
+
+fn add(conn: &Connection, ctx: &Context, args: &Value) -> Result<Value, String> {
+    let cmd = TaskCommand {
+        content: required_str(args, "content")?.to_string(),
+        timestr: optional_str(args, "due")?.map(str::to_string),
+        category: optional_str(args, "category")?.map(str::to_string),
+        reminder: None,
+        project: optional_str(args, "project")?.map(str::to_string),
+        assignee: optional_str(args, "assignee")?.map(str::to_string),
+        priority: optional_str(args, "priority")?.map(parse_priority).transpose()?,
+        estimate: optional_str(args, "estimate")?.map(parse_estimate).transpose()?,
+        from_issue: None,
+    };
+    let task = add_task(conn, ctx, &cmd)?;
+    Ok(json!({ "task": task_json(conn, &task)? }))
+}
+
+fn list(conn: &Connection, ctx: &Context, args: &Value) -> Result<Value, String> {
+    let mut query = ItemQuery::new()
+        .with_actions(vec![TASK, RECURRING_TASK])
+        .with_awake_at(Local::now().timestamp())
+        .with_order_by(TARGET_TIME_COL);
+    query = with_status(query, optional_str(args, "status")?.unwrap_or("open"))?;
+    if let Some(category) = optional_str(args, "category")? {
+        query = query.with_category(category);
+    }
+    if let Some(project) = optional_str(args, "project")? {
+        query = query.with_project(project);
+    }
+    if let Some(assignee) = optional_str(args, "assignee")? {
+        let assignee_id = match assignee {
+            "me" => ctx.current_user_id,
+            name => {
+                get_user_by_name(conn, name)?
+                    .ok_or_else(|| format!("User '{}' not found", name))?
+                    .id
+            }
+        };
+        query = query.with_assignee_id(assignee_id);
+    }
+
+    let tasks = visible(ctx, query_items(conn, &query).map_err(|e| e.to_string())?, limit(args)?);
+    Ok(json!({
+        "namespace": ctx.current_namespace_name,
+        "tasks": tasks_json(conn, &tasks)?,
+    }))
+}
+
+fn complete(conn: &Connection, ctx: &Context, args: &Value) -> Result<Value, String> {
+    let item = find_task(conn, ctx, args)?;
+    let status = parse_status(optional_str(args, "status")?.unwrap_or("done"))?;
+    if !CLOSED_STATUS_CODES.contains(&status) {
+        return Err("'status' must be done, cancelled, duplicate or removed".to_string());
+    }
+    let item = complete_item(conn, ctx, item, status, optional_str(args, "comment")?)?;
+    Ok(json!({ "task": task_json(conn, &item)? }))
+}
+
+fn note(conn: &Connection, ctx: &Context, args: &Value) -> Result<Value, String> {
+    let item = find_task(conn, ctx, args)?;
+    let note_id = add_item_note(conn, ctx, &item, required_str(args, "content")?)?;
+    Ok(json!({ "note_id": note_id, "task": task_json(conn, &item)? }))
+}
+
+fn link(conn: &Connection, ctx: &Context, args: &Value) -> Result<Value, String> {
+    let item = find_task(conn, ctx, args)?;
+    let reference = required_str(args, "reference")?;
+    let link_type = resolve_link_type(required_str(args, "kind")?, reference)?;
+    let link_id = add_item_link(conn, ctx, &item, link_type, reference, optional_str(args, "title")?)?;
+    Ok(json!({ "link_id": link_id, "link_type": link_type, "task": task_json(conn, &item)? }))
+}
+
+fn claim(conn: &Connection, ctx: &Context, args: &Value) -> Result<Value, String> {
+    let item = claim_item(conn, ctx, find_task(conn, ctx, args)?)?;
+    Ok(json!({ "task": task_json(conn, &item)? }))
+}
+
+fn search(conn: &Connection, ctx: &Context, args: &Value) -> Result<Value, String> {
+    let text = required_str(args, "query")?;
+    let limit = limit(args)?;
+
+    let task_query = ItemQuery::new()
+        .with_actions(vec![TASK, RECURRING_TASK])
+        .with_content_like(text)
+        .with_order_by(TARGET_TIME_COL);
+    let task_query = with_status(task_query, optional_str(args, "status")?.unwrap_or("all"))?;
+    let tasks = visible(ctx, query_items(conn, &task_query).map_err(|e| e.to_string())?, limit);
+
+    let record_query = ItemQuery::new()
+        .with_actions(vec![RECORD, RECURRING_TASK_RECORD])
+        .with_content_like(text)
+        .with_order_by(CREATE_TIME_COL);
+    let mut records = query_items(conn, &record_query).map_err(|e| e.to_string())?;
+    // The most recent records first
+    records.reverse();
+    let records = visible(ctx, records, limit);
+
+    Ok(json!({
+        "tasks": tasks_json(conn, &tasks)?,
+        "records": records_json(&records),
+    }))
+}
+
+/// The task an `id` argument names, if the current namespace can see it
+pub fn find_task(conn: &Connection, ctx: &Context, args: &Value) -> Result<Item, String> {
+    let id = match args.get("id") {
+        Some(Value::Number(number)) => number.as_i64(),
+        Some(Value::String(id)) => parse_task_id(id),
+        _ => return Err("'id' is required".to_string()),
+    }
+    .ok_or_else(|| "'id' must be a task ID like 42 or ctm-42".to_string())?;
+    lookup_task(conn, ctx, id)
+}
+
+/// "42" or "ctm-42" -> 42
+pub fn parse_task_id(id: &str) -> Option<i64> {
+    let id = id.trim();
+    id.parse().ok().or_else(|| task_id_from_branch(id))
+}
+
+/// A task of the current namespace by ID
+pub fn lookup_task(conn: &Connection, ctx: &Context, id: i64) -> Result<Item, String> {
+    let not_found = || format!("Task ctm-{} not found", id);
+    let item = get_item(conn, id).map_err(|_| not_found())?;
+    if item.action == RECORD || item.action == RECURRING_TASK_RECORD || !in_namespace(ctx, &item) {
+        return Err(not_found());
+    }
+    Ok(item)
+}
+
+/// Whether the current namespace sees an item; items from before namespaces
+/// have none and are seen from all of them
+fn in_namespace(ctx: &Context, item: &Item) -> bool {
+    item.namespace_id.is_none_or(|id| id == ctx.current_namespace_id)
+}
+
+/// A task with its notes and links
+pub fn task_detail(conn: &Connection, item: &Item) -> Result<Value, String> {
+    let id = item.id.ok_or_else(|| "Cannot show an item without id".to_string())?;
+    let mut task = task_json(conn, item)?;
+
+    let mut notes = Vec::new();
+    for note in get_notes_for_item(conn, id)? {
+        notes.push(json!({
+            "content": note.content,
+            "created_at": note.created_at,
+            "author": user_name(conn, note.created_by)?,
+        }));
+    }
+    task["notes"] = json!(notes);
+
+    let mut links = Vec::new();
+    for link in get_links_for_item(conn, id)? {
+        links.push(json!({
+            "type": link.link_type,
+            "reference": link.reference,
+            "title": link.title,
+            "created_at": link.created_at,
+            "author": user_name(conn, link.created_by)?,
+        }));
+    }
+    task["links"] = json!(links);
+    Ok(json!({ "task": task }))
+}
+
+fn task_json(conn: &Connection, item: &Item) -> Result<Value, String> {
+    let mut task = items_json(std::slice::from_ref(item)).remove(0);
+    task["ref"] = json!(format!("ctm-{}", item.id.unwrap_or_default()));
+    task["owner"] = json!(user_name(conn, item.owner_id)?);
+    task["assignee"] = json!(user_name(conn, item.assignee_id)?);
+    Ok(task)
+}
+
+fn tasks_json(conn: &Connection, items: &[Item]) -> Result<Vec<Value>, String> {
+    items.iter().map(|item| task_json(conn, item)).collect()
+}
+
+fn user_name(conn: &Connection, user_id: Option<i64>) -> Result<Option<String>, String> {
+    match user_id {
+        Some(id) => Ok(get_user_by_id(conn, id)?.map(|user| user.name)),
+        None => Ok(None),
+    }
+}
+
+fn visible(ctx: &Context, items: Vec<Item>, limit: usize) -> Vec<Item> {
+    items.into_iter().filter(|item| in_namespace(ctx, item)).take(limit).collect()
+}
+
+// open, closed and all as in `ctm list task --status`
+fn with_status<'a>(query: ItemQuery<'a>, status: &str) -> Result<ItemQuery<'a>, String> {
+    Ok(match parse_status(status)? {
+        255 => query,
+        254 => query.with_statuses(OPEN_STATUS_CODES.to_vec()),
+        253 => query.with_statuses(CLOSED_STATUS_CODES.to_vec()),
+        code => query.with_statuses(vec![code]),
+    })
+}
+
+fn optional_str<'a>(args: &'a Value, key: &str) -> Result<Option<&'a str>, String> {
+    match args.get(key) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(value)) => Ok(Some(value)),
+        Some(_) => Err(format!("'{}' must be a string", key)),
+    }
+}
+
+fn required_str<'a>(args: &'a Value, key: &str) -> Result<&'a str, String> {
+    optional_str(args, key)?
+        .filter(|value| !value.trim().is_empty())
+        .ok_or_else(|| format!("'{}' is required", key))
+}
+
+fn limit(args: &Value) -> Result<usize, String> {
+    match args.get("limit") {
+        None | Some(Value::Null) => Ok(DEFAULT_LIMIT),
+        Some(value) => value
+            .as_u64()
+            .filter(|limit| *limit > 0)
+            .map(|limit| limit as usize)
+            .ok_or_else(|| "'limit' must be a positive integer".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{
+            link::get_links_for_item,
+            namespace::create_namespace,
+        },
+        tests::{get_test_conn, insert_record},
+    };
+
+    fn call_ok(conn: &Connection, ctx: &Context, name: &str, args: Value) -> Value {
+        call(conn, ctx, name, &args).unwrap().unwrap()
+    }
+
+    fn add_one(conn: &Connection, ctx: &Context, content: &str) -> String {
+        let added = call_ok(conn, ctx, "add_task", json!({ "content": content, "due": "tomorrow" }));
+        added["task"]["ref"].as_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_task_tools() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let id = add_one(&conn, &ctx, "Fix the login crash");
+
+        let noted = call_ok(&conn, &ctx, "add_note", json!({ "id": id, "content": "Repro on Safari" }));
+        assert!(noted["note_id"].as_i64().is_some());
+        let linked = call_ok(
+            &conn,
+            &ctx,
+            "add_link",
+            json!({ "id": id, "kind": "pr", "reference": "acme/web#12", "title": "Guard empty password" }),
+        );
+        assert_eq!(linked["link_type"], "pr");
+        let err = call(&conn, &ctx, "add_link", &json!({ "id": id, "kind": "pr", "reference": "acme/web#12" }));
+        assert_eq!(err.unwrap().unwrap_err(), "Link 'acme/web#12' already exists for this task");
+
+        let claimed = call_ok(&conn, &ctx, "claim_task", json!({ "id": id }));
+        assert_eq!(claimed["task"]["assignee"], json!(ctx.current_user_name));
+
+        // Numeric IDs work as well as refs
+        let numeric = parse_task_id(&id).unwrap();
+        let shown = call_ok(&conn, &ctx, "show_task", json!({ "id": numeric }));
+        assert_eq!(shown["task"]["notes"][0]["content"], "Repro on Safari");
+        assert_eq!(shown["task"]["notes"][0]["author"], json!(ctx.current_user_name));
+        assert_eq!(shown["task"]["links"][0]["title"], "Guard empty password");
+        assert_eq!(shown["task"]["links"][0]["author"], json!(ctx.current_user_name));
+        assert_eq!(get_links_for_item(&conn, numeric).unwrap().len(), 1);
+
+        let done = call_ok(&conn, &ctx, "complete_task", json!({ "id": id, "comment": "Shipped" }));
+        assert_eq!(done["task"]["status"], "completed");
+        assert!(call_ok(&conn, &ctx, "list_tasks", json!({}))["tasks"].as_array().unwrap().is_empty());
+        let closed = call_ok(&conn, &ctx, "list_tasks", json!({ "status": "closed" }));
+        assert_eq!(closed["tasks"][0]["ref"], json!(id));
+
+        let err = call(&conn, &ctx, "complete_task", &json!({ "id": id, "status": "open" }));
+        assert!(err.unwrap().is_err());
+        let err = call(&conn, &ctx, "add_note", &json!({ "id": id }));
+        assert_eq!(err.unwrap().unwrap_err(), "'content' is required");
+    }
+
+    #[test]
+    fn test_list_and_search() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        add_one(&conn, &ctx, "Rotate the API keys");
+        add_one(&conn, &ctx, "Update the API docs");
+        add_one(&conn, &ctx, "Book the offsite");
+        insert_record(&conn, "ops", "Rotated the staging API keys", "yesterday");
+
+        let listed = call_ok(&conn, &ctx, "list_tasks", json!({ "limit": 2 }));
+        assert_eq!(listed["namespace"], "default");
+        assert_eq!(listed["tasks"].as_array().unwrap().len(), 2);
+        assert!(call(&conn, &ctx, "list_tasks", &json!({ "limit": 0 })).unwrap().is_err());
+        let mine = call_ok(&conn, &ctx, "list_tasks", json!({ "assignee": "me" }));
+        assert!(mine["tasks"].as_array().unwrap().is_empty());
+
+        let found = call_ok(&conn, &ctx, "search", json!({ "query": "API" }));
+        assert_eq!(found["tasks"].as_array().unwrap().len(), 2);
+        assert_eq!(found["records"][0]["content"], "Rotated the staging API keys");
+    }
+
+    #[test]
+    fn test_namespaces_are_separate() {
+        let (conn, _temp_file) = get_test_conn();
+        let ctx = Context::default_from_db(&conn).unwrap();
+        let id = add_one(&conn, &ctx, "Default namespace task");
+
+        create_namespace(&conn, "work", None, ctx.current_user_id).unwrap();
+        let work = Context::resolve(&conn, None, Some("work")).unwrap();
+        add_one(&conn, &work, "Work task");
+
+        let listed = call_ok(&conn, &work, "list_tasks", json!({}));
+        assert_eq!(listed["tasks"].as_array().unwrap().len(), 1);
+        assert_eq!(listed["tasks"][0]["content"], "Work task");
+        let err = call(&conn, &work, "show_task", &json!({ "id": id })).unwrap().unwrap_err();
+        assert_eq!(err, format!("Task {} not found", id));
+    }
+}
@@ -81,3 +81,174 @@ pub fn insert_recurring_record(
     );
     insert_item(conn, &record).unwrap()
 }
+
+/// A stand-in for the gh CLI: a shell script that logs its arguments and
+/// answers `view` and `list` calls from JSON files written with `respond`.
+#[cfg(unix)]
+pub struct StubGh {
+    pub dir: tempfile::TempDir,
+    pub gh: crate::github::GhCli,
+}
+
+#[cfg(unix)]
+impl Default for StubGh {
+    fn default() -> Self {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("gh");
+        let body = format!(
+            "#!/bin/sh\n\
+             echo \"$@\" >> '{dir}/calls'\n\
+             case \"$1 $2\" in\n\
+               \"issue view\"|\"pr view\") cat '{dir}'/\"$1-$3.json\" ;;\n\
+               \"issue list\") cat '{dir}/issue-list.json' ;;\n\
+             esac\n",
+            dir = dir.path().display()
+        );
+        std::fs::write(&script, body).unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let gh = crate::github::GhCli::new(script.to_str().unwrap());
+        StubGh { dir, gh }
+    }
+}
+
+#[cfg(unix)]
+impl StubGh {
+    /// Serves `json` for `gh <kind> view <number>`
+    pub fn respond(&self, kind: &str, number: u32, json: &str) {
+        std::fs::write(self.dir.path().join(format!("{}-{}.json", kind, number)), json).unwrap();
+    }
+
+    /// Serves `json` for `gh issue list`
+    pub fn respond_list(&self, json: &str) {
+        std::fs::write(self.dir.path().join("issue-list.json"), json).unwrap();
+    }
+
+    /// Every invocation so far, one line of arguments each
+    pub fn calls(&self) -> Vec<String> {
+        std::fs::read_to_string(self.dir.path().join("calls"))
+            .map(|s| s.lines().map(|l| l.to_string()).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// A stand-in for the GitHub API: a local HTTP server answering each
+/// "METHOD /path?query" from canned routes, and 404 for anything else.
+pub struct MockApi {
+    pub url: String,
+    // Request line and Authorization header, and the body, of each request
+    requests: std::sync::Arc<std::sync::Mutex<Vec<(String, String)>>>,
+}
+
+impl MockApi {
+    pub fn start(routes: Vec<(&'static str, u16, String)>) -> Self {
+        use std::io::{
+            BufRead,
+            BufReader,
+            Read,
+        };
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = requests.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let (mut auth, mut length) = (String::new(), 0);
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    let header = header.trim_end();
+                    if header.is_empty() {
+                        break;
+                    }
+                    let (name, value) = header.split_once(':').unwrap_or((header, ""));
+                    match name.to_lowercase().as_str() {
+                        "authorization" => auth = value.trim().to_string(),
+                        "content-length" => length = value.trim().parse().unwrap_or(0),
+                        _ => {}
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+
+                let route: Vec<&str> = request_line.split_whitespace().take(2).collect();
+                let route = route.join(" ");
+                log.lock()
+                    .unwrap()
+                    .push((format!("{} {}", route, auth), String::from_utf8_lossy(&body).to_string()));
+                let (status, reply) = routes
+                    .iter()
+                    .find(|(r, _, _)| *r == route)
+                    .map(|(_, status, reply)| (*status, reply.clone()))
+                    .unwrap_or((404, r#"{"message": "Not Found"}"#.to_string()));
+                let response = format!(
+                    "HTTP/1.1 {} Mock\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    reply.len(),
+                    reply
+                );
+                std::io::Write::write_all(&mut stream, response.as_bytes()).unwrap();
+            }
+        });
+        MockApi { url, requests }
+    }
+
+    /// "METHOD /path?query Authorization" of every request so far
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().iter().map(|(r, _)| r.clone()).collect()
+    }
+
+    /// The non-empty request bodies so far
+    pub fn bodies(&self) -> Vec<String> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, body)| !body.is_empty())
+            .map(|(_, body)| body.clone())
+            .collect()
+    }
+}
+
+/// A git repository in a temp directory, on branch main with one commit
+pub struct GitRepo {
+    pub dir: tempfile::TempDir,
+    pub git: crate::utils::git::Git,
+}
+
+impl GitRepo {
+    pub fn init() -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let git = crate::utils::git::Git::new(dir.path());
+        git.run(&["init", "--quiet", "-b", "main"]).unwrap();
+        let repo = GitRepo { dir, git };
+        repo.commit("Initial commit");
+        repo
+    }
+
+    /// Commits with this message and returns the commit hash
+    pub fn commit(&self, message: &str) -> String {
+        self.git
+            .run(&[
+                "-c",
+                "user.name=ctm",
+                "-c",
+                "user.email=ctm@example.com",
+                "-c",
+                "commit.gpgsign=false",
+                "commit",
+                "--quiet",
+                "--allow-empty",
+                "-m",
+                message,
+            ])
+            .unwrap();
+        self.git.run(&["rev-parse", "HEAD"]).unwrap()
+    }
+}
@@ -0,0 +1,70 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Runs git in a working directory
+#[derive(Debug, Clone)]
+pub struct Git {
+    dir: PathBuf,
+}
+
+impl Git {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Git { dir: dir.into() }
+    }
+
+    /// Runs git and returns its trimmed stdout, or its stderr as the error
+    pub fn run(&self, args: &[&str]) -> Result<String, String> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(&self.dir)
+            .args(args)
+            .output()
+            .map_err(|e| format!("Failed to run git: {}", e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("git {} failed: {}", args.join(" "), stderr.trim()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+    }
+
+    /// The top directory of the repository, if the directory is in one
+    pub fn toplevel(&self) -> Option<PathBuf> {
+        self.run(&["rev-parse", "--show-toplevel"]).ok().map(PathBuf::from)
+    }
+
+    /// The top directory of the main working tree; for a linked worktree,
+    /// that of the repository it was added to
+    pub fn main_toplevel(&self) -> Option<PathBuf> {
+        let common_dir = self.run(&["rev-parse", "--git-common-dir"]).ok()?;
+        let common_dir = absolute(&self.dir, Path::new(&common_dir));
+        match common_dir.file_name() {
+            Some(name) if name == ".git" => common_dir.parent().map(Path::to_path_buf),
+            _ => self.toplevel(),
+        }
+    }
+
+    /// The checked out branch; None on a detached HEAD
+    pub fn current_branch(&self) -> Option<String> {
+        self.run(&["symbolic-ref", "--quiet", "--short", "HEAD"]).ok()
+    }
+
+    /// Where hooks are read from, honouring core.hooksPath
+    pub fn hooks_dir(&self) -> Result<PathBuf, String> {
+        let path = match self.run(&["config", "--get", "core.hooksPath"]) {
+            Ok(path) if !path.is_empty() => path,
+            _ => self.run(&["rev-parse", "--git-path", "hooks"])?,
+        };
+        Ok(absolute(&self.dir, Path::new(&path)))
+    }
+}
+
+// git reports some paths relative to the directory it ran in
+fn absolute(dir: &Path, path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        dir.join(path)
+    }
+}
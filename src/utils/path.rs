@@ -1,5 +1,6 @@
 /// Convert Linux path to Windows path for wt.exe
 /// /mnt/c/python/myapp -> C:\python\myapp
+#[allow(dead_code)]
 pub fn linux_to_windows_path(linux_path: &str) -> Result<String, String> {
     if linux_path.starts_with("/mnt/") {
         let parts: Vec<&str> = linux_path.splitn(4, '/').collect();
@@ -19,6 +20,7 @@ pub fn linux_to_windows_path(linux_path: &str) -> Result<String, String> {
 
 /// Build spawn command for Windows Terminal
 /// Uses /init workaround for WSL interop execute permission issues
+#[allow(dead_code)]
 pub fn build_spawn_command(
     terminal_profile: &str,
     windows_path: &str,